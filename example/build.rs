@@ -1,5 +1,10 @@
+use std::path::PathBuf;
+
 use miette::{IntoDiagnostic, Result};
-use wgsl_bindgen::{WgslBindgenOptionBuilder, GlamWgslTypeMap, WgslShaderSourceOutputType, WgslTypeSerializeStrategy};
+use wgsl_bindgen::{
+    GlamWgslTypeMap, WgslBindgenOptionBuilder, WgslRustTypeMap, WgslShaderSourceOutputType,
+    WgslType, WgslTypeSerializeStrategy,
+};
 
 fn main() -> Result<()> {
     WgslBindgenOptionBuilder::default()
@@ -13,5 +18,103 @@ fn main() -> Result<()> {
         .shader_source_output_type(WgslShaderSourceOutputType::Composer)
         .build()?
         .generate()
+        .into_diagnostic()?;
+
+    // Bindings with the options the example doesn't use, compiled by the tests in `tests`.
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").into_diagnostic()?);
+    WgslBindgenOptionBuilder::default()
+        .add_entry_point("src/shader/testbed.wgsl")
+        .skip_hash_check(true)
+        .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+        .wgsl_type_map(GlamWgslTypeMap)
+        .override_wgsl_type(WgslType::Vec3f, "[f32; 3]".parse().unwrap())
+        .pad_uniform_structs(true)
+        .generate_field_offsets(true)
+        .generate_layout_tests(true)
+        .output_file(out_dir.join("bytemuck_bindings.rs"))
+        .build()?
+        .generate()
+        .into_diagnostic()?;
+
+    // The derive macros of bytemuck need a type map of `Pod` types, unlike `glam::Vec3A`.
+    WgslBindgenOptionBuilder::default()
+        .add_entry_point("src/shader/testbed.wgsl")
+        .add_source_str(
+            "padded_vertex",
+            r#"
+            struct VertexInput {
+                @location(0) weight: f32,
+                @location(1) color: vec4<f32>,
+            };
+
+            @vertex
+            fn main(input: VertexInput) -> @builtin(position) vec4<f32> {
+                return input.color * input.weight;
+            }
+            "#,
+        )
+        .skip_hash_check(true)
+        .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+        .wgsl_type_map(GlamWgslTypeMap::packed())
+        .derive_bytemuck(true)
+        .output_file(out_dir.join("bytemuck_derive_bindings.rs"))
+        .build()?
+        .generate()
+        .into_diagnostic()?;
+
+    WgslBindgenOptionBuilder::default()
+        .add_entry_point("src/shader/testbed.wgsl")
+        .skip_hash_check(true)
+        .serialization_strategy(WgslTypeSerializeStrategy::Encase)
+        .wgsl_type_map(WgslRustTypeMap)
+        .impl_default(true)
+        .output_file(out_dir.join("encase_bindings.rs"))
+        .build()?
+        .generate()
+        .into_diagnostic()?;
+
+    // encase lays out the Rust arrays of the default type map like WGSL arrays, so the
+    // layout tests use glam types encase has vector and matrix layouts for.
+    WgslBindgenOptionBuilder::default()
+        .add_source_str(
+            "layouts",
+            r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+                id: u32,
+            };
+
+            struct Scene {
+                ambient: vec4<f32>,
+                view: mat4x4<f32>,
+                weights: array<f32, 3>,
+                light: Light,
+            };
+
+            struct Lights {
+                count: u32,
+                lights: array<Light>,
+            };
+
+            @group(0) @binding(0) var<storage> scene: Scene;
+            @group(0) @binding(1) var<storage, read_write> lights: Lights;
+
+            @compute @workgroup_size(1)
+            fn main() {
+                let intensity = scene.light.intensity * lights.lights[0].intensity;
+            }
+            "#,
+        )
+        .skip_hash_check(true)
+        .serialization_strategy(WgslTypeSerializeStrategy::Encase)
+        .wgsl_type_map(GlamWgslTypeMap)
+        .generate_layout_tests(true)
+        .generate_encase_layout_tests(true)
+        .generate_typed_buffers(true)
+        .generate_readback_helpers(true)
+        .output_file(out_dir.join("encase_layout_bindings.rs"))
+        .build()?
+        .generate()
         .into_diagnostic()
 }
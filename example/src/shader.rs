@@ -2,7 +2,7 @@
 //
 // ^ wgsl_bindgen version 0.6.11
 // Changes made to this file will not be saved.
-// SourceHash: cf595a1f863090091448656b4027f6c953ea1ff608464fc167c7ddc784e4fc8f
+// SourceHash: 722aa556090cd6a41af42e72e5c8bb21bf8344d64a606c67b2130b4f1ad1a59e
 
 #[allow(unused)]
 mod _root {
@@ -21,6 +21,7 @@ mod _root {
 pub mod reachme {
     #[allow(unused_imports)]
     use super::{_root, _root::*};
+    #[repr(C, align(4))]
     #[derive(Debug, PartialEq, Clone, Copy)]
     pub struct RtsStruct<const N: usize> {
         /// size: 4, offset: 0x0, type: `i32`
@@ -478,12 +479,12 @@ pub mod testbed {
             pub color_texture: &'a wgpu::TextureView,
             pub color_sampler: &'a wgpu::Sampler,
         }
-        const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float {
                             filterable: true,
@@ -495,7 +496,7 @@ pub mod testbed {
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
@@ -548,26 +549,26 @@ pub mod testbed {
             pub ONE: wgpu::BufferBinding<'a>,
             pub uniforms: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::VERTEX,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(4),
                     },
                     count: None,
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(32),
                     },
                     count: None,
                 },
@@ -622,7 +623,7 @@ pub mod testbed {
             pub h: wgpu::BufferBinding<'a>,
             pub i: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR2: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR2: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -633,7 +634,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(8),
                     },
                     count: None,
                 },
@@ -645,7 +646,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(12),
                     },
                     count: None,
                 },
@@ -657,7 +658,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(48),
                     },
                     count: None,
                 },
@@ -669,7 +670,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(48),
                     },
                     count: None,
                 },
@@ -681,7 +682,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(48),
                     },
                     count: None,
                 },
@@ -693,7 +694,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(368),
                     },
                     count: None,
                 },
@@ -705,7 +706,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(32864),
                     },
                     count: None,
                 },
@@ -717,7 +718,7 @@ pub mod testbed {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(416),
                     },
                     count: None,
                 },
@@ -807,13 +808,18 @@ pub mod testbed {
         bind_group2.set(pass);
     }
     impl VertexInput {
+        pub const LOCATION_POSITION: u32 = 0;
         pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = [
             wgpu::VertexAttribute {
                 format: wgpu::VertexFormat::Float32x3,
                 offset: std::mem::offset_of!(VertexInput, position) as u64,
-                shader_location: 0,
+                shader_location: Self::LOCATION_POSITION,
             },
         ];
+        pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+        pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+            Self::STEP_MODE,
+        );
         pub const fn vertex_buffer_layout(
             step_mode: wgpu::VertexStepMode,
         ) -> wgpu::VertexBufferLayout<'static> {
@@ -841,6 +847,10 @@ pub mod testbed {
             buffers: &entry.buffers,
         }
     }
+    /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+    pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[
+        VertexInput::VERTEX_BUFFER_LAYOUT,
+    ];
     pub fn vs_main_entry(vertex_input: wgpu::VertexStepMode) -> VertexEntry<1> {
         VertexEntry {
             entry_point: ENTRY_VS_MAIN,
@@ -962,12 +972,12 @@ pub mod triangle {
             pub color_texture: &'a wgpu::TextureView,
             pub color_sampler: &'a wgpu::Sampler,
         }
-        const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float {
                             filterable: true,
@@ -979,7 +989,7 @@ pub mod triangle {
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
@@ -1031,16 +1041,16 @@ pub mod triangle {
         pub struct BindGroupLayout1<'a> {
             pub uniforms: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(16),
                     },
                     count: None,
                 },
@@ -1098,13 +1108,18 @@ pub mod triangle {
         bind_group1.set(pass);
     }
     impl VertexInput {
+        pub const LOCATION_POSITION: u32 = 0;
         pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = [
             wgpu::VertexAttribute {
                 format: wgpu::VertexFormat::Float32x3,
                 offset: std::mem::offset_of!(VertexInput, position) as u64,
-                shader_location: 0,
+                shader_location: Self::LOCATION_POSITION,
             },
         ];
+        pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+        pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+            Self::STEP_MODE,
+        );
         pub const fn vertex_buffer_layout(
             step_mode: wgpu::VertexStepMode,
         ) -> wgpu::VertexBufferLayout<'static> {
@@ -1132,6 +1147,10 @@ pub mod triangle {
             buffers: &entry.buffers,
         }
     }
+    /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+    pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[
+        VertexInput::VERTEX_BUFFER_LAYOUT,
+    ];
     pub fn vs_main_entry(vertex_input: wgpu::VertexStepMode) -> VertexEntry<1> {
         VertexEntry {
             entry_point: ENTRY_VS_MAIN,
@@ -0,0 +1,38 @@
+//! Compiles the bindings generated by `build.rs` with the options the example doesn't use,
+//! and runs their layout tests.
+#![allow(dead_code)]
+
+mod bytemuck_bindings {
+    include!(concat!(env!("OUT_DIR"), "/bytemuck_bindings.rs"));
+}
+
+mod bytemuck_derive_bindings {
+    include!(concat!(env!("OUT_DIR"), "/bytemuck_derive_bindings.rs"));
+}
+
+mod encase_bindings {
+    include!(concat!(env!("OUT_DIR"), "/encase_bindings.rs"));
+}
+
+mod encase_layout_bindings {
+    include!(concat!(env!("OUT_DIR"), "/encase_layout_bindings.rs"));
+}
+
+#[test]
+fn overridden_vec3_is_padded_explicitly() {
+    let vectors = bytemuck_bindings::types::VectorsF32::new(
+        [1.0, 2.0],
+        [3.0, 4.0, 5.0],
+        glam::vec4(6.0, 7.0, 8.0, 9.0),
+    );
+    let floats: &[f32] = bytemuck::cast_slice(bytemuck::bytes_of(&vectors));
+    assert_eq!(floats[4..7], [3.0, 4.0, 5.0]);
+    assert_eq!(floats[8..12], [6.0, 7.0, 8.0, 9.0]);
+}
+
+#[test]
+fn default_creates_large_arrays() {
+    let arrays = encase_bindings::types::StaticArrays::default();
+    assert_eq!(arrays.c.len(), 512);
+    assert_eq!(arrays.c[511], [[0.0; 4]; 4]);
+}
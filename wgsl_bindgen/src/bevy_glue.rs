@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use case::CaseExt;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::bevy_util::demangle;
+use crate::bindgroup::GroupData;
+use crate::{indexed_name_to_ident, wgsl, WgslBindgenOption};
+
+/// The `bevy` module with the bind group layouts and vertex buffer layouts in bevy's render
+/// types, behind the `bevy` feature of the crate including the bindings.
+///
+/// bevy re-exports the wgpu types it uses, so the layouts are built from the generated wgpu
+/// consts. This requires the `wgpu` dependency to be the version used by bevy.
+pub(crate) fn bevy_module(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  module: &naga::Module,
+  mod_name: &str,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_bevy_glue {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  let item_visibility = options.item_visibility_tokens(2);
+
+  let bind_group_layouts: Vec<_> = bind_group_data
+    .keys()
+    .map(|group_no| {
+      let descriptor = indexed_name_to_ident("LAYOUT_DESCRIPTOR", *group_no);
+      let entries_name = indexed_name_to_ident("bind_group_layout_entries", *group_no);
+      let create_name = indexed_name_to_ident("create_bind_group_layout", *group_no);
+      let label = format!("{mod_name}::BindGroup{group_no}");
+      quote! {
+        /// The layout entries of the bind group, like the ones returned by
+        /// `AsBindGroup::bind_group_layout_entries`.
+        #item_visibility fn #entries_name() -> Vec<::bevy::render::render_resource::BindGroupLayoutEntry> {
+          super::bind_groups::#descriptor.entries.to_vec()
+        }
+
+        #item_visibility fn #create_name(
+          render_device: &::bevy::render::renderer::RenderDevice,
+        ) -> ::bevy::render::render_resource::BindGroupLayout {
+          render_device.create_bind_group_layout(#label, super::bind_groups::#descriptor.entries)
+        }
+      }
+    })
+    .collect();
+
+  let create_layouts = if bind_group_data.is_empty() {
+    quote!()
+  } else {
+    let create_layouts = bind_group_data.keys().map(|group_no| {
+      let create_name = indexed_name_to_ident("create_bind_group_layout", *group_no);
+      quote!(#create_name(render_device))
+    });
+    quote! {
+      /// The layouts of all bind groups in group order, for `RenderPipelineDescriptor::layout`.
+      #item_visibility fn bind_group_layouts(
+        render_device: &::bevy::render::renderer::RenderDevice,
+      ) -> Vec<::bevy::render::render_resource::BindGroupLayout> {
+        vec![#(#create_layouts),*]
+      }
+    }
+  };
+
+  let vertex_layouts: Vec<_> = wgsl::get_vertex_input_structs(module)
+    .iter()
+    .map(|input| {
+      let rust_name = options.rust_struct_name(&demangle(&input.name));
+      let name = Ident::new(&rust_name, Span::call_site());
+      if options.non_interleaved_vertex_buffers {
+        let fn_name = format!("{}_vertex_buffer_layouts", rust_name.to_snake());
+        let fn_name = Ident::new(&fn_name, Span::call_site());
+        quote! {
+          #item_visibility fn #fn_name() -> Vec<::bevy::render::render_resource::VertexBufferLayout> {
+            super::#name::VERTEX_BUFFER_LAYOUTS.iter().map(|layout| {
+              ::bevy::render::render_resource::VertexBufferLayout {
+                array_stride: layout.array_stride,
+                step_mode: layout.step_mode,
+                attributes: layout.attributes.to_vec(),
+              }
+            }).collect()
+          }
+        }
+      } else {
+        let fn_name = format!("{}_vertex_buffer_layout", rust_name.to_snake());
+        let fn_name = Ident::new(&fn_name, Span::call_site());
+        quote! {
+          #item_visibility fn #fn_name() -> ::bevy::render::render_resource::VertexBufferLayout {
+            ::bevy::render::render_resource::VertexBufferLayout {
+              array_stride: super::#name::VERTEX_BUFFER_LAYOUT.array_stride,
+              step_mode: super::#name::STEP_MODE,
+              attributes: super::#name::VERTEX_ATTRIBUTES.to_vec(),
+            }
+          }
+        }
+      }
+    })
+    .collect();
+
+  quote! {
+    /// Bind group and vertex buffer layouts in bevy's render types, for specializing custom
+    /// pipelines and materials.
+    #[cfg(feature = "bevy")]
+    #visibility mod bevy {
+      #(#bind_group_layouts)*
+      #create_layouts
+
+      #(#vertex_layouts)*
+    }
+  }
+}
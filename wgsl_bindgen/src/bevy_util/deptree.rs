@@ -91,6 +91,7 @@ pub struct DependencyTree {
   resolver: ModulePathResolver,
   parsed_sources: FxIndexMap<SourceFilePath, SourceFile>,
   entry_points: FxIndexSet<SourceFilePath>,
+  virtual_modules: FxIndexMap<SourceModuleName, String>,
 }
 
 /// Represents a dependency tree for tracking the dependencies between source files.
@@ -111,6 +112,9 @@ impl DependencyTree {
   /// * `module_prefix` - An optional module prefix to be used when generating import paths.
   /// * `entry_points` - A vector of source file paths representing the entry points of the
   ///   dependency tree.
+  /// * `additional_scan_dirs` - Additional directories to resolve imports from.
+  /// * `virtual_modules` - WGSL sources keyed by module path like `my_lib::lighting`, used
+  ///   for the imports which don't resolve to a file.
   ///
   /// # Returns
   ///
@@ -120,6 +124,7 @@ impl DependencyTree {
     entry_module_prefix: Option<String>,
     entry_points: Vec<SourceFilePath>, // path to entry points
     additional_scan_dirs: Vec<AdditionalScanDirectory>,
+    virtual_modules: FxIndexMap<String, String>,
   ) -> Result<Self, DependencyTreeError> {
     let resolver = ModulePathResolver::new(entry_module_prefix, additional_scan_dirs);
 
@@ -127,6 +132,10 @@ impl DependencyTree {
       resolver,
       parsed_sources: Default::default(),
       entry_points: Default::default(),
+      virtual_modules: virtual_modules
+        .into_iter()
+        .map(|(module_path, source)| (SourceModuleName::new(module_path), source))
+        .collect(),
     };
 
     for entry_point in entry_points {
@@ -155,7 +164,8 @@ impl DependencyTree {
       .resolver
       .generate_best_possible_paths(&entry_dir, &imported_path, parent_source_path)
       .into_iter()
-      .find(|(_, path)| path.is_file()); // make sure this is not reimporting itself
+      .find(|(_, path)| path.is_file()) // make sure this is not reimporting itself
+      .or_else(|| self.find_virtual_module(imported_path));
 
     let Some(parent_source) = self.parsed_sources.get_mut(parent_source_path) else {
       unreachable!("{:?} source code as not parsed", parent_source_path)
@@ -198,15 +208,24 @@ impl DependencyTree {
     module_name: Option<SourceModuleName>,
     limiter: &mut MaxRecursionLimiter,
   ) -> Result<(), DependencyTreeError> {
+    // Virtual modules use their module path as source path, see `find_virtual_module`.
+    let virtual_source = self
+      .virtual_modules
+      .get(&SourceModuleName::new(source_path.to_string()));
+
     match self.parsed_sources.entry(source_path.clone()) {
       Entry::Occupied(_) => {} // do nothing
       Entry::Vacant(entry) => {
-        let content = entry.key().read_contents().or(Err(SourceNotFound {
-          path: entry.key().clone(),
-        }))?;
-
-        let source_file =
+        let content = match virtual_source {
+          Some(content) => content.clone(),
+          None => entry.key().read_contents().or(Err(SourceNotFound {
+            path: entry.key().clone(),
+          }))?,
+        };
+
+        let mut source_file =
           SourceFile::create(entry.key().clone(), module_name.clone(), content);
+        source_file.is_virtual = virtual_source.is_some();
         entry.insert(source_file);
       }
     };
@@ -228,6 +247,26 @@ impl DependencyTree {
     Ok(())
   }
 
+  /// The virtual module with the longest module path that is the imported path or one of
+  /// its parents, like `my_lib::lighting` for `my_lib::lighting::PointLight`.
+  fn find_virtual_module(
+    &self,
+    imported_path: &ImportedPath,
+  ) -> Option<(SourceModuleName, SourceFilePath)> {
+    self
+      .virtual_modules
+      .keys()
+      .filter(|name| {
+        let name = name.as_str();
+        imported_path.as_str() == name
+          || imported_path
+            .strip_prefix(name)
+            .is_some_and(|item| item.starts_with("::"))
+      })
+      .max_by_key(|name| name.len())
+      .map(|name| (name.clone(), SourceFilePath::new(name.as_str())))
+  }
+
   /// Returns all the source files including their dependencies in the dependency tree.
   pub fn all_files_including_dependencies(&self) -> FxIndexSet<SourceFilePath> {
     self.parsed_sources.keys().cloned().collect()
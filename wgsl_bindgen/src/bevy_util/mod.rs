@@ -3,7 +3,9 @@ mod module_path_resolver;
 mod name_demangle;
 pub mod parse_imports;
 pub mod source_file;
+mod source_glob;
 
 pub use deptree::*;
 use module_path_resolver::*;
 pub use name_demangle::*;
+pub(crate) use source_glob::*;
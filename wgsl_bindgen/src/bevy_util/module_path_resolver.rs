@@ -79,6 +79,22 @@ impl ModulePathResolver {
       .rev()
   }
 
+  /// The parts of the imported path after the module prefix, if it starts with the prefix.
+  fn strip_module_prefix<'a>(
+    imported_path: &'a ImportedPath,
+    module_prefix: &str,
+  ) -> Option<SmallVec<[&'a str; 10]>> {
+    let mut import_parts = imported_path.split("::").filter(|part| !part.is_empty());
+    for prefix_part in module_prefix.split("::") {
+      if import_parts.next() != Some(prefix_part) {
+        return None;
+      }
+    }
+
+    let remaining: SmallVec<[&str; 10]> = import_parts.collect();
+    (!remaining.is_empty()).then_some(remaining)
+  }
+
   /// Generates possible import paths for a given import path fragment.
   pub fn generate_best_possible_paths(
     &self,
@@ -118,6 +134,21 @@ impl ModulePathResolver {
 
     for scan_dir in &self.additional_scan_dirs {
       let scan_path = Path::new(&scan_dir.directory);
+
+      // The import root maps imports like `my_lib::utils` to `utils.wgsl` in the directory.
+      let remapped_parts = scan_dir
+        .module_import_root
+        .as_deref()
+        .and_then(|root| Self::strip_module_prefix(imported_path, root));
+      if let Some(remapped_parts) = remapped_parts {
+        paths.extend(Self::generate_paths_for_dir(
+          &scan_dir.module_import_root,
+          remapped_parts,
+          scan_path,
+          source_path,
+        ))
+      }
+
       paths.extend(Self::generate_paths_for_dir(
         &scan_dir.module_import_root,
         import_parts.clone(),
@@ -136,7 +167,10 @@ mod tests {
   use pretty_assertions::assert_eq;
 
   use crate::bevy_util::ModulePathResolver;
-  use crate::{ImportedPath, SourceFileDir, SourceFilePath, SourceModuleName};
+  use crate::{
+    AdditionalScanDirectory, ImportedPath, SourceFileDir, SourceFilePath,
+    SourceModuleName,
+  };
 
   #[test]
   fn should_generate_single_import_path() {
@@ -264,6 +298,28 @@ mod tests {
     assert_eq!(result, expected);
   }
 
+  #[test]
+  fn should_remap_import_root_of_additional_scan_dir() {
+    let source_path = SourceFilePath::new("mydir/source.wgsl");
+    let imported_path = ImportedPath::new("my_lib::lighting::PointLight");
+    let scan_dir = AdditionalScanDirectory {
+      module_import_root: Some("my_lib".to_string()),
+      directory: "../shaders/lib".to_string(),
+    };
+
+    let result = ModulePathResolver::new(None, vec![scan_dir])
+      .generate_best_possible_paths(&source_path.dir(), &imported_path, &source_path);
+
+    assert!(result.contains(&(
+      SourceModuleName::new("my_lib::lighting::PointLight"),
+      SourceFilePath::new("../shaders/lib/lighting/PointLight.wgsl")
+    )));
+    assert!(result.contains(&(
+      SourceModuleName::new("my_lib::lighting"),
+      SourceFilePath::new("../shaders/lib/lighting.wgsl")
+    )));
+  }
+
   #[test]
   fn should_return_valid_pbr_paths_back_to_current_dir() {
     let module_prefix = Some("bevy_pbr".to_string());
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::sync::OnceLock;
 
+use case::CaseExt;
 use regex::Regex;
 use smallvec::SmallVec;
 
@@ -73,11 +74,40 @@ pub fn demangle_splitting_mod_path_and_item(string: &str) -> (Option<String>, St
   }
 }
 
+/// Converts the type arguments of generic struct names, like `Stack<f32>` in SPIR-V from
+/// shading languages with generics, to a valid identifier like `StackF32`.
+pub fn demangle_generic_arguments(name: &str) -> Cow<'_, str> {
+  let Some((base, arguments)) = name.split_once('<') else {
+    return Cow::Borrowed(name);
+  };
+
+  let arguments: String = arguments
+    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+    .filter(|argument| !argument.is_empty())
+    .map(|argument| {
+      // The scalar types of HLSL and Slang are named like in WGSL.
+      let argument = match argument {
+        "half" => "f16",
+        "float" => "f32",
+        "double" => "f64",
+        "int" => "i32",
+        "uint" => "u32",
+        argument => argument,
+      };
+      argument.to_camel()
+    })
+    .collect();
+  Cow::Owned(format!("{}{arguments}", base.trim_end()))
+}
+
 #[cfg(test)]
 mod tests {
   use pretty_assertions::assert_eq;
 
-  use crate::bevy_util::{demangle_splitting_mod_path_and_item, make_valid_rust_import};
+  use crate::bevy_util::{
+    demangle_generic_arguments, demangle_splitting_mod_path_and_item,
+    make_valid_rust_import,
+  };
 
   #[test]
   fn test_make_valid_rust_import() {
@@ -97,4 +127,13 @@ mod tests {
       (Some("types".into()), "Uniforms".into())
     );
   }
+
+  #[test]
+  fn test_demangle_generic_arguments() {
+    assert_eq!(demangle_generic_arguments("Stack"), "Stack");
+    assert_eq!(demangle_generic_arguments("Stack<f32>"), "StackF32");
+    assert_eq!(demangle_generic_arguments("Stack<float>"), "StackF32");
+    assert_eq!(demangle_generic_arguments("Pair<vec3<f32>, uint>"), "PairVec3F32U32");
+    assert_eq!(demangle_generic_arguments("Node<point_light>"), "NodePointLight");
+  }
 }
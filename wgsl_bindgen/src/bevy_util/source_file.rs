@@ -14,6 +14,8 @@ pub struct SourceFile {
   pub content: String,
   pub imports: SmallVec<[ImportStatement; 4]>,
   pub direct_dependencies: FxIndexSet<SourceFilePath>,
+  /// Whether the source was registered as a virtual module instead of read from a file.
+  pub is_virtual: bool,
 }
 
 impl SourceFile {
@@ -29,6 +31,7 @@ impl SourceFile {
       content: normalized_content,
       imports: SmallVec::default(),
       direct_dependencies: FxIndexSet::default(),
+      is_virtual: false,
     };
 
     source.imports =
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// The files matching a glob like `shaders/**/*.wgsl`, where `**` matches any number of
+/// directories, `*` any characters but `/` and `?` a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceGlob {
+  /// The directory before the first wildcard, which the matching files are under.
+  pub base_dir: PathBuf,
+  /// The matching files sorted by path.
+  pub files: Vec<PathBuf>,
+}
+
+impl SourceGlob {
+  pub fn expand(pattern: &str) -> std::io::Result<Self> {
+    let pattern = pattern.replace('\\', "/");
+    let base_dir = pattern
+      .split('/')
+      .take_while(|part| !part.contains(['*', '?']))
+      .collect::<Vec<_>>()
+      .join("/");
+    let base_dir = PathBuf::from(if base_dir.is_empty() { "." } else { &base_dir });
+
+    let regex = glob_regex(pattern.trim_start_matches("./"));
+    let mut files = Vec::new();
+    collect_files(&base_dir, &mut files)?;
+    files.retain(|file| {
+      let path = file.to_string_lossy().replace('\\', "/");
+      regex.is_match(path.trim_start_matches("./"))
+    });
+    files.sort();
+
+    Ok(Self { base_dir, files })
+  }
+}
+
+/// Whether the WGSL source has a `@vertex`, `@fragment` or `@compute` entry point.
+pub(crate) fn has_entry_point(source: &str) -> bool {
+  Regex::new(r"(?m)^\s*@(vertex|fragment|compute)\b")
+    .unwrap()
+    .is_match(source)
+}
+
+fn glob_regex(pattern: &str) -> Regex {
+  let mut regex = String::from("^");
+  let mut chars = pattern.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '*' if chars.peek() == Some(&'*') => {
+        chars.next();
+        if chars.peek() == Some(&'/') {
+          chars.next();
+          regex.push_str("(?:.*/)?");
+        } else {
+          regex.push_str(".*");
+        }
+      }
+      '*' => regex.push_str("[^/]*"),
+      '?' => regex.push_str("[^/]"),
+      c => regex.push_str(&regex::escape(&c.to_string())),
+    }
+  }
+  regex.push('$');
+  Regex::new(&regex).expect("Failed to compile regex")
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      collect_files(&path, files)?;
+    } else {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn should_match_nested_directories_with_double_star() {
+    let regex = glob_regex("shaders/**/*.wgsl");
+
+    assert!(regex.is_match("shaders/main.wgsl"));
+    assert!(regex.is_match("shaders/lib/utils/math.wgsl"));
+    assert!(!regex.is_match("shaders/main.rs"));
+    assert!(!regex.is_match("other/main.wgsl"));
+  }
+
+  #[test]
+  fn should_expand_glob_from_base_dir() {
+    let glob = SourceGlob::expand("tests/shaders/glob/**/*.wgsl").unwrap();
+
+    assert_eq!(glob.base_dir, PathBuf::from("tests/shaders/glob"));
+    assert_eq!(
+      glob.files,
+      vec![
+        PathBuf::from("tests/shaders/glob/common.wgsl"),
+        PathBuf::from("tests/shaders/glob/main.wgsl"),
+        PathBuf::from("tests/shaders/glob/nested/effect.wgsl"),
+      ]
+    );
+  }
+
+  #[test]
+  fn should_find_entry_points() {
+    assert!(has_entry_point("@compute @workgroup_size(1)\nfn main() {}"));
+    assert!(!has_entry_point("// @vertex\nfn helper() {}"));
+  }
+}
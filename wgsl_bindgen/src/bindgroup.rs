@@ -1,12 +1,20 @@
 use std::collections::BTreeMap;
 
+use case::CaseExt;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{Ident, Index};
 
-use crate::{indexed_name_to_ident, wgsl::buffer_binding_type, CreateModuleError};
+use crate::{
+  indexed_name_to_ident,
+  wgsl::{self, buffer_binding_type, shader_stages_tokens, storage_texture_format},
+  CreateModuleError, WgslBindgenOption,
+};
 use crate::bevy_util::demangle_splitting_mod_path_and_item;
 
+/// The default `min_uniform_buffer_offset_alignment` and `min_storage_buffer_offset_alignment`.
+pub(crate) const DYNAMIC_OFFSET_ALIGNMENT: u32 = 256;
+
 pub struct GroupData<'a> {
   pub bindings: Vec<GroupBinding<'a>>,
 }
@@ -14,52 +22,205 @@ pub struct GroupData<'a> {
 pub struct GroupBinding<'a> {
   pub name: Option<String>,
   pub binding_index: u32,
+  /// The type of the bound resource, or of its elements for binding arrays.
   pub binding_type: &'a naga::Type,
   pub address_space: naga::AddressSpace,
+  /// The size of the bound type, with a single element for runtime-sized arrays.
+  pub size: u32,
+  /// The number of elements of binding arrays.
+  pub array_count: Option<u32>,
+  /// The stages of the entry points using the binding.
+  pub stages: wgpu::ShaderStages,
+}
+
+impl GroupBinding<'_> {
+  /// The stages the binding is visible to in the bind group layout.
+  fn visibility(&self, options: &WgslBindgenOption) -> wgpu::ShaderStages {
+    if options.all_stages_visibility {
+      wgpu::ShaderStages::all()
+    } else {
+      self.stages
+    }
+  }
+
+  fn demangled_name(&self) -> String {
+    let (_, demangled_name) =
+      demangle_splitting_mod_path_and_item(self.name.as_ref().unwrap());
+    demangled_name
+  }
+
+  /// The Rust type of the bound resource and the [wgpu::BindingResource] variant for it.
+  fn resource_type(&self) -> (TokenStream, TokenStream) {
+    // TODO: Support more types.
+    let (ty, variant, array_variant) = match self.binding_type.inner {
+      naga::TypeInner::Image { .. } => (
+        quote!(&'a wgpu::TextureView),
+        quote!(TextureView),
+        quote!(TextureViewArray),
+      ),
+      naga::TypeInner::Sampler { .. } => (
+        quote!(&'a wgpu::Sampler),
+        quote!(Sampler),
+        quote!(SamplerArray),
+      ),
+      naga::TypeInner::Scalar(_)
+      | naga::TypeInner::Struct { .. }
+      | naga::TypeInner::Array { .. } => (
+        quote!(wgpu::BufferBinding<'a>),
+        quote!(Buffer),
+        quote!(BufferArray),
+      ),
+      _ => panic!("Unsupported type for binding fields."),
+    };
+
+    match self.array_count {
+      Some(_) => (quote!(&'a [#ty]), quote!(wgpu::BindingResource::#array_variant)),
+      None => (ty, quote!(wgpu::BindingResource::#variant)),
+    }
+  }
+
+  fn has_dynamic_offset(&self, options: &WgslBindgenOption) -> bool {
+    options.dynamic_offset_bindings.contains(&self.demangled_name())
+  }
+
+  fn is_filtering(&self, options: &WgslBindgenOption) -> bool {
+    // Multisampled float textures can't be filtered.
+    let is_multisampled = matches!(
+      self.binding_type.inner,
+      naga::TypeInner::Image {
+        class: naga::ImageClass::Sampled { multi: true, .. },
+        ..
+      }
+    );
+    !is_multisampled && !options.non_filtering_bindings.contains(&self.demangled_name())
+  }
 }
 
 // TODO: Take an iterator instead?
 pub fn bind_groups_module(
   bind_group_data: &BTreeMap<u32, GroupData>,
   shader_stages: wgpu::ShaderStages,
+  options: &WgslBindgenOption,
 ) -> TokenStream {
-  let bind_groups: Vec<_> = bind_group_data
+  if bind_group_data.is_empty() {
+    // Don't include empty modules.
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  let bind_groups = bind_group_items(bind_group_data, shader_stages, options)
+    .into_iter()
+    .map(|(_, items)| items);
+  let bind_groups_struct = bind_groups_struct(bind_group_data, shader_stages, options);
+  let set_bind_groups = set_bind_groups_function(bind_group_data, shader_stages, options);
+
+  // Create a module to avoid name conflicts with user structs.
+  quote! {
+      #visibility mod bind_groups {
+          #(#bind_groups)*
+
+          #bind_groups_struct
+      }
+      #set_bind_groups
+  }
+}
+
+/// The items of each bind group of the `bind_groups` module, like its layout descriptor
+/// and its `BindGroupN` struct.
+pub fn bind_group_items(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  shader_stages: wgpu::ShaderStages,
+  options: &WgslBindgenOption,
+) -> Vec<(u32, TokenStream)> {
+  // Items of the bind_groups module are nested one module deeper.
+  let item_visibility = options.item_visibility_tokens(2);
+
+  bind_group_data
     .iter()
     .map(|(group_no, group)| {
       let group_name = indexed_name_to_ident("BindGroup", *group_no);
 
-      let layout = bind_group_layout(*group_no, group);
+      let layout = bind_group_layout(*group_no, group, &item_visibility);
       let layout_descriptor =
-        bind_group_layout_descriptor(*group_no, group, shader_stages);
-      let group_impl = bind_group(*group_no, group, shader_stages);
+        bind_group_layout_descriptor(*group_no, group, &item_visibility, options);
+      let group_impl =
+        bind_group(*group_no, group, shader_stages, &item_visibility, options);
+      let group_builder = if options.generate_bind_group_builders {
+        bind_group_builder(*group_no, group, &item_visibility)
+      } else {
+        quote!()
+      };
+      let group_cache = if options.generate_bind_group_caches {
+        bind_group_cache(*group_no, group, &item_visibility)
+      } else {
+        quote!()
+      };
 
-      quote! {
+      let items = quote! {
           #[derive(Debug)]
-          pub struct #group_name(wgpu::BindGroup);
+          #item_visibility struct #group_name(wgpu::BindGroup);
           #layout
           #layout_descriptor
           #group_impl
-      }
+          #group_builder
+          #group_cache
+      };
+      (*group_no, items)
     })
-    .collect();
+    .collect()
+}
+
+/// The pass the bind groups are set in.
+fn pass_type(shader_stages: wgpu::ShaderStages) -> TokenStream {
+  // TODO: Support compute shader with vertex/fragment in the same module?
+  let is_compute = shader_stages == wgpu::ShaderStages::COMPUTE;
+  if is_compute {
+    quote!(wgpu::ComputePass<'a>)
+  } else {
+    quote!(wgpu::RenderPass<'a>)
+  }
+}
 
+/// The `BindGroups` struct of the `bind_groups` module, setting all the bind groups.
+pub fn bind_groups_struct(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  shader_stages: wgpu::ShaderStages,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let item_visibility = options.item_visibility_tokens(2);
   let bind_group_fields: Vec<_> = bind_group_data
     .keys()
     .map(|group_no| {
       let group_name = indexed_name_to_ident("BindGroup", *group_no);
       let field = indexed_name_to_ident("bind_group", *group_no);
-      quote!(pub #field: &'a #group_name)
+      quote!(#item_visibility #field: &'a #group_name)
     })
     .collect();
+  let render_pass = pass_type(shader_stages);
+  let set_groups = set_groups(bind_group_data);
 
-  // TODO: Support compute shader with vertex/fragment in the same module?
-  let is_compute = shader_stages == wgpu::ShaderStages::COMPUTE;
-  let render_pass = if is_compute {
-    quote!(wgpu::ComputePass<'a>)
-  } else {
-    quote!(wgpu::RenderPass<'a>)
-  };
+  quote! {
+      #[derive(Debug, Copy, Clone)]
+      #item_visibility struct BindGroups<'a> {
+          #(#bind_group_fields),*
+      }
+
+      impl<'a> BindGroups<'a> {
+          #item_visibility fn set(&self, pass: &mut #render_pass) {
+              #(self.#set_groups)*
+          }
+      }
+  }
+}
 
+/// The `set_bind_groups` function of the entry point module.
+pub fn set_bind_groups_function(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  shader_stages: wgpu::ShaderStages,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let visibility = options.item_visibility_tokens(1);
+  let render_pass = pass_type(shader_stages);
   let group_parameters: Vec<_> = bind_group_data
     .keys()
     .map(|group_no| {
@@ -68,67 +229,41 @@ pub fn bind_groups_module(
       quote!(#group: &'a bind_groups::#group_type)
     })
     .collect();
+  let set_groups = set_groups(bind_group_data);
 
-  // The set function for each bind group already sets the index.
-  let set_groups: Vec<_> = bind_group_data
-    .keys()
-    .map(|group_no| {
-      let group = indexed_name_to_ident("bind_group", *group_no);
-      quote!(#group.set(pass);)
-    })
-    .collect();
-
-  let set_bind_groups = quote! {
-      pub fn set_bind_groups<'a>(
+  quote! {
+      #visibility fn set_bind_groups<'a>(
           pass: &mut #render_pass,
           #(#group_parameters),*
       ) {
           #(#set_groups)*
       }
-  };
-
-  if bind_groups.is_empty() {
-    // Don't include empty modules.
-    quote!()
-  } else {
-    // Create a module to avoid name conflicts with user structs.
-    quote! {
-        pub mod bind_groups {
-            #(#bind_groups)*
-
-            #[derive(Debug, Copy, Clone)]
-            pub struct BindGroups<'a> {
-                #(#bind_group_fields),*
-            }
-
-            impl<'a> BindGroups<'a> {
-                pub fn set(&self, pass: &mut #render_pass) {
-                    #(self.#set_groups)*
-                }
-            }
-        }
-        #set_bind_groups
-    }
   }
 }
 
-fn bind_group_layout(group_no: u32, group: &GroupData) -> TokenStream {
+/// Sets each bind group, whose set function already sets the index.
+fn set_groups(bind_group_data: &BTreeMap<u32, GroupData>) -> Vec<TokenStream> {
+  bind_group_data
+    .keys()
+    .map(|group_no| {
+      let group = indexed_name_to_ident("bind_group", *group_no);
+      quote!(#group.set(pass);)
+    })
+    .collect()
+}
+
+fn bind_group_layout(
+  group_no: u32,
+  group: &GroupData,
+  visibility: &TokenStream,
+) -> TokenStream {
   let fields: Vec<_> = group
     .bindings
     .iter()
     .map(|binding| {
-      let (_, demangled_name) = demangle_splitting_mod_path_and_item(binding.name.as_ref().unwrap());
-      let field_name = Ident::new(&demangled_name, Span::call_site());
-      // TODO: Support more types.
-      let field_type = match binding.binding_type.inner {
-        naga::TypeInner::Struct { .. } => quote!(wgpu::BufferBinding<'a>),
-        naga::TypeInner::Image { .. } => quote!(&'a wgpu::TextureView),
-        naga::TypeInner::Sampler { .. } => quote!(&'a wgpu::Sampler),
-        naga::TypeInner::Array { .. } => quote!(wgpu::BufferBinding<'a>),
-        naga::TypeInner::Scalar(_) => quote!(wgpu::BufferBinding<'a>),
-        _ => panic!("Unsupported type for binding fields."),
-      };
-      quote!(pub #field_name: #field_type)
+      let field_name = Ident::new(&binding.demangled_name(), Span::call_site());
+      let (field_type, _) = binding.resource_type();
+      quote!(#visibility #field_name: #field_type)
     })
     .collect();
 
@@ -136,7 +271,7 @@ fn bind_group_layout(group_no: u32, group: &GroupData) -> TokenStream {
   quote! {
       #[allow(non_snake_case)]
       #[derive(Debug)]
-      pub struct #name<'a> {
+      #visibility struct #name<'a> {
           #(#fields),*
       }
   }
@@ -145,39 +280,32 @@ fn bind_group_layout(group_no: u32, group: &GroupData) -> TokenStream {
 fn bind_group_layout_descriptor(
   group_no: u32,
   group: &GroupData,
-  shader_stages: wgpu::ShaderStages,
+  visibility: &TokenStream,
+  options: &WgslBindgenOption,
 ) -> TokenStream {
   let entries: Vec<_> = group
     .bindings
     .iter()
-    .map(|binding| bind_group_layout_entry(binding, shader_stages))
+    .map(|binding| bind_group_layout_entry(binding, options))
     .collect();
 
   let name = indexed_name_to_ident("LAYOUT_DESCRIPTOR", group_no);
   quote! {
-      const #name: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-          label: None,
-          entries: &[
-              #(#entries),*
-          ],
-      };
+      #visibility const #name: wgpu::BindGroupLayoutDescriptor<'static> =
+          wgpu::BindGroupLayoutDescriptor {
+              label: None,
+              entries: &[
+                  #(#entries),*
+              ],
+          };
   }
 }
 
 fn bind_group_layout_entry(
   binding: &GroupBinding,
-  shader_stages: wgpu::ShaderStages,
+  options: &WgslBindgenOption,
 ) -> TokenStream {
-  // TODO: Assume storage is only used for compute?
-  // TODO: Support just vertex or fragment?
-  // TODO: Visible from all stages?
-  let stages = match shader_stages {
-    wgpu::ShaderStages::VERTEX_FRAGMENT => quote!(wgpu::ShaderStages::VERTEX_FRAGMENT),
-    wgpu::ShaderStages::COMPUTE => quote!(wgpu::ShaderStages::COMPUTE),
-    wgpu::ShaderStages::VERTEX => quote!(wgpu::ShaderStages::VERTEX),
-    wgpu::ShaderStages::FRAGMENT => quote!(wgpu::ShaderStages::FRAGMENT),
-    _ => todo!(),
-  };
+  let stages = shader_stages_tokens(binding.visibility(options));
 
   let binding_index = Index::from(binding.binding_index as usize);
   // TODO: Support more types.
@@ -186,46 +314,45 @@ fn bind_group_layout_entry(
     | naga::TypeInner::Struct { .. }
     | naga::TypeInner::Array { .. } => {
       let buffer_binding_type = buffer_binding_type(binding.address_space);
+      let has_dynamic_offset = binding.has_dynamic_offset(options);
+      // wgpu validates the buffer sizes when creating bind groups instead of draw calls.
+      let min_binding_size = match binding.size {
+        0 => quote!(None),
+        size => {
+          let size = Index::from(size as usize);
+          quote!(wgpu::BufferSize::new(#size))
+        }
+      };
 
       quote!(wgpu::BindingType::Buffer {
           ty: #buffer_binding_type,
-          has_dynamic_offset: false,
-          min_binding_size: None,
+          has_dynamic_offset: #has_dynamic_offset,
+          min_binding_size: #min_binding_size,
       })
     }
-    naga::TypeInner::Image { dim, class, .. } => {
-      let view_dim = match dim {
-        naga::ImageDimension::D1 => quote!(wgpu::TextureViewDimension::D1),
-        naga::ImageDimension::D2 => quote!(wgpu::TextureViewDimension::D2),
-        naga::ImageDimension::D3 => quote!(wgpu::TextureViewDimension::D3),
-        naga::ImageDimension::Cube => quote!(wgpu::TextureViewDimension::Cube),
-      };
+    naga::TypeInner::Image {
+      dim,
+      arrayed,
+      class,
+    } => {
+      let view_dim = view_dimension(dim, arrayed);
 
       match class {
-        naga::ImageClass::Sampled { kind: _, multi } => {
-          // TODO: Don't assume all textures are filterable.
-          quote!(wgpu::BindingType::Texture {
-              sample_type: wgpu::TextureSampleType::Float { filterable: true },
-              view_dimension: #view_dim,
-              multisampled: #multi,
-          })
-        }
-        naga::ImageClass::Depth { multi } => {
+        naga::ImageClass::Sampled { multi, .. } | naga::ImageClass::Depth { multi } => {
+          let sample_type = texture_sample_type(class, binding.is_filtering(options));
           quote!(wgpu::BindingType::Texture {
-              sample_type: wgpu::TextureSampleType::Depth,
+              sample_type: #sample_type,
               view_dimension: #view_dim,
               multisampled: #multi,
           })
         }
         naga::ImageClass::Storage { format, access } => {
-          // TODO: Will the debug implementation always work with the macro?
-          // Assume texture format variants are the same as storage formats.
-          let format = syn::Ident::new(&format!("{format:?}"), Span::call_site());
+          let format = texture_format(format);
           let storage_access = storage_access(access);
 
           quote!(wgpu::BindingType::StorageTexture {
               access: #storage_access,
-              format: wgpu::TextureFormat::#format,
+              format: #format,
               view_dimension: #view_dim,
           })
         }
@@ -234,8 +361,10 @@ fn bind_group_layout_entry(
     naga::TypeInner::Sampler { comparison } => {
       let sampler_type = if comparison {
         quote!(wgpu::SamplerBindingType::Comparison)
-      } else {
+      } else if binding.is_filtering(options) {
         quote!(wgpu::SamplerBindingType::Filtering)
+      } else {
+        quote!(wgpu::SamplerBindingType::NonFiltering)
       };
       quote!(wgpu::BindingType::Sampler(#sampler_type))
     }
@@ -243,16 +372,176 @@ fn bind_group_layout_entry(
     _ => panic!("Failed to generate BindingType."),
   };
 
+  let count = match binding.array_count {
+    Some(count) => {
+      let count = Index::from(count as usize);
+      quote!(std::num::NonZeroU32::new(#count))
+    }
+    None => quote!(None),
+  };
+
   quote! {
       wgpu::BindGroupLayoutEntry {
           binding: #binding_index,
           visibility: #stages,
           ty: #binding_type,
-          count: None,
+          count: #count,
       }
   }
 }
 
+fn view_dimension(dim: naga::ImageDimension, arrayed: bool) -> TokenStream {
+  match (dim, arrayed) {
+    (naga::ImageDimension::D1, _) => quote!(wgpu::TextureViewDimension::D1),
+    (naga::ImageDimension::D2, false) => quote!(wgpu::TextureViewDimension::D2),
+    (naga::ImageDimension::D2, true) => quote!(wgpu::TextureViewDimension::D2Array),
+    (naga::ImageDimension::D3, _) => quote!(wgpu::TextureViewDimension::D3),
+    (naga::ImageDimension::Cube, false) => quote!(wgpu::TextureViewDimension::Cube),
+    (naga::ImageDimension::Cube, true) => quote!(wgpu::TextureViewDimension::CubeArray),
+  }
+}
+
+fn texture_sample_type(class: naga::ImageClass, filterable: bool) -> TokenStream {
+  match class {
+    naga::ImageClass::Sampled {
+      kind: naga::ScalarKind::Sint,
+      ..
+    } => quote!(wgpu::TextureSampleType::Sint),
+    naga::ImageClass::Sampled {
+      kind: naga::ScalarKind::Uint,
+      ..
+    } => quote!(wgpu::TextureSampleType::Uint),
+    naga::ImageClass::Sampled { .. } => {
+      quote!(wgpu::TextureSampleType::Float { filterable: #filterable })
+    }
+    naga::ImageClass::Depth { .. } => quote!(wgpu::TextureSampleType::Depth),
+    naga::ImageClass::Storage { .. } => panic!("Storage textures have no sample type."),
+  }
+}
+
+/// The consts describing the sampled texture bindings and functions creating matching views.
+fn texture_metadata(
+  binding: &GroupBinding,
+  visibility: &TokenStream,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let naga::TypeInner::Image {
+    dim,
+    arrayed,
+    class,
+  } = binding.binding_type.inner
+  else {
+    return quote!();
+  };
+  let (naga::ImageClass::Sampled { multi, .. } | naga::ImageClass::Depth { multi }) = class
+  else {
+    return quote!();
+  };
+  if binding.array_count.is_some() {
+    return quote!();
+  }
+
+  let name = binding.demangled_name();
+  let prefix = name.to_snake().to_uppercase();
+  let sample_type_name = Ident::new(&format!("{prefix}_SAMPLE_TYPE"), Span::call_site());
+  let view_dimension_name =
+    Ident::new(&format!("{prefix}_VIEW_DIMENSION"), Span::call_site());
+  let multisampled_name = Ident::new(&format!("{prefix}_MULTISAMPLED"), Span::call_site());
+  let create_view_name =
+    Ident::new(&format!("create_{}_view", name.to_snake()), Span::call_site());
+
+  let sample_type = texture_sample_type(class, binding.is_filtering(options));
+  let view_dimension = view_dimension(dim, arrayed);
+  // Textures with stencil only have a sample type for their depth aspect.
+  let aspect = match class {
+    naga::ImageClass::Depth { .. } => quote!(wgpu::TextureAspect::DepthOnly),
+    _ => quote!(wgpu::TextureAspect::All),
+  };
+
+  let sample_type_doc = format!(" The sample type of the `{name}` texture.");
+  let view_dimension_doc = format!(" The view dimension of the `{name}` texture.");
+  let multisampled_doc = format!(" Whether the `{name}` texture is multisampled.");
+  let create_view_doc = format!(" Creates a view of `texture` for the `{name}` binding.");
+  let dimension_message =
+    format!("`{name}` expects a texture compatible with a {{:?}} view");
+  let multisampled_message = format!("`{name}` expects `multisampled: {{}}`");
+  let sample_type_message = format!("`{name}` expects a texture with a {{:?}} sample type");
+
+  quote! {
+    #[doc = #sample_type_doc]
+    #visibility const #sample_type_name: wgpu::TextureSampleType = #sample_type;
+    #[doc = #view_dimension_doc]
+    #visibility const #view_dimension_name: wgpu::TextureViewDimension = #view_dimension;
+    #[doc = #multisampled_doc]
+    #visibility const #multisampled_name: bool = #multi;
+
+    #[doc = #create_view_doc]
+    ///
+    /// Panics in debug builds if `texture` doesn't match the binding.
+    #visibility fn #create_view_name(texture: &wgpu::Texture) -> wgpu::TextureView {
+      debug_assert_eq!(
+        texture.dimension(),
+        Self::#view_dimension_name.compatible_texture_dimension(),
+        #dimension_message,
+        Self::#view_dimension_name,
+      );
+      debug_assert_eq!(
+        texture.sample_count() > 1,
+        Self::#multisampled_name,
+        #multisampled_message,
+        Self::#multisampled_name,
+      );
+      let sample_type = texture.format().sample_type(Some(#aspect), None);
+      let is_compatible_sample_type = match (sample_type, Self::#sample_type_name) {
+        // Filterable textures can also be bound without filtering.
+        (
+          Some(wgpu::TextureSampleType::Float { .. }),
+          wgpu::TextureSampleType::Float { filterable: false },
+        ) => true,
+        (sample_type, expected) => sample_type == Some(expected),
+      };
+      debug_assert!(
+        is_compatible_sample_type,
+        #sample_type_message,
+        Self::#sample_type_name,
+      );
+      texture.create_view(&wgpu::TextureViewDescriptor {
+        dimension: Some(Self::#view_dimension_name),
+        aspect: #aspect,
+        ..Default::default()
+      })
+    }
+  }
+}
+
+fn texture_format(format: naga::StorageFormat) -> TokenStream {
+  // The storage formats are simple variants, so their debug names are the variant names.
+  let format = storage_texture_format(format);
+  let format = Ident::new(&format!("{format:?}"), Span::call_site());
+  quote!(wgpu::TextureFormat::#format)
+}
+
+/// The format of storage texture bindings, so matching textures can be created.
+fn storage_texture_format_const(binding: &GroupBinding, visibility: &TokenStream) -> TokenStream {
+  let naga::TypeInner::Image {
+    class: naga::ImageClass::Storage { format, .. },
+    ..
+  } = binding.binding_type.inner
+  else {
+    return quote!();
+  };
+
+  let name = binding.demangled_name();
+  let const_name = format!("{}_FORMAT", name.to_snake().to_uppercase());
+  let const_name = Ident::new(&const_name, Span::call_site());
+  let format = texture_format(format);
+  let doc = format!(" The format of the `{name}` storage texture.");
+  quote! {
+    #[doc = #doc]
+    #visibility const #const_name: wgpu::TextureFormat = #format;
+  }
+}
+
 fn storage_access(access: naga::StorageAccess) -> TokenStream {
   let is_read = access.contains(naga::StorageAccess::LOAD);
   let is_write = access.contains(naga::StorageAccess::STORE);
@@ -268,29 +557,17 @@ fn bind_group(
   group_no: u32,
   group: &GroupData,
   shader_stages: wgpu::ShaderStages,
+  visibility: &TokenStream,
+  options: &WgslBindgenOption,
 ) -> TokenStream {
   let entries: Vec<_> = group
     .bindings
     .iter()
     .map(|binding| {
       let binding_index = Index::from(binding.binding_index as usize);
-      let (_, demangled_name) = demangle_splitting_mod_path_and_item(binding.name.as_ref().unwrap());
-      let binding_name = Ident::new(&demangled_name, Span::call_site());
-      let resource_type = match binding.binding_type.inner {
-        naga::TypeInner::Scalar(_)
-        | naga::TypeInner::Struct { .. }
-        | naga::TypeInner::Array { .. } => {
-          quote!(wgpu::BindingResource::Buffer(bindings.#binding_name))
-        }
-        naga::TypeInner::Image { .. } => {
-          quote!(wgpu::BindingResource::TextureView(bindings.#binding_name))
-        }
-        naga::TypeInner::Sampler { .. } => {
-          quote!(wgpu::BindingResource::Sampler(bindings.#binding_name))
-        }
-        // TODO: Better error handling.
-        _ => panic!("Failed to generate BindingType."),
-      };
+      let binding_name = Ident::new(&binding.demangled_name(), Span::call_site());
+      let (_, resource) = binding.resource_type();
+      let resource_type = quote!(#resource(bindings.#binding_name));
 
       quote! {
           wgpu::BindGroupEntry {
@@ -317,13 +594,64 @@ fn bind_group(
 
   let group_no = Index::from(group_no as usize);
 
+  let dynamic_bindings: Vec<_> = group
+    .bindings
+    .iter()
+    .filter(|binding| binding.has_dynamic_offset(options))
+    .collect();
+  let dynamic_offsets = if dynamic_bindings.is_empty() {
+    quote!()
+  } else {
+    let aligned_sizes = dynamic_bindings.iter().map(|binding| {
+      let name = binding.demangled_name();
+      let const_name = format!("{}_ALIGNED_SIZE", name.to_snake().to_uppercase());
+      let const_name = Ident::new(&const_name, Span::call_site());
+      let aligned_size =
+        Index::from(binding.size.next_multiple_of(DYNAMIC_OFFSET_ALIGNMENT) as usize);
+      let doc = format!(
+        " The size of `{name}` rounded up to the default `min_uniform_buffer_offset_alignment`."
+      );
+      quote! {
+        #[doc = #doc]
+        #visibility const #const_name: wgpu::BufferAddress = #aligned_size;
+      }
+    });
+    quote! {
+      #(#aligned_sizes)*
+
+      #visibility fn set_with_offsets<'a>(
+        &'a self,
+        render_pass: &mut #render_pass,
+        offsets: &[wgpu::DynamicOffset],
+      ) {
+        render_pass.set_bind_group(#group_no, &self.0, offsets);
+      }
+    }
+  };
+
+  let storage_texture_formats = group
+    .bindings
+    .iter()
+    .map(|binding| storage_texture_format_const(binding, visibility));
+
+  let texture_metadata = if options.generate_texture_metadata {
+    let metadata = group
+      .bindings
+      .iter()
+      .map(|binding| texture_metadata(binding, visibility, options));
+    quote!(#(#metadata)*)
+  } else {
+    quote!()
+  };
+
   quote! {
       impl #bind_group_name {
-          pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+
+          #visibility fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
               device.create_bind_group_layout(&#layout_descriptor_name)
           }
 
-          pub fn from_bindings(device: &wgpu::Device, bindings: #bind_group_layout_name) -> Self {
+          #visibility fn from_bindings(device: &wgpu::Device, bindings: #bind_group_layout_name) -> Self {
               let bind_group_layout = device.create_bind_group_layout(&#layout_descriptor_name);
               let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
                   layout: &bind_group_layout,
@@ -335,89 +663,488 @@ fn bind_group(
               Self(bind_group)
           }
 
-          pub fn set<'a>(&'a self, render_pass: &mut #render_pass) {
+          #visibility fn set<'a>(&'a self, render_pass: &mut #render_pass) {
               render_pass.set_bind_group(#group_no, &self.0, &[]);
           }
+
+          #(#storage_texture_formats)*
+
+          #dynamic_offsets
+
+          #texture_metadata
       }
   }
 }
 
-pub fn get_bind_group_data(
-  module: &naga::Module,
-) -> Result<BTreeMap<u32, GroupData>, CreateModuleError> {
-  // Use a BTree to sort type and field names by group index.
-  // This isn't strictly necessary but makes the generated code cleaner.
-  let mut groups = BTreeMap::new();
-
-  for global_handle in module.global_variables.iter() {
-    let global = &module.global_variables[global_handle.0];
-    if let Some(binding) = &global.binding {
-      let group = groups.entry(binding.group).or_insert(GroupData {
-        bindings: Vec::new(),
-      });
-      let binding_type = &module.types[module.global_variables[global_handle.0].ty];
+pub(crate) fn add_prelude_binding_fallback(options: &WgslBindgenOption) -> TokenStream {
+  if !options.generate_bind_group_builders {
+    return quote!();
+  }
 
-      let group_binding = GroupBinding {
-        name: global.name.clone(),
-        binding_index: binding.binding,
-        binding_type,
-        address_space: global.space,
-      };
-      // Repeated bindings will probably cause a compile error.
-      // We'll still check for it here just in case.
-      if group
-        .bindings
-        .iter()
-        .any(|g| g.binding_index == binding.binding)
-      {
-        return Err(CreateModuleError::DuplicateBinding {
-          binding: binding.binding,
-        });
-      }
-      group.bindings.push(group_binding);
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// Provides the resources of the bindings left unset in bind group builders,
+    /// like a placeholder texture or a default sampler.
+    #visibility trait BindingFallback {
+      fn fallback(&self, entry: &wgpu::BindGroupLayoutEntry) -> wgpu::BindingResource<'_>;
     }
   }
+}
 
-  // wgpu expects bind groups to be consecutive starting from 0.
-  if groups.keys().map(|i| *i as usize).eq(0..groups.len()) {
-    Ok(groups)
+/// The items of the `buffers` module, with the buffers typed by their contents.
+pub(crate) fn typed_buffers(options: &WgslBindgenOption) -> TokenStream {
+  let is_bytemuck = options.serialization_strategy.is_bytemuck();
+  let (bounds, size) = if is_bytemuck {
+    (quote!(bytemuck::Pod), quote!(std::mem::size_of::<T>() as u64))
   } else {
-    Err(CreateModuleError::NonConsecutiveBindGroups)
-  }
-}
+    (
+      quote!(encase::ShaderSize + encase::internal::WriteInto),
+      quote!(T::SHADER_SIZE.get()),
+    )
+  };
 
-#[cfg(test)]
-mod tests {
-  use indoc::indoc;
+  // The readback helpers copy from the storage buffers into their staging buffers.
+  let (storage_usage, storage_doc) = if options.generate_readback_helpers {
+    (
+      quote!(STORAGE | wgpu::BufferUsages::COPY_SRC),
+      "A storage buffer holding a `T`, with the size of `T`. It has the `COPY_SRC` usage \
+       to be read back by the `read_*` functions.",
+    )
+  } else {
+    (quote!(STORAGE), "A storage buffer holding a `T`, with the size of `T`.")
+  };
 
-  use super::*;
-  use crate::assert_tokens_eq;
+  let visibility = options.item_visibility_tokens(1);
+  let buffers = [
+    (
+      quote!(UniformBuffer),
+      quote!(UNIFORM),
+      "A uniform buffer holding a `T`, with the size of `T`.",
+    ),
+    (quote!(StorageBuffer), storage_usage, storage_doc),
+  ]
+  .map(|(name, usage, doc)| {
+    // The contents are written with the serialization strategy of the structs, where
+    // encase has a buffer type of the same name.
+    let write = if is_bytemuck {
+      quote!(queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(value));)
+    } else {
+      quote! {
+        let mut buffer = encase::#name::new(Vec::new());
+        buffer.write(value).unwrap();
+        queue.write_buffer(&self.buffer, 0, &buffer.into_inner());
+      }
+    };
 
-  #[test]
-  fn bind_group_data_consecutive_bind_groups() {
-    let source = indoc! {r#"
-            @group(0) @binding(0) var<uniform> a: vec4<f32>;
-            @group(1) @binding(0) var<uniform> b: vec4<f32>;
-            @group(2) @binding(0) var<uniform> c: vec4<f32>;
+    quote! {
+      #[doc = #doc]
+      #[derive(Debug)]
+      #visibility struct #name<T> {
+        buffer: wgpu::Buffer,
+        _contents: std::marker::PhantomData<T>,
+      }
 
-            @fragment
-            fn main() {}
-        "#};
+      impl<T: #bounds> #name<T> {
+        /// The size of the buffer in bytes.
+        #visibility const SIZE: u64 = #size;
+
+        #visibility fn new(device: &wgpu::Device) -> Self {
+          let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(std::any::type_name::<T>()),
+            size: Self::SIZE,
+            usage: wgpu::BufferUsages::#usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+          });
+          Self {
+            buffer,
+            _contents: std::marker::PhantomData,
+          }
+        }
 
-    let module = naga::front::wgsl::parse_str(source).unwrap();
-    assert_eq!(3, get_bind_group_data(&module).unwrap().len());
-  }
+        #visibility fn write(&self, queue: &wgpu::Queue, value: &T) {
+          #write
+        }
 
-  #[test]
-  fn bind_group_data_first_group_not_zero() {
-    let source = indoc! {r#"
-            @group(1) @binding(0) var<uniform> a: vec4<f32>;
+        /// The binding of the whole buffer, for the fields of the bind group layouts.
+        #visibility fn binding(&self) -> wgpu::BufferBinding<'_> {
+          self.buffer.as_entire_buffer_binding()
+        }
 
-            @fragment
-            fn main() {}
-        "#};
+        #visibility fn buffer(&self) -> &wgpu::Buffer {
+          &self.buffer
+        }
+      }
+    }
+  });
 
-    let module = naga::front::wgsl::parse_str(source).unwrap();
+  quote!(#(#buffers)*)
+}
+
+pub(crate) fn add_prelude_reflection_types(options: &WgslBindgenOption) -> TokenStream {
+  if !options.generate_reflection {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// The kind of resource bound to a binding, see [BindingReflection].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #visibility enum BindingKind {
+      UniformBuffer,
+      StorageBuffer { read_only: bool },
+      Texture,
+      StorageTexture { access: wgpu::StorageTextureAccess },
+      Sampler { comparison: bool },
+    }
+
+    /// A binding of a shader, as listed in the `BINDINGS` of its `reflection` module.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #visibility struct BindingReflection {
+      pub name: &'static str,
+      pub group: u32,
+      pub binding: u32,
+      pub kind: BindingKind,
+      /// The WGSL type of the bound resource, or of its elements for binding arrays.
+      pub type_name: &'static str,
+      /// The size of buffers in bytes, with a single element for runtime-sized arrays.
+      pub size: Option<u64>,
+      /// The number of elements of binding arrays.
+      pub count: Option<u32>,
+      pub visibility: wgpu::ShaderStages,
+    }
+  }
+}
+
+/// The `reflection` module listing the bindings of the shader as const data.
+pub fn reflection_module(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_reflection {
+    return quote!();
+  }
+
+  let bindings = bind_group_data.iter().flat_map(|(group_no, group)| {
+    group.bindings.iter().map(|binding| {
+      let name = binding.demangled_name();
+      let stages = shader_stages_tokens(binding.visibility(options));
+      let group = Index::from(*group_no as usize);
+      let binding_index = Index::from(binding.binding_index as usize);
+      let (kind, size) = match binding.binding_type.inner {
+        naga::TypeInner::Image {
+          class: naga::ImageClass::Storage { access, .. },
+          ..
+        } => {
+          let access = storage_access(access);
+          (quote!(StorageTexture { access: #access }), None)
+        }
+        naga::TypeInner::Image { .. } => (quote!(Texture), None),
+        naga::TypeInner::Sampler { comparison } => {
+          (quote!(Sampler { comparison: #comparison }), None)
+        }
+        _ => match binding.address_space {
+          naga::AddressSpace::Storage { access } => {
+            let read_only = !access.contains(naga::StorageAccess::STORE);
+            (quote!(StorageBuffer { read_only: #read_only }), Some(binding.size))
+          }
+          _ => (quote!(UniformBuffer), Some(binding.size)),
+        },
+      };
+      let type_name = match binding.binding_type.name.as_ref() {
+        Some(type_name) => demangle_splitting_mod_path_and_item(type_name).1,
+        None => binding.binding_type.inner.to_wgsl(&module.to_ctx()),
+      };
+      let size = match size {
+        Some(size) => {
+          let size = Index::from(size as usize);
+          quote!(Some(#size))
+        }
+        None => quote!(None),
+      };
+      let count = match binding.array_count {
+        Some(count) => {
+          let count = Index::from(count as usize);
+          quote!(Some(#count))
+        }
+        None => quote!(None),
+      };
+
+      quote! {
+        super::_root::BindingReflection {
+          name: #name,
+          group: #group,
+          binding: #binding_index,
+          kind: super::_root::BindingKind::#kind,
+          type_name: #type_name,
+          size: #size,
+          count: #count,
+          visibility: #stages,
+        }
+      }
+    })
+  });
+
+  let visibility = options.item_visibility_tokens(1);
+  let item_visibility = options.item_visibility_tokens(2);
+  quote! {
+    #visibility mod reflection {
+      #item_visibility const BINDINGS: &[super::_root::BindingReflection] = &[
+        #(#bindings),*
+      ];
+    }
+  }
+}
+
+fn bind_group_builder(
+  group_no: u32,
+  group: &GroupData,
+  visibility: &TokenStream,
+) -> TokenStream {
+  let bind_group_name = indexed_name_to_ident("BindGroup", group_no);
+  let builder_name = Ident::new(&format!("{bind_group_name}Builder"), Span::call_site());
+  let layout_descriptor_name = indexed_name_to_ident("LAYOUT_DESCRIPTOR", group_no);
+
+  let mut fields = Vec::new();
+  let mut setters = Vec::new();
+  let mut entries = Vec::new();
+  for (i, binding) in group.bindings.iter().enumerate() {
+    let name = Ident::new(&binding.demangled_name(), Span::call_site());
+    let (field_type, resource) = binding.resource_type();
+    let binding_index = Index::from(binding.binding_index as usize);
+    let entry_index = Index::from(i);
+
+    fields.push(quote!(#name: Option<#field_type>));
+    setters.push(quote! {
+      #visibility fn #name(mut self, #name: #field_type) -> Self {
+        self.#name = Some(#name);
+        self
+      }
+    });
+    entries.push(quote! {
+      wgpu::BindGroupEntry {
+        binding: #binding_index,
+        resource: match self.#name {
+          Some(resource) => #resource(resource),
+          None => fallback.fallback(&#layout_descriptor_name.entries[#entry_index]),
+        },
+      }
+    });
+  }
+
+  let doc =
+    format!(" Builds a [{bind_group_name}], using a fallback for the bindings left unset.");
+  quote! {
+    #[doc = #doc]
+    #[derive(Debug, Default)]
+    #visibility struct #builder_name<'a> {
+      #(#fields),*
+    }
+
+    impl<'a> #builder_name<'a> {
+      #(#setters)*
+
+      #visibility fn build(
+        self,
+        device: &wgpu::Device,
+        fallback: &'a impl super::_root::BindingFallback,
+      ) -> #bind_group_name {
+        let bind_group_layout = device.create_bind_group_layout(&#layout_descriptor_name);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+          layout: &bind_group_layout,
+          entries: &[
+            #(#entries),*
+          ],
+          label: None,
+        });
+        #bind_group_name(bind_group)
+      }
+    }
+
+    impl #bind_group_name {
+      #visibility fn builder<'a>() -> #builder_name<'a> {
+        #builder_name::default()
+      }
+    }
+  }
+}
+
+fn bind_group_cache(
+  group_no: u32,
+  group: &GroupData,
+  visibility: &TokenStream,
+) -> TokenStream {
+  let bind_group_name = indexed_name_to_ident("BindGroup", group_no);
+  let bind_group_layout_name = indexed_name_to_ident("BindGroupLayout", group_no);
+  let cache_name = Ident::new(&format!("{bind_group_name}Cache"), Span::call_site());
+
+  let (key_types, keys): (Vec<_>, Vec<_>) = group
+    .bindings
+    .iter()
+    .map(|binding| {
+      let name = Ident::new(&binding.demangled_name(), Span::call_site());
+      let resource = match binding.array_count {
+        Some(_) => quote!(resource),
+        None => quote!(bindings.#name),
+      };
+      let (key_type, key) = match binding.binding_type.inner {
+        naga::TypeInner::Image { .. } => (
+          quote!(wgpu::Id<wgpu::TextureView>),
+          quote!(#resource.global_id()),
+        ),
+        naga::TypeInner::Sampler { .. } => (
+          quote!(wgpu::Id<wgpu::Sampler>),
+          quote!(#resource.global_id()),
+        ),
+        _ => (
+          quote!((
+            wgpu::Id<wgpu::Buffer>,
+            wgpu::BufferAddress,
+            Option<wgpu::BufferSize>
+          )),
+          quote!((#resource.buffer.global_id(), #resource.offset, #resource.size)),
+        ),
+      };
+      match binding.array_count {
+        Some(_) => (
+          quote!(Vec<#key_type>),
+          quote!(bindings.#name.iter().map(|#resource| #key).collect()),
+        ),
+        None => (key_type, key),
+      }
+    })
+    .unzip();
+
+  let doc = format!(
+    " Reuses the [{bind_group_name}]s created for the same resources, which are kept alive until \
+     the cache is cleared."
+  );
+  quote! {
+    #[doc = #doc]
+    #[derive(Debug, Default)]
+    #visibility struct #cache_name(
+      std::collections::HashMap<(#(#key_types,)*), #bind_group_name>
+    );
+
+    impl #cache_name {
+      #visibility fn get_or_create(
+        &mut self,
+        device: &wgpu::Device,
+        bindings: #bind_group_layout_name,
+      ) -> &#bind_group_name {
+        let key = (#(#keys,)*);
+        self
+          .0
+          .entry(key)
+          .or_insert_with(|| #bind_group_name::from_bindings(device, bindings))
+      }
+
+      #visibility fn clear(&mut self) {
+        self.0.clear();
+      }
+    }
+  }
+}
+
+pub fn get_bind_group_data(
+  module: &naga::Module,
+) -> Result<BTreeMap<u32, GroupData>, CreateModuleError> {
+  let groups = get_bind_group_data_with_gaps(module)?;
+
+  // wgpu expects bind groups to be consecutive starting from 0.
+  if groups.keys().map(|i| *i as usize).eq(0..groups.len()) {
+    Ok(groups)
+  } else {
+    Err(CreateModuleError::NonConsecutiveBindGroups)
+  }
+}
+
+/// The bind groups of the module, which can leave out groups whose layouts are given by
+/// the caller, like after remapping the groups.
+pub fn get_bind_group_data_with_gaps(
+  module: &naga::Module,
+) -> Result<BTreeMap<u32, GroupData<'_>>, CreateModuleError> {
+  // Use a BTree to sort type and field names by group index.
+  // This isn't strictly necessary but makes the generated code cleaner.
+  let mut groups = BTreeMap::new();
+
+  for global_handle in module.global_variables.iter() {
+    let global = &module.global_variables[global_handle.0];
+    if let Some(binding) = &global.binding {
+      let group = groups.entry(binding.group).or_insert(GroupData {
+        bindings: Vec::new(),
+      });
+      let (binding_type, array_count) = match module.types[global.ty].inner {
+        naga::TypeInner::BindingArray { base, size } => match size {
+          naga::ArraySize::Constant(count) => (&module.types[base], Some(count.get())),
+          naga::ArraySize::Dynamic => {
+            return Err(CreateModuleError::UnsizedBindingArray {
+              binding: global.name.clone().unwrap_or_default(),
+            });
+          }
+        },
+        _ => (&module.types[global.ty], None),
+      };
+
+      let group_binding = GroupBinding {
+        name: global.name.clone(),
+        binding_index: binding.binding,
+        binding_type,
+        address_space: global.space,
+        size: binding_type.inner.size(module.to_ctx()),
+        array_count,
+        stages: wgsl::global_stages(module, global_handle.0),
+      };
+      // Repeated bindings will probably cause a compile error.
+      // We'll still check for it here just in case.
+      if group
+        .bindings
+        .iter()
+        .any(|g| g.binding_index == binding.binding)
+      {
+        return Err(CreateModuleError::DuplicateBinding {
+          binding: binding.binding,
+        });
+      }
+      group.bindings.push(group_binding);
+    }
+  }
+
+  Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+  use indoc::indoc;
+
+  use super::*;
+  use crate::assert_tokens_eq;
+
+  #[test]
+  fn bind_group_data_consecutive_bind_groups() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> a: vec4<f32>;
+            @group(1) @binding(0) var<uniform> b: vec4<f32>;
+            @group(2) @binding(0) var<uniform> c: vec4<f32>;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    assert_eq!(3, get_bind_group_data(&module).unwrap().len());
+  }
+
+  #[test]
+  fn bind_group_data_first_group_not_zero() {
+    let source = indoc! {r#"
+            @group(1) @binding(0) var<uniform> a: vec4<f32>;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
     assert!(matches!(
       get_bind_group_data(&module),
       Err(CreateModuleError::NonConsecutiveBindGroups)
@@ -465,7 +1192,11 @@ mod tests {
     let module = naga::front::wgsl::parse_str(source).unwrap();
     let bind_group_data = get_bind_group_data(&module).unwrap();
 
-    let actual = bind_groups_module(&bind_group_data, wgpu::ShaderStages::COMPUTE);
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::COMPUTE,
+      &WgslBindgenOption::default(),
+    );
 
     assert_tokens_eq!(
       quote! {
@@ -479,7 +1210,7 @@ mod tests {
                   pub vertex_weights: wgpu::BufferBinding<'a>,
                   pub dst: wgpu::BufferBinding<'a>,
               }
-              const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
@@ -490,7 +1221,7 @@ mod tests {
                                   read_only: true,
                               },
                               has_dynamic_offset: false,
-                              min_binding_size: None,
+                              min_binding_size: wgpu::BufferSize::new(16),
                           },
                           count: None,
                       },
@@ -562,7 +1293,7 @@ mod tests {
               pub struct BindGroupLayout1<'a> {
                   pub transforms: wgpu::BufferBinding<'a>,
               }
-              const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
@@ -668,8 +1399,11 @@ mod tests {
     let module = naga::front::wgsl::parse_str(source).unwrap();
     let bind_group_data = get_bind_group_data(&module).unwrap();
 
-    let actual =
-      bind_groups_module(&bind_group_data, wgpu::ShaderStages::VERTEX_FRAGMENT);
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::VERTEX_FRAGMENT,
+      &WgslBindgenOption::default(),
+    );
 
     // TODO: Are storage buffers valid for vertex/fragment?
     assert_tokens_eq!(
@@ -690,7 +1424,7 @@ mod tests {
                   pub color_texture_msaa: &'a wgpu::TextureView,
                   pub depth_texture_msaa: &'a wgpu::TextureView,
               }
-              const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
@@ -762,7 +1496,7 @@ mod tests {
                           visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                           ty: wgpu::BindingType::Texture {
                               sample_type: wgpu::TextureSampleType::Float {
-                                  filterable: true,
+                                  filterable: false,
                               },
                               view_dimension: wgpu::TextureViewDimension::D2,
                               multisampled: true,
@@ -841,10 +1575,559 @@ mod tests {
                                           ),
                                       },
                                       wgpu::BindGroupEntry {
-                                          binding: 8,
-                                          resource: wgpu::BindingResource::TextureView(
-                                              bindings.depth_texture_msaa,
-                                          ),
+                                          binding: 8,
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.depth_texture_msaa,
+                                          ),
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      Self(bind_group)
+                  }
+                  pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+                      render_pass.set_bind_group(0, &self.0, &[]);
+                  }
+                  /// The format of the `storage_tex_read` storage texture.
+                  pub const STORAGE_TEX_READ_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+                  /// The format of the `storage_tex_write` storage texture.
+                  pub const STORAGE_TEX_WRITE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Sint;
+                  /// The format of the `storage_tex_read_write` storage texture.
+                  pub const STORAGE_TEX_READ_WRITE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Uint;
+              }
+              #[derive(Debug)]
+              pub struct BindGroup1(wgpu::BindGroup);
+              #[allow(non_snake_case)]
+              #[derive(Debug)]
+              pub struct BindGroupLayout1<'a> {
+                  pub transforms: wgpu::BufferBinding<'a>,
+                  pub one: wgpu::BufferBinding<'a>,
+              }
+              pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+                  label: None,
+                  entries: &[
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 0,
+                          visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: false,
+                              min_binding_size: None,
+                          },
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 1,
+                          visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: false,
+                              min_binding_size: wgpu::BufferSize::new(4),
+                          },
+                          count: None,
+                      },
+                  ],
+              };
+              impl BindGroup1 {
+                  pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR1)
+                  }
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout1) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR1);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 1,
+                                          resource: wgpu::BindingResource::Buffer(bindings.one),
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      Self(bind_group)
+                  }
+                  pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+                      render_pass.set_bind_group(1, &self.0, &[]);
+                  }
+              }
+              #[derive(Debug, Copy, Clone)]
+              pub struct BindGroups<'a> {
+                  pub bind_group0: &'a BindGroup0,
+                  pub bind_group1: &'a BindGroup1,
+              }
+              impl<'a> BindGroups<'a> {
+                  pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
+                      self.bind_group0.set(pass);
+                      self.bind_group1.set(pass);
+                  }
+              }
+          }
+          pub fn set_bind_groups<'a>(
+              pass: &mut wgpu::RenderPass<'a>,
+              bind_group0: &'a bind_groups::BindGroup0,
+              bind_group1: &'a bind_groups::BindGroup1,
+
+          ) {
+              bind_group0.set(pass);
+              bind_group1.set(pass);
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_groups_module_vertex() {
+    // The actual content of the structs doesn't matter.
+    // We only care about the groups and bindings.
+    let source = indoc! {r#"
+            struct Transforms {};
+
+            @group(0) @binding(0) var<uniform> transforms: Transforms;
+
+            @vertex
+            fn vs_main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::VERTEX,
+      &WgslBindgenOption::default(),
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod bind_groups {
+              #[derive(Debug)]
+              pub struct BindGroup0(wgpu::BindGroup);
+              #[allow(non_snake_case)]
+              #[derive(Debug)]
+              pub struct BindGroupLayout0<'a> {
+                  pub transforms: wgpu::BufferBinding<'a>,
+              }
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+                  label: None,
+                  entries: &[
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 0,
+                          visibility: wgpu::ShaderStages::VERTEX,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: false,
+                              min_binding_size: None,
+                          },
+                          count: None,
+                      },
+                  ],
+              };
+              impl BindGroup0 {
+                  pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
+                  }
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      Self(bind_group)
+                  }
+                  pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+                      render_pass.set_bind_group(0, &self.0, &[]);
+                  }
+              }
+              #[derive(Debug, Copy, Clone)]
+              pub struct BindGroups<'a> {
+                  pub bind_group0: &'a BindGroup0,
+              }
+              impl<'a> BindGroups<'a> {
+                  pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
+                      self.bind_group0.set(pass);
+                  }
+              }
+          }
+          pub fn set_bind_groups<'a>(
+              pass: &mut wgpu::RenderPass<'a>,
+              bind_group0: &'a bind_groups::BindGroup0,
+          ) {
+              bind_group0.set(pass);
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_groups_module_fragment() {
+    // The actual content of the structs doesn't matter.
+    // We only care about the groups and bindings.
+    let source = indoc! {r#"
+            struct Transforms {};
+
+            @group(0) @binding(0) var<uniform> transforms: Transforms;
+
+            @fragment
+            fn fs_main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::FRAGMENT,
+      &WgslBindgenOption::default(),
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod bind_groups {
+              #[derive(Debug)]
+              pub struct BindGroup0(wgpu::BindGroup);
+              #[allow(non_snake_case)]
+              #[derive(Debug)]
+              pub struct BindGroupLayout0<'a> {
+                  pub transforms: wgpu::BufferBinding<'a>,
+              }
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+                  label: None,
+                  entries: &[
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 0,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: false,
+                              min_binding_size: None,
+                          },
+                          count: None,
+                      },
+                  ],
+              };
+              impl BindGroup0 {
+                  pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
+                  }
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      Self(bind_group)
+                  }
+                  pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+                      render_pass.set_bind_group(0, &self.0, &[]);
+                  }
+              }
+              #[derive(Debug, Copy, Clone)]
+              pub struct BindGroups<'a> {
+                  pub bind_group0: &'a BindGroup0,
+              }
+              impl<'a> BindGroups<'a> {
+                  pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
+                      self.bind_group0.set(pass);
+                  }
+              }
+          }
+          pub fn set_bind_groups<'a>(
+              pass: &mut wgpu::RenderPass<'a>,
+              bind_group0: &'a bind_groups::BindGroup0,
+          ) {
+              bind_group0.set(pass);
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_groups_module_builders() {
+    let source = indoc! {r#"
+            struct Tint { color: vec4<f32> };
+
+            @group(0) @binding(0) var color_texture: texture_2d<f32>;
+            @group(0) @binding(1) var color_sampler: sampler;
+            @group(0) @binding(2) var<uniform> tint: Tint;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::FRAGMENT,
+      &WgslBindgenOption {
+        generate_bind_group_builders: true,
+        ..Default::default()
+      },
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod bind_groups {
+              #[derive(Debug)]
+              pub struct BindGroup0(wgpu::BindGroup);
+              #[allow(non_snake_case)]
+              #[derive(Debug)]
+              pub struct BindGroupLayout0<'a> {
+                  pub color_texture: &'a wgpu::TextureView,
+                  pub color_sampler: &'a wgpu::Sampler,
+                  pub tint: wgpu::BufferBinding<'a>,
+              }
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+                  label: None,
+                  entries: &[
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 0,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Float {
+                                  filterable: true,
+                              },
+                              view_dimension: wgpu::TextureViewDimension::D2,
+                              multisampled: false,
+                          },
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 1,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 2,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: false,
+                              min_binding_size: wgpu::BufferSize::new(16),
+                          },
+                          count: None,
+                      },
+                  ],
+              };
+              impl BindGroup0 {
+                  pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
+                  }
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.color_texture,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 1,
+                                          resource: wgpu::BindingResource::Sampler(
+                                              bindings.color_sampler,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 2,
+                                          resource: wgpu::BindingResource::Buffer(bindings.tint),
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      Self(bind_group)
+                  }
+                  pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+                      render_pass.set_bind_group(0, &self.0, &[]);
+                  }
+              }
+              /// Builds a [BindGroup0], using a fallback for the bindings left unset.
+              #[derive(Debug, Default)]
+              pub struct BindGroup0Builder<'a> {
+                  color_texture: Option<&'a wgpu::TextureView>,
+                  color_sampler: Option<&'a wgpu::Sampler>,
+                  tint: Option<wgpu::BufferBinding<'a>>,
+              }
+              impl<'a> BindGroup0Builder<'a> {
+                  pub fn color_texture(mut self, color_texture: &'a wgpu::TextureView) -> Self {
+                      self.color_texture = Some(color_texture);
+                      self
+                  }
+                  pub fn color_sampler(mut self, color_sampler: &'a wgpu::Sampler) -> Self {
+                      self.color_sampler = Some(color_sampler);
+                      self
+                  }
+                  pub fn tint(mut self, tint: wgpu::BufferBinding<'a>) -> Self {
+                      self.tint = Some(tint);
+                      self
+                  }
+                  pub fn build(
+                      self,
+                      device: &wgpu::Device,
+                      fallback: &'a impl super::_root::BindingFallback,
+                  ) -> BindGroup0 {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: match self.color_texture {
+                                              Some(resource) => {
+                                                  wgpu::BindingResource::TextureView(resource)
+                                              }
+                                              None => fallback.fallback(&LAYOUT_DESCRIPTOR0.entries[0]),
+                                          },
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 1,
+                                          resource: match self.color_sampler {
+                                              Some(resource) => wgpu::BindingResource::Sampler(resource),
+                                              None => fallback.fallback(&LAYOUT_DESCRIPTOR0.entries[1]),
+                                          },
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 2,
+                                          resource: match self.tint {
+                                              Some(resource) => wgpu::BindingResource::Buffer(resource),
+                                              None => fallback.fallback(&LAYOUT_DESCRIPTOR0.entries[2]),
+                                          },
+                                      },
+                                  ],
+                                  label: None,
+                              },
+                          );
+                      BindGroup0(bind_group)
+                  }
+              }
+              impl BindGroup0 {
+                  pub fn builder<'a>() -> BindGroup0Builder<'a> {
+                      BindGroup0Builder::default()
+                  }
+              }
+              #[derive(Debug, Copy, Clone)]
+              pub struct BindGroups<'a> {
+                  pub bind_group0: &'a BindGroup0,
+              }
+              impl<'a> BindGroups<'a> {
+                  pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
+                      self.bind_group0.set(pass);
+                  }
+              }
+          }
+          pub fn set_bind_groups<'a>(
+              pass: &mut wgpu::RenderPass<'a>,
+              bind_group0: &'a bind_groups::BindGroup0,
+          ) {
+              bind_group0.set(pass);
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_groups_module_dynamic_offsets() {
+    let source = indoc! {r#"
+            struct Transform { model: mat4x4<f32>, layer: u32 };
+
+            @group(0) @binding(0) var<uniform> transform: Transform;
+
+            @vertex
+            fn main() -> @builtin(position) vec4<f32> {
+              return vec4(0.0);
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::VERTEX,
+      &WgslBindgenOption {
+        dynamic_offset_bindings: ["transform".to_owned()].into_iter().collect(),
+        ..Default::default()
+      },
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod bind_groups {
+              #[derive(Debug)]
+              pub struct BindGroup0(wgpu::BindGroup);
+              #[allow(non_snake_case)]
+              #[derive(Debug)]
+              pub struct BindGroupLayout0<'a> {
+                  pub transform: wgpu::BufferBinding<'a>,
+              }
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+                  label: None,
+                  entries: &[
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 0,
+                          visibility: wgpu::ShaderStages::VERTEX,
+                          ty: wgpu::BindingType::Buffer {
+                              ty: wgpu::BufferBindingType::Uniform,
+                              has_dynamic_offset: true,
+                              min_binding_size: wgpu::BufferSize::new(80),
+                          },
+                          count: None,
+                      },
+                  ],
+              };
+              impl BindGroup0 {
+                  pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
+                  }
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+                      let bind_group = device
+                          .create_bind_group(
+                              &wgpu::BindGroupDescriptor {
+                                  layout: &bind_group_layout,
+                                  entries: &[
+                                      wgpu::BindGroupEntry {
+                                          binding: 0,
+                                          resource: wgpu::BindingResource::Buffer(bindings.transform),
                                       },
                                   ],
                                   label: None,
@@ -855,46 +2138,113 @@ mod tests {
                   pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
                       render_pass.set_bind_group(0, &self.0, &[]);
                   }
+                  /// The size of `transform` rounded up to the default `min_uniform_buffer_offset_alignment`.
+                  pub const TRANSFORM_ALIGNED_SIZE: wgpu::BufferAddress = 256;
+                  pub fn set_with_offsets<'a>(
+                      &'a self,
+                      render_pass: &mut wgpu::RenderPass<'a>,
+                      offsets: &[wgpu::DynamicOffset],
+                  ) {
+                      render_pass.set_bind_group(0, &self.0, offsets);
+                  }
+              }
+              #[derive(Debug, Copy, Clone)]
+              pub struct BindGroups<'a> {
+                  pub bind_group0: &'a BindGroup0,
+              }
+              impl<'a> BindGroups<'a> {
+                  pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
+                      self.bind_group0.set(pass);
+                  }
               }
+          }
+          pub fn set_bind_groups<'a>(
+              pass: &mut wgpu::RenderPass<'a>,
+              bind_group0: &'a bind_groups::BindGroup0,
+          ) {
+              bind_group0.set(pass);
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_groups_module_caches() {
+    let source = indoc! {r#"
+            struct Tint { color: vec4<f32> };
+
+            @group(0) @binding(0) var color_texture: texture_2d<f32>;
+            @group(0) @binding(1) var color_sampler: sampler;
+            @group(0) @binding(2) var<uniform> tint: Tint;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::FRAGMENT,
+      &WgslBindgenOption {
+        generate_bind_group_caches: true,
+        ..Default::default()
+      },
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod bind_groups {
               #[derive(Debug)]
-              pub struct BindGroup1(wgpu::BindGroup);
+              pub struct BindGroup0(wgpu::BindGroup);
               #[allow(non_snake_case)]
               #[derive(Debug)]
-              pub struct BindGroupLayout1<'a> {
-                  pub transforms: wgpu::BufferBinding<'a>,
-                  pub one: wgpu::BufferBinding<'a>,
+              pub struct BindGroupLayout0<'a> {
+                  pub color_texture: &'a wgpu::TextureView,
+                  pub color_sampler: &'a wgpu::Sampler,
+                  pub tint: wgpu::BufferBinding<'a>,
               }
-              const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
                           binding: 0,
-                          visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                          ty: wgpu::BindingType::Buffer {
-                              ty: wgpu::BufferBindingType::Uniform,
-                              has_dynamic_offset: false,
-                              min_binding_size: None,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Float {
+                                  filterable: true,
+                              },
+                              view_dimension: wgpu::TextureViewDimension::D2,
+                              multisampled: false,
                           },
                           count: None,
                       },
                       wgpu::BindGroupLayoutEntry {
                           binding: 1,
-                          visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 2,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
                           ty: wgpu::BindingType::Buffer {
                               ty: wgpu::BufferBindingType::Uniform,
                               has_dynamic_offset: false,
-                              min_binding_size: None,
+                              min_binding_size: wgpu::BufferSize::new(16),
                           },
                           count: None,
                       },
                   ],
               };
-              impl BindGroup1 {
+              impl BindGroup0 {
                   pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
-                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR1)
+                      device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
                   }
-                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout1) -> Self {
-                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR1);
+                  pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                      let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
                       let bind_group = device
                           .create_bind_group(
                               &wgpu::BindGroupDescriptor {
@@ -902,11 +2252,19 @@ mod tests {
                                   entries: &[
                                       wgpu::BindGroupEntry {
                                           binding: 0,
-                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.color_texture,
+                                          ),
                                       },
                                       wgpu::BindGroupEntry {
                                           binding: 1,
-                                          resource: wgpu::BindingResource::Buffer(bindings.one),
+                                          resource: wgpu::BindingResource::Sampler(
+                                              bindings.color_sampler,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 2,
+                                          resource: wgpu::BindingResource::Buffer(bindings.tint),
                                       },
                                   ],
                                   label: None,
@@ -915,29 +2273,59 @@ mod tests {
                       Self(bind_group)
                   }
                   pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
-                      render_pass.set_bind_group(1, &self.0, &[]);
+                      render_pass.set_bind_group(0, &self.0, &[]);
+                  }
+              }
+              /// Reuses the [BindGroup0]s created for the same resources, which are kept alive until the cache is cleared.
+              #[derive(Debug, Default)]
+              pub struct BindGroup0Cache(
+                  std::collections::HashMap<
+                      (
+                          wgpu::Id<wgpu::TextureView>,
+                          wgpu::Id<wgpu::Sampler>,
+                          (wgpu::Id<wgpu::Buffer>, wgpu::BufferAddress, Option<wgpu::BufferSize>),
+                      ),
+                      BindGroup0,
+                  >,
+              );
+              impl BindGroup0Cache {
+                  pub fn get_or_create(
+                      &mut self,
+                      device: &wgpu::Device,
+                      bindings: BindGroupLayout0,
+                  ) -> &BindGroup0 {
+                      let key = (
+                          bindings.color_texture.global_id(),
+                          bindings.color_sampler.global_id(),
+                          (
+                              bindings.tint.buffer.global_id(),
+                              bindings.tint.offset,
+                              bindings.tint.size,
+                          ),
+                      );
+                      self.0
+                          .entry(key)
+                          .or_insert_with(|| BindGroup0::from_bindings(device, bindings))
+                  }
+                  pub fn clear(&mut self) {
+                      self.0.clear();
                   }
               }
               #[derive(Debug, Copy, Clone)]
               pub struct BindGroups<'a> {
                   pub bind_group0: &'a BindGroup0,
-                  pub bind_group1: &'a BindGroup1,
               }
               impl<'a> BindGroups<'a> {
                   pub fn set(&self, pass: &mut wgpu::RenderPass<'a>) {
                       self.bind_group0.set(pass);
-                      self.bind_group1.set(pass);
                   }
               }
           }
           pub fn set_bind_groups<'a>(
               pass: &mut wgpu::RenderPass<'a>,
               bind_group0: &'a bind_groups::BindGroup0,
-              bind_group1: &'a bind_groups::BindGroup1,
-
           ) {
               bind_group0.set(pass);
-              bind_group1.set(pass);
           }
       },
       actual
@@ -945,22 +2333,26 @@ mod tests {
   }
 
   #[test]
-  fn bind_groups_module_vertex() {
-    // The actual content of the structs doesn't matter.
-    // We only care about the groups and bindings.
+  fn bind_groups_module_binding_array() {
     let source = indoc! {r#"
-            struct Transforms {};
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+            @group(0) @binding(1) var samplers: binding_array<sampler, 2>;
 
-            @group(0) @binding(0) var<uniform> transforms: Transforms;
-
-            @vertex
-            fn vs_main() {}
+            @fragment
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
     let bind_group_data = get_bind_group_data(&module).unwrap();
 
-    let actual = bind_groups_module(&bind_group_data, wgpu::ShaderStages::VERTEX);
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::FRAGMENT,
+      &WgslBindgenOption {
+        generate_bind_group_caches: true,
+        ..Default::default()
+      },
+    );
 
     assert_tokens_eq!(
       quote! {
@@ -970,20 +2362,29 @@ mod tests {
               #[allow(non_snake_case)]
               #[derive(Debug)]
               pub struct BindGroupLayout0<'a> {
-                  pub transforms: wgpu::BufferBinding<'a>,
+                  pub textures: &'a [&'a wgpu::TextureView],
+                  pub samplers: &'a [&'a wgpu::Sampler],
               }
-              const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
                           binding: 0,
-                          visibility: wgpu::ShaderStages::VERTEX,
-                          ty: wgpu::BindingType::Buffer {
-                              ty: wgpu::BufferBindingType::Uniform,
-                              has_dynamic_offset: false,
-                              min_binding_size: None,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Float {
+                                  filterable: true,
+                              },
+                              view_dimension: wgpu::TextureViewDimension::D2,
+                              multisampled: false,
                           },
-                          count: None,
+                          count: std::num::NonZeroU32::new(4),
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 1,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                          count: std::num::NonZeroU32::new(2),
                       },
                   ],
               };
@@ -1000,7 +2401,15 @@ mod tests {
                                   entries: &[
                                       wgpu::BindGroupEntry {
                                           binding: 0,
-                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                          resource: wgpu::BindingResource::TextureViewArray(
+                                              bindings.textures,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 1,
+                                          resource: wgpu::BindingResource::SamplerArray(
+                                              bindings.samplers,
+                                          ),
                                       },
                                   ],
                                   label: None,
@@ -1012,6 +2421,32 @@ mod tests {
                       render_pass.set_bind_group(0, &self.0, &[]);
                   }
               }
+              /// Reuses the [BindGroup0]s created for the same resources, which are kept alive until the cache is cleared.
+              #[derive(Debug, Default)]
+              pub struct BindGroup0Cache(
+                  std::collections::HashMap<
+                      (Vec<wgpu::Id<wgpu::TextureView>>, Vec<wgpu::Id<wgpu::Sampler>>),
+                      BindGroup0,
+                  >,
+              );
+              impl BindGroup0Cache {
+                  pub fn get_or_create(
+                      &mut self,
+                      device: &wgpu::Device,
+                      bindings: BindGroupLayout0,
+                  ) -> &BindGroup0 {
+                      let key = (
+                          bindings.textures.iter().map(|resource| resource.global_id()).collect(),
+                          bindings.samplers.iter().map(|resource| resource.global_id()).collect(),
+                      );
+                      self.0
+                          .entry(key)
+                          .or_insert_with(|| BindGroup0::from_bindings(device, bindings))
+                  }
+                  pub fn clear(&mut self) {
+                      self.0.clear();
+                  }
+              }
               #[derive(Debug, Copy, Clone)]
               pub struct BindGroups<'a> {
                   pub bind_group0: &'a BindGroup0,
@@ -1034,22 +2469,43 @@ mod tests {
   }
 
   #[test]
-  fn bind_groups_module_fragment() {
-    // The actual content of the structs doesn't matter.
-    // We only care about the groups and bindings.
+  fn bind_group_data_unsized_binding_array() {
     let source = indoc! {r#"
-            struct Transforms {};
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>>;
 
-            @group(0) @binding(0) var<uniform> transforms: Transforms;
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    assert!(matches!(
+      get_bind_group_data(&module),
+      Err(CreateModuleError::UnsizedBindingArray { binding }) if binding == "textures"
+    ));
+  }
+
+  #[test]
+  fn bind_groups_module_texture_metadata() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var color_texture: texture_2d_array<u32>;
+            @group(0) @binding(1) var shadow_map: texture_depth_multisampled_2d;
+            @group(0) @binding(2) var color_msaa: texture_multisampled_2d<f32>;
 
             @fragment
-            fn fs_main() {}
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
     let bind_group_data = get_bind_group_data(&module).unwrap();
 
-    let actual = bind_groups_module(&bind_group_data, wgpu::ShaderStages::FRAGMENT);
+    let actual = bind_groups_module(
+      &bind_group_data,
+      wgpu::ShaderStages::FRAGMENT,
+      &WgslBindgenOption {
+        generate_texture_metadata: true,
+        ..Default::default()
+      },
+    );
 
     assert_tokens_eq!(
       quote! {
@@ -1059,18 +2515,42 @@ mod tests {
               #[allow(non_snake_case)]
               #[derive(Debug)]
               pub struct BindGroupLayout0<'a> {
-                  pub transforms: wgpu::BufferBinding<'a>,
+                  pub color_texture: &'a wgpu::TextureView,
+                  pub shadow_map: &'a wgpu::TextureView,
+                  pub color_msaa: &'a wgpu::TextureView,
               }
-              const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+              pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
                   label: None,
                   entries: &[
                       wgpu::BindGroupLayoutEntry {
                           binding: 0,
                           visibility: wgpu::ShaderStages::FRAGMENT,
-                          ty: wgpu::BindingType::Buffer {
-                              ty: wgpu::BufferBindingType::Uniform,
-                              has_dynamic_offset: false,
-                              min_binding_size: None,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Uint,
+                              view_dimension: wgpu::TextureViewDimension::D2Array,
+                              multisampled: false,
+                          },
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 1,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Depth,
+                              view_dimension: wgpu::TextureViewDimension::D2,
+                              multisampled: true,
+                          },
+                          count: None,
+                      },
+                      wgpu::BindGroupLayoutEntry {
+                          binding: 2,
+                          visibility: wgpu::ShaderStages::FRAGMENT,
+                          ty: wgpu::BindingType::Texture {
+                              sample_type: wgpu::TextureSampleType::Float {
+                                  filterable: false,
+                              },
+                              view_dimension: wgpu::TextureViewDimension::D2,
+                              multisampled: true,
                           },
                           count: None,
                       },
@@ -1089,7 +2569,21 @@ mod tests {
                                   entries: &[
                                       wgpu::BindGroupEntry {
                                           binding: 0,
-                                          resource: wgpu::BindingResource::Buffer(bindings.transforms),
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.color_texture,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 1,
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.shadow_map,
+                                          ),
+                                      },
+                                      wgpu::BindGroupEntry {
+                                          binding: 2,
+                                          resource: wgpu::BindingResource::TextureView(
+                                              bindings.color_msaa,
+                                          ),
                                       },
                                   ],
                                   label: None,
@@ -1100,6 +2594,150 @@ mod tests {
                   pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
                       render_pass.set_bind_group(0, &self.0, &[]);
                   }
+                  /// The sample type of the `color_texture` texture.
+                  pub const COLOR_TEXTURE_SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Uint;
+                  /// The view dimension of the `color_texture` texture.
+                  pub const COLOR_TEXTURE_VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2Array;
+                  /// Whether the `color_texture` texture is multisampled.
+                  pub const COLOR_TEXTURE_MULTISAMPLED: bool = false;
+                  /// Creates a view of `texture` for the `color_texture` binding.
+                  ///
+                  /// Panics in debug builds if `texture` doesn't match the binding.
+                  pub fn create_color_texture_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+                      debug_assert_eq!(
+                          texture.dimension(), Self::COLOR_TEXTURE_VIEW_DIMENSION
+                          .compatible_texture_dimension(),
+                          "`color_texture` expects a texture compatible with a {:?} view",
+                          Self::COLOR_TEXTURE_VIEW_DIMENSION,
+                      );
+                      debug_assert_eq!(
+                          texture.sample_count() > 1, Self::COLOR_TEXTURE_MULTISAMPLED,
+                          "`color_texture` expects `multisampled: {}`",
+                          Self::COLOR_TEXTURE_MULTISAMPLED,
+                      );
+                      let sample_type = texture
+                          .format()
+                          .sample_type(Some(wgpu::TextureAspect::All), None);
+                      let is_compatible_sample_type = match (
+                          sample_type,
+                          Self::COLOR_TEXTURE_SAMPLE_TYPE,
+                      ) {
+                          (
+                              Some(wgpu::TextureSampleType::Float { .. }),
+                              wgpu::TextureSampleType::Float { filterable: false },
+                          ) => true,
+                          (sample_type, expected) => sample_type == Some(expected),
+                      };
+                      debug_assert!(
+                          is_compatible_sample_type,
+                          "`color_texture` expects a texture with a {:?} sample type",
+                          Self::COLOR_TEXTURE_SAMPLE_TYPE,
+                      );
+                      texture
+                          .create_view(
+                              &wgpu::TextureViewDescriptor {
+                                  dimension: Some(Self::COLOR_TEXTURE_VIEW_DIMENSION),
+                                  aspect: wgpu::TextureAspect::All,
+                                  ..Default::default()
+                              },
+                          )
+                  }
+                  /// The sample type of the `shadow_map` texture.
+                  pub const SHADOW_MAP_SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Depth;
+                  /// The view dimension of the `shadow_map` texture.
+                  pub const SHADOW_MAP_VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2;
+                  /// Whether the `shadow_map` texture is multisampled.
+                  pub const SHADOW_MAP_MULTISAMPLED: bool = true;
+                  /// Creates a view of `texture` for the `shadow_map` binding.
+                  ///
+                  /// Panics in debug builds if `texture` doesn't match the binding.
+                  pub fn create_shadow_map_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+                      debug_assert_eq!(
+                          texture.dimension(), Self::SHADOW_MAP_VIEW_DIMENSION
+                          .compatible_texture_dimension(),
+                          "`shadow_map` expects a texture compatible with a {:?} view",
+                          Self::SHADOW_MAP_VIEW_DIMENSION,
+                      );
+                      debug_assert_eq!(
+                          texture.sample_count() > 1, Self::SHADOW_MAP_MULTISAMPLED,
+                          "`shadow_map` expects `multisampled: {}`", Self::SHADOW_MAP_MULTISAMPLED,
+                      );
+                      let sample_type = texture
+                          .format()
+                          .sample_type(Some(wgpu::TextureAspect::DepthOnly), None);
+                      let is_compatible_sample_type = match (
+                          sample_type,
+                          Self::SHADOW_MAP_SAMPLE_TYPE,
+                      ) {
+                          (
+                              Some(wgpu::TextureSampleType::Float { .. }),
+                              wgpu::TextureSampleType::Float { filterable: false },
+                          ) => true,
+                          (sample_type, expected) => sample_type == Some(expected),
+                      };
+                      debug_assert!(
+                          is_compatible_sample_type,
+                          "`shadow_map` expects a texture with a {:?} sample type",
+                          Self::SHADOW_MAP_SAMPLE_TYPE,
+                      );
+                      texture
+                          .create_view(
+                              &wgpu::TextureViewDescriptor {
+                                  dimension: Some(Self::SHADOW_MAP_VIEW_DIMENSION),
+                                  aspect: wgpu::TextureAspect::DepthOnly,
+                                  ..Default::default()
+                              },
+                          )
+                  }
+                  /// The sample type of the `color_msaa` texture.
+                  pub const COLOR_MSAA_SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Float {
+                      filterable: false,
+                  };
+                  /// The view dimension of the `color_msaa` texture.
+                  pub const COLOR_MSAA_VIEW_DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2;
+                  /// Whether the `color_msaa` texture is multisampled.
+                  pub const COLOR_MSAA_MULTISAMPLED: bool = true;
+                  /// Creates a view of `texture` for the `color_msaa` binding.
+                  ///
+                  /// Panics in debug builds if `texture` doesn't match the binding.
+                  pub fn create_color_msaa_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+                      debug_assert_eq!(
+                          texture.dimension(), Self::COLOR_MSAA_VIEW_DIMENSION
+                          .compatible_texture_dimension(),
+                          "`color_msaa` expects a texture compatible with a {:?} view",
+                          Self::COLOR_MSAA_VIEW_DIMENSION,
+                      );
+                      debug_assert_eq!(
+                          texture.sample_count() > 1, Self::COLOR_MSAA_MULTISAMPLED,
+                          "`color_msaa` expects `multisampled: {}`", Self::COLOR_MSAA_MULTISAMPLED,
+                      );
+                      let sample_type = texture
+                          .format()
+                          .sample_type(Some(wgpu::TextureAspect::All), None);
+                      let is_compatible_sample_type = match (
+                          sample_type,
+                          Self::COLOR_MSAA_SAMPLE_TYPE,
+                      ) {
+                          (
+                              Some(wgpu::TextureSampleType::Float { .. }),
+                              wgpu::TextureSampleType::Float { filterable: false },
+                          ) => true,
+                          (sample_type, expected) => sample_type == Some(expected),
+                      };
+                      debug_assert!(
+                          is_compatible_sample_type,
+                          "`color_msaa` expects a texture with a {:?} sample type",
+                          Self::COLOR_MSAA_SAMPLE_TYPE,
+                      );
+                      texture
+                          .create_view(
+                              &wgpu::TextureViewDescriptor {
+                                  dimension: Some(Self::COLOR_MSAA_VIEW_DIMENSION),
+                                  aspect: wgpu::TextureAspect::All,
+                                  ..Default::default()
+                              },
+                          )
+                  }
               }
               #[derive(Debug, Copy, Clone)]
               pub struct BindGroups<'a> {
@@ -1121,4 +2759,119 @@ mod tests {
       actual
     );
   }
+
+  #[test]
+  fn bind_group_layout_descriptor_sampler_types() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var depth_sampler: sampler_comparison;
+            @group(0) @binding(1) var color_sampler: sampler;
+            @group(0) @binding(2) var height_map: texture_2d<f32>;
+            @group(0) @binding(3) var height_sampler: sampler;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let actual = bind_group_layout_descriptor(
+      0,
+      &bind_group_data[&0],
+      &quote!(pub),
+      &WgslBindgenOption {
+        non_filtering_bindings: ["height_map", "height_sampler"]
+          .map(String::from)
+          .into_iter()
+          .collect(),
+        ..Default::default()
+      },
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
+              label: None,
+              entries: &[
+                  wgpu::BindGroupLayoutEntry {
+                      binding: 0,
+                      visibility: wgpu::ShaderStages::FRAGMENT,
+                      ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                      count: None,
+                  },
+                  wgpu::BindGroupLayoutEntry {
+                      binding: 1,
+                      visibility: wgpu::ShaderStages::FRAGMENT,
+                      ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                      count: None,
+                  },
+                  wgpu::BindGroupLayoutEntry {
+                      binding: 2,
+                      visibility: wgpu::ShaderStages::FRAGMENT,
+                      ty: wgpu::BindingType::Texture {
+                          sample_type: wgpu::TextureSampleType::Float {
+                              filterable: false,
+                          },
+                          view_dimension: wgpu::TextureViewDimension::D2,
+                          multisampled: false,
+                      },
+                      count: None,
+                  },
+                  wgpu::BindGroupLayoutEntry {
+                      binding: 3,
+                      visibility: wgpu::ShaderStages::FRAGMENT,
+                      ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                      count: None,
+                  },
+              ],
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn bind_group_layout_descriptor_binding_stages() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> transform: mat4x4<f32>;
+            @group(0) @binding(1) var color_texture: texture_2d<f32>;
+            @group(0) @binding(2) var color_sampler: sampler;
+
+            @vertex
+            fn vs_main(@location(0) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+              return transform * position;
+            }
+
+            @fragment
+            fn fs_main() -> @location(0) vec4<f32> {
+              return textureSample(color_texture, color_sampler, vec2(0.0));
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let bind_group_data = get_bind_group_data(&module).unwrap();
+
+    let visibilities = |options: &WgslBindgenOption| {
+      bind_group_data[&0]
+        .bindings
+        .iter()
+        .map(|binding| binding.visibility(options))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      vec![
+        wgpu::ShaderStages::VERTEX,
+        wgpu::ShaderStages::FRAGMENT,
+        wgpu::ShaderStages::FRAGMENT
+      ],
+      visibilities(&WgslBindgenOption::default())
+    );
+    assert_eq!(
+      vec![wgpu::ShaderStages::all(); 3],
+      visibilities(&WgslBindgenOption {
+        all_stages_visibility: true,
+        ..Default::default()
+      })
+    );
+  }
 }
@@ -0,0 +1,650 @@
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use serde::Deserialize;
+
+use crate::*;
+
+/// The contents of a `wgsl_bindgen.toml` file. The keys are the names of the
+/// [WgslBindgenOption] fields, and the options left out keep their defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WgslBindgenConfig {
+  entry_points: Vec<String>,
+  entry_point_globs: Vec<String>,
+  entry_point_sources: FxIndexMap<String, String>,
+  module_import_root: Option<String>,
+  emit_rerun_if_change: Option<bool>,
+  skip_header_comments: Option<bool>,
+  header_comment: Option<String>,
+  skip_header_provenance: Option<bool>,
+  skip_hash_check: Option<bool>,
+  incremental_cache_dir: Option<PathBuf>,
+  continue_on_error: Option<bool>,
+  serialization_strategy: Option<WgslTypeSerializeStrategy>,
+  derive_serde: Option<bool>,
+  derive_bytemuck: Option<bool>,
+  serde_rename_all: Option<String>,
+  serde_skip_padding: Option<bool>,
+  bool_member_strategy: Option<WgslBoolMemberStrategy>,
+  init_struct_strategy: Option<WgslInitStructStrategy>,
+  hide_padding: Option<bool>,
+  const_fn_new: Option<bool>,
+  padded_matrix_types: Option<bool>,
+  generate_runtime_array_helpers: Option<bool>,
+  no_std: Option<bool>,
+  generate_layout_tests: Option<bool>,
+  generate_encase_layout_tests: Option<bool>,
+  skip_unused_structs: Option<bool>,
+  warn_unused_bindings: Option<bool>,
+  generate_byte_helpers: Option<bool>,
+  generate_encase_helpers: Option<bool>,
+  generate_typed_buffers: Option<bool>,
+  generate_readback_helpers: Option<bool>,
+  generate_aligned_sizes: Option<bool>,
+  generate_field_offsets: Option<bool>,
+  pad_uniform_structs: Option<bool>,
+  unify_duplicate_structs: Option<bool>,
+  share_bind_groups: Option<bool>,
+  all_stages_visibility: Option<bool>,
+  generate_bind_group_builders: Option<bool>,
+  generate_bind_group_caches: Option<bool>,
+  generate_texture_metadata: Option<bool>,
+  generate_relaxed_shader_module: Option<bool>,
+  generate_hot_reload_shader_module: Option<bool>,
+  generate_bevy_glue: Option<bool>,
+  generate_spirv_shader_module: Option<bool>,
+  backend_outputs: Vec<WgslShaderBackend>,
+  generate_reflection: Option<bool>,
+  generate_markdown_docs: Option<bool>,
+  generate_entry_point_modules: Option<bool>,
+  generate_fragment_targets: Option<bool>,
+  generate_shader_entry: Option<bool>,
+  generate_struct_builders: Option<bool>,
+  impl_default: Option<bool>,
+  additional_struct_derives: Vec<String>,
+  additional_struct_derives_by_name: FxIndexMap<String, Vec<String>>,
+  name_casing: Option<WgslNameCasing>,
+  dynamic_offset_bindings: Vec<String>,
+  non_filtering_bindings: Vec<String>,
+  instance_step_mode_structs: Vec<String>,
+  instance_step_mode_pattern: Option<String>,
+  non_interleaved_vertex_buffers: Option<bool>,
+  /// The vertex formats keyed by the struct name, then the member name.
+  vertex_format_overrides: FxIndexMap<String, FxIndexMap<String, ConfigVertexFormat>>,
+  /// The names of the capability flags, like `FLOAT64`.
+  naga_capabilities: Option<Vec<String>>,
+  limits: Option<ConfigLimits>,
+  group_remaps: Vec<ConfigRemap<u32>>,
+  binding_remaps: Vec<ConfigRemap<(u32, u32)>>,
+  struct_renames: FxIndexMap<String, String>,
+  /// The member renames keyed by the struct name, then the member name.
+  struct_member_renames: FxIndexMap<String, FxIndexMap<String, String>>,
+  struct_conversions: FxIndexMap<String, Vec<ConfigStructConversion>>,
+  item_visibility: Option<WgslItemVisibility>,
+  entry_modules_root_dir: Option<PathBuf>,
+  root_module: Option<String>,
+  shared_module: Option<String>,
+  split_entry_modules: Option<bool>,
+  shader_source_output_type: Option<WgslShaderSourceOutputType>,
+  wgpu_crate_path: Option<String>,
+  wgpu_version: Option<WgpuVersion>,
+  crate_paths: FxIndexMap<String, String>,
+  shader_defs: FxIndexMap<String, ConfigShaderDefValue>,
+  shader_def_permutations: FxIndexMap<String, FxIndexMap<String, ConfigShaderDefValue>>,
+  wgsl_type_map: Option<ConfigWgslTypeMap>,
+  wgsl_type_map_overrides: FxIndexMap<WgslType, String>,
+  wgsl_type_map_aligned_overrides: FxIndexMap<WgslType, String>,
+  type_overrides: FxIndexMap<String, String>,
+  output_file: Option<PathBuf>,
+  output_dir: Option<PathBuf>,
+  additional_scan_dirs: Vec<ConfigScanDirectory>,
+  virtual_modules: FxIndexMap<String, String>,
+}
+
+/// A shader def value, written as a TOML boolean or integer.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum ConfigShaderDefValue {
+  Bool(bool),
+  Int(i32),
+  UInt(u32),
+}
+
+impl From<ConfigShaderDefValue> for ShaderDefValue {
+  fn from(value: ConfigShaderDefValue) -> Self {
+    match value {
+      ConfigShaderDefValue::Bool(value) => ShaderDefValue::Bool(value),
+      ConfigShaderDefValue::Int(value) => ShaderDefValue::Int(value),
+      ConfigShaderDefValue::UInt(value) => ShaderDefValue::UInt(value),
+    }
+  }
+}
+
+/// The type maps shipped with wgsl_bindgen, by name.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ConfigWgslTypeMap {
+  Rust,
+  Glam,
+  GlamPacked,
+  Cgmath,
+  Mint,
+  Ultraviolet,
+  Vek,
+  Nalgebra,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigScanDirectory {
+  directory: String,
+  module_import_root: Option<String>,
+}
+
+/// Defines `ConfigVertexFormat` with the `wgpu::VertexFormat` variants, written in
+/// lowercase like `unorm8x4`.
+macro_rules! config_vertex_formats {
+  ($($format:ident),* $(,)?) => {
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum ConfigVertexFormat {
+      $($format,)*
+    }
+
+    impl From<ConfigVertexFormat> for wgpu::VertexFormat {
+      fn from(value: ConfigVertexFormat) -> Self {
+        match value {
+          $(ConfigVertexFormat::$format => wgpu::VertexFormat::$format,)*
+        }
+      }
+    }
+  };
+}
+
+config_vertex_formats!(
+  Uint8x2, Uint8x4, Sint8x2, Sint8x4, Unorm8x2, Unorm8x4, Snorm8x2, Snorm8x4, Uint16x2,
+  Uint16x4, Sint16x2, Sint16x4, Unorm16x2, Unorm16x4, Snorm16x2, Snorm16x4, Float16x2,
+  Float16x4, Float32, Float32x2, Float32x3, Float32x4, Uint32, Uint32x2, Uint32x3,
+  Uint32x4, Sint32, Sint32x2, Sint32x3, Sint32x4, Float64, Float64x2, Float64x3,
+  Float64x4,
+);
+
+/// The device limits checked by the generation, starting from the `base` limits.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigLimits {
+  base: ConfigLimitsBase,
+  max_bind_groups: Option<u32>,
+  max_bindings_per_bind_group: Option<u32>,
+  max_push_constant_size: Option<u32>,
+  max_vertex_attributes: Option<u32>,
+}
+
+/// The `wgpu::Limits` constructors, by name.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ConfigLimitsBase {
+  #[default]
+  Default,
+  DownlevelDefaults,
+  DownlevelWebgl2Defaults,
+}
+
+impl From<ConfigLimits> for wgpu::Limits {
+  fn from(config: ConfigLimits) -> Self {
+    let mut limits = match config.base {
+      ConfigLimitsBase::Default => wgpu::Limits::default(),
+      ConfigLimitsBase::DownlevelDefaults => wgpu::Limits::downlevel_defaults(),
+      ConfigLimitsBase::DownlevelWebgl2Defaults => {
+        wgpu::Limits::downlevel_webgl2_defaults()
+      }
+    };
+    if let Some(value) = config.max_bind_groups {
+      limits.max_bind_groups = value;
+    }
+    if let Some(value) = config.max_bindings_per_bind_group {
+      limits.max_bindings_per_bind_group = value;
+    }
+    if let Some(value) = config.max_push_constant_size {
+      limits.max_push_constant_size = value;
+    }
+    if let Some(value) = config.max_vertex_attributes {
+      limits.max_vertex_attributes = value;
+    }
+    limits
+  }
+}
+
+/// A bind group, or a `[group, binding]` pair, moved to other indices.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigRemap<T> {
+  from: T,
+  to: T,
+}
+
+/// A user type converted from and into a generated struct, with its fields keyed by the
+/// member names.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigStructConversion {
+  rust_type: String,
+  #[serde(default)]
+  fields: FxIndexMap<String, String>,
+}
+
+/// Sets the builder options given in the config, which are `Option`s.
+macro_rules! set_options {
+  ($builder:ident, $config:ident, $($option:ident),* $(,)?) => {
+    $(
+      if let Some(value) = $config.$option {
+        $builder.$option(value);
+      }
+    )*
+  };
+}
+
+fn parse_tokens(option: &str, tokens: &str) -> Result<TokenStream, WgslBindgenError> {
+  tokens
+    .parse()
+    .map_err(|_| WgslBindgenError::ConfigTokensError {
+      option: option.to_string(),
+      tokens: tokens.to_string(),
+    })
+}
+
+impl WgslBindgenConfig {
+  fn apply(self, builder: &mut WgslBindgenOptionBuilder) -> Result<(), WgslBindgenError> {
+    let config = self;
+
+    // The type map is built for the serialization strategy, which is set first.
+    if let Some(wgsl_type_map) = config.wgsl_type_map {
+      builder.serialization_strategy(config.serialization_strategy.unwrap_or_default());
+      match wgsl_type_map {
+        ConfigWgslTypeMap::Rust => builder.wgsl_type_map(WgslRustTypeMap),
+        ConfigWgslTypeMap::Glam => builder.wgsl_type_map(GlamWgslTypeMap),
+        ConfigWgslTypeMap::GlamPacked => builder.wgsl_type_map(GlamPackedWgslTypeMap),
+        ConfigWgslTypeMap::Cgmath => builder.wgsl_type_map(CgmathWgslTypeMap),
+        ConfigWgslTypeMap::Mint => builder.wgsl_type_map(MintWgslTypeMap),
+        ConfigWgslTypeMap::Ultraviolet => builder.wgsl_type_map(UltravioletWgslTypeMap),
+        ConfigWgslTypeMap::Vek => builder.wgsl_type_map(VekWgslTypeMap),
+        ConfigWgslTypeMap::Nalgebra => builder.wgsl_type_map(NalgebraWgslTypeMap),
+      };
+    }
+
+    set_options!(
+      builder,
+      config,
+      module_import_root,
+      emit_rerun_if_change,
+      skip_header_comments,
+      header_comment,
+      skip_header_provenance,
+      skip_hash_check,
+      incremental_cache_dir,
+      continue_on_error,
+      serialization_strategy,
+      derive_serde,
+      derive_bytemuck,
+      serde_rename_all,
+      serde_skip_padding,
+      bool_member_strategy,
+      init_struct_strategy,
+      hide_padding,
+      const_fn_new,
+      padded_matrix_types,
+      generate_runtime_array_helpers,
+      no_std,
+      generate_layout_tests,
+      generate_encase_layout_tests,
+      skip_unused_structs,
+      warn_unused_bindings,
+      generate_byte_helpers,
+      generate_encase_helpers,
+      generate_typed_buffers,
+      generate_readback_helpers,
+      generate_aligned_sizes,
+      generate_field_offsets,
+      pad_uniform_structs,
+      unify_duplicate_structs,
+      share_bind_groups,
+      all_stages_visibility,
+      generate_bind_group_builders,
+      generate_bind_group_caches,
+      generate_texture_metadata,
+      generate_relaxed_shader_module,
+      generate_hot_reload_shader_module,
+      generate_bevy_glue,
+      generate_spirv_shader_module,
+      generate_reflection,
+      generate_markdown_docs,
+      generate_entry_point_modules,
+      generate_fragment_targets,
+      generate_shader_entry,
+      generate_struct_builders,
+      impl_default,
+      name_casing,
+      instance_step_mode_pattern,
+      non_interleaved_vertex_buffers,
+      item_visibility,
+      entry_modules_root_dir,
+      root_module,
+      shared_module,
+      split_entry_modules,
+      shader_source_output_type,
+      wgpu_crate_path,
+      wgpu_version,
+      output_file,
+      output_dir,
+    );
+
+    for entry_point in config.entry_points {
+      builder.add_entry_point(entry_point);
+    }
+    for glob in config.entry_point_globs {
+      builder.add_entry_points_glob(glob);
+    }
+    for (module_path, source) in config.entry_point_sources {
+      builder.add_source_str(module_path, source);
+    }
+    for (module_path, source) in config.virtual_modules {
+      builder.add_virtual_module(module_path, source);
+    }
+    for scan_dir in config.additional_scan_dirs {
+      builder.additional_scan_dir(AdditionalScanDirectory {
+        module_import_root: scan_dir.module_import_root,
+        directory: scan_dir.directory,
+      });
+    }
+
+    for (name, value) in config.shader_defs {
+      builder.add_shader_def(name, value.into());
+    }
+    for (name, shader_defs) in config.shader_def_permutations {
+      let shader_defs = shader_defs
+        .into_iter()
+        .map(|(name, value)| (name, ShaderDefValue::from(value)));
+      builder.add_shader_def_permutation(name, shader_defs);
+    }
+
+    for derive in config.additional_struct_derives {
+      builder
+        .additional_struct_derive(parse_tokens("additional_struct_derives", &derive)?);
+    }
+    for (wgsl_struct_name, derives) in config.additional_struct_derives_by_name {
+      for derive in derives {
+        let derive = parse_tokens("additional_struct_derives_by_name", &derive)?;
+        builder.additional_struct_derive_for(wgsl_struct_name.clone(), derive);
+      }
+    }
+
+    for binding_name in config.dynamic_offset_bindings {
+      builder.add_dynamic_offset_binding(binding_name);
+    }
+    for binding_name in config.non_filtering_bindings {
+      builder.add_non_filtering_binding(binding_name);
+    }
+    for wgsl_struct_name in config.instance_step_mode_structs {
+      builder.add_instance_step_mode_struct(wgsl_struct_name);
+    }
+    for (wgsl_struct_name, formats) in config.vertex_format_overrides {
+      for (wgsl_member_name, format) in formats {
+        builder.override_vertex_format(
+          wgsl_struct_name.clone(),
+          wgsl_member_name,
+          format.into(),
+        );
+      }
+    }
+    if let Some(names) = config.naga_capabilities {
+      let mut capabilities = naga::valid::Capabilities::empty();
+      for name in names {
+        capabilities |= naga::valid::Capabilities::from_name(&name).ok_or_else(|| {
+          WgslBindgenError::ConfigValueError {
+            option: "naga_capabilities".into(),
+            value: name,
+          }
+        })?;
+      }
+      builder.naga_capabilities(capabilities);
+    }
+    if let Some(limits) = config.limits {
+      builder.limits(wgpu::Limits::from(limits));
+    }
+
+    for remap in config.group_remaps {
+      builder.remap_group(remap.from, remap.to);
+    }
+    for remap in config.binding_remaps {
+      builder.remap_binding(remap.from, remap.to);
+    }
+    for backend in config.backend_outputs {
+      builder.add_backend_output(backend);
+    }
+
+    for (crate_name, path) in config.crate_paths {
+      builder.crate_path(crate_name, path);
+    }
+
+    for (wgsl_struct_name, rust_name) in config.struct_renames {
+      builder.rename_struct(wgsl_struct_name, rust_name);
+    }
+    for (wgsl_struct_name, member_renames) in config.struct_member_renames {
+      for (wgsl_member_name, rust_name) in member_renames {
+        builder.rename_struct_member(
+          wgsl_struct_name.clone(),
+          wgsl_member_name,
+          rust_name,
+        );
+      }
+    }
+    for (wgsl_struct_name, conversions) in config.struct_conversions {
+      for conversion in conversions {
+        let rust_type = parse_tokens("struct_conversions", &conversion.rust_type)?;
+        builder.add_struct_conversion(
+          wgsl_struct_name.clone(),
+          rust_type,
+          conversion.fields,
+        );
+      }
+    }
+
+    for (ty, rust_type) in config.wgsl_type_map_overrides {
+      builder
+        .override_wgsl_type(ty, parse_tokens("wgsl_type_map_overrides", &rust_type)?);
+    }
+    for (ty, rust_type) in config.wgsl_type_map_aligned_overrides {
+      let tokens = parse_tokens("wgsl_type_map_aligned_overrides", &rust_type)?;
+      builder.override_wgsl_type_aligned(ty, tokens);
+    }
+    for (wgsl_struct_name, rust_type) in config.type_overrides {
+      builder
+        .type_override(wgsl_struct_name, parse_tokens("type_overrides", &rust_type)?);
+    }
+
+    Ok(())
+  }
+}
+
+impl WgslBindgenOption {
+  /// Creates a builder with the options of a TOML config file like `wgsl_bindgen.toml`,
+  /// which can be further customized before building.
+  ///
+  /// The keys are the names of the option fields, like `entry_points` or `derive_serde`, with
+  /// the enum variants in `snake_case`, the Rust types as strings and the type map by name:
+  ///
+  /// ```toml
+  /// entry_points = ["src/shader/triangle.wgsl"]
+  /// serialization_strategy = "bytemuck"
+  /// wgsl_type_map = "glam"
+  /// output_file = "src/shader.rs"
+  ///
+  /// [type_overrides]
+  /// "lighting::PointLight" = "crate::PointLight"
+  /// ```
+  ///
+  /// Relative paths are relative to the working directory, like with the builder.
+  pub fn from_config_file(
+    path: impl AsRef<Path>,
+  ) -> Result<WgslBindgenOptionBuilder, WgslBindgenError> {
+    let path = path.as_ref();
+    let config = std::fs::read_to_string(path).map_err(|inner| {
+      WgslBindgenError::ConfigReadError {
+        path: path.to_path_buf(),
+        inner,
+      }
+    })?;
+    Self::from_config_str(&config)
+  }
+
+  /// Creates a builder with the options of a TOML config, see `from_config_file`.
+  pub fn from_config_str(
+    config: &str,
+  ) -> Result<WgslBindgenOptionBuilder, WgslBindgenError> {
+    let config: WgslBindgenConfig = toml::from_str(config)?;
+    let mut builder = WgslBindgenOptionBuilder::default();
+    config.apply(&mut builder)?;
+    Ok(builder)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_reject_unknown_options() {
+    let result = WgslBindgenOption::from_config_str("derive_serdes = true");
+
+    assert!(matches!(result, Err(WgslBindgenError::ConfigParseError(_))));
+  }
+
+  #[test]
+  fn should_reject_invalid_rust_types() {
+    let result = WgslBindgenOption::from_config_str(
+      "[type_overrides]\n\"lighting::PointLight\" = \"crate::PointLight(\"",
+    );
+
+    assert!(matches!(
+      result,
+      Err(WgslBindgenError::ConfigTokensError { option, .. }) if option == "type_overrides"
+    ));
+  }
+
+  #[test]
+  fn should_read_struct_conversions() {
+    let bindgen = WgslBindgenOption::from_config_str(
+      r#"
+        wgsl_type_map = "glam"
+
+        [[struct_conversions.Camera]]
+        rust_type = "crate::Camera"
+        fields = { view_proj = "view_projection" }
+      "#,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let options = bindgen.options;
+
+    let conversion = &options.struct_conversions["Camera"][0];
+    assert_eq!(conversion.rust_type.to_string(), "crate :: Camera");
+    assert_eq!(conversion.fields["view_proj"], "view_projection");
+  }
+
+  #[test]
+  fn should_read_crate_paths() {
+    let bindgen = WgslBindgenOption::from_config_str(
+      r#"
+        wgsl_type_map = "glam"
+        wgpu_crate_path = "::bevy::render::render_resource"
+        wgpu_version = "22"
+
+        [crate_paths]
+        glam = "::bevy::math"
+      "#,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let options = bindgen.options;
+
+    assert_eq!(
+      options.wgpu_crate_path.as_deref(),
+      Some("::bevy::render::render_resource")
+    );
+    assert_eq!(options.wgpu_version, WgpuVersion::V22);
+    assert_eq!(options.crate_paths["glam"], "::bevy::math");
+  }
+
+  #[test]
+  fn should_read_remaps() {
+    let bindgen = WgslBindgenOption::from_config_str(
+      r#"
+        wgsl_type_map = "glam"
+
+        [[group_remaps]]
+        from = 0
+        to = 1
+
+        [[binding_remaps]]
+        from = [1, 1]
+        to = [2, 4]
+      "#,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let options = bindgen.options;
+
+    assert_eq!(options.group_remaps[&0], 1);
+    assert_eq!(options.binding_remaps[&(1, 1)], (2, 4));
+  }
+
+  #[test]
+  fn should_read_validation_options() {
+    let bindgen = WgslBindgenOption::from_config_str(
+      r#"
+        wgsl_type_map = "glam"
+        naga_capabilities = ["FLOAT64", "PUSH_CONSTANT"]
+
+        [limits]
+        base = "downlevel_webgl2_defaults"
+        max_bind_groups = 2
+
+        [vertex_format_overrides.VertexInput]
+        color = "unorm8x4"
+      "#,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let options = bindgen.options;
+
+    assert_eq!(
+      options.naga_capabilities,
+      naga::valid::Capabilities::FLOAT64 | naga::valid::Capabilities::PUSH_CONSTANT
+    );
+    let limits = options.limits.unwrap();
+    assert_eq!(limits.max_bind_groups, 2);
+    assert_eq!(
+      limits.max_vertex_attributes,
+      wgpu::Limits::downlevel_webgl2_defaults().max_vertex_attributes
+    );
+    assert_eq!(
+      options.vertex_format_overrides[&("VertexInput".to_string(), "color".to_string())],
+      wgpu::VertexFormat::Unorm8x4
+    );
+  }
+
+  #[test]
+  fn should_reject_unknown_capabilities() {
+    let result = WgslBindgenOption::from_config_str("naga_capabilities = [\"FLOAT128\"]");
+
+    assert!(matches!(
+      result,
+      Err(WgslBindgenError::ConfigValueError { option, .. }) if option == "naga_capabilities"
+    ));
+  }
+}
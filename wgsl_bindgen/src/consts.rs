@@ -1,10 +1,16 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::Ident;
+use syn::{Ident, Index};
 
-use crate::{bevy_util::demangle_splitting_mod_path_and_item, quote_gen::RustSourceItem};
+use crate::{
+  bevy_util::demangle_splitting_mod_path_and_item, quote_gen::RustSourceItem,
+  WgslBindgenOption,
+};
 
-pub fn consts_items(module: &naga::Module) -> Vec<RustSourceItem> {
+pub fn consts_items(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> Vec<RustSourceItem> {
   // Create matching Rust constants for WGSl constants.
   module
     .constants
@@ -13,36 +19,66 @@ pub fn consts_items(module: &naga::Module) -> Vec<RustSourceItem> {
       let name_str = t.name.as_ref()?;
 
       // we don't need full qualification here
-      let (_, demangled_name) = demangle_splitting_mod_path_and_item(name_str);
+      let (mod_path, demangled_name) = demangle_splitting_mod_path_and_item(name_str);
+      let visibility = options.items_visibility_tokens(mod_path.as_deref());
 
       let name = Ident::new(&demangled_name, Span::call_site());
 
-      // TODO: Add support for f64 and f16 once naga supports them.
-      let type_and_value = match &module.const_expressions[t.init] {
-        naga::Expression::Literal(literal) => match literal {
-          naga::Literal::F64(v) => Some(quote!(f32 = #v)),
-          naga::Literal::F32(v) => Some(quote!(f32 = #v)),
-          naga::Literal::U32(v) => Some(quote!(u32 = #v)),
-          naga::Literal::I32(v) => Some(quote!(i32 = #v)),
-          naga::Literal::Bool(v) => Some(quote!(bool = #v)),
-          naga::Literal::I64(v) => Some(quote!(i64 = #v)),
-          naga::Literal::AbstractInt(v) => Some(quote!(i64 = #v)),
-          naga::Literal::AbstractFloat(v) => Some(quote!(f64 = #v)),
-        },
-        _ => None,
-      }?;
+      let (ty, value) = const_type_and_value(module, t.init)?;
+      let type_and_value = quote!(#ty = #value);
 
       Some(RustSourceItem::from_mangled(
         &name_str,
-        quote! { pub const #name: #type_and_value;},
+        quote! { #visibility const #name: #type_and_value;},
       ))
     })
     .collect()
 }
 
+/// The Rust type and value of a constant expression, with vectors and matrices as arrays.
+fn const_type_and_value(
+  module: &naga::Module,
+  expr: naga::Handle<naga::Expression>,
+) -> Option<(TokenStream, TokenStream)> {
+  match &module.const_expressions[expr] {
+    // TODO: Add support for f16 once naga supports it.
+    naga::Expression::Literal(literal) => match literal {
+      naga::Literal::F64(v) => Some((quote!(f64), quote!(#v))),
+      naga::Literal::F32(v) => Some((quote!(f32), quote!(#v))),
+      naga::Literal::U32(v) => Some((quote!(u32), quote!(#v))),
+      naga::Literal::I32(v) => Some((quote!(i32), quote!(#v))),
+      naga::Literal::Bool(v) => Some((quote!(bool), quote!(#v))),
+      naga::Literal::I64(v) => Some((quote!(i64), quote!(#v))),
+      naga::Literal::AbstractInt(v) => Some((quote!(i64), quote!(#v))),
+      naga::Literal::AbstractFloat(v) => Some((quote!(f64), quote!(#v))),
+    },
+    naga::Expression::Compose { ty, components } => {
+      // Structs aren't supported, as their Rust types may not have `const` constructors.
+      if let naga::TypeInner::Struct { .. } = module.types[*ty].inner {
+        return None;
+      }
+
+      let components = components
+        .iter()
+        .map(|c| const_type_and_value(module, *c))
+        .collect::<Option<Vec<_>>>()?;
+      let (element_type, _) = components.first()?;
+      let len = Index::from(components.len());
+      let values = components.iter().map(|(_, value)| value);
+      Some((quote!([#element_type; #len]), quote!([#(#values),*])))
+    }
+    naga::Expression::Splat { size, value } => {
+      let (element_type, value) = const_type_and_value(module, *value)?;
+      let len = Index::from(*size as usize);
+      Some((quote!([#element_type; #len]), quote!([#value; #len])))
+    }
+    _ => None,
+  }
+}
+
 #[allow(unused)]
-pub fn consts(module: &naga::Module) -> Vec<TokenStream> {
-  consts_items(module).into_iter().map(|i| i.item).collect()
+pub fn consts(module: &naga::Module, options: &WgslBindgenOption) -> Vec<TokenStream> {
+  consts_items(module, options).into_iter().map(|i| i.item).collect()
 }
 
 #[cfg(test)]
@@ -71,7 +107,7 @@ mod tests {
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
-    let consts = consts(&module);
+    let consts = consts(&module, &WgslBindgenOption::default());
     let actual = quote!(#(#consts)*);
     eprintln!("{actual}");
 
@@ -85,4 +121,36 @@ mod tests {
       actual
     );
   }
+
+  #[test]
+  fn write_composite_consts() {
+    let source = indoc! {r#"
+            const TILE_SIZE: u32 = 8u;
+            const GROUP_SIZE = TILE_SIZE * 4u;
+            const UP = vec3<f32>(0.0, 1.0, 0.0);
+            const ONES = vec2(1i);
+            const KERNEL = array<f32, 3>(0.25, 0.5, 0.25);
+            const BASIS = mat2x2<f32>(vec2(1.0, 0.0), vec2(0.0, 1.0));
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let consts = consts(&module, &WgslBindgenOption::default());
+    let actual = quote!(#(#consts)*);
+
+    assert_tokens_eq!(
+      quote! {
+          pub const TILE_SIZE: u32 = 8u32;
+          pub const GROUP_SIZE: u32 = 32u32;
+          pub const UP: [f32; 3] = [0f32, 1f32, 0f32];
+          pub const ONES: [i32; 2] = [1i32; 2];
+          pub const KERNEL: [f32; 3] = [0.25f32, 0.5f32, 0.25f32];
+          pub const BASIS: [[f32; 2]; 2] = [[1f32, 0f32], [0f32, 1f32]];
+      },
+      actual
+    );
+  }
 }
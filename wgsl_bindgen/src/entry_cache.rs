@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shared_items::SharedItemKind;
+use crate::WgslEntryItem;
+
+/// The items generated for an entry point module, with the hash of what they were
+/// generated from.
+#[derive(Serialize, Deserialize)]
+struct CachedEntry {
+  hash: String,
+  items: Vec<CachedItem>,
+  /// The `cargo:warning`s of the module, emitted again when its items are loaded.
+  #[serde(default)]
+  warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedItem {
+  mod_path: String,
+  name: Option<String>,
+  tokens: String,
+  #[serde(default)]
+  shareable: Option<SharedItemKind>,
+}
+
+/// A directory with a file per entry point module, like `shaders.triangle.toml` for
+/// `shaders::triangle`, keeping the items generated for it.
+pub(crate) struct EntryCache {
+  dir: PathBuf,
+}
+
+impl EntryCache {
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self { dir: dir.into() }
+  }
+
+  fn file_path(&self, mod_name: &str) -> PathBuf {
+    self
+      .dir
+      .join(format!("{}.toml", mod_name.replace("::", ".")))
+  }
+
+  /// The items cached for the module with its warnings, if they were generated from the
+  /// same hash.
+  pub fn load(
+    &self,
+    mod_name: &str,
+    hash: &str,
+  ) -> Option<(Vec<WgslEntryItem>, Vec<String>)> {
+    let content = std::fs::read_to_string(self.file_path(mod_name)).ok()?;
+    let cached: CachedEntry = toml::from_str(&content).ok()?;
+    if cached.hash != hash {
+      return None;
+    }
+
+    let items = cached
+      .items
+      .into_iter()
+      .map(|cached| {
+        Some(WgslEntryItem {
+          mod_path: cached.mod_path,
+          name: cached.name,
+          item: cached.tokens.parse().ok()?,
+          shareable: cached.shareable,
+        })
+      })
+      .collect::<Option<_>>()?;
+    Some((items, cached.warnings))
+  }
+
+  pub fn store(
+    &self,
+    mod_name: &str,
+    hash: &str,
+    items: &[WgslEntryItem],
+    warnings: &[String],
+  ) -> std::io::Result<()> {
+    let cached = CachedEntry {
+      hash: hash.to_string(),
+      items: items
+        .iter()
+        .map(|item| CachedItem {
+          mod_path: item.mod_path.clone(),
+          name: item.name.clone(),
+          tokens: item.item.to_string(),
+          shareable: item.shareable,
+        })
+        .collect(),
+      warnings: warnings.to_vec(),
+    };
+    let content = toml::to_string(&cached).expect("failed to serialize the entry cache");
+
+    std::fs::create_dir_all(&self.dir)?;
+    std::fs::write(self.file_path(mod_name), content)
+  }
+}
@@ -38,28 +38,53 @@
 #[allow(dead_code, unused)]
 extern crate wgpu_types as wgpu;
 
-use bevy_util::SourceWithFullDependenciesResult;
-use bindgroup::{bind_groups_module, get_bind_group_data};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use bevy_glue::bevy_module;
+use bevy_util::source_file::SourceFile;
+use bevy_util::{demangle, SourceWithFullDependenciesResult};
+use bindgroup::{
+  add_prelude_binding_fallback, add_prelude_reflection_types, bind_group_items,
+  bind_groups_module, bind_groups_struct, get_bind_group_data,
+  get_bind_group_data_with_gaps, reflection_module, set_bind_groups_function,
+  typed_buffers,
+};
 use case::CaseExt;
 use derive_more::IsVariant;
 use naga::ShaderStage;
-use naga_util::module_to_source;
+use naga_util::{module_to_source, module_to_spirv};
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use quote_gen::{
-  add_prelude_types_assertions, create_shader_raw_string_literal, RustModBuilder,
-  MOD_REFERENCE_ROOT,
+  add_prelude_bool_type, add_prelude_padded_matrix_types, add_prelude_serde_padding,
+  add_prelude_types_assertions, create_shader_raw_string_literal, replace_crate_path,
+  RustModBuilder, RustModBuilderError, RustSourceItem, MOD_REFERENCE_ROOT,
 };
+use readback::{add_prelude_read_buffer, readback_functions};
+use shader_entry::{shader_entry_impl, shader_entry_trait};
+use shared_items::{definitions_diff, share_duplicate_items, SharedItemKind};
+use struct_docs::WgslStructDocs;
 use syn::{Ident, Index};
 use thiserror::Error;
 
+mod bevy_glue;
 pub mod bevy_util;
 mod bindgroup;
+mod config;
 mod consts;
+mod entry_cache;
+mod markdown_docs;
 mod naga_util;
 mod quote_gen;
+mod readback;
+mod shader_entry;
+mod shared_items;
+mod struct_docs;
 mod structs;
 mod types;
+#[cfg(feature = "watch")]
+mod watch;
 mod wgsl;
 mod wgsl_bindgen;
 mod wgsl_type;
@@ -72,7 +97,8 @@ pub use wgsl_type::*;
 ///
 /// This enum is used to specify how WGSL types should be serialized when converted
 /// to Rust types.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, IsVariant)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, IsVariant, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WgslTypeSerializeStrategy {
   #[default]
   Encase,
@@ -91,72 +117,447 @@ pub enum CreateModuleError {
   /// Each binding resource must be associated with exactly one binding index.
   #[error("duplicate binding found with index `{binding}`")]
   DuplicateBinding { binding: u32 },
+
+  /// `bool` is not host-shareable, see [WgslBoolMemberStrategy].
+  #[error("member `{member}` of struct `{struct_name}` is a bool, which can't be shared with the host")]
+  BoolMember { struct_name: String, member: String },
+
+  /// Only the last member of a struct can be a runtime-sized array.
+  #[error("member `{member}` of struct `{struct_name}` is a runtime-sized array, but isn't the last member")]
+  RuntimeSizedArrayNotLast { struct_name: String, member: String },
+
+  /// The layout entries of binding arrays need a fixed number of elements.
+  #[error("binding array `{binding}` must have a fixed size")]
+  UnsizedBindingArray { binding: String },
+
+  /// The shader must be valid for the SPIR-V backend of naga, see
+  /// `generate_spirv_shader_module`.
+  #[error("failed to translate the shader to SPIR-V: {message}")]
+  SpirvTranslation { message: String },
+
+  /// Structs of the same module must have different Rust names, see `rename_struct`.
+  #[error("structs `{first}` and `{second}` are both named `{rust_name}`, rename one of them with `rename_struct`")]
+  StructNameCollision {
+    first: String,
+    second: String,
+    rust_name: String,
+  },
+
+  /// Fragment outputs must be scalars or vectors of floats or integers to have a color
+  /// target, see `generate_fragment_targets`.
+  #[error(
+    "fragment output `{output}` of entry point `{entry_point}` has no color target type"
+  )]
+  UnsupportedFragmentOutput { entry_point: String, output: String },
+
+  /// `group_remaps` and `binding_remaps` must leave each binding with its own indices.
+  #[error(
+    "`{first}` and `{second}` are both remapped to @group({group}) @binding({binding})"
+  )]
+  RemappedBindingCollision {
+    first: String,
+    second: String,
+    group: u32,
+    binding: u32,
+  },
+
+  /// Uniform buffers have stricter layout rules than storage buffers, like arrays with a
+  /// stride that is a multiple of 16 bytes.
+  #[error("{member} in a uniform buffer {reason}")]
+  UniformLayout { member: String, reason: String },
+
+  /// Imported modules must generate the same items for all the entry points importing
+  /// them, like structs with the same members regardless of the shader defs.
+  #[error("`{name}` in `{module}` is generated differently for `{first_entry}` and `{second_entry}`:\n{diff}")]
+  ConflictingDefinitions {
+    name: String,
+    module: String,
+    first_entry: String,
+    second_entry: String,
+    diff: String,
+  },
+
+  /// The shader needs more than the `limits` of the devices it runs on allow.
+  #[error("{usage} needs a `{limit}` of {required}, but the limit is {max}")]
+  LimitExceeded {
+    limit: &'static str,
+    max: u32,
+    required: u32,
+    usage: String,
+  },
 }
 
 pub(crate) struct WgslEntryResult<'a> {
   mod_name: String,
   naga_module: naga::Module,
   source_including_deps: SourceWithFullDependenciesResult<'a>,
+  /// The shader defs the module was composed with, including those of its permutation.
+  shader_defs: FxIndexMap<String, ShaderDefValue>,
+}
+
+/// An item generated for an entry point, in the module with the given path.
+pub(crate) struct WgslEntryItem {
+  mod_path: String,
+  /// The name of the item if other entry points can generate it too, like the structs of
+  /// imported modules, which are only added once.
+  name: Option<String>,
+  item: TokenStream,
+  /// The kind of the item if it can be shared with the identical items of other entry
+  /// points, like the structs defined by the entry point.
+  shareable: Option<SharedItemKind>,
+}
+
+impl WgslEntryItem {
+  fn new(mod_path: &str, item: TokenStream) -> Self {
+    Self {
+      mod_path: mod_path.to_string(),
+      name: None,
+      item,
+      shareable: None,
+    }
+  }
+
+  /// The item of a struct or const, which is in `default_mod_path` unless imported.
+  fn from_source_item(default_mod_path: &str, item: RustSourceItem) -> Self {
+    Self {
+      mod_path: item
+        .mod_path
+        .unwrap_or_else(|| default_mod_path.to_string()),
+      name: Some(item.name),
+      item: item.item,
+      shareable: None,
+    }
+  }
 }
 
 fn create_rust_bindings(
-  entries: Vec<WgslEntryResult<'_>>,
+  entries: Vec<(String, Vec<WgslEntryItem>)>,
   options: &WgslBindgenOption,
 ) -> Result<String, CreateModuleError> {
-  let mut mod_builder = RustModBuilder::new(true);
-  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_types_assertions(options));
+  let output = create_rust_mod_builder(entries, options)?.generate();
+  Ok(pretty_print(&with_crate_paths(output, options)))
+}
 
-  for entry in entries.iter() {
-    let WgslEntryResult {
-      mod_name,
-      naga_module,
+/// Creates the bindings as a `mod.rs` and a file per module, with the paths relative to
+/// the `mod.rs`.
+fn create_rust_binding_files(
+  entries: Vec<(String, Vec<WgslEntryItem>)>,
+  options: &WgslBindgenOption,
+) -> Result<Vec<(PathBuf, String)>, CreateModuleError> {
+  let files = create_rust_mod_builder(entries, options)?
+    .generate_files()
+    .into_iter()
+    .map(|(path, output)| (path, pretty_print(&with_crate_paths(output, options))))
+    .collect();
+  Ok(files)
+}
+
+/// The type items using `core` instead of `std` with `no_std`.
+fn with_core_paths(item: TokenStream, options: &WgslBindgenOption) -> TokenStream {
+  if options.no_std {
+    replace_crate_path(item, "std", &quote!(core))
+  } else {
+    item
+  }
+}
+
+/// The generated code using the `wgpu_crate_path` and the `crate_paths` instead of the
+/// crate names.
+fn with_crate_paths(mut output: TokenStream, options: &WgslBindgenOption) -> TokenStream {
+  let wgpu_crate_path = options.wgpu_crate_path.as_ref().map(|path| ("wgpu", path));
+  let crate_paths = options
+    .crate_paths
+    .iter()
+    .map(|(crate_name, path)| (crate_name.as_str(), path));
+  for (crate_name, path) in wgpu_crate_path.into_iter().chain(crate_paths) {
+    let path: syn::Path = syn::parse_str(path).unwrap();
+    output = replace_crate_path(output, crate_name, &quote!(#path));
+  }
+  output
+}
+
+fn create_rust_mod_builder(
+  entries: Vec<(String, Vec<WgslEntryItem>)>,
+  options: &WgslBindgenOption,
+) -> Result<RustModBuilder, CreateModuleError> {
+  let mut mod_builder = RustModBuilder::new(true)
+    .with_item_visibility(options.item_visibility)
+    .with_root_module(options.root_module.clone());
+  mod_builder.add(
+    MOD_REFERENCE_ROOT,
+    with_core_paths(add_prelude_types_assertions(options), options),
+  );
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_bool_type(options));
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_padded_matrix_types(options));
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_serde_padding(options));
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_binding_fallback(options));
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_reflection_types(options));
+  mod_builder.add(MOD_REFERENCE_ROOT, add_prelude_read_buffer(options));
+
+  if options.generate_typed_buffers {
+    mod_builder.add("buffers", typed_buffers(options));
+  }
+
+  if options.generate_shader_entry {
+    mod_builder.add("shader_entry", shader_entry_trait(options));
+  }
+
+  if let Some(shared_module) = options.shared_module.as_ref() {
+    // The imported modules are referenced from the root as if they weren't nested.
+    let shared_module: syn::Path = syn::parse_str(shared_module).unwrap();
+    mod_builder.add(MOD_REFERENCE_ROOT, quote!(pub use super::#shared_module::*;));
+  }
+
+  let entries = if options.unify_duplicate_structs || options.share_bind_groups {
+    share_duplicate_items(entries, options)
+  } else {
+    entries
+  };
+
+  // The entry point that first generated each item of imported modules.
+  let mut first_entries = HashMap::new();
+  for (entry, items) in entries {
+    for WgslEntryItem {
+      mod_path,
+      name,
+      item,
       ..
-    } = entry;
-    let bind_group_data = get_bind_group_data(naga_module)?;
-    let shader_stages = wgsl::shader_stages(naga_module);
-
-    // Write all the structs, including uniforms and entry function inputs.
-    mod_builder
-      .add_items(mod_name, structs::structs_items(naga_module, options))
-      .unwrap();
-
-    mod_builder
-      .add_items(mod_name, consts::consts_items(naga_module))
-      .unwrap();
-
-    mod_builder.add(mod_name, bind_groups_module(&bind_group_data, shader_stages));
-    mod_builder.add(mod_name, vertex_struct_methods(naga_module));
-
-    mod_builder.add(mod_name, compute_module(naga_module));
-    mod_builder.add(mod_name, entry_point_constants(naga_module));
-    mod_builder.add(mod_name, vertex_states(naga_module));
-
-    let bind_group_layouts: Vec<_> = bind_group_data
-      .keys()
-      .map(|group_no| {
-        let group = indexed_name_to_ident("BindGroup", *group_no);
-        quote!(bind_groups::#group::get_bind_group_layout(device))
-      })
-      .collect();
-
-    let create_pipeline_layout = quote! {
-        pub fn create_pipeline_layout(device: &wgpu::Device) -> wgpu::PipelineLayout {
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: None,
-                bind_group_layouts: &[
-                    #(&#bind_group_layouts),*
-                ],
-                push_constant_ranges: &[],
-            })
-        }
+    } in items
+    {
+      let Some(name) = name else {
+        mod_builder.add(&mod_path, item);
+        continue;
+      };
+
+      let first_entry: &String = first_entries
+        .entry((mod_path.clone(), name.clone()))
+        .or_insert_with(|| entry.clone());
+      mod_builder.add_unique(&mod_path, &name, item).map_err(
+        |RustModBuilderError::DuplicateContentError {
+           existing, received, ..
+         }| CreateModuleError::ConflictingDefinitions {
+          diff: definitions_diff(&name, &existing, &received),
+          name: name.clone(),
+          module: mod_path.clone(),
+          first_entry: first_entry.clone(),
+          second_entry: entry.clone(),
+        },
+      )?;
+    }
+  }
+
+  Ok(mod_builder)
+}
+
+/// Creates the items of the modules for an entry point.
+fn create_entry_items(
+  entry: &WgslEntryResult,
+  options: &WgslBindgenOption,
+) -> Result<Vec<WgslEntryItem>, CreateModuleError> {
+  let WgslEntryResult {
+    mod_name,
+    naga_module,
+    source_including_deps,
+    ..
+  } = entry;
+  // GLSL and SPIR-V sources can't be composed at runtime, so the translated WGSL is
+  // embedded. The same goes for the remapped bindings.
+  let output_type = options.shader_source_output_type;
+  let entry_path = &source_including_deps.source_file.file_path;
+  let translated_options;
+  let options = if entry_path.needs_translation() || options.remaps_bindings() {
+    translated_options = WgslBindgenOption {
+      shader_source_output_type: WgslShaderSourceOutputType::FinalShaderString,
+      generate_hot_reload_shader_module: false,
+      ..options.clone()
     };
+    &translated_options
+  } else {
+    options
+  };
+  let entry_options = &options.for_entry_module(mod_name);
+  if let Some(limits) = options.limits.as_ref() {
+    wgsl::validate_limits(naga_module, limits)?;
+  }
+  // The groups left without bindings by remapping are given by the caller.
+  let bind_group_data = if options.remaps_bindings() {
+    get_bind_group_data_with_gaps(naga_module)?
+  } else {
+    get_bind_group_data(naga_module)?
+  };
+  let shader_stages = wgsl::shader_stages(naga_module);
+  let mut items = Vec::new();
+
+  // The types and the pipeline items go in their own submodules when splitting.
+  let (types_mod, pipeline_mod) = if options.split_entry_modules {
+    let types_mod = format!("{mod_name}::types");
+    let pipeline_mod = format!("{mod_name}::pipeline");
+    // The pipeline imports the types, so the module has to exist without types.
+    items.push(WgslEntryItem::new(&types_mod, quote!()));
+    items.push(WgslEntryItem::new(
+      &pipeline_mod,
+      quote! {
+        #[allow(unused_imports)]
+        use super::{types::*, *};
+      },
+    ));
+    (types_mod, pipeline_mod)
+  } else {
+    (mod_name.clone(), mod_name.clone())
+  };
+  let options = &options.for_entry_module(&pipeline_mod);
+
+  // Write all the structs, including uniforms and entry function inputs.
+  let structs = structs::structs_items(
+    naga_module,
+    options,
+    &WgslStructDocs::from_entry(source_including_deps),
+  )?;
+  let consts = consts::consts_items(naga_module, options);
+  // The vertex inputs have vertex layouts depending on the shader.
+  let vertex_inputs: Vec<_> = wgsl::get_vertex_input_structs(naga_module)
+    .into_iter()
+    .map(|input| demangle(&input.name).to_string())
+    .collect();
+  let struct_count = structs.len();
+  for (index, mut item) in
+    nest_imported_items(structs.into_iter().chain(consts).collect(), options)
+      .into_iter()
+      .enumerate()
+  {
+    let is_local_struct = index < struct_count
+      && item.mod_path.is_none()
+      && !vertex_inputs.contains(&item.name);
+    item.item = with_core_paths(item.item, options);
+    items.push(WgslEntryItem {
+      shareable: is_local_struct.then_some(SharedItemKind::Struct),
+      ..WgslEntryItem::from_source_item(&types_mod, item)
+    });
+  }
+  // The vertex layouts use wgpu, so they aren't part of the types with `no_std`.
+  let vertex_methods_mod = if options.no_std {
+    &pipeline_mod
+  } else {
+    &types_mod
+  };
 
-    mod_builder.add(mod_name, create_pipeline_layout);
-    mod_builder.add(mod_name, shader_module(entry, options));
+  if options.share_bind_groups && !bind_group_data.is_empty() {
+    // Each bind group is an item of the `bind_groups` module to share it.
+    let bind_groups_mod = format!("{mod_name}::bind_groups");
+    for (group_no, group_items) in
+      bind_group_items(&bind_group_data, shader_stages, entry_options)
+    {
+      items.push(WgslEntryItem {
+        name: Some(format!("BindGroup{group_no}")),
+        shareable: Some(SharedItemKind::BindGroup),
+        ..WgslEntryItem::new(&bind_groups_mod, group_items)
+      });
+    }
+    items.extend([
+      WgslEntryItem::new(
+        &bind_groups_mod,
+        bind_groups_struct(&bind_group_data, shader_stages, entry_options),
+      ),
+      WgslEntryItem::new(
+        mod_name,
+        set_bind_groups_function(&bind_group_data, shader_stages, entry_options),
+      ),
+    ]);
+  } else {
+    items.push(WgslEntryItem::new(
+      mod_name,
+      bind_groups_module(&bind_group_data, shader_stages, entry_options),
+    ));
   }
 
-  let output = mod_builder.generate();
-  Ok(pretty_print(&output))
+  items.extend([
+    WgslEntryItem::new(
+      mod_name,
+      reflection_module(&bind_group_data, naga_module, entry_options),
+    ),
+    WgslEntryItem::new(vertex_methods_mod, vertex_struct_methods(naga_module, options)),
+    WgslEntryItem::new(
+      &pipeline_mod,
+      bevy_module(&bind_group_data, naga_module, mod_name, options),
+    ),
+    WgslEntryItem::new(
+      &pipeline_mod,
+      readback_functions(&bind_group_data, naga_module, options),
+    ),
+    WgslEntryItem::new(&pipeline_mod, compute_module(naga_module, options)),
+    WgslEntryItem::new(&pipeline_mod, fragment_module(naga_module, options)?),
+    WgslEntryItem::new(&pipeline_mod, entry_point_constants(naga_module, options)),
+    WgslEntryItem::new(&pipeline_mod, entry_point_modules(naga_module, options)),
+    WgslEntryItem::new(&pipeline_mod, required_features(naga_module, options)),
+    WgslEntryItem::new(&pipeline_mod, push_constant_ranges(naga_module, options)),
+    WgslEntryItem::new(&pipeline_mod, vertex_states(naga_module, options)),
+    WgslEntryItem::new(
+      &types_mod,
+      with_core_paths(structs::layout_tests_module(naga_module, options), options),
+    ),
+  ]);
+
+  let missing_layouts = missing_bind_group_layouts(naga_module);
+  let group_count = bind_group_data.keys().max().map_or(0, |group| group + 1);
+  let bind_group_layouts: Vec<_> = (0..group_count)
+    .map(|group_no| {
+      if bind_group_data.contains_key(&group_no) {
+        let group = indexed_name_to_ident("BindGroup", group_no);
+        quote!(&bind_groups::#group::get_bind_group_layout(device))
+      } else {
+        let layout = indexed_name_to_ident("bind_group_layout", group_no);
+        quote!(#layout)
+      }
+    })
+    .collect();
+
+  let push_constant_ranges = if wgsl::push_constant_ranges(naga_module).is_empty() {
+    quote!(&[])
+  } else {
+    quote!(PUSH_CONSTANT_RANGES)
+  };
+
+  let visibility = options.item_visibility_tokens(1);
+  let create_pipeline_layout = quote! {
+      #visibility fn create_pipeline_layout(
+          device: &wgpu::Device,
+          #(#missing_layouts: &wgpu::BindGroupLayout,)*
+      ) -> wgpu::PipelineLayout {
+          device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+              label: None,
+              bind_group_layouts: &[
+                  #(#bind_group_layouts),*
+              ],
+              push_constant_ranges: #push_constant_ranges,
+          })
+      }
+  };
+
+  items.extend([
+    WgslEntryItem::new(&pipeline_mod, create_pipeline_layout),
+    WgslEntryItem::new(&pipeline_mod, shader_module(entry, options)),
+    WgslEntryItem::new(&pipeline_mod, shader_module_spirv(entry, options)?),
+    WgslEntryItem::new(
+      &pipeline_mod,
+      shader_entry_impl(&bind_group_data, naga_module, output_type, options),
+    ),
+  ]);
+
+  Ok(items)
+}
+
+/// Moves the items of imported modules into the `shared_module`.
+fn nest_imported_items(
+  items: Vec<RustSourceItem>,
+  options: &WgslBindgenOption,
+) -> Vec<RustSourceItem> {
+  items
+    .into_iter()
+    .map(|item| RustSourceItem {
+      mod_path: options.items_module_path(item.mod_path.as_deref()),
+      ..item
+    })
+    .collect()
 }
 
 fn pretty_print(tokens: &TokenStream) -> String {
@@ -169,11 +570,15 @@ fn indexed_name_to_ident(name: &str, index: u32) -> Ident {
   Ident::new(&format!("{name}{index}"), Span::call_site())
 }
 
-fn shader_module_using_final_shader_string(entry: &WgslEntryResult) -> TokenStream {
+fn shader_module_using_final_shader_string(
+  entry: &WgslEntryResult,
+  options: &WgslBindgenOption,
+) -> TokenStream {
   let shader_content = module_to_source(&entry.naga_module).unwrap();
   let shader_literal = create_shader_raw_string_literal(&shader_content);
+  let visibility = options.item_visibility_tokens(1);
   let create_shader_module = quote! {
-      pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+      #visibility fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
           let source = std::borrow::Cow::Borrowed(SHADER_STRING);
           device.create_shader_module(wgpu::ShaderModuleDescriptor {
               label: None,
@@ -181,32 +586,82 @@ fn shader_module_using_final_shader_string(entry: &WgslEntryResult) -> TokenStre
           })
       }
   };
+  let create_shader_module_relaxed = shader_module_relaxed(
+    quote!(let source = std::borrow::Cow::Borrowed(SHADER_STRING);),
+    options,
+  );
   let shader_str_def = quote!(const SHADER_STRING: &'static str = #shader_literal;);
 
   quote! {
     #create_shader_module
+    #create_shader_module_relaxed
     #shader_str_def
   }
 }
 
+fn shader_module_relaxed(source: TokenStream, options: &WgslBindgenOption) -> TokenStream {
+  if !options.generate_relaxed_shader_module {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// Creates the shader module without wgpu's runtime checks, like the bounds checks of
+    /// array accesses, when the `relaxed-shaders` feature of the crate including the
+    /// bindings is enabled, like for release builds. Without it, the checks are kept.
+    ///
+    /// # Safety
+    /// The shader must not perform operations leading to undefined behavior without the
+    /// checks, like indexing out of bounds.
+    #visibility unsafe fn create_shader_module_relaxed(device: &wgpu::Device) -> wgpu::ShaderModule {
+      #source
+      let descriptor = wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(source)
+      };
+      if cfg!(feature = "relaxed-shaders") {
+        unsafe { device.create_shader_module_unchecked(descriptor) }
+      } else {
+        device.create_shader_module(descriptor)
+      }
+    }
+  }
+}
+
 fn shader_module_using_composer(
   entry: &WgslEntryResult,
   options: &WgslBindgenOption,
 ) -> TokenStream {
-  let output_dir = options
-    .output_file
-    .as_ref()
-    .and_then(|output_file| output_file.parent().map(|p| p.to_path_buf()))
-    .unwrap_or_else(|| {
-      std::env::var("CARGO_MANIFEST_DIR")
-        .unwrap_or_else(|_| ".".into())
-        .into()
-    });
+  let output_dir = if let Some(output_dir) = options.output_dir.as_ref() {
+    // The module is in its own file, under the directories of its parent modules.
+    let mut parent_mods = options
+      .root_module
+      .iter()
+      .map(String::as_str)
+      .chain(entry.mod_name.split("::"))
+      .chain(options.split_entry_modules.then_some("pipeline"))
+      .collect::<Vec<_>>();
+    parent_mods.pop();
+    parent_mods
+      .iter()
+      .fold(output_dir.clone(), |dir, name| dir.join(name))
+  } else {
+    options
+      .output_file
+      .as_ref()
+      .and_then(|output_file| output_file.parent().map(|p| p.to_path_buf()))
+      .unwrap_or_else(|| {
+        std::env::var("CARGO_MANIFEST_DIR")
+          .unwrap_or_else(|_| ".".into())
+          .into()
+      })
+  };
 
   let get_relative_path = |file: &SourceFilePath| -> String {
     let relative_path = pathdiff::diff_paths(file.as_path(), &output_dir)
       .expect("failed to get relative path");
-    relative_path.to_str().unwrap().to_string()
+    // The same separators on every platform keep the generated code the same.
+    relative_path.to_str().unwrap().replace('\\', "/")
   };
 
   let add_shader_modules_token_stream = entry
@@ -214,7 +669,14 @@ fn shader_module_using_composer(
     .full_dependencies
     .iter()
     .map(|dep| {
-      let relative_file_path = get_relative_path(&dep.file_path);
+      // Virtual modules aren't files, so embed their source instead.
+      let (source, relative_file_path) = if dep.is_virtual {
+        let content = &dep.content;
+        (quote!(#content), dep.file_path.to_string())
+      } else {
+        let relative_file_path = get_relative_path(&dep.file_path);
+        (quote!(include_str!(#relative_file_path)), relative_file_path)
+      };
       let as_name = dep.module_name.as_ref().map(|name| name.to_string());
 
       let as_name_assignment = match as_name {
@@ -225,7 +687,7 @@ fn shader_module_using_composer(
       quote! {
         composer.add_composable_module(
           naga_oil::compose::ComposableModuleDescriptor {
-            source: include_str!(#relative_file_path),
+            source: #source,
             file_path: #relative_file_path,
             language: naga_oil::compose::ShaderLanguage::Wgsl,
             #as_name_assignment,
@@ -236,26 +698,43 @@ fn shader_module_using_composer(
     })
     .collect::<Vec<_>>();
 
-  let entry_relative_path =
-    get_relative_path(&entry.source_including_deps.source_file.file_path);
+  let entry_source_file = entry.source_including_deps.source_file;
+  let (entry_source, entry_relative_path) = if entry_source_file.is_virtual {
+    let content = &entry_source_file.content;
+    (quote!(#content), entry_source_file.file_path.to_string())
+  } else {
+    let entry_relative_path = get_relative_path(&entry_source_file.file_path);
+    (quote!(include_str!(#entry_relative_path)), entry_relative_path)
+  };
+  let shader_defs = shader_defs_field(entry);
+  let visibility = options.item_visibility_tokens(1);
+  let create_shader_module_relaxed = shader_module_relaxed(
+    quote! {
+      let mut composer = init_composer();
+      let module = make_naga_module(&mut composer);
+      let source = std::borrow::Cow::Owned(naga_module_to_string(&module));
+    },
+    options,
+  );
 
   quote! {
-    pub fn init_composer() -> naga_oil::compose::Composer {
+    #visibility fn init_composer() -> naga_oil::compose::Composer {
       #[allow(unused_mut)]
       let mut composer = naga_oil::compose::Composer::default();
       #(#add_shader_modules_token_stream)*
       composer
     }
 
-    pub fn make_naga_module(composer: &mut naga_oil::compose::Composer) -> wgpu::naga::Module {
+    #visibility fn make_naga_module(composer: &mut naga_oil::compose::Composer) -> wgpu::naga::Module {
       composer.make_naga_module(naga_oil::compose::NagaModuleDescriptor {
-        source: include_str!(#entry_relative_path),
+        source: #entry_source,
         file_path: #entry_relative_path,
+        #shader_defs
         ..Default::default()
       }).expect("failed to build naga module")
     }
 
-    pub fn naga_module_to_string(module: &wgpu::naga::Module) -> String {
+    #visibility fn naga_module_to_string(module: &wgpu::naga::Module) -> String {
         // Mini validation to get module info
       let info = wgpu::naga::valid::Validator::new(
         wgpu::naga::valid::ValidationFlags::empty(),
@@ -273,7 +752,7 @@ fn shader_module_using_composer(
       ).expect("failed to convert naga module to source")
     }
 
-    pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+    #visibility fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
       let mut composer = init_composer();
       let module = make_naga_module(&mut composer);
 
@@ -284,26 +763,244 @@ fn shader_module_using_composer(
         source: wgpu::ShaderSource::Wgsl(source)
       })
     }
+
+    #create_shader_module_relaxed
+  }
+}
+
+/// The `shader_defs` field of the `NagaModuleDescriptor` composing the entry point.
+fn shader_defs_field(entry: &WgslEntryResult) -> TokenStream {
+  if entry.shader_defs.is_empty() {
+    return quote!();
+  }
+
+  let shader_defs = entry.shader_defs.iter().map(|(name, value)| {
+    let value = match value {
+      ShaderDefValue::Bool(value) => quote!(Bool(#value)),
+      ShaderDefValue::Int(value) => quote!(Int(#value)),
+      ShaderDefValue::UInt(value) => quote!(UInt(#value)),
+    };
+    quote!((#name.into(), naga_oil::compose::ShaderDefValue::#value))
+  });
+  quote!(shader_defs: std::collections::HashMap::from([#(#shader_defs),*]),)
+}
+
+/// The statements composing the entry point from the WGSL files relative to `dir` into
+/// the validated `source`, returning the errors with `?`.
+fn compose_from_path(entry: &WgslEntryResult) -> TokenStream {
+  // The files are read from the directory their paths are relative to, and virtual
+  // modules aren't files, so their source is embedded.
+  let source_and_path = |file: &SourceFile| {
+    let file_path = file.file_path.to_string().replace('\\', "/");
+    if file.is_virtual {
+      let content = &file.content;
+      (quote!(#content), file_path)
+    } else {
+      (quote!(&std::fs::read_to_string(dir.join(#file_path))?), file_path)
+    }
+  };
+
+  let add_composable_modules = entry
+    .source_including_deps
+    .full_dependencies
+    .iter()
+    .map(|dep| {
+      let (source, file_path) = source_and_path(dep);
+      let as_name_assignment = match dep.module_name.as_ref() {
+        Some(as_name) => {
+          let as_name = as_name.to_string();
+          quote!(as_name: Some(#as_name.into()),)
+        }
+        None => quote!(),
+      };
+
+      quote! {
+        composer.add_composable_module(
+          naga_oil::compose::ComposableModuleDescriptor {
+            source: #source,
+            file_path: #file_path,
+            language: naga_oil::compose::ShaderLanguage::Wgsl,
+            #as_name_assignment
+            ..Default::default()
+          }
+        )?;
+      }
+    });
+
+  let (entry_source, entry_file_path) =
+    source_and_path(entry.source_including_deps.source_file);
+  let shader_defs = shader_defs_field(entry);
+
+  quote! {
+    let dir = dir.as_ref();
+    #[allow(unused_mut)]
+    let mut composer = naga_oil::compose::Composer::default();
+    #(#add_composable_modules)*
+
+    let module = composer.make_naga_module(naga_oil::compose::NagaModuleDescriptor {
+      source: #entry_source,
+      file_path: #entry_file_path,
+      #shader_defs
+      ..Default::default()
+    })?;
+    let info = wgpu::naga::valid::Validator::new(
+      wgpu::naga::valid::ValidationFlags::all(),
+      wgpu::naga::valid::Capabilities::all(),
+    )
+    .validate(&module)?;
+    let source = wgpu::naga::back::wgsl::write_string(
+      &module,
+      &info,
+      wgpu::naga::back::wgsl::WriterFlags::empty(),
+    )?;
+  }
+}
+
+fn shader_module_from_path(
+  entry: &WgslEntryResult,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_hot_reload_shader_module {
+    return quote!();
+  }
+
+  let compose = compose_from_path(entry);
+  let visibility = options.item_visibility_tokens(1);
+
+  quote! {
+    /// Composes the shader with its imports from the WGSL files at runtime, so changes to
+    /// the shaders don't need recompiling. The file paths are relative to `dir`, which is
+    /// the crate directory for bindings generated by a build script.
+    #[cfg(feature = "hot-reload")]
+    #visibility fn create_shader_module_from_path(
+      device: &wgpu::Device,
+      dir: impl AsRef<std::path::Path>,
+    ) -> Result<wgpu::ShaderModule, Box<dyn std::error::Error>> {
+      #compose
+
+      Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(source)),
+      }))
+    }
+  }
+}
+
+fn shader_module_using_runtime_files(
+  entry: &WgslEntryResult,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let compose = compose_from_path(entry);
+  let shader_path = entry
+    .source_including_deps
+    .source_file
+    .file_path
+    .to_string()
+    .replace('\\', "/");
+  let visibility = options.item_visibility_tokens(1);
+
+  quote! {
+    /// The path of the entry point shader, relative to the directory the shaders are
+    /// loaded from.
+    #visibility const SHADER_PATH: &str = #shader_path;
+
+    /// Composes and validates the shader with its imports from the WGSL files relative
+    /// to `dir`.
+    #visibility fn load_shader_source(
+      dir: impl AsRef<std::path::Path>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+      #compose
+      Ok(source)
+    }
+
+    #visibility fn create_shader_module(
+      device: &wgpu::Device,
+      dir: impl AsRef<std::path::Path>,
+    ) -> Result<wgpu::ShaderModule, Box<dyn std::error::Error>> {
+      let source = load_shader_source(dir)?;
+      Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(source)),
+      }))
+    }
+  }
+}
+
+fn shader_module_spirv(
+  entry: &WgslEntryResult,
+  options: &WgslBindgenOption,
+) -> Result<TokenStream, CreateModuleError> {
+  if !options.generate_spirv_shader_module {
+    return Ok(quote!());
   }
+
+  let words = module_to_spirv(&entry.naga_module, options.naga_capabilities)
+    .map_err(|message| CreateModuleError::SpirvTranslation { message })?;
+  let words = words
+    .iter()
+    .map(|word| syn::LitInt::new(&format!("{word:#010x}"), Span::call_site()));
+  let visibility = options.item_visibility_tokens(1);
+
+  Ok(quote! {
+    /// The shader translated to SPIR-V by naga when generating the bindings.
+    #visibility const SHADER_SPIRV: &[u32] = &[#(#words),*];
+
+    /// Creates the shader module from the embedded SPIR-V, which skips parsing WGSL at
+    /// runtime. Requires the `spirv` feature of wgpu.
+    #visibility fn create_shader_module_spirv(device: &wgpu::Device) -> wgpu::ShaderModule {
+      device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::SpirV(std::borrow::Cow::Borrowed(SHADER_SPIRV)),
+      })
+    }
+
+    /// Passes the embedded SPIR-V to the backend as is, without translating it. Requires
+    /// `wgpu::Features::SPIRV_SHADER_PASSTHROUGH`, which is only supported on Vulkan.
+    ///
+    /// # Safety
+    /// wgpu doesn't validate the shader, see `wgpu::Device::create_shader_module_spirv`.
+    #visibility unsafe fn create_shader_module_spirv_passthrough(
+      device: &wgpu::Device,
+    ) -> wgpu::ShaderModule {
+      device.create_shader_module_spirv(&wgpu::ShaderModuleDescriptorSpirV {
+        label: None,
+        source: std::borrow::Cow::Borrowed(SHADER_SPIRV),
+      })
+    }
+  })
 }
 
 fn shader_module(entry: &WgslEntryResult, options: &WgslBindgenOption) -> TokenStream {
-  match options.shader_source_output_type {
+  let create_shader_module = match options.shader_source_output_type {
     WgslShaderSourceOutputType::FinalShaderString => {
-      shader_module_using_final_shader_string(entry)
+      shader_module_using_final_shader_string(entry, options)
     }
     WgslShaderSourceOutputType::Composer => shader_module_using_composer(entry, options),
+    // The shader is always read from the files, so there's nothing to hot reload.
+    WgslShaderSourceOutputType::RuntimeFiles => {
+      return shader_module_using_runtime_files(entry, options);
+    }
+  };
+  let create_shader_module_from_path = shader_module_from_path(entry, options);
+
+  quote! {
+    #create_shader_module
+    #create_shader_module_from_path
   }
 }
 
-fn compute_module(module: &naga::Module) -> TokenStream {
+fn compute_module(module: &naga::Module, options: &WgslBindgenOption) -> TokenStream {
+  // Items of the compute module are nested one module deeper.
+  let item_visibility = options.item_visibility_tokens(2);
+  let missing_layouts = missing_bind_group_layouts(module);
   let entry_points: Vec<_> = module
     .entry_points
     .iter()
     .filter_map(|e| {
       if e.stage == naga::ShaderStage::Compute {
-        let workgroup_size_constant = workgroup_size(e);
-        let create_pipeline = create_compute_pipeline(e);
+        let workgroup_size_constant = workgroup_size(e, &item_visibility);
+        let create_pipeline =
+          create_compute_pipeline(e, &missing_layouts, &item_visibility, options);
 
         Some(quote! {
             #workgroup_size_constant
@@ -319,49 +1016,209 @@ fn compute_module(module: &naga::Module) -> TokenStream {
     // Don't include empty modules.
     quote!()
   } else {
+    let visibility = options.item_visibility_tokens(1);
     quote! {
-        pub mod compute {
+        #visibility mod compute {
             #(#entry_points)*
         }
     }
   }
 }
 
-fn create_compute_pipeline(e: &naga::EntryPoint) -> TokenStream {
+fn fragment_module(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> Result<TokenStream, CreateModuleError> {
+  if !options.generate_fragment_targets {
+    return Ok(quote!());
+  }
+
+  // Items of the fragment module are nested one module deeper.
+  let visibility = options.item_visibility_tokens(2);
+  let entry_points = module
+    .entry_points
+    .iter()
+    .filter(|e| e.stage == naga::ShaderStage::Fragment)
+    .map(|e| fragment_targets(module, e, &visibility))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  if entry_points.is_empty() {
+    // Don't include empty modules.
+    Ok(quote!())
+  } else {
+    let visibility = options.item_visibility_tokens(1);
+    Ok(quote! {
+        #visibility mod fragment {
+            #(#entry_points)*
+        }
+    })
+  }
+}
+
+fn fragment_targets(
+  module: &naga::Module,
+  e: &naga::EntryPoint,
+  visibility: &TokenStream,
+) -> Result<TokenStream, CreateModuleError> {
+  let outputs = wgsl::fragment_outputs(module, e)?;
+  // Unused locations between the outputs have no color target.
+  let num_color_targets = outputs
+    .last()
+    .map_or(0, |(location, _, _)| *location as usize + 1);
+  let sample_types = (0..num_color_targets as u32)
+    .map(|location| {
+      let output = outputs
+        .iter()
+        .find(|(output_location, _, _)| *output_location == location);
+      match output {
+        Some((_, naga::ScalarKind::Float, _)) => {
+          Ok(quote!(Some(wgpu::TextureSampleType::Float { filterable: false })))
+        }
+        Some((_, naga::ScalarKind::Sint, _)) => {
+          Ok(quote!(Some(wgpu::TextureSampleType::Sint)))
+        }
+        Some((_, naga::ScalarKind::Uint, _)) => {
+          Ok(quote!(Some(wgpu::TextureSampleType::Uint)))
+        }
+        Some((_, _, name)) => Err(CreateModuleError::UnsupportedFragmentOutput {
+          entry_point: e.name.clone(),
+          output: name.clone(),
+        }),
+        None => Ok(quote!(None)),
+      }
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let upper_name = e.name.to_uppercase();
+  let num_color_targets_name =
+    Ident::new(&format!("{upper_name}_NUM_COLOR_TARGETS"), Span::call_site());
+  let sample_types_name =
+    Ident::new(&format!("{upper_name}_COLOR_TARGET_SAMPLE_TYPES"), Span::call_site());
+  let targets_name = Ident::new(&format!("{}_targets", e.name), Span::call_site());
+  let num_color_targets = Index::from(num_color_targets);
+
+  Ok(quote! {
+      #visibility const #num_color_targets_name: usize = #num_color_targets;
+      /// The sample types of the texture formats compatible with each color target.
+      /// The float targets accept both filterable and unfilterable formats.
+      #visibility const #sample_types_name: [Option<wgpu::TextureSampleType>; #num_color_targets] =
+          [#(#sample_types),*];
+
+      /// Creates the color targets with the given formats, without blending.
+      /// The formats of unused locations are ignored.
+      #visibility fn #targets_name(
+          formats: [wgpu::TextureFormat; #num_color_targets],
+      ) -> [Option<wgpu::ColorTargetState>; #num_color_targets] {
+          std::array::from_fn(|location| {
+              let format = formats[location];
+              let sample_type = #sample_types_name[location]?;
+              let is_compatible_format = match (format.sample_type(None, None), sample_type) {
+                  (
+                      Some(wgpu::TextureSampleType::Float { .. }),
+                      wgpu::TextureSampleType::Float { .. },
+                  ) => true,
+                  (format_sample_type, sample_type) => format_sample_type == Some(sample_type),
+              };
+              debug_assert!(
+                  is_compatible_format,
+                  "the color target {location} expects a format with a {sample_type:?} sample type",
+              );
+              Some(wgpu::ColorTargetState {
+                  format,
+                  blend: None,
+                  write_mask: wgpu::ColorWrites::ALL,
+              })
+          })
+      }
+  })
+}
+
+/// The parameters with the layouts of the bind groups left without bindings, which are
+/// given by the caller.
+fn missing_bind_group_layouts(module: &naga::Module) -> Vec<Ident> {
+  wgsl::missing_bind_groups(module)
+    .into_iter()
+    .map(|group_no| indexed_name_to_ident("bind_group_layout", group_no))
+    .collect()
+}
+
+fn create_compute_pipeline(
+  e: &naga::EntryPoint,
+  missing_layouts: &[Ident],
+  visibility: &TokenStream,
+  options: &WgslBindgenOption,
+) -> TokenStream {
   // Compute pipeline creation has few parameters and can be generated.
   let pipeline_name =
     Ident::new(&format!("create_{}_pipeline", e.name), Span::call_site());
   let entry_point = &e.name;
   // TODO: Include a user supplied module name in the label?
   let label = format!("Compute Pipeline {}", e.name);
+  let entry_point_fields =
+    options.entry_point_fields(quote!(#entry_point), quote!(compilation_options));
+  let compilation_options_param = options.compilation_options_param();
+  let cache_field = options.pipeline_cache_field();
+  // The shader module can fail to load from the files, so it's created by the caller.
+  if options.shader_source_output_type == WgslShaderSourceOutputType::RuntimeFiles {
+    return quote! {
+        #visibility fn #pipeline_name(
+            device: &wgpu::Device,
+            module: &wgpu::ShaderModule,
+            #(#missing_layouts: &wgpu::BindGroupLayout,)*
+            #compilation_options_param
+        ) -> wgpu::ComputePipeline {
+            let layout = super::create_pipeline_layout(device #(, #missing_layouts)*);
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(#label),
+                layout: Some(&layout),
+                module,
+                #entry_point_fields
+                #cache_field
+            })
+        }
+    };
+  }
+
   quote! {
-      pub fn #pipeline_name(device: &wgpu::Device) -> wgpu::ComputePipeline {
+      #visibility fn #pipeline_name(
+          device: &wgpu::Device,
+          #(#missing_layouts: &wgpu::BindGroupLayout,)*
+          #compilation_options_param
+      ) -> wgpu::ComputePipeline {
           let module = super::create_shader_module(device);
-          let layout = super::create_pipeline_layout(device);
+          let layout = super::create_pipeline_layout(device #(, #missing_layouts)*);
           device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
               label: Some(#label),
               layout: Some(&layout),
               module: &module,
-              entry_point: #entry_point,
+              #entry_point_fields
+              #cache_field
           })
       }
   }
 }
 
-fn workgroup_size(e: &naga::EntryPoint) -> TokenStream {
+fn workgroup_size(e: &naga::EntryPoint, visibility: &TokenStream) -> TokenStream {
   // Use Index to avoid specifying the type on literals.
   let name =
     Ident::new(&format!("{}_WORKGROUP_SIZE", e.name.to_uppercase()), Span::call_site());
   let [x, y, z] = e.workgroup_size.map(|s| Index::from(s as usize));
-  quote!(pub const #name: [u32; 3] = [#x, #y, #z];)
+  quote!(#visibility const #name: [u32; 3] = [#x, #y, #z];)
 }
 
-fn vertex_struct_methods(module: &naga::Module) -> TokenStream {
-  let structs = vertex_input_structs(module);
+fn vertex_struct_methods(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let structs = vertex_input_structs(module, options);
   quote!(#(#structs)*)
 }
 
-fn entry_point_constants(module: &naga::Module) -> TokenStream {
+fn entry_point_constants(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let visibility = options.item_visibility_tokens(1);
   let entry_points: Vec<TokenStream> = module
     .entry_points
     .iter()
@@ -372,7 +1229,7 @@ fn entry_point_constants(module: &naga::Module) -> TokenStream {
         Span::call_site(),
       );
       quote! {
-          pub const #const_name: &str = #entry_name;
+          #visibility const #const_name: &str = #entry_name;
       }
     })
     .collect();
@@ -382,23 +1239,169 @@ fn entry_point_constants(module: &naga::Module) -> TokenStream {
   }
 }
 
-fn vertex_states(module: &naga::Module) -> TokenStream {
-  let vertex_inputs = wgsl::get_vertex_input_structs(module);
-  let mut step_mode_params = vec![];
-  let layout_expressions: Vec<TokenStream> = vertex_inputs
-    .iter()
-    .map(|input| {
-      let name = Ident::new(&input.name, Span::call_site());
-      let step_mode = Ident::new(&input.name.to_snake(), Span::call_site());
-      step_mode_params.push(quote!(#step_mode: wgpu::VertexStepMode));
-      quote!(#name::vertex_buffer_layout(#step_mode))
+fn entry_point_modules(module: &naga::Module, options: &WgslBindgenOption) -> TokenStream {
+  if !options.generate_entry_point_modules || module.entry_points.is_empty() {
+    return quote!();
+  }
+
+  // Items of the entry point modules are nested two modules deeper.
+  let visibility = options.item_visibility_tokens(3);
+  let modules = module.entry_points.iter().map(|entry_point| {
+    let name = &entry_point.name;
+    let upper_name = name.to_uppercase();
+    let mod_name = Ident::new(name, Span::call_site());
+    let const_name = Ident::new(&format!("ENTRY_{upper_name}"), Span::call_site());
+    let stage = wgsl::shader_stages_tokens(match entry_point.stage {
+      naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+      naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+      naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    });
+    let bind_groups = wgsl::used_bind_groups(module, entry_point)
+      .into_iter()
+      .map(|group| Index::from(group as usize));
+
+    let helpers = match entry_point.stage {
+      naga::ShaderStage::Vertex => {
+        let entry_fn = Ident::new(&format!("{name}_entry"), Span::call_site());
+        quote!(#visibility use super::super::#entry_fn as entry;)
+      }
+      naga::ShaderStage::Fragment if options.generate_fragment_targets => {
+        let num_color_targets =
+          Ident::new(&format!("{upper_name}_NUM_COLOR_TARGETS"), Span::call_site());
+        let sample_types =
+          Ident::new(&format!("{upper_name}_COLOR_TARGET_SAMPLE_TYPES"), Span::call_site());
+        let targets = Ident::new(&format!("{name}_targets"), Span::call_site());
+        quote! {
+          #visibility use super::super::fragment::{
+            #num_color_targets as NUM_COLOR_TARGETS,
+            #sample_types as COLOR_TARGET_SAMPLE_TYPES,
+            #targets as targets,
+          };
+        }
+      }
+      naga::ShaderStage::Fragment => quote!(),
+      naga::ShaderStage::Compute => {
+        let workgroup_size =
+          Ident::new(&format!("{upper_name}_WORKGROUP_SIZE"), Span::call_site());
+        let create_pipeline =
+          Ident::new(&format!("create_{name}_pipeline"), Span::call_site());
+        quote! {
+          #visibility use super::super::compute::{
+            #create_pipeline as create_pipeline,
+            #workgroup_size as WORKGROUP_SIZE,
+          };
+        }
+      }
+    };
+
+    let nested_visibility = options.item_visibility_tokens(2);
+    quote! {
+      #nested_visibility mod #mod_name {
+        #visibility const NAME: &str = super::super::#const_name;
+        #visibility const STAGE: wgpu::ShaderStages = #stage;
+        /// The groups of the bindings used by the entry point.
+        #visibility const BIND_GROUPS: &[u32] = &[#(#bind_groups),*];
+        #helpers
+      }
+    }
+  });
+
+  let mod_visibility = options.item_visibility_tokens(1);
+  quote! {
+    #mod_visibility mod entry_points {
+      #(#modules)*
+    }
+  }
+}
+
+fn required_features(module: &naga::Module, options: &WgslBindgenOption) -> TokenStream {
+  let features = wgsl::required_features(module);
+  if features.is_empty() {
+    // Most shaders only need the default features.
+    return quote!();
+  }
+
+  let flags: Vec<_> = features
+    .iter_names()
+    .map(|(name, _)| {
+      let name = Ident::new(name, Span::call_site());
+      quote!(wgpu::Features::#name)
     })
     .collect();
+  let first = &flags[0];
+  let rest = &flags[1..];
+  let visibility = options.item_visibility_tokens(1);
 
-  let vertex_entries: Vec<TokenStream> = module
-    .entry_points
-    .iter()
-    .filter_map(|entry_point| match &entry_point.stage {
+  quote! {
+      #visibility const REQUIRED_FEATURES: wgpu::Features = #first #(.union(#rest))*;
+  }
+}
+
+fn push_constant_ranges(module: &naga::Module, options: &WgslBindgenOption) -> TokenStream {
+  let ranges = wgsl::push_constant_ranges(module);
+  if ranges.is_empty() {
+    return quote!();
+  }
+
+  let ranges = ranges.into_iter().map(|(stages, size)| {
+    let stages = wgsl::shader_stages_tokens(stages);
+    let size = Index::from(size as usize);
+    quote! {
+      wgpu::PushConstantRange {
+        stages: #stages,
+        range: 0..#size,
+      }
+    }
+  });
+  let visibility = options.item_visibility_tokens(1);
+
+  quote! {
+      /// The push constants used by the entry points of each shader stage.
+      #visibility const PUSH_CONSTANT_RANGES: &[wgpu::PushConstantRange] = &[#(#ranges),*];
+  }
+}
+
+fn vertex_states(module: &naga::Module, options: &WgslBindgenOption) -> TokenStream {
+  let visibility = options.item_visibility_tokens(1);
+  let vertex_inputs = wgsl::get_vertex_input_structs(module);
+  let mut step_mode_params = vec![];
+  let mut vertex_layouts = vec![];
+  let mut layout_bindings = vec![];
+  let mut const_layout_bindings = vec![];
+  let mut layout_expressions = vec![];
+  for input in &vertex_inputs {
+    let wgsl_name = demangle(&input.name);
+    let rust_name = options.rust_struct_name(&wgsl_name);
+    let name = Ident::new(&rust_name, Span::call_site());
+    let step_mode = Ident::new(&rust_name.to_snake(), Span::call_site());
+    step_mode_params.push(quote!(#step_mode: wgpu::VertexStepMode));
+
+    if options.non_interleaved_vertex_buffers {
+      // Each member is a separate buffer slot. The layouts aren't Copy, so destructure
+      // the arrays instead of indexing them.
+      let layouts: Vec<_> = input
+        .fields
+        .iter()
+        .map(|(_, m)| {
+          let member = options.rust_member_name(&wgsl_name, m.name.as_ref().unwrap());
+          Ident::new(&format!("{step_mode}_{member}"), Span::call_site())
+        })
+        .collect();
+      layout_bindings
+        .push(quote!(let [#(#layouts),*] = #name::vertex_buffer_layouts(#step_mode);));
+      const_layout_bindings.push(quote!(let [#(#layouts),*] = #name::VERTEX_BUFFER_LAYOUTS;));
+      vertex_layouts.extend(layouts.iter().map(|layout| quote!(#layout)));
+      layout_expressions.extend(layouts.iter().map(|layout| quote!(#layout)));
+    } else {
+      vertex_layouts.push(quote!(#name::VERTEX_BUFFER_LAYOUT));
+      layout_expressions.push(quote!(#name::vertex_buffer_layout(#step_mode)));
+    }
+  }
+
+  let vertex_entries: Vec<TokenStream> = module
+    .entry_points
+    .iter()
+    .filter_map(|entry_point| match &entry_point.stage {
       ShaderStage::Vertex => {
         let fn_name =
           Ident::new(&format!("{}_entry", &entry_point.name), Span::call_site());
@@ -406,10 +1409,11 @@ fn vertex_states(module: &naga::Module) -> TokenStream {
           &format!("ENTRY_{}", &entry_point.name.to_uppercase()),
           Span::call_site(),
         );
-        let n = vertex_inputs.len();
+        let n = layout_expressions.len();
         let n = Literal::usize_unsuffixed(n);
         Some(quote! {
-            pub fn #fn_name(#(#step_mode_params),*) -> VertexEntry<#n> {
+            #visibility fn #fn_name(#(#step_mode_params),*) -> VertexEntry<#n> {
+                #(#layout_bindings)*
                 VertexEntry {
                     entry_point: #const_name,
                     buffers: [
@@ -423,47 +1427,136 @@ fn vertex_states(module: &naga::Module) -> TokenStream {
     })
     .collect();
 
+  let vertex_layouts = if const_layout_bindings.is_empty() {
+    quote!(&[#(#vertex_layouts),*])
+  } else {
+    quote!(&{
+        #(#const_layout_bindings)*
+        [#(#vertex_layouts),*]
+    })
+  };
+
+  // The `compilation_options` field of the returned state can be set by the caller.
+  let entry_point_fields = options.entry_point_fields(
+    quote!(entry.entry_point),
+    quote!(compilation_options: Default::default()),
+  );
+
   // Don't generate unused code.
   if vertex_entries.is_empty() {
     quote!()
   } else {
     quote! {
         #[derive(Debug)]
-        pub struct VertexEntry<const N: usize> {
+        #visibility struct VertexEntry<const N: usize> {
             entry_point: &'static str,
             buffers: [wgpu::VertexBufferLayout<'static>; N]
         }
 
-        pub fn vertex_state<'a, const N: usize>(
+        #visibility fn vertex_state<'a, const N: usize>(
             module: &'a wgpu::ShaderModule,
             entry: &'a VertexEntry<N>,
         ) -> wgpu::VertexState<'a> {
             wgpu::VertexState {
                 module,
-                entry_point: entry.entry_point,
+                #entry_point_fields
                 buffers: &entry.buffers,
             }
         }
 
+        /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+        #visibility const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = #vertex_layouts;
+
         #(#vertex_entries)*
     }
   }
 }
 
-fn vertex_input_structs(module: &naga::Module) -> Vec<TokenStream> {
+fn vertex_input_structs(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> Vec<TokenStream> {
+  let visibility = options.item_visibility_tokens(1);
   let vertex_inputs = wgsl::get_vertex_input_structs(module);
   vertex_inputs.iter().map(|input|  {
-        let name = Ident::new(&input.name, Span::call_site());
+        let wgsl_name = demangle(&input.name);
+        let name = Ident::new(&options.rust_struct_name(&wgsl_name), Span::call_site());
 
         // Use index to avoid adding prefix to literals.
         let count = Index::from(input.fields.len());
+        let location_consts: Vec<_> = input
+            .fields
+            .iter()
+            .map(|(_, m)| {
+                let field_name = options.rust_member_name(&wgsl_name, m.name.as_ref().unwrap());
+                Ident::new(&format!("LOCATION_{}", field_name.to_snake().to_uppercase()), Span::call_site())
+            })
+            .collect();
+        let locations = input.fields.iter().map(|(location, _)| Index::from(*location as usize));
+        let locations = quote! {
+            #(#visibility const #location_consts: u32 = #locations;)*
+        };
+
+        let step_mode = if options.is_instance_step_mode(&wgsl_name) {
+            quote!(wgpu::VertexStepMode::Instance)
+        } else {
+            quote!(wgpu::VertexStepMode::Vertex)
+        };
+
+        if options.non_interleaved_vertex_buffers {
+            // Each member is tightly packed in its own buffer, so its offset is zero and
+            // the stride is the size of its vertex format.
+            let layouts: Vec<_> = input
+                .fields
+                .iter()
+                .zip(&location_consts)
+                .map(|((_, m), location)| {
+                    let format = options
+                        .vertex_format_override(&wgsl_name, m.name.as_ref().unwrap())
+                        .unwrap_or_else(|| wgsl::vertex_format(&module.types[m.ty]));
+                    let stride = Index::from(format.size() as usize);
+                    let format = Ident::new(&format!("{format:?}"), Span::call_site());
+
+                    quote! {
+                        wgpu::VertexBufferLayout {
+                            array_stride: #stride,
+                            step_mode,
+                            attributes: &[wgpu::VertexAttribute {
+                                format: wgpu::VertexFormat::#format,
+                                offset: 0,
+                                shader_location: Self::#location,
+                            }],
+                        }
+                    }
+                })
+                .collect();
+
+            return quote! {
+                impl #name {
+                    #locations
+
+                    #visibility const STEP_MODE: wgpu::VertexStepMode = #step_mode;
+
+                    #visibility const VERTEX_BUFFER_LAYOUTS: [wgpu::VertexBufferLayout<'static>; #count] =
+                        Self::vertex_buffer_layouts(Self::STEP_MODE);
+
+                    #visibility const fn vertex_buffer_layouts(step_mode: wgpu::VertexStepMode) -> [wgpu::VertexBufferLayout<'static>; #count] {
+                        [#(#layouts),*]
+                    }
+                }
+            };
+        }
+
         let attributes: Vec<_> = input
             .fields
             .iter()
-            .map(|(location, m)| {
-                let field_name: TokenStream = m.name.as_ref().unwrap().parse().unwrap();
-                let location = Index::from(*location as usize);
-                let format = wgsl::vertex_format(&module.types[m.ty]);
+            .zip(&location_consts)
+            .map(|((_, m), location)| {
+                let field_name = options.rust_member_name(&wgsl_name, m.name.as_ref().unwrap());
+                let field_name = Ident::new(&field_name, Span::call_site());
+                let format = options
+                    .vertex_format_override(&wgsl_name, m.name.as_ref().unwrap())
+                    .unwrap_or_else(|| wgsl::vertex_format(&module.types[m.ty]));
                 // TODO: Will the debug implementation always work with the macro?
                 let format = Ident::new(&format!("{format:?}"), Span::call_site());
 
@@ -471,7 +1564,7 @@ fn vertex_input_structs(module: &naga::Module) -> Vec<TokenStream> {
                     wgpu::VertexAttribute {
                         format: wgpu::VertexFormat::#format,
                         offset: std::mem::offset_of!(#name, #field_name) as u64,
-                        shader_location: #location,
+                        shader_location: Self::#location,
                     }
                 }
             })
@@ -489,9 +1582,16 @@ fn vertex_input_structs(module: &naga::Module) -> Vec<TokenStream> {
         // TODO: Support vertex inputs that aren't in a struct.
         quote! {
             impl #name {
-                pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; #count] = [#(#attributes),*];
+                #locations
 
-                pub const fn vertex_buffer_layout(step_mode: wgpu::VertexStepMode) -> wgpu::VertexBufferLayout<'static> {
+                #visibility const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; #count] = [#(#attributes),*];
+
+                #visibility const STEP_MODE: wgpu::VertexStepMode = #step_mode;
+
+                #visibility const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> =
+                    Self::vertex_buffer_layout(Self::STEP_MODE);
+
+                #visibility const fn vertex_buffer_layout(step_mode: wgpu::VertexStepMode) -> wgpu::VertexBufferLayout<'static> {
                     wgpu::VertexBufferLayout {
                         array_stride: std::mem::size_of::<#name>() as u64,
                         step_mode,
@@ -518,7 +1618,6 @@ macro_rules! assert_tokens_eq {
 mod test {
   use indoc::indoc;
 
-  use self::bevy_util::source_file::SourceFile;
   use super::*;
 
   fn create_shader_module(
@@ -534,9 +1633,11 @@ mod test {
         full_dependencies: Default::default(),
         source_file: &dummy_source,
       },
+      shader_defs: Default::default(),
     };
 
-    create_rust_bindings(vec![entry], &options)
+    let items = create_entry_items(&entry, &options)?;
+    create_rust_bindings(vec![(entry.mod_name, items)], &options)
   }
 
   #[test]
@@ -588,6 +1689,94 @@ mod test {
     );
   }
 
+  #[test]
+  fn create_shader_module_item_visibility_pub_super() {
+    let source = indoc! {r#"
+            struct Params {
+                scale: f32,
+            };
+            const SCALE = 2.0;
+
+            @compute
+            @workgroup_size(64)
+            fn main() {}
+        "#};
+
+    let options = WgslBindgenOption {
+      item_visibility: WgslItemVisibility::PubSuper,
+      ..Default::default()
+    };
+    let actual = create_shader_module(source, options).unwrap();
+
+    pretty_assertions::assert_eq!(
+      indoc! {r##"
+                #[allow(unused)]
+                mod _root {
+                    pub use super::*;
+                }
+                pub(super) mod test {
+                    #[allow(unused_imports)]
+                    use super::{_root, _root::*};
+                    pub(in super::super) const SCALE: f32 = 2f32;
+                    pub(in super::super) mod compute {
+                        pub(in super::super::super) const MAIN_WORKGROUP_SIZE: [u32; 3] = [64, 1, 1];
+                        pub(in super::super::super) fn create_main_pipeline(
+                            device: &wgpu::Device,
+                        ) -> wgpu::ComputePipeline {
+                            let module = super::create_shader_module(device);
+                            let layout = super::create_pipeline_layout(device);
+                            device
+                                .create_compute_pipeline(
+                                    &wgpu::ComputePipelineDescriptor {
+                                        label: Some("Compute Pipeline main"),
+                                        layout: Some(&layout),
+                                        module: &module,
+                                        entry_point: "main",
+                                    },
+                                )
+                        }
+                    }
+                    pub(in super::super) const ENTRY_MAIN: &str = "main";
+                    pub(in super::super) fn create_pipeline_layout(
+                        device: &wgpu::Device,
+                    ) -> wgpu::PipelineLayout {
+                        device
+                            .create_pipeline_layout(
+                                &wgpu::PipelineLayoutDescriptor {
+                                    label: None,
+                                    bind_group_layouts: &[],
+                                    push_constant_ranges: &[],
+                                },
+                            )
+                    }
+                    pub(in super::super) fn create_shader_module(
+                        device: &wgpu::Device,
+                    ) -> wgpu::ShaderModule {
+                        let source = std::borrow::Cow::Borrowed(SHADER_STRING);
+                        device
+                            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                                label: None,
+                                source: wgpu::ShaderSource::Wgsl(source),
+                            })
+                    }
+                    const SHADER_STRING: &'static str = r#"
+                struct Params {
+                    scale: f32,
+                }
+
+                const SCALE: f32 = 2f;
+
+                @compute @workgroup_size(64, 1, 1) 
+                fn main() {
+                    return;
+                }
+                "#;
+                }
+            "##},
+      actual
+    );
+  }
+
   #[test]
   fn create_shader_module_consecutive_bind_groups() {
     let source = indoc! {r#"
@@ -641,6 +1830,76 @@ mod test {
     assert!(matches!(result, Err(CreateModuleError::DuplicateBinding { binding: 2 })));
   }
 
+  #[test]
+  fn create_shader_module_exceeding_max_bind_groups() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> a: vec4<f32>;
+            @group(1) @binding(0) var<uniform> b: vec4<f32>;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let options = WgslBindgenOption {
+      limits: Some(wgpu::Limits {
+        max_bind_groups: 1,
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    let result = create_shader_module(source, options);
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "`b` at @group(1) @binding(0) needs a `max_bind_groups` of 2, but the limit is 1"
+    );
+  }
+
+  #[test]
+  fn create_shader_module_exceeding_max_vertex_attributes() {
+    let source = indoc! {r#"
+            struct VertexInput {
+                @location(0) position: vec3<f32>,
+                @location(1) normal: vec3<f32>,
+            };
+
+            @vertex
+            fn vs_main(input: VertexInput, @location(2) uv: vec2<f32>) -> @builtin(position) vec4<f32> {
+                return vec4(input.position, uv.x);
+            }
+        "#};
+
+    let limits = wgpu::Limits {
+      max_vertex_attributes: 2,
+      ..Default::default()
+    };
+    let result = create_shader_module(
+      source,
+      WgslBindgenOption {
+        limits: Some(limits.clone()),
+        ..Default::default()
+      },
+    );
+    assert!(matches!(
+      result,
+      Err(CreateModuleError::LimitExceeded {
+        limit: "max_vertex_attributes",
+        max: 2,
+        required: 3,
+        ..
+      })
+    ));
+
+    let limits = wgpu::Limits {
+      max_vertex_attributes: 3,
+      ..limits
+    };
+    let options = WgslBindgenOption {
+      limits: Some(limits),
+      ..Default::default()
+    };
+    assert!(create_shader_module(source, options).is_ok());
+  }
+
   #[test]
   fn write_vertex_module_empty() {
     let source = indoc! {r#"
@@ -649,7 +1908,7 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_struct_methods(&module);
+    let actual = vertex_struct_methods(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(quote!(), actual);
   }
@@ -669,33 +1928,41 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_struct_methods(&module);
+    let actual = vertex_struct_methods(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
           impl VertexInput0 {
+              pub const LOCATION_A: u32 = 0;
+              pub const LOCATION_B: u32 = 1;
+              pub const LOCATION_C: u32 = 2;
+              pub const LOCATION_D: u32 = 3;
               pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float32,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 0,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float32x2,
                       offset: std::mem::offset_of!(VertexInput0, b) as u64,
-                      shader_location: 1,
+                      shader_location: Self::LOCATION_B,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float32x3,
                       offset: std::mem::offset_of!(VertexInput0, c) as u64,
-                      shader_location: 2,
+                      shader_location: Self::LOCATION_C,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float32x4,
                       offset: std::mem::offset_of!(VertexInput0, d) as u64,
-                      shader_location: 3,
+                      shader_location: Self::LOCATION_D,
                   },
               ];
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+              pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+                  Self::STEP_MODE,
+              );
               pub const fn vertex_buffer_layout(
                   step_mode: wgpu::VertexStepMode,
               ) -> wgpu::VertexBufferLayout<'static> {
@@ -726,33 +1993,41 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_struct_methods(&module);
+    let actual = vertex_struct_methods(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
           impl VertexInput0 {
+              pub const LOCATION_A: u32 = 0;
+              pub const LOCATION_B: u32 = 1;
+              pub const LOCATION_C: u32 = 2;
+              pub const LOCATION_D: u32 = 3;
               pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float64,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 0,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float64x2,
                       offset: std::mem::offset_of!(VertexInput0, b) as u64,
-                      shader_location: 1,
+                      shader_location: Self::LOCATION_B,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float64x3,
                       offset: std::mem::offset_of!(VertexInput0, c) as u64,
-                      shader_location: 2,
+                      shader_location: Self::LOCATION_C,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Float64x4,
                       offset: std::mem::offset_of!(VertexInput0, d) as u64,
-                      shader_location: 3,
+                      shader_location: Self::LOCATION_D,
                   },
               ];
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+              pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+                  Self::STEP_MODE,
+              );
               pub const fn vertex_buffer_layout(
                   step_mode: wgpu::VertexStepMode,
               ) -> wgpu::VertexBufferLayout<'static> {
@@ -784,33 +2059,41 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_struct_methods(&module);
+    let actual = vertex_struct_methods(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
           impl VertexInput0 {
+              pub const LOCATION_A: u32 = 0;
+              pub const LOCATION_A: u32 = 1;
+              pub const LOCATION_A: u32 = 2;
+              pub const LOCATION_A: u32 = 3;
               pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Sint32,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 0,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Sint32x2,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 1,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Sint32x3,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 2,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Sint32x4,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 3,
+                      shader_location: Self::LOCATION_A,
                   },
               ];
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+              pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+                  Self::STEP_MODE,
+              );
               pub const fn vertex_buffer_layout(
                   step_mode: wgpu::VertexStepMode,
               ) -> wgpu::VertexBufferLayout<'static> {
@@ -841,33 +2124,41 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_struct_methods(&module);
+    let actual = vertex_struct_methods(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
           impl VertexInput0 {
+              pub const LOCATION_A: u32 = 0;
+              pub const LOCATION_B: u32 = 1;
+              pub const LOCATION_C: u32 = 2;
+              pub const LOCATION_D: u32 = 3;
               pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Uint32,
                       offset: std::mem::offset_of!(VertexInput0, a) as u64,
-                      shader_location: 0,
+                      shader_location: Self::LOCATION_A,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Uint32x2,
                       offset: std::mem::offset_of!(VertexInput0, b) as u64,
-                      shader_location: 1,
+                      shader_location: Self::LOCATION_B,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Uint32x3,
                       offset: std::mem::offset_of!(VertexInput0, c) as u64,
-                      shader_location: 2,
+                      shader_location: Self::LOCATION_C,
                   },
                   wgpu::VertexAttribute {
                       format: wgpu::VertexFormat::Uint32x4,
                       offset: std::mem::offset_of!(VertexInput0, d) as u64,
-                      shader_location: 3,
+                      shader_location: Self::LOCATION_D,
                   },
               ];
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+              pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+                  Self::STEP_MODE,
+              );
               pub const fn vertex_buffer_layout(
                   step_mode: wgpu::VertexStepMode,
               ) -> wgpu::VertexBufferLayout<'static> {
@@ -891,7 +2182,7 @@ mod test {
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = compute_module(&module);
+    let actual = compute_module(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(quote!(), actual);
   }
@@ -910,7 +2201,7 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = compute_module(&module);
+    let actual = compute_module(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
@@ -967,7 +2258,7 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = entry_point_constants(&module);
+    let actual = entry_point_constants(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
@@ -989,7 +2280,7 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_states(&module);
+    let actual = vertex_states(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
@@ -1008,6 +2299,8 @@ mod test {
                   buffers: &entry.buffers,
               }
           }
+          /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+          pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[];
           pub fn vs_main_entry() -> VertexEntry<0> {
               VertexEntry {
                   entry_point: ENTRY_VS_MAIN,
@@ -1034,7 +2327,7 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_states(&module);
+    let actual = vertex_states(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
@@ -1053,6 +2346,10 @@ mod test {
                   buffers: &entry.buffers,
               }
           }
+          /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+          pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[
+              VertexInput::VERTEX_BUFFER_LAYOUT,
+          ];
           pub fn vs_main_1_entry(vertex_input: wgpu::VertexStepMode) -> VertexEntry<1> {
               VertexEntry {
                   entry_point: ENTRY_VS_MAIN_1,
@@ -1085,7 +2382,7 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_states(&module);
+    let actual = vertex_states(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(
       quote! {
@@ -1104,6 +2401,11 @@ mod test {
                   buffers: &entry.buffers,
               }
           }
+          /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+          pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[
+              Input0::VERTEX_BUFFER_LAYOUT,
+              Input1::VERTEX_BUFFER_LAYOUT,
+          ];
           pub fn vs_main_entry(input0: wgpu::VertexStepMode, input1: wgpu::VertexStepMode) -> VertexEntry<2> {
               VertexEntry {
                   entry_point: ENTRY_VS_MAIN,
@@ -1130,8 +2432,678 @@ mod test {
     };
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
-    let actual = vertex_states(&module);
+    let actual = vertex_states(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(quote!(), actual)
+  }
+
+  #[test]
+  fn write_required_features_none() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> a: vec4<f32>;
+            @fragment
+            fn main() {}
+        "#
+    };
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let actual = required_features(&module, &WgslBindgenOption::default());
 
     assert_tokens_eq!(quote!(), actual)
   }
+
+  #[test]
+  fn write_required_features_f16() {
+    // The WGSL frontend doesn't parse `enable f16;` yet, so add the type directly.
+    let mut module = naga::Module::default();
+    module.types.insert(
+      naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+          size: naga::VectorSize::Quad,
+          scalar: naga::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 2,
+          },
+        },
+      },
+      naga::Span::UNDEFINED,
+    );
+
+    let actual = required_features(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(
+      quote! {
+          pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::SHADER_F16;
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_required_features_f64() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> a: vec4<f64>;
+            @fragment
+            fn main() {}
+        "#
+    };
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let actual = required_features(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(
+      quote! {
+          pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::SHADER_F64;
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_required_features_binding_array() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+            @group(0) @binding(1) var<storage, read> buffers: binding_array<array<f32>, 2>;
+            @fragment
+            fn main() {}
+        "#
+    };
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let actual = required_features(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(
+      quote! {
+          pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::TEXTURE_BINDING_ARRAY
+              .union(wgpu::Features::BUFFER_BINDING_ARRAY)
+              .union(wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY);
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_required_features_storage_textures() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var a: texture_storage_2d<rgba8unorm, write>;
+            @group(0) @binding(1) var b: texture_storage_2d<bgra8unorm, write>;
+            @group(0) @binding(2) var c: texture_storage_2d<r8unorm, write>;
+            @compute @workgroup_size(1)
+            fn main() {}
+        "#
+    };
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let actual = required_features(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(
+      quote! {
+          pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::BGRA8UNORM_STORAGE
+              .union(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_required_features_entry_points() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<storage, read_write> counter: array<u32>;
+
+            struct FragmentOutput {
+                @location(0) color: vec4<f32>,
+                @location(0) @second_blend_source blend: vec4<f32>,
+            };
+
+            @vertex
+            fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+                counter[index] = 1u;
+                return vec4(0.0);
+            }
+
+            @fragment
+            @early_depth_test(less_equal)
+            fn fs_main(@builtin(primitive_index) primitive: u32) -> FragmentOutput {
+                return FragmentOutput(vec4(f32(primitive)), vec4(0.0));
+            }
+        "#
+    };
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let actual = required_features(&module, &WgslBindgenOption::default());
+
+    assert_tokens_eq!(
+      quote! {
+          pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::VERTEX_WRITABLE_STORAGE
+              .union(wgpu::Features::SHADER_PRIMITIVE_INDEX)
+              .union(wgpu::Features::SHADER_EARLY_DEPTH_TEST)
+              .union(wgpu::Features::DUAL_SOURCE_BLENDING);
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn create_shader_module_push_constants() {
+    let source = indoc! {r#"
+            var<push_constant> scale: vec4<f32>;
+
+            fn scaled(position: vec4<f32>) -> vec4<f32> {
+                return position * scale;
+            }
+
+            @vertex
+            fn vs_main() -> @builtin(position) vec4<f32> {
+                return scaled(vec4(1.0));
+            }
+
+            @fragment
+            fn fs_main() -> @location(0) vec4<f32> {
+                return vec4(0.0);
+            }
+        "#};
+
+    let actual = create_shader_module(source, WgslBindgenOption::default()).unwrap();
+
+    pretty_assertions::assert_eq!(
+      indoc! {r##"
+                #[allow(unused)]
+                mod _root {
+                    pub use super::*;
+                }
+                pub mod test {
+                    #[allow(unused_imports)]
+                    use super::{_root, _root::*};
+                    pub const ENTRY_VS_MAIN: &str = "vs_main";
+                    pub const ENTRY_FS_MAIN: &str = "fs_main";
+                    pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::PUSH_CONSTANTS;
+                    /// The push constants used by the entry points of each shader stage.
+                    pub const PUSH_CONSTANT_RANGES: &[wgpu::PushConstantRange] = &[
+                        wgpu::PushConstantRange {
+                            stages: wgpu::ShaderStages::VERTEX,
+                            range: 0..16,
+                        },
+                    ];
+                    #[derive(Debug)]
+                    pub struct VertexEntry<const N: usize> {
+                        entry_point: &'static str,
+                        buffers: [wgpu::VertexBufferLayout<'static>; N],
+                    }
+                    pub fn vertex_state<'a, const N: usize>(
+                        module: &'a wgpu::ShaderModule,
+                        entry: &'a VertexEntry<N>,
+                    ) -> wgpu::VertexState<'a> {
+                        wgpu::VertexState {
+                            module,
+                            entry_point: entry.entry_point,
+                            buffers: &entry.buffers,
+                        }
+                    }
+                    /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+                    pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &[];
+                    pub fn vs_main_entry() -> VertexEntry<0> {
+                        VertexEntry {
+                            entry_point: ENTRY_VS_MAIN,
+                            buffers: [],
+                        }
+                    }
+                    pub fn create_pipeline_layout(device: &wgpu::Device) -> wgpu::PipelineLayout {
+                        device
+                            .create_pipeline_layout(
+                                &wgpu::PipelineLayoutDescriptor {
+                                    label: None,
+                                    bind_group_layouts: &[],
+                                    push_constant_ranges: PUSH_CONSTANT_RANGES,
+                                },
+                            )
+                    }
+                    pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+                        let source = std::borrow::Cow::Borrowed(SHADER_STRING);
+                        device
+                            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                                label: None,
+                                source: wgpu::ShaderSource::Wgsl(source),
+                            })
+                    }
+                    const SHADER_STRING: &'static str = r#"
+                var<push_constant> scale: vec4<f32>;
+
+                fn scaled(position: vec4<f32>) -> vec4<f32> {
+                    let _e2 = scale;
+                    return (position * _e2);
+                }
+
+                @vertex 
+                fn vs_main() -> @builtin(position) vec4<f32> {
+                    let _e2 = scaled(vec4(1f));
+                    return _e2;
+                }
+
+                @fragment 
+                fn fs_main() -> @location(0) vec4<f32> {
+                    return vec4(0f);
+                }
+                "#;
+                }
+            "##},
+      actual
+    );
+  }
+
+  #[test]
+  fn create_shader_module_relaxed() {
+    let source = indoc! {r#"
+            @fragment
+            fn fs_main() {}
+        "#};
+
+    let options = WgslBindgenOption {
+      generate_relaxed_shader_module: true,
+      ..Default::default()
+    };
+    let actual = create_shader_module(source, options).unwrap();
+
+    pretty_assertions::assert_eq!(
+      indoc! {r##"
+                #[allow(unused)]
+                mod _root {
+                    pub use super::*;
+                }
+                pub mod test {
+                    #[allow(unused_imports)]
+                    use super::{_root, _root::*};
+                    pub const ENTRY_FS_MAIN: &str = "fs_main";
+                    pub fn create_pipeline_layout(device: &wgpu::Device) -> wgpu::PipelineLayout {
+                        device
+                            .create_pipeline_layout(
+                                &wgpu::PipelineLayoutDescriptor {
+                                    label: None,
+                                    bind_group_layouts: &[],
+                                    push_constant_ranges: &[],
+                                },
+                            )
+                    }
+                    pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+                        let source = std::borrow::Cow::Borrowed(SHADER_STRING);
+                        device
+                            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                                label: None,
+                                source: wgpu::ShaderSource::Wgsl(source),
+                            })
+                    }
+                    /// Creates the shader module without wgpu's runtime checks, like the bounds checks of
+                    /// array accesses, when the `relaxed-shaders` feature of the crate including the
+                    /// bindings is enabled, like for release builds. Without it, the checks are kept.
+                    ///
+                    /// # Safety
+                    /// The shader must not perform operations leading to undefined behavior without the
+                    /// checks, like indexing out of bounds.
+                    pub unsafe fn create_shader_module_relaxed(
+                        device: &wgpu::Device,
+                    ) -> wgpu::ShaderModule {
+                        let source = std::borrow::Cow::Borrowed(SHADER_STRING);
+                        let descriptor = wgpu::ShaderModuleDescriptor {
+                            label: None,
+                            source: wgpu::ShaderSource::Wgsl(source),
+                        };
+                        if cfg!(feature = "relaxed-shaders") {
+                            unsafe { device.create_shader_module_unchecked(descriptor) }
+                        } else {
+                            device.create_shader_module(descriptor)
+                        }
+                    }
+                    const SHADER_STRING: &'static str = r#"
+                @fragment 
+                fn fs_main() {
+                    return;
+                }
+                "#;
+                }
+            "##},
+      actual
+    );
+  }
+
+  #[test]
+  fn write_entry_point_modules() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> scale: f32;
+            @group(1) @binding(0) var<storage, read_write> values: array<f32>;
+
+            @vertex
+            fn vs_main() -> @builtin(position) vec4<f32> {
+                return vec4(scale);
+            }
+
+            @fragment
+            fn fs_main() {}
+
+            @compute
+            @workgroup_size(64)
+            fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+                values[id.x] *= scale;
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let options = WgslBindgenOption {
+      generate_entry_point_modules: true,
+      ..Default::default()
+    };
+    let actual = entry_point_modules(&module, &options);
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod entry_points {
+              pub mod vs_main {
+                  pub const NAME: &str = super::super::ENTRY_VS_MAIN;
+                  pub const STAGE: wgpu::ShaderStages = wgpu::ShaderStages::VERTEX;
+                  /// The groups of the bindings used by the entry point.
+                  pub const BIND_GROUPS: &[u32] = &[0];
+                  pub use super::super::vs_main_entry as entry;
+              }
+              pub mod fs_main {
+                  pub const NAME: &str = super::super::ENTRY_FS_MAIN;
+                  pub const STAGE: wgpu::ShaderStages = wgpu::ShaderStages::FRAGMENT;
+                  /// The groups of the bindings used by the entry point.
+                  pub const BIND_GROUPS: &[u32] = &[];
+              }
+              pub mod cs_main {
+                  pub const NAME: &str = super::super::ENTRY_CS_MAIN;
+                  pub const STAGE: wgpu::ShaderStages = wgpu::ShaderStages::COMPUTE;
+                  /// The groups of the bindings used by the entry point.
+                  pub const BIND_GROUPS: &[u32] = &[0, 1];
+                  pub use super::super::compute::{
+                      create_cs_main_pipeline as create_pipeline,
+                      CS_MAIN_WORKGROUP_SIZE as WORKGROUP_SIZE,
+                  };
+              }
+          }
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_fragment_module() {
+    let source = indoc! {r#"
+            struct FragmentOutput {
+                @location(0) color: vec4<f32>,
+                @location(2) id: u32,
+                @builtin(frag_depth) depth: f32,
+            };
+
+            @fragment
+            fn fs_main() -> FragmentOutput {
+                return FragmentOutput(vec4(1.0), 0u, 0.0);
+            }
+
+            @fragment
+            fn fs_single() -> @location(0) vec4<i32> {
+                return vec4(0);
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let options = WgslBindgenOption {
+      generate_fragment_targets: true,
+      ..Default::default()
+    };
+    let actual = fragment_module(&module, &options).unwrap();
+
+    assert_tokens_eq!(
+      quote! {
+          pub mod fragment {
+              pub const FS_MAIN_NUM_COLOR_TARGETS: usize = 3;
+              /// The sample types of the texture formats compatible with each color target.
+              /// The float targets accept both filterable and unfilterable formats.
+              pub const FS_MAIN_COLOR_TARGET_SAMPLE_TYPES: [Option<wgpu::TextureSampleType>; 3] = [
+                  Some(wgpu::TextureSampleType::Float {
+                      filterable: false,
+                  }),
+                  None,
+                  Some(wgpu::TextureSampleType::Uint),
+              ];
+              /// Creates the color targets with the given formats, without blending.
+              /// The formats of unused locations are ignored.
+              pub fn fs_main_targets(
+                  formats: [wgpu::TextureFormat; 3],
+              ) -> [Option<wgpu::ColorTargetState>; 3] {
+                  std::array::from_fn(|location| {
+                      let format = formats[location];
+                      let sample_type = FS_MAIN_COLOR_TARGET_SAMPLE_TYPES[location]?;
+                      let is_compatible_format = match (
+                          format.sample_type(None, None),
+                          sample_type,
+                      ) {
+                          (
+                              Some(wgpu::TextureSampleType::Float { .. }),
+                              wgpu::TextureSampleType::Float { .. },
+                          ) => true,
+                          (format_sample_type, sample_type) => {
+                              format_sample_type == Some(sample_type)
+                          }
+                      };
+                      debug_assert!(
+                          is_compatible_format,
+                          "the color target {location} expects a format with a {sample_type:?} sample type",
+                      );
+                      Some(wgpu::ColorTargetState {
+                          format,
+                          blend: None,
+                          write_mask: wgpu::ColorWrites::ALL,
+                      })
+                  })
+              }
+              pub const FS_SINGLE_NUM_COLOR_TARGETS: usize = 1;
+              /// The sample types of the texture formats compatible with each color target.
+              /// The float targets accept both filterable and unfilterable formats.
+              pub const FS_SINGLE_COLOR_TARGET_SAMPLE_TYPES: [Option<
+                  wgpu::TextureSampleType,
+              >; 1] = [Some(wgpu::TextureSampleType::Sint)];
+              /// Creates the color targets with the given formats, without blending.
+              /// The formats of unused locations are ignored.
+              pub fn fs_single_targets(
+                  formats: [wgpu::TextureFormat; 1],
+              ) -> [Option<wgpu::ColorTargetState>; 1] {
+                  std::array::from_fn(|location| {
+                      let format = formats[location];
+                      let sample_type = FS_SINGLE_COLOR_TARGET_SAMPLE_TYPES[location]?;
+                      let is_compatible_format = match (
+                          format.sample_type(None, None),
+                          sample_type,
+                      ) {
+                          (
+                              Some(wgpu::TextureSampleType::Float { .. }),
+                              wgpu::TextureSampleType::Float { .. },
+                          ) => true,
+                          (format_sample_type, sample_type) => {
+                              format_sample_type == Some(sample_type)
+                          }
+                      };
+                      debug_assert!(
+                          is_compatible_format,
+                          "the color target {location} expects a format with a {sample_type:?} sample type",
+                      );
+                      Some(wgpu::ColorTargetState {
+                          format,
+                          blend: None,
+                          write_mask: wgpu::ColorWrites::ALL,
+                      })
+                  })
+              }
+          }
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn fragment_module_unsupported_output() {
+    let source = indoc! {r#"
+            struct FragmentOutput {
+                @location(0) color: vec4<f32>,
+                @location(1) mask: bool,
+            };
+
+            @fragment
+            fn fs_main() -> FragmentOutput {
+                return FragmentOutput(vec4(1.0), true);
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let options = WgslBindgenOption {
+      generate_fragment_targets: true,
+      ..Default::default()
+    };
+    let result = fragment_module(&module, &options);
+
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "fragment output `mask` of entry point `fs_main` has no color target type"
+    );
+  }
+
+  #[test]
+  fn write_vertex_module_instance_step_mode() {
+    let source = indoc! {r#"
+            struct Instance {
+                @location(0) offset: vec2<f32>,
+            };
+
+            @vertex
+            fn main(instance: Instance) {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let options = WgslBindgenOption {
+      instance_step_mode_structs: ["Instance".to_owned()].into_iter().collect(),
+      ..Default::default()
+    };
+    let actual = vertex_struct_methods(&module, &options);
+
+    assert_tokens_eq!(
+      quote! {
+          impl Instance {
+              pub const LOCATION_OFFSET: u32 = 0;
+              pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = [
+                  wgpu::VertexAttribute {
+                      format: wgpu::VertexFormat::Float32x2,
+                      offset: std::mem::offset_of!(Instance, offset) as u64,
+                      shader_location: Self::LOCATION_OFFSET,
+                  },
+              ];
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Instance;
+              pub const VERTEX_BUFFER_LAYOUT: wgpu::VertexBufferLayout<'static> = Self::vertex_buffer_layout(
+                  Self::STEP_MODE,
+              );
+              pub const fn vertex_buffer_layout(
+                  step_mode: wgpu::VertexStepMode,
+              ) -> wgpu::VertexBufferLayout<'static> {
+                  wgpu::VertexBufferLayout {
+                      array_stride: std::mem::size_of::<Instance>() as u64,
+                      step_mode,
+                      attributes: &Instance::VERTEX_ATTRIBUTES,
+                  }
+              }
+          }
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_vertex_module_non_interleaved() {
+    let source = indoc! {r#"
+            struct VertexInput {
+                @location(0) position: vec3<f32>,
+                @location(1) uv: vec2<f32>,
+            };
+
+            @vertex
+            fn vs_main(input: VertexInput) {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let options = WgslBindgenOption {
+      non_interleaved_vertex_buffers: true,
+      ..Default::default()
+    };
+    let methods = vertex_struct_methods(&module, &options);
+    let states = vertex_states(&module, &options);
+    let actual = quote!(#methods #states);
+
+    assert_tokens_eq!(
+      quote! {
+          impl VertexInput {
+              pub const LOCATION_POSITION: u32 = 0;
+              pub const LOCATION_UV: u32 = 1;
+              pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+              pub const VERTEX_BUFFER_LAYOUTS: [wgpu::VertexBufferLayout<'static>; 2] = Self::vertex_buffer_layouts(
+                  Self::STEP_MODE,
+              );
+              pub const fn vertex_buffer_layouts(
+                  step_mode: wgpu::VertexStepMode,
+              ) -> [wgpu::VertexBufferLayout<'static>; 2] {
+                  [
+                      wgpu::VertexBufferLayout {
+                          array_stride: 12,
+                          step_mode,
+                          attributes: &[
+                              wgpu::VertexAttribute {
+                                  format: wgpu::VertexFormat::Float32x3,
+                                  offset: 0,
+                                  shader_location: Self::LOCATION_POSITION,
+                              },
+                          ],
+                      },
+                      wgpu::VertexBufferLayout {
+                          array_stride: 8,
+                          step_mode,
+                          attributes: &[
+                              wgpu::VertexAttribute {
+                                  format: wgpu::VertexFormat::Float32x2,
+                                  offset: 0,
+                                  shader_location: Self::LOCATION_UV,
+                              },
+                          ],
+                      },
+                  ]
+              }
+          }
+          #[derive(Debug)]
+          pub struct VertexEntry<const N: usize> {
+              entry_point: &'static str,
+              buffers: [wgpu::VertexBufferLayout<'static>; N],
+          }
+          pub fn vertex_state<'a, const N: usize>(
+              module: &'a wgpu::ShaderModule,
+              entry: &'a VertexEntry<N>,
+          ) -> wgpu::VertexState<'a> {
+              wgpu::VertexState {
+                  module,
+                  entry_point: entry.entry_point,
+                  buffers: &entry.buffers,
+              }
+          }
+          /// The layouts of the vertex inputs with their `STEP_MODE`, in buffer slot order.
+          pub const VERTEX_LAYOUTS: &[wgpu::VertexBufferLayout<'static>] = &{
+              let [vertex_input_position, vertex_input_uv] = VertexInput::VERTEX_BUFFER_LAYOUTS;
+              [vertex_input_position, vertex_input_uv]
+          };
+          pub fn vs_main_entry(vertex_input: wgpu::VertexStepMode) -> VertexEntry<2> {
+              let [vertex_input_position, vertex_input_uv] = VertexInput::vertex_buffer_layouts(
+                  vertex_input,
+              );
+              VertexEntry {
+                  entry_point: ENTRY_VS_MAIN,
+                  buffers: [vertex_input_position, vertex_input_uv],
+              }
+          }
+      },
+      actual
+    )
+  }
 }
@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::bevy_util::demangle;
+use crate::bindgroup::get_bind_group_data_with_gaps;
+use crate::struct_docs::WgslStructDocs;
+use crate::structs::add_types_recursive;
+use crate::{wgsl, CreateModuleError, WgslEntryResult};
+
+/// A Markdown document with the entry points, bind groups, vertex inputs and the layout of
+/// the buffer structs of the shader, for reading the interface without the Rust sources.
+pub(crate) fn entry_markdown(
+  entry: &WgslEntryResult,
+) -> Result<String, CreateModuleError> {
+  let module = &entry.naga_module;
+  let source_path = &entry.source_including_deps.source_file.file_path;
+  let mut text = String::new();
+
+  writeln!(text, "# `{}`", entry.mod_name).unwrap();
+  writeln!(text).unwrap();
+  writeln!(
+    text,
+    "Generated by {} from `{source_path}`. Changes made to this file will not be saved.",
+    env!("CARGO_PKG_NAME")
+  )
+  .unwrap();
+
+  write_entry_points(&mut text, module);
+  write_bind_groups(&mut text, module)?;
+  write_vertex_inputs(&mut text, module);
+  write_structs(
+    &mut text,
+    module,
+    &WgslStructDocs::from_entry(&entry.source_including_deps),
+  );
+
+  Ok(text)
+}
+
+fn write_entry_points(text: &mut String, module: &naga::Module) {
+  if module.entry_points.is_empty() {
+    return;
+  }
+
+  writeln!(text, "\n## Entry points\n").unwrap();
+  writeln!(text, "| Name | Stage | Bind groups | Workgroup size |").unwrap();
+  writeln!(text, "| --- | --- | --- | --- |").unwrap();
+  for entry_point in module.entry_points.iter() {
+    let (stage, workgroup_size) = match entry_point.stage {
+      naga::ShaderStage::Vertex => ("vertex", String::new()),
+      naga::ShaderStage::Fragment => ("fragment", String::new()),
+      naga::ShaderStage::Compute => {
+        let [x, y, z] = entry_point.workgroup_size;
+        ("compute", format!("{x}, {y}, {z}"))
+      }
+    };
+    let bind_groups = wgsl::used_bind_groups(module, entry_point)
+      .iter()
+      .map(|group| group.to_string())
+      .collect::<Vec<_>>()
+      .join(", ");
+    writeln!(
+      text,
+      "| `{}` | {stage} | {bind_groups} | {workgroup_size} |",
+      entry_point.name
+    )
+    .unwrap();
+  }
+}
+
+fn write_bind_groups(
+  text: &mut String,
+  module: &naga::Module,
+) -> Result<(), CreateModuleError> {
+  let bind_group_data = get_bind_group_data_with_gaps(module)?;
+  if bind_group_data.is_empty() {
+    return Ok(());
+  }
+
+  writeln!(text, "\n## Bind groups").unwrap();
+  for (group_no, group) in bind_group_data.iter() {
+    writeln!(text, "\n### Group {group_no}\n").unwrap();
+    writeln!(text, "| Binding | Name | Resource | Type | Size |").unwrap();
+    writeln!(text, "| --- | --- | --- | --- | --- |").unwrap();
+    for binding in group.bindings.iter() {
+      let (resource, size) = match binding.binding_type.inner {
+        naga::TypeInner::Image {
+          class: naga::ImageClass::Storage { access, .. },
+          ..
+        } => {
+          let resource = if !access.contains(naga::StorageAccess::LOAD) {
+            "storage texture (write-only)"
+          } else if !access.contains(naga::StorageAccess::STORE) {
+            "storage texture (read-only)"
+          } else {
+            "storage texture (read-write)"
+          };
+          (resource, String::new())
+        }
+        naga::TypeInner::Image { .. } => ("texture", String::new()),
+        naga::TypeInner::Sampler { comparison: true } => {
+          ("comparison sampler", String::new())
+        }
+        naga::TypeInner::Sampler { comparison: false } => ("sampler", String::new()),
+        _ => {
+          let resource = match binding.address_space {
+            naga::AddressSpace::Storage { access }
+              if access.contains(naga::StorageAccess::STORE) =>
+            {
+              "storage buffer (read-write)"
+            }
+            naga::AddressSpace::Storage { .. } => "storage buffer (read-only)",
+            _ => "uniform buffer",
+          };
+          (resource, format!("{} bytes", binding.size))
+        }
+      };
+      let name = demangle(binding.name.as_deref().unwrap_or_default());
+      let type_name = match binding.binding_type.name.as_ref() {
+        Some(type_name) => demangle(type_name).into_owned(),
+        None => {
+          demangle(&binding.binding_type.inner.to_wgsl(&module.to_ctx())).into_owned()
+        }
+      };
+      let type_name = match binding.array_count {
+        Some(count) => format!("binding_array<{type_name}, {count}>"),
+        None => type_name,
+      };
+      writeln!(
+        text,
+        "| {} | `{name}` | {resource} | `{type_name}` | {size} |",
+        binding.binding_index
+      )
+      .unwrap();
+    }
+  }
+
+  Ok(())
+}
+
+fn write_vertex_inputs(text: &mut String, module: &naga::Module) {
+  let vertex_entry_points = module
+    .entry_points
+    .iter()
+    .filter(|entry_point| entry_point.stage == naga::ShaderStage::Vertex)
+    .collect::<Vec<_>>();
+  if vertex_entry_points.is_empty() {
+    return;
+  }
+
+  writeln!(text, "\n## Vertex inputs").unwrap();
+  for entry_point in vertex_entry_points {
+    writeln!(text, "\n### `{}`\n", entry_point.name).unwrap();
+    writeln!(text, "| Location | Name | Type |").unwrap();
+    writeln!(text, "| --- | --- | --- |").unwrap();
+
+    // The inputs are either arguments with a location, or the members of struct arguments.
+    let mut inputs = Vec::new();
+    for argument in entry_point.function.arguments.iter() {
+      match (&argument.binding, &module.types[argument.ty].inner) {
+        (Some(naga::Binding::Location { location, .. }), _) => {
+          inputs.push((*location, argument.name.as_deref(), argument.ty));
+        }
+        (None, naga::TypeInner::Struct { members, .. }) => {
+          for member in members.iter() {
+            if let Some(naga::Binding::Location { location, .. }) = member.binding {
+              inputs.push((location, member.name.as_deref(), member.ty));
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+    inputs.sort_by_key(|(location, ..)| *location);
+
+    for (location, name, ty) in inputs {
+      let name = name.unwrap_or_default();
+      let type_name = type_name(module, ty);
+      writeln!(text, "| {location} | `{name}` | `{type_name}` |").unwrap();
+    }
+  }
+}
+
+/// The structs in uniform, storage and push constant buffers, with the offsets and sizes of
+/// their members.
+fn write_structs(text: &mut String, module: &naga::Module, docs: &WgslStructDocs) {
+  let mut buffer_types = HashSet::new();
+  for (_, global) in module.global_variables.iter() {
+    if matches!(
+      global.space,
+      naga::AddressSpace::Uniform
+        | naga::AddressSpace::Storage { .. }
+        | naga::AddressSpace::PushConstant
+    ) {
+      add_types_recursive(&mut buffer_types, module, global.ty);
+    }
+  }
+
+  let structs = module
+    .types
+    .iter()
+    .filter(|(handle, _)| buffer_types.contains(handle))
+    .filter_map(|(handle, ty)| match &ty.inner {
+      naga::TypeInner::Struct { members, .. } => Some((handle, ty, members)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  if structs.is_empty() {
+    return;
+  }
+
+  let mut layouter = naga::proc::Layouter::default();
+  layouter.update(module.to_ctx()).unwrap();
+
+  writeln!(text, "\n## Structs").unwrap();
+  for (handle, ty, members) in structs {
+    let name = demangle(ty.name.as_deref().unwrap_or_default());
+    let struct_docs = docs.get(&name);
+    writeln!(text, "\n### `{name}`\n").unwrap();
+    if let Some(struct_docs) = struct_docs.filter(|docs| !docs.docs.is_empty()) {
+      writeln!(text, "{}\n", doc_text(&struct_docs.docs)).unwrap();
+    }
+
+    let layout = layouter[handle];
+    writeln!(
+      text,
+      "Size: {} bytes, alignment: {} bytes.\n",
+      layout.size, layout.alignment
+    )
+    .unwrap();
+    writeln!(text, "| Offset | Field | Type | Size | Description |").unwrap();
+    writeln!(text, "| --- | --- | --- | --- | --- |").unwrap();
+    for member in members.iter() {
+      let member_name = member.name.as_deref().unwrap_or_default();
+      let size = match module.types[member.ty].inner {
+        naga::TypeInner::Array {
+          size: naga::ArraySize::Dynamic,
+          stride,
+          ..
+        } => format!("{stride} bytes per element"),
+        ref inner => format!("{} bytes", inner.size(module.to_ctx())),
+      };
+      let description = struct_docs
+        .and_then(|docs| docs.members.get(member_name))
+        .map(|docs| doc_text(docs))
+        .unwrap_or_default();
+      writeln!(
+        text,
+        "| {} | `{member_name}` | `{}` | {size} | {description} |",
+        member.offset,
+        type_name(module, member.ty)
+      )
+      .unwrap();
+    }
+  }
+}
+
+/// The WGSL type, with the demangled names of the imported structs.
+fn type_name(module: &naga::Module, ty: naga::Handle<naga::Type>) -> String {
+  demangle(&ty.to_wgsl(&module.to_ctx())).into_owned()
+}
+
+/// The comment lines joined into a single line, which fits in a table cell.
+fn doc_text(lines: &[String]) -> String {
+  lines
+    .iter()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ")
+    .replace('|', "\\|")
+}
@@ -0,0 +1,41 @@
+use naga_oil::compose::ShaderDefValue;
+
+use super::{name_unnamed_items, validate_module};
+use crate::FxIndexMap;
+
+/// Parses the GLSL source of a single stage with the shader defs as preprocessor defines,
+/// where `false` booleans are left undefined, and validates the module.
+pub fn glsl_to_module(
+  source: &str,
+  stage: naga::ShaderStage,
+  shader_defs: &FxIndexMap<String, ShaderDefValue>,
+  capabilities: naga::valid::Capabilities,
+) -> Result<naga::Module, String> {
+  let mut options = naga::front::glsl::Options::from(stage);
+  for (name, value) in shader_defs.iter() {
+    let value = match value {
+      ShaderDefValue::Bool(false) => continue,
+      ShaderDefValue::Bool(true) => "1".to_string(),
+      ShaderDefValue::Int(value) => value.to_string(),
+      ShaderDefValue::UInt(value) => format!("{value}u"),
+    };
+    options.defines.insert(name.clone(), value);
+  }
+
+  let mut module = naga::front::glsl::Frontend::default()
+    .parse(&options, source)
+    .map_err(|errors| {
+      errors
+        .iter()
+        .map(|err| {
+          let location = err.meta.location(source);
+          format!("{}:{}: {err}", location.line_number, location.line_position)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+    })?;
+  name_unnamed_items(&mut module);
+  validate_module(&module, capabilities)?;
+
+  Ok(module)
+}
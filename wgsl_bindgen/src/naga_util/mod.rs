@@ -1,2 +1,75 @@
+mod glsl_to_module;
+mod module_to_backend;
 mod module_to_source;
-pub use module_to_source::*;
\ No newline at end of file
+mod module_to_spirv;
+mod spirv_to_module;
+pub use glsl_to_module::*;
+pub use module_to_backend::*;
+pub use module_to_source::*;
+pub use module_to_spirv::*;
+pub use spirv_to_module::*;
+
+use case::CaseExt;
+
+/// Validates the module with all the validation flags, as needed by the naga backends.
+fn validate_module(
+  module: &naga::Module,
+  capabilities: naga::valid::Capabilities,
+) -> Result<naga::valid::ModuleInfo, String> {
+  naga::valid::Validator::new(naga::valid::ValidationFlags::all(), capabilities)
+    .validate(module)
+    .map_err(|err| err.into_inner().to_string())
+}
+
+/// Names the items needed for the bindings which don't have a name, like the GLSL blocks
+/// without an instance name or the variables and types of stripped SPIR-V.
+fn name_unnamed_items(module: &mut naga::Module) {
+  // The bindings are named after their type, or their group and binding.
+  for (_, global) in module.global_variables.iter_mut() {
+    let Some(binding) = global.binding.as_ref() else {
+      continue;
+    };
+    if global.name.as_ref().is_some_and(|name| !name.is_empty()) {
+      continue;
+    }
+
+    global.name = Some(match module.types[global.ty].name.as_ref() {
+      Some(name) if !name.is_empty() => name.to_snake(),
+      _ => format!("binding_{}_{}", binding.group, binding.binding),
+    });
+  }
+
+  // The structs are named after the binding using them, like `ParamsBlock` for `params`,
+  // or their index, and the members after their index.
+  let unnamed_structs = module
+    .types
+    .iter()
+    .filter(|(_, ty)| match &ty.inner {
+      naga::TypeInner::Struct { members, .. } => {
+        ty.name.is_none() || members.iter().any(|member| member.name.is_none())
+      }
+      _ => false,
+    })
+    .map(|(handle, _)| handle)
+    .collect::<Vec<_>>();
+  for handle in unnamed_structs {
+    let mut ty = module.types[handle].clone();
+    if ty.name.is_none() {
+      let binding_name = module
+        .global_variables
+        .iter()
+        .find(|(_, global)| global.ty == handle && global.binding.is_some())
+        .and_then(|(_, global)| global.name.as_ref());
+      ty.name = Some(match binding_name {
+        Some(name) => format!("{}Block", name.to_camel()),
+        None => format!("Struct{}", handle.index()),
+      });
+    }
+    if let naga::TypeInner::Struct { members, .. } = &mut ty.inner {
+      for (index, member) in members.iter_mut().enumerate() {
+        member.name.get_or_insert_with(|| format!("member_{index}"));
+      }
+    }
+    module.types.replace(handle, ty);
+  }
+}
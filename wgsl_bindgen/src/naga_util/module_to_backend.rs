@@ -0,0 +1,62 @@
+use super::validate_module;
+use crate::WgslShaderBackend;
+
+/// Translates the module with the naga backend of the shading language, returning the
+/// file extensions with the sources. GLSL has a source per entry point, so its extensions
+/// are like `vs_main.vert`.
+pub fn module_to_backend_sources(
+  module: &naga::Module,
+  capabilities: naga::valid::Capabilities,
+  backend: WgslShaderBackend,
+) -> Result<Vec<(String, String)>, String> {
+  let info = validate_module(module, capabilities)?;
+
+  match backend {
+    WgslShaderBackend::Msl => {
+      let (source, _) = naga::back::msl::write_string(
+        module,
+        &info,
+        &Default::default(),
+        &Default::default(),
+      )
+      .map_err(|err| err.to_string())?;
+      Ok(vec![("metal".to_string(), source)])
+    }
+    WgslShaderBackend::Hlsl => {
+      let mut source = String::new();
+      naga::back::hlsl::Writer::new(&mut source, &Default::default())
+        .write(module, &info)
+        .map_err(|err| err.to_string())?;
+      Ok(vec![("hlsl".to_string(), source)])
+    }
+    WgslShaderBackend::Glsl => module
+      .entry_points
+      .iter()
+      .map(|entry_point| {
+        let extension = match entry_point.stage {
+          naga::ShaderStage::Vertex => "vert",
+          naga::ShaderStage::Fragment => "frag",
+          naga::ShaderStage::Compute => "comp",
+        };
+        let pipeline_options = naga::back::glsl::PipelineOptions {
+          shader_stage: entry_point.stage,
+          entry_point: entry_point.name.clone(),
+          multiview: None,
+        };
+
+        let mut source = String::new();
+        naga::back::glsl::Writer::new(
+          &mut source,
+          module,
+          &info,
+          &Default::default(),
+          &pipeline_options,
+          Default::default(),
+        )
+        .and_then(|mut writer| writer.write())
+        .map_err(|err| err.to_string())?;
+        Ok((format!("{}.{extension}", entry_point.name), source))
+      })
+      .collect(),
+  }
+}
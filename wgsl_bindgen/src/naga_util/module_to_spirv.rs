@@ -0,0 +1,12 @@
+use super::validate_module;
+
+/// Validates the module with the given capabilities and translates it to SPIR-V words,
+/// with the default options wgpu uses for Vulkan.
+pub fn module_to_spirv(
+  module: &naga::Module,
+  capabilities: naga::valid::Capabilities,
+) -> Result<Vec<u32>, String> {
+  let info = validate_module(module, capabilities)?;
+  naga::back::spv::write_vec(module, &info, &naga::back::spv::Options::default(), None)
+    .map_err(|err| err.to_string())
+}
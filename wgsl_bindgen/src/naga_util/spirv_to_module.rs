@@ -0,0 +1,22 @@
+use super::{name_unnamed_items, validate_module};
+
+/// Parses the SPIR-V words, written in hex one per line as read by
+/// [crate::SourceFilePath::read_contents], and validates the module.
+pub fn spirv_to_module(
+  content: &str,
+  capabilities: naga::valid::Capabilities,
+) -> Result<naga::Module, String> {
+  let words = content
+    .lines()
+    .map(|word| u32::from_str_radix(word, 16).map_err(|err| err.to_string()))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut module =
+    naga::front::spv::Frontend::new(words.into_iter(), &Default::default())
+      .parse()
+      .map_err(|err| err.to_string())?;
+  name_unnamed_items(&mut module);
+  validate_module(&module, capabilities)?;
+
+  Ok(module)
+}
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use miette::Diagnostic;
 use naga::FastIndexMap;
 use proc_macro2::TokenStream;
@@ -6,8 +8,9 @@ use smallvec::SmallVec;
 use syn::Ident;
 use thiserror::Error;
 
-use super::{constants::MOD_REFERENCE_ROOT, RustSourceItem};
+use super::constants::MOD_REFERENCE_ROOT;
 use crate::quote_gen::constants::mod_reference_root;
+use crate::WgslItemVisibility;
 
 #[derive(Debug, Error, Diagnostic)]
 pub enum RustModBuilderError {
@@ -84,14 +87,14 @@ impl RustMod {
       .or_insert_with(|| RustMod::new(name, true, self.initial_contents.clone()))
   }
 
-  fn generate(&self) -> TokenStream {
+  fn generate(&self, depth: usize, item_visibility: WgslItemVisibility) -> TokenStream {
     let name = Ident::new(&self.name, proc_macro2::Span::call_site());
 
     let initial_contents = &self.initial_contents;
     let content = &self.content;
 
     let visibility = if self.is_public {
-      quote!(pub)
+      item_visibility.tokens(depth)
     } else {
       quote!()
     };
@@ -99,7 +102,7 @@ impl RustMod {
     let submodules = self
       .submodules
       .values()
-      .map(|m| m.generate())
+      .map(|m| m.generate(depth + 1, item_visibility))
       .collect::<Vec<_>>();
 
     let mod_attr = &self.module_attributes;
@@ -113,11 +116,55 @@ impl RustMod {
       }
     }
   }
+
+  /// Adds the file of this module and its submodules under `dir`, and returns the `mod`
+  /// declaration of this module for its parent.
+  fn generate_files(
+    &self,
+    dir: &Path,
+    depth: usize,
+    item_visibility: WgslItemVisibility,
+    files: &mut Vec<(PathBuf, TokenStream)>,
+  ) -> TokenStream {
+    let name = Ident::new(&self.name, proc_macro2::Span::call_site());
+
+    let initial_contents = &self.initial_contents;
+    let content = &self.content;
+
+    let visibility = if self.is_public {
+      item_visibility.tokens(depth)
+    } else {
+      quote!()
+    };
+
+    let index = files.len();
+    let submodules = self
+      .submodules
+      .values()
+      .map(|m| m.generate_files(&dir.join(&self.name), depth + 1, item_visibility, files))
+      .collect::<Vec<_>>();
+
+    let file_contents = quote! {
+      #initial_contents
+      #( #content )*
+      #( #submodules )*
+    };
+    files.insert(index, (dir.join(format!("{}.rs", self.name)), file_contents));
+
+    let mod_attr = &self.module_attributes;
+
+    quote! {
+      #mod_attr
+      #visibility mod #name;
+    }
+  }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct RustModBuilderConfig {
   use_relative_root: bool,
+  item_visibility: WgslItemVisibility,
+  root_module: Option<String>,
 }
 
 impl RustModBuilderConfig {
@@ -169,7 +216,11 @@ pub(crate) struct RustModBuilder {
 
 impl RustModBuilder {
   pub fn new(use_relative_root: bool) -> Self {
-    let config = RustModBuilderConfig { use_relative_root };
+    let config = RustModBuilderConfig {
+      use_relative_root,
+      item_visibility: WgslItemVisibility::default(),
+      root_module: None,
+    };
 
     Self {
       modules: config.initial_modules(),
@@ -177,6 +228,18 @@ impl RustModBuilder {
     }
   }
 
+  /// Sets the visibility of the generated modules.
+  pub fn with_item_visibility(mut self, item_visibility: WgslItemVisibility) -> Self {
+    self.config.item_visibility = item_visibility;
+    self
+  }
+
+  /// Sets the module wrapping all the generated modules.
+  pub fn with_root_module(mut self, root_module: Option<String>) -> Self {
+    self.config.root_module = root_module;
+    self
+  }
+
   fn get_or_create_module(&mut self, path: &str) -> &mut RustMod {
     if path.is_empty() {
       panic!("path cannot be empty");
@@ -195,24 +258,6 @@ impl RustModBuilder {
     current_module
   }
 
-  pub fn add_items(
-    &mut self,
-    default_mod_path: &str,
-    items: Vec<RustSourceItem>,
-  ) -> Result<(), RustModBuilderError> {
-    for item in items {
-      let module_path = item
-        .mod_path
-        .as_ref()
-        .map(|s| s.as_str())
-        .unwrap_or(default_mod_path);
-
-      self.add_unique(module_path, &item.name, item.item)?;
-    }
-
-    Ok(())
-  }
-
   pub fn add(&mut self, path: &str, content: TokenStream) {
     self.get_or_create_module(path).add_content(content);
   }
@@ -228,15 +273,65 @@ impl RustModBuilder {
 
   /// Generates the top level root module that includes other modules
   pub fn generate(&self) -> TokenStream {
-    let modules: Vec<TokenStream> = self.modules.values().map(|m| m.generate()).collect();
-    quote! {
-      #( #modules )*
+    let item_visibility = self.config.item_visibility;
+    let depth = self.root_module_depth();
+    let modules: Vec<TokenStream> = self
+      .modules
+      .values()
+      .map(|m| m.generate(depth, item_visibility))
+      .collect();
+
+    match self.config.root_module.as_ref() {
+      Some(root_module) => {
+        let name = Ident::new(root_module, proc_macro2::Span::call_site());
+        let visibility = item_visibility.tokens(0);
+        quote! {
+          #visibility mod #name {
+            #( #modules )*
+          }
+        }
+      }
+      None => quote! {
+        #( #modules )*
+      },
     }
   }
+
+  fn root_module_depth(&self) -> usize {
+    usize::from(self.config.root_module.is_some())
+  }
+
+  /// Generates a `mod.rs` file declaring the top level modules, and a file for each module
+  /// and submodule, like `a.rs` and `a/b.rs`, with the paths relative to the `mod.rs`.
+  pub fn generate_files(&self) -> Vec<(PathBuf, TokenStream)> {
+    let item_visibility = self.config.item_visibility;
+    let depth = self.root_module_depth();
+    let dir = self.config.root_module.as_deref().unwrap_or("");
+    let mut files = Vec::new();
+    let modules: Vec<TokenStream> = self
+      .modules
+      .values()
+      .map(|m| m.generate_files(Path::new(dir), depth, item_visibility, &mut files))
+      .collect();
+
+    match self.config.root_module.as_ref() {
+      Some(root_module) => {
+        let name = Ident::new(root_module, proc_macro2::Span::call_site());
+        let visibility = item_visibility.tokens(0);
+        let root_file = PathBuf::from(format!("{root_module}.rs"));
+        files.insert(0, (root_file, quote! { #( #modules )* }));
+        files.insert(0, (PathBuf::from("mod.rs"), quote! { #visibility mod #name; }));
+      }
+      None => files.insert(0, (PathBuf::from("mod.rs"), quote! { #( #modules )* })),
+    }
+    files
+  }
 }
 
 #[cfg(test)]
 mod tests {
+  use std::path::PathBuf;
+
   use pretty_assertions::assert_eq;
   use quote::quote;
 
@@ -306,6 +401,33 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_module_files_generation_works() {
+    let mut mod_builder = RustModBuilder::new(false);
+    mod_builder.add("a::b", quote! {struct A;});
+    mod_builder.add("a", quote! {struct B;});
+    mod_builder.add("c", quote! {struct C;});
+
+    let files = mod_builder.generate_files();
+
+    let paths = files
+      .iter()
+      .map(|(path, _)| path.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      paths,
+      vec![
+        PathBuf::from("mod.rs"),
+        PathBuf::from("a.rs"),
+        PathBuf::from("a/b.rs"),
+        PathBuf::from("c.rs"),
+      ]
+    );
+    assert_tokens_eq!(files[0].1.clone(), quote! { pub mod a; pub mod c; });
+    assert_tokens_eq!(files[1].1.clone(), quote! { struct B; pub mod b; });
+    assert_tokens_eq!(files[2].1.clone(), quote! { struct A; });
+  }
+
   #[test]
   fn test_module_add_duplicates() -> Result<(), RustModBuilderError> {
     let mut mod_builder = RustModBuilder::new(false);
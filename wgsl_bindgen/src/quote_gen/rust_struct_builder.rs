@@ -1,16 +1,143 @@
 use std::{borrow::Cow, usize};
 
+use case::CaseExt;
 use naga::StructMember;
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use syn::{Ident, Index};
 
-use super::rust_type;
+use super::{
+  rust_packed_vector_type, rust_type, rust_unpadded_matrix_column_type,
+  rust_unpadded_vector_type, rust_vertex_format_type, MOD_REFERENCE_ROOT,
+};
 use crate::{
-  bevy_util::demangle_splitting_mod_path_and_item, WgslTypeSerializeStrategy,
-  WgslBindgenOption,
+  bevy_util::{demangle, demangle_splitting_mod_path_and_item},
+  bindgroup::DYNAMIC_OFFSET_ALIGNMENT,
+  struct_docs::StructDocs,
+  WgslBindgenOption, WgslInitStructStrategy, WgslTypeSerializeStrategy,
 };
 
+const ATOMIC_MEMBER_DOC: &str =
+  " An atomic in WGSL, so the buffer must be bound as `var<storage, read_write>`.";
+
+/// The assertions of a test comparing the layout of an encase struct to the WGSL offsets and
+/// size. Each member is created from non-zero bytes in an otherwise zeroed struct, so the
+/// written bytes start at its offset.
+pub(crate) fn encase_layout_assertions(
+  path: &TokenStream,
+  members: &[(Ident, Index)],
+  size: u32,
+  has_rts_array: bool,
+) -> TokenStream {
+  let buffer_size = Index::from(size as usize);
+  let offsets = members.iter().map(|(name, offset)| {
+    let message = name.to_string();
+    quote! {
+      let mut value = zeroed();
+      value.#name = ones.create().unwrap();
+      assert_eq!(written_offset(&value), Some(#offset), #message);
+    }
+  });
+  // encase sizes runtime-sized arrays by the number of elements at runtime.
+  let assert_size = (!has_rts_array).then(|| {
+    let min_size = quote!(<#path as encase::ShaderType>::min_size().get());
+    quote!(assert_eq!(#min_size, #buffer_size);)
+  });
+
+  quote! {
+    let zeroed = || -> #path {
+      encase::StorageBuffer::new(vec![0u8; #buffer_size]).create().unwrap()
+    };
+    let ones = encase::StorageBuffer::new(vec![1u8; #buffer_size]);
+    let written_offset = |value: &#path| {
+      let mut buffer = encase::StorageBuffer::new(Vec::new());
+      buffer.write(value).unwrap();
+      buffer.into_inner().iter().position(|&byte| byte != 0)
+    };
+    #(#offsets)*
+    #assert_size
+  }
+}
+
+/// Atomics are plain integers on the host, see [ATOMIC_MEMBER_DOC].
+fn is_atomic(naga_module: &naga::Module, naga_type: &naga::Type) -> bool {
+  match naga_type.inner {
+    naga::TypeInner::Atomic(_) => true,
+    naga::TypeInner::Array { base, .. } => is_atomic(naga_module, &naga_module.types[base]),
+    _ => false,
+  }
+}
+
+/// How the `write_{member}` helpers write a member with bytemuck.
+enum MemberWrite {
+  /// The Rust type of the member is `Pod`.
+  Pod,
+  /// A `Pod` type for the value in place of the padded Rust type of the member, like
+  /// `glam::Vec3` for `glam::Vec3A`, with the statements writing the `value` at `offset`.
+  Unpadded(TokenStream, TokenStream),
+  /// The Rust type of the member might not be `Pod`, like for the `type_overrides`.
+  Unknown,
+}
+
+fn member_write(
+  naga_module: &naga::Module,
+  naga_type: &naga::Type,
+  options: &WgslBindgenOption,
+) -> MemberWrite {
+  let write_bytes = quote! {
+    queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+  };
+  let write_each = |stride: u32, write: TokenStream| {
+    let stride = Literal::u32_unsuffixed(stride);
+    quote! {
+      for (i, value) in value.iter().enumerate() {
+        let offset = offset + i as u64 * #stride;
+        #write
+      }
+    }
+  };
+
+  match &naga_type.inner {
+    naga::TypeInner::Vector { size, scalar } => {
+      match rust_unpadded_vector_type(*size, *scalar, options) {
+        Some(vector) => MemberWrite::Unpadded(vector, write_bytes),
+        None => MemberWrite::Pod,
+      }
+    }
+    naga::TypeInner::Matrix {
+      columns,
+      rows,
+      scalar,
+    } => match rust_unpadded_matrix_column_type(*columns, *rows, *scalar, options) {
+      Some((column, stride)) => {
+        let count = Index::from(*columns as usize);
+        MemberWrite::Unpadded(quote!([#column; #count]), write_each(stride, write_bytes))
+      }
+      None => MemberWrite::Pod,
+    },
+    naga::TypeInner::Array {
+      base,
+      size: naga::ArraySize::Constant(count),
+      stride,
+    } => match member_write(naga_module, &naga_module.types[*base], options) {
+      MemberWrite::Unpadded(element, write) => {
+        let count = Index::from(count.get() as usize);
+        MemberWrite::Unpadded(quote!([#element; #count]), write_each(*stride, write))
+      }
+      write => write,
+    },
+    naga::TypeInner::Struct { .. } => {
+      let name = demangle(naga_type.name.as_ref().unwrap());
+      if options.type_overrides.contains_key(name.as_ref()) {
+        MemberWrite::Unknown
+      } else {
+        MemberWrite::Pod
+      }
+    }
+    _ => MemberWrite::Pod,
+  }
+}
+
 #[derive(Clone)]
 pub struct RustStructMemberEntryPadding {
   pub pad_name: Ident,
@@ -24,10 +151,10 @@ impl RustStructMemberEntryPadding {
     quote!(#pad_name: [0; #pad_size])
   }
 
-  fn generate_member_definition(&self) -> TokenStream {
+  fn generate_member_definition(&self, visibility: &TokenStream) -> TokenStream {
     let pad_name = &self.pad_name;
     let pad_size = &self.pad_size_tokens;
-    quote!(pub #pad_name: [u8; #pad_size])
+    quote!(#visibility #pad_name: [u8; #pad_size])
   }
 }
 
@@ -39,6 +166,7 @@ struct NagaToRustStructState<'a> {
 
 impl<'a> NagaToRustStructState<'a> {
   fn create_fold(
+    wgsl_struct_name: Cow<'a, str>,
     naga_members: &'a [StructMember],
     naga_module: &'a naga::Module,
     options: &'a WgslBindgenOption,
@@ -49,30 +177,49 @@ impl<'a> NagaToRustStructState<'a> {
     let fold = move |mut state: NagaToRustStructState<'a>,
                      naga_member: &'a StructMember|
           -> NagaToRustStructState<'a> {
-      let name_ident = Ident::new(naga_member.name.as_ref().unwrap(), Span::call_site());
+      let name =
+        options.rust_member_name(&wgsl_struct_name, naga_member.name.as_ref().unwrap());
+      let name_ident = Ident::new(&name, Span::call_site());
       let naga_type = &naga_module.types[naga_member.ty];
 
-      let rust_type = rust_type(naga_module, naga_type, &options);
+      let member_name = naga_member.name.as_ref().unwrap();
+      let mut rust_type =
+        match options.vertex_format_override(&wgsl_struct_name, member_name) {
+          Some(format) => rust_vertex_format_type(format),
+          None => rust_type(naga_module, naga_type, &options),
+        };
       let is_rsa = rust_type.size.is_none();
 
-      if is_rsa && state.index != naga_members.len() - 1 {
-        panic!("Only the last field of a struct can be a runtime-sized array");
+      debug_assert!(
+        !is_rsa || state.index == naga_members.len() - 1,
+        "only the last member can be a runtime-sized array, see `rust_struct`"
+      );
+
+      let current_offset = naga_member.offset as usize;
+      let next_offset = if state.index + 1 < naga_members.len() {
+        naga_members[state.index + 1].offset as usize
+      } else {
+        layout_size
+      };
+      let required_member_size = next_offset - current_offset;
+
+      // The next member is packed into the trailing padding of this vector.
+      if is_directly_sharable
+        && rust_type
+          .size_after_alignment()
+          .is_some_and(|size| size > required_member_size)
+      {
+        if let Some(packed) = rust_packed_vector_type(naga_type, options) {
+          rust_type = packed;
+        }
       }
 
       // check if we need padding bytes
       let padding = if is_rsa || !is_directly_sharable {
         None
       } else {
-        let current_offset = naga_member.offset as usize;
-        let next_offset = if state.index + 1 < naga_members.len() {
-          naga_members[state.index + 1].offset as usize
-        } else {
-          layout_size
-        };
         let rust_type = &rust_type;
-
-        let pad_name = format!("_pad_{}", naga_member.name.clone().unwrap());
-        let required_member_size = next_offset - current_offset;
+        let pad_name = format!("_pad_{}", name_ident);
 
         match rust_type.size_after_alignment() {
           Some(rust_type_size) if required_member_size == rust_type_size => None,
@@ -128,10 +275,10 @@ impl<'a> RustStructMemberEntry<'a> {
     quote!(#name: #other_struct_var_name.#name)
   }
 
-  fn generate_member_definition(&self) -> TokenStream {
+  fn generate_member_definition(&self, visibility: &TokenStream) -> TokenStream {
     let name = &self.name_ident;
     let ty = &self.rust_type;
-    quote!(pub #name: #ty)
+    quote!(#visibility #name: #ty)
   }
 
   fn generate_fn_new_param(&self) -> TokenStream {
@@ -141,6 +288,7 @@ impl<'a> RustStructMemberEntry<'a> {
   }
 
   fn from_naga(
+    wgsl_struct_name: Cow<'a, str>,
     naga_members: &'a [naga::StructMember],
     naga_module: &'a naga::Module,
     options: &'a WgslBindgenOption,
@@ -150,6 +298,7 @@ impl<'a> RustStructMemberEntry<'a> {
     let state = naga_members.iter().fold(
       NagaToRustStructState::default(),
       NagaToRustStructState::create_fold(
+        wgsl_struct_name,
         naga_members,
         naga_module,
         options,
@@ -161,14 +310,36 @@ impl<'a> RustStructMemberEntry<'a> {
   }
 }
 
+/// The default value of a member with encase, creating arrays element by element as
+/// `Default` is only implemented for arrays of up to 32 elements.
+fn default_member_value(ty: &syn::Type) -> TokenStream {
+  match ty {
+    syn::Type::Array(array) => {
+      let element = default_member_value(&array.elem);
+      quote!(std::array::from_fn(|_| #element))
+    }
+    syn::Type::Group(group) => default_member_value(&group.elem),
+    syn::Type::Verbatim(tokens) => match syn::parse2::<syn::Type>(tokens.clone()) {
+      Ok(syn::Type::Verbatim(_)) | Err(_) => quote!(Default::default()),
+      Ok(ty) => default_member_value(&ty),
+    },
+    _ => quote!(Default::default()),
+  }
+}
+
 pub struct RustStructBuilder<'a> {
   name: Cow<'a, str>,
+  /// The demangled WGSL name, including the module path for imported structs.
+  wgsl_name: Cow<'a, str>,
   members: Vec<RustStructMemberEntry<'a>>,
   is_host_sharable: bool,
   has_rts_array: bool,
+  /// Whether the struct is the type of a uniform buffer.
+  is_uniform: bool,
   naga_module: &'a naga::Module,
   layout: naga::proc::TypeLayout,
   options: &'a WgslBindgenOption,
+  docs: Option<&'a StructDocs>,
 }
 
 impl<'a> RustStructBuilder<'a> {
@@ -176,6 +347,12 @@ impl<'a> RustStructBuilder<'a> {
     Ident::new(self.name.as_ref(), Span::call_site())
   }
 
+  fn visibility(&self) -> TokenStream {
+    // Imported structs are nested in modules following their module path.
+    let (mod_path, _) = demangle_splitting_mod_path_and_item(&self.wgsl_name);
+    self.options.items_visibility_tokens(mod_path.as_deref())
+  }
+
   fn is_directly_shareable(&self) -> bool {
     self.options.serialization_strategy == WgslTypeSerializeStrategy::Bytemuck
       && self.is_host_sharable
@@ -186,10 +363,23 @@ impl<'a> RustStructBuilder<'a> {
       && self.options.serialization_strategy == WgslTypeSerializeStrategy::Bytemuck
   }
 
+  /// The derive macros can't verify the padding of generic structs, and reject the
+  /// implicit padding of the structs only used as vertex inputs.
+  fn derives_bytemuck(&self) -> bool {
+    self.options.derive_bytemuck
+      && self.options.serialization_strategy == WgslTypeSerializeStrategy::Bytemuck
+      && self.is_host_sharable
+      && !self.uses_generics_for_rts()
+  }
+
   fn uses_padding(&self) -> bool {
     self.members.iter().any(|m| m.padding.is_some())
   }
 
+  fn hides_padding(&self) -> bool {
+    self.options.hide_padding && self.uses_padding()
+  }
+
   fn struct_name_in_usage_fragment(&self) -> TokenStream {
     let ident = self.name_ident();
 
@@ -230,6 +420,26 @@ impl<'a> RustStructBuilder<'a> {
     }
   }
 
+  fn builder_name_in_usage_fragment(&self) -> TokenStream {
+    let name = format!("{}Builder", self.name);
+    let ident = Ident::new(&name, Span::call_site());
+    if self.uses_generics_for_rts() {
+      quote!(#ident<N>)
+    } else {
+      quote!(#ident)
+    }
+  }
+
+  fn builder_name_in_definition_fragment(&self) -> TokenStream {
+    let name = format!("{}Builder", self.name);
+    let ident = Ident::new(&name, Span::call_site());
+    if self.uses_generics_for_rts() {
+      quote!(#ident<const N: usize>)
+    } else {
+      quote!(#ident)
+    }
+  }
+
   fn impl_trait_for_fragment(&self) -> TokenStream {
     if self.uses_generics_for_rts() {
       quote!(impl<const N:usize>)
@@ -238,8 +448,99 @@ impl<'a> RustStructBuilder<'a> {
     }
   }
 
+  /// Helpers for writing structs with a runtime-sized array without hand-rolling its tail.
+  fn build_runtime_array_helpers(&self) -> TokenStream {
+    if !self.options.generate_runtime_array_helpers
+      || self.options.no_std
+      || !self.has_rts_array
+      || !self.is_host_sharable
+    {
+      return quote!();
+    }
+
+    let visibility = self.visibility();
+
+    if !self.options.serialization_strategy.is_bytemuck() {
+      // encase already supports runtime-sized arrays with a `Vec`.
+      let struct_name = self.name_ident();
+      return quote! {
+        impl #struct_name {
+          #visibility fn to_bytes(&self) -> Vec<u8> {
+            let mut buffer = encase::StorageBuffer::new(Vec::new());
+            buffer.write(self).unwrap();
+            buffer.into_inner()
+          }
+        }
+      };
+    }
+
+    let vec_struct_name = Ident::new(&format!("{}Vec", self.name), Span::call_site());
+    let (array, header) = self.members.split_last().unwrap();
+    let (element_type, stride) = match array.naga_type.inner {
+      naga::TypeInner::Array { base, stride, .. } => {
+        let element =
+          rust_type(self.naga_module, &self.naga_module.types[base], self.options);
+        (element, Index::from(stride as usize))
+      }
+      _ => unreachable!("the last member is a runtime-sized array"),
+    };
+    let array_name = &array.name_ident;
+    let array_offset = Index::from(array.naga_member.offset as usize);
+    let alignment = Index::from(self.layout.alignment.round_up(1) as usize);
+
+    let header_members: Vec<_> = header
+      .iter()
+      .map(|m| m.generate_member_definition(&visibility))
+      .collect();
+    let header_writes: Vec<_> = header
+      .iter()
+      .map(|m| {
+        let name = &m.name_ident;
+        let ty = &m.rust_type;
+        let offset = Index::from(m.naga_member.offset as usize);
+        quote! {
+          bytes[#offset..#offset + core::mem::size_of::<#ty>()]
+            .copy_from_slice(bytemuck::bytes_of(&self.#name));
+        }
+      })
+      .collect();
+
+    quote! {
+      #[derive(Debug, PartialEq, Clone)]
+      #visibility struct #vec_struct_name {
+        #(#header_members,)*
+        #visibility #array_name: Vec<#element_type>
+      }
+
+      impl #vec_struct_name {
+        /// The bytes following the WGSL layout, with at least one array element.
+        #visibility fn to_bytes(&self) -> Vec<u8> {
+          let len = self.#array_name.len().max(1);
+          let size = (#array_offset + len * #stride).next_multiple_of(#alignment);
+          let mut bytes = vec![0u8; size];
+          #(#header_writes)*
+          for (i, element) in self.#array_name.iter().enumerate() {
+            let offset = #array_offset + i * #stride;
+            bytes[offset..offset + core::mem::size_of::<#element_type>()]
+              .copy_from_slice(bytemuck::bytes_of(element));
+          }
+          bytes
+        }
+      }
+    }
+  }
+
   fn build_init_struct(&self) -> TokenStream {
-    if !self.is_directly_shareable() || !self.uses_padding() {
+    let should_generate = match self.options.init_struct_strategy {
+      WgslInitStructStrategy::Never => false,
+      WgslInitStructStrategy::WhenPadded => {
+        self.is_directly_shareable() && self.uses_padding()
+      }
+      WgslInitStructStrategy::Always => {
+        !self.has_rts_array || self.options.serialization_strategy.is_bytemuck()
+      }
+    };
+    if !should_generate {
       return quote!();
     }
 
@@ -253,9 +554,10 @@ impl<'a> RustStructBuilder<'a> {
     let mut mem_assignments = vec![];
 
     let init_var_name = Ident::new("self", Span::call_site());
+    let visibility = self.visibility();
 
     for entry in self.members.iter() {
-      init_struct_members.push(entry.generate_member_definition());
+      init_struct_members.push(entry.generate_member_definition(&visibility));
       mem_assignments.push(entry.generate_member_instantiate(&init_var_name));
 
       for pad in entry.padding.iter() {
@@ -266,12 +568,12 @@ impl<'a> RustStructBuilder<'a> {
     quote! {
       #[repr(C)]
       #[derive(Debug, PartialEq, Clone, Copy)]
-      pub struct #init_struct_name_def {
+      #visibility struct #init_struct_name_def {
         #(#init_struct_members),*
       }
 
       #impl_fragment #init_struct_name_usage {
-        pub const fn const_into(&self) -> #struct_name_usage {
+        #visibility const fn const_into(&self) -> #struct_name_usage {
           #struct_name {
             #(#mem_assignments),*
           }
@@ -286,9 +588,310 @@ impl<'a> RustStructBuilder<'a> {
     }
   }
 
+  fn build_struct_builder(&self) -> TokenStream {
+    if !self.options.generate_struct_builders {
+      return quote!();
+    }
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name = self.name_ident();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let builder_name_def = self.builder_name_in_definition_fragment();
+    let builder_name_usage = self.builder_name_in_usage_fragment();
+    let visibility = self.visibility();
+
+    let mut builder_members = Vec::new();
+    let mut setters = Vec::new();
+    let mut new_args = Vec::new();
+
+    for entry in &self.members {
+      let name = &entry.name_ident;
+      let ty = &entry.rust_type;
+      let missing = name.to_string();
+
+      builder_members.push(quote!(#name: Option<#ty>));
+      setters.push(quote! {
+        #visibility fn #name(mut self, #name: #ty) -> Self {
+          self.#name = Some(#name);
+          self
+        }
+      });
+      new_args.push(quote!(self.#name.ok_or(#missing)?));
+    }
+
+    quote! {
+      #[derive(Debug, Default, Clone)]
+      #visibility struct #builder_name_def {
+        #(#builder_members),*
+      }
+
+      #impl_fragment #builder_name_usage {
+        #(#setters)*
+
+        /// Builds the struct, or returns the name of the first field which wasn't set.
+        #visibility fn build(self) -> Result<#struct_name_usage, &'static str> {
+          Ok(#struct_name::new(#(#new_args),*))
+        }
+      }
+
+      #impl_fragment #struct_name_usage {
+        #visibility fn builder() -> #builder_name_usage {
+          Default::default()
+        }
+      }
+    }
+  }
+
+  fn build_byte_helpers(&self) -> TokenStream {
+    if !self.options.generate_byte_helpers
+      || self.options.serialization_strategy != WgslTypeSerializeStrategy::Bytemuck
+    {
+      return quote!();
+    }
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let visibility = self.visibility();
+
+    quote! {
+      #impl_fragment #struct_name_usage {
+        #visibility fn as_bytes(&self) -> &[u8] {
+          bytemuck::bytes_of(self)
+        }
+
+        /// Panics if `bytes` has the wrong size or alignment.
+        #visibility fn from_bytes(bytes: &[u8]) -> &Self {
+          bytemuck::from_bytes(bytes)
+        }
+
+        #visibility fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+          bytemuck::cast_slice(slice)
+        }
+
+        /// Panics if `bytes` has the wrong size or alignment.
+        #visibility fn slice_from_bytes(bytes: &[u8]) -> &[Self] {
+          bytemuck::cast_slice(bytes)
+        }
+      }
+    }
+  }
+
+  /// Helpers wrapping the encase buffers, for the structs used in uniform or storage buffers.
+  fn build_encase_helpers(&self) -> TokenStream {
+    if !self.options.generate_encase_helpers
+      || self.options.no_std
+      || !self.options.serialization_strategy.is_encase()
+      || !self.is_host_sharable
+    {
+      return quote!();
+    }
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let visibility = self.visibility();
+
+    // Runtime-sized arrays have no fixed size and can't be in uniform buffers.
+    let sized_helpers = if self.has_rts_array {
+      quote!()
+    } else {
+      quote! {
+        /// The size of the struct in a buffer, with the padding of the WGSL layout.
+        #visibility const SHADER_SIZE: u64 = <Self as encase::ShaderSize>::SHADER_SIZE.get();
+
+        /// The bytes of the struct in a uniform buffer.
+        #visibility fn write_into_uniform(&self) -> Vec<u8> {
+          let mut buffer = encase::UniformBuffer::new(Vec::new());
+          buffer.write(self).unwrap();
+          buffer.into_inner()
+        }
+      }
+    };
+
+    quote! {
+      #impl_fragment #struct_name_usage {
+        #sized_helpers
+
+        /// The bytes of the struct in a storage buffer.
+        #visibility fn write_into_storage(&self) -> Vec<u8> {
+          let mut buffer = encase::StorageBuffer::new(Vec::new());
+          buffer.write(self).unwrap();
+          buffer.into_inner()
+        }
+      }
+    }
+  }
+
+  fn build_aligned_size(&self) -> TokenStream {
+    if !self.options.generate_aligned_sizes || !self.is_uniform || self.has_rts_array {
+      return quote!();
+    }
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let visibility = self.visibility();
+    let size = Literal::u64_suffixed(self.layout.size as u64);
+    let default_alignment = Literal::u32_unsuffixed(DYNAMIC_OFFSET_ALIGNMENT);
+
+    quote! {
+      #impl_fragment #struct_name_usage {
+        /// The size of the struct rounded up to the alignment of dynamic offsets, like the
+        /// `min_uniform_buffer_offset_alignment` limit, for the stride between instances.
+        #visibility const fn aligned_size(alignment: u32) -> u64 {
+          #size.next_multiple_of(alignment as u64)
+        }
+
+        /// The size of the struct rounded up to the default alignment of dynamic offsets.
+        #visibility const ALIGNED_SIZE: u64 = Self::aligned_size(#default_alignment);
+      }
+    }
+  }
+
+  /// The `{struct}_offsets` module, for updating members of a struct in a buffer.
+  fn build_field_offsets(&self) -> TokenStream {
+    if !self.options.generate_field_offsets || !self.is_host_sharable {
+      return quote!();
+    }
+
+    let struct_name = self.name_ident();
+    let mod_name = format!("{}_offsets", self.name.to_snake());
+    let mod_name = Ident::new(&mod_name, Span::call_site());
+    let (mod_path, _) = demangle_splitting_mod_path_and_item(&self.wgsl_name);
+    let visibility = self.visibility();
+    let item_visibility = self
+      .options
+      .nested_items_visibility_tokens(mod_path.as_deref());
+
+    let items = self.members.iter().map(|member| {
+      let name = member.name_ident.to_string();
+      let name = name.trim_start_matches("r#");
+      let const_name = Ident::new(&name.to_uppercase(), Span::call_site());
+      let offset = Literal::u64_unsuffixed(member.naga_member.offset as u64);
+      let offset_const = quote!(#item_visibility const #const_name: u64 = #offset;);
+
+      // The runtime-sized arrays are written with the whole struct.
+      if member.is_rsa || self.options.no_std {
+        return offset_const;
+      }
+
+      let write_name = Ident::new(&format!("write_{name}"), Span::call_site());
+      let doc = format!(
+        " Writes `{name}` to the [{struct_name}] at `base_offset` in the buffer."
+      );
+      let ty = &member.rust_type;
+      let (unsafety, ty, write) = match self.options.serialization_strategy {
+        WgslTypeSerializeStrategy::Bytemuck => {
+          match member_write(self.naga_module, member.naga_type, self.options) {
+            MemberWrite::Pod => (
+              item_visibility.clone(),
+              quote!(#ty),
+              quote!(queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));),
+            ),
+            MemberWrite::Unpadded(ty, write) => (item_visibility.clone(), ty, write),
+            // The bytes of the overrides are only read when they hold the WGSL layout.
+            MemberWrite::Unknown => {
+              let size = member.naga_type.inner.size(self.naga_module.to_ctx());
+              let safety = [
+                String::new(),
+                " # Safety".to_owned(),
+                String::new(),
+                format!(
+                  " `value` must have the layout of the WGSL member, with all its {size} \
+                   bytes initialized."
+                ),
+              ];
+              let size = Literal::usize_unsuffixed(size as usize);
+              let write = quote! {
+                let bytes = unsafe {
+                  std::slice::from_raw_parts((value as *const #ty).cast::<u8>(), #size)
+                };
+                queue.write_buffer(buffer, offset, bytes);
+              };
+              let unsafety = quote!(#(#[doc = #safety])* #item_visibility unsafe);
+              (unsafety, quote!(#ty), write)
+            }
+          }
+        }
+        WgslTypeSerializeStrategy::Encase => {
+          let write = quote! {
+            let mut bytes = encase::StorageBuffer::new(Vec::new());
+            bytes.write(value).unwrap();
+            queue.write_buffer(buffer, offset, &bytes.into_inner());
+          };
+          (item_visibility.clone(), quote!(#ty), write)
+        }
+      };
+
+      quote! {
+        #offset_const
+
+        #[doc = #doc]
+        #unsafety fn #write_name(
+          queue: &wgpu::Queue,
+          buffer: &wgpu::Buffer,
+          base_offset: u64,
+          value: &#ty,
+        ) {
+          let offset = base_offset + #const_name;
+          #write
+        }
+      }
+    });
+
+    let doc = format!(" The offsets of the members of [{struct_name}] in bytes.");
+    quote! {
+      #[doc = #doc]
+      #visibility mod #mod_name {
+        #[allow(unused_imports)]
+        use super::*;
+
+        #(#items)*
+      }
+    }
+  }
+
+  fn build_default_impl(&self) -> TokenStream {
+    // Structs with hidden padding can't be constructed with a struct expression.
+    if !self.options.impl_default && !self.hides_padding() {
+      return quote!();
+    }
+
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let impl_fragment = self.impl_trait_for_fragment();
+
+    let default_value = match self.options.serialization_strategy {
+      // Also zeroes the padding, which `Default` isn't implemented for when large.
+      WgslTypeSerializeStrategy::Bytemuck => quote!(bytemuck::Zeroable::zeroed()),
+      WgslTypeSerializeStrategy::Encase => {
+        let members = self.members.iter().map(|entry| {
+          let name = &entry.name_ident;
+          let default_value = default_member_value(&entry.rust_type);
+          quote!(#name: #default_value)
+        });
+        quote!(Self { #(#members),* })
+      }
+    };
+
+    quote! {
+      #impl_fragment Default for #struct_name_usage {
+        fn default() -> Self {
+          #default_value
+        }
+      }
+    }
+  }
+
   fn build_fn_new(&self) -> TokenStream {
     let struct_name_usage = self.struct_name_in_usage_fragment();
     let impl_fragment = self.impl_trait_for_fragment();
+    let visibility = self.visibility();
+    // Encase uses a `Vec` for runtime-sized arrays.
+    let constness = if self.options.const_fn_new
+      && !(self.has_rts_array && self.options.serialization_strategy.is_encase())
+    {
+      quote!(const)
+    } else {
+      quote!()
+    };
 
     let mut non_padding_members = Vec::new();
     let mut member_assignments = Vec::new();
@@ -305,7 +908,7 @@ impl<'a> RustStructBuilder<'a> {
 
     quote! {
       #impl_fragment #struct_name_usage {
-        pub fn new(
+        #visibility #constness fn new(
           #(#non_padding_members),*
         ) -> Self {
           Self {
@@ -316,8 +919,97 @@ impl<'a> RustStructBuilder<'a> {
     }
   }
 
+  /// The `From` impls in both directions between the struct and the user types of
+  /// `struct_conversions`, converting each field with `Into`.
+  fn build_conversions(&self) -> TokenStream {
+    let Some(conversions) = self.options.struct_conversions.get(self.wgsl_name.as_ref())
+    else {
+      return quote!();
+    };
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let conversions = conversions.iter().map(|conversion| {
+      let rust_type = &conversion.rust_type;
+      let (members, fields): (Vec<_>, Vec<_>) = self
+        .members
+        .iter()
+        .map(|entry| {
+          let name = &entry.name_ident;
+          let member_name = entry.naga_member.name.as_ref().unwrap();
+          let field = match conversion.fields.get(member_name) {
+            Some(field) => syn::parse_str::<syn::Member>(field).unwrap(),
+            None => syn::Member::Named(name.clone()),
+          };
+          (name, field)
+        })
+        .unzip();
+
+      quote! {
+        #impl_fragment From<#rust_type> for #struct_name_usage {
+          fn from(value: #rust_type) -> Self {
+            Self::new(#(value.#fields.into()),*)
+          }
+        }
+
+        #impl_fragment From<#struct_name_usage> for #rust_type {
+          fn from(value: #struct_name_usage) -> Self {
+            Self {
+              #(#fields: value.#members.into()),*
+            }
+          }
+        }
+      }
+    });
+
+    quote!(#(#conversions)*)
+  }
+
+  fn build_accessors(&self) -> TokenStream {
+    if !self.hides_padding() {
+      return quote!();
+    }
+
+    let impl_fragment = self.impl_trait_for_fragment();
+    let struct_name_usage = self.struct_name_in_usage_fragment();
+    let visibility = self.visibility();
+
+    let accessors = self.members.iter().map(|entry| {
+      let name = &entry.name_ident;
+      let ty = &entry.rust_type;
+      let setter = Ident::new(&format!("set_{name}"), Span::call_site());
+      quote! {
+        #visibility fn #name(&self) -> &#ty {
+          &self.#name
+        }
+        #visibility fn #setter(&mut self, #name: #ty) {
+          self.#name = #name;
+        }
+      }
+    });
+
+    quote! {
+      #impl_fragment #struct_name_usage {
+        #(#accessors)*
+      }
+    }
+  }
+
   fn build_fields(&self) -> Vec<TokenStream> {
     let gctx = self.naga_module.to_ctx();
+    let serde_padding_attribute = if self.options.derive_serde
+      && self.options.serde_skip_padding
+    {
+      let default = format!("{MOD_REFERENCE_ROOT}::zeroed_padding");
+      quote!(#[serde(skip, default = #default)])
+    } else {
+      quote!()
+    };
+    let visibility = if self.hides_padding() {
+      quote!()
+    } else {
+      self.visibility()
+    };
     let members = self
       .members
       .iter()
@@ -330,17 +1022,37 @@ impl<'a> RustStructBuilder<'a> {
            naga_type,
            padding,
          }| {
-          let doc = if self.is_directly_shareable() {
+          let comments = member
+            .name
+            .as_ref()
+            .and_then(|name| self.docs?.members.get(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+          let mut paragraphs = vec![comments.to_vec()];
+          if is_atomic(self.naga_module, naga_type) {
+            paragraphs.push(vec![ATOMIC_MEMBER_DOC.to_owned()]);
+          }
+          if self.is_directly_shareable() {
             let offset = member.offset;
             let size = naga_type.inner.size(gctx);
             let ty_name = naga_type.inner.to_wgsl(&gctx);
             let doc =
               format!(" size: {}, offset: 0x{:X}, type: `{}`", size, offset, ty_name);
+            paragraphs.push(vec![doc]);
+          }
 
-            quote!(#[doc = #doc])
-          } else {
-            quote!()
-          };
+          // Keep the comments, the atomic note and the layout in their own paragraphs.
+          paragraphs.retain(|paragraph| !paragraph.is_empty());
+          let doc = paragraphs.iter().enumerate().map(|(i, paragraph)| {
+            let separator = if i == 0 {
+              quote!()
+            } else {
+              quote!(#[doc = ""])
+            };
+            quote!(#separator #(#[doc = #paragraph])*)
+          });
+          let doc = quote!(#(#doc)*);
 
           let runtime_size_attribute = if *is_rts
             && matches!(
@@ -355,11 +1067,12 @@ impl<'a> RustStructBuilder<'a> {
           let mut qs = vec![quote! {
             #doc
             #runtime_size_attribute
-            pub #name: #rust_type
+            #visibility #name: #rust_type
           }];
 
           for padding in padding.iter() {
-            qs.push(padding.generate_member_definition());
+            let definition = padding.generate_member_definition(&visibility);
+            qs.push(quote!(#serde_padding_attribute #definition));
           }
 
           quote!(#(#qs), *)
@@ -379,6 +1092,10 @@ impl<'a> RustStructBuilder<'a> {
     match self.options.serialization_strategy {
       WgslTypeSerializeStrategy::Bytemuck => {
         derives.push(quote!(Copy));
+        if self.derives_bytemuck() {
+          derives.push(quote!(bytemuck::Pod));
+          derives.push(quote!(bytemuck::Zeroable));
+        }
       }
       WgslTypeSerializeStrategy::Encase => {
         if !self.has_rts_array {
@@ -391,6 +1108,14 @@ impl<'a> RustStructBuilder<'a> {
       derives.push(quote!(serde::Serialize));
       derives.push(quote!(serde::Deserialize));
     }
+    derives.extend(self.options.additional_struct_derives.iter().cloned());
+    if let Some(struct_derives) = self
+      .options
+      .additional_struct_derives_by_name
+      .get(self.wgsl_name.as_ref())
+    {
+      derives.extend(struct_derives.iter().cloned());
+    }
     derives
   }
 
@@ -406,10 +1131,9 @@ impl<'a> RustStructBuilder<'a> {
       .members
       .iter()
       .map(|m| {
-        let m = m.naga_member;
-        let name = Ident::new(m.name.as_ref().unwrap(), Span::call_site());
+        let name = &m.name_ident;
         let rust_offset = quote!(std::mem::offset_of!(#struct_name, #name));
-        let wgsl_offset = Index::from(m.offset as usize);
+        let wgsl_offset = Index::from(m.naga_member.offset as usize);
         quote!(assert!(#rust_offset == #wgsl_offset);)
       })
       .collect();
@@ -427,11 +1151,48 @@ impl<'a> RustStructBuilder<'a> {
           assert!(std::mem::size_of::<#struct_name>() == #struct_size);
         };
       }
+    } else if self.is_host_sharable
+      && self.options.serialization_strategy == WgslTypeSerializeStrategy::Encase
+      && self.options.generate_encase_layout_tests
+    {
+      self.build_encase_layout_test()
     } else {
       quote!()
     }
   }
 
+  /// encase computes the layout at runtime, so it's checked by a generated test instead.
+  fn build_encase_layout_test(&self) -> TokenStream {
+    let ident = self.name_ident();
+    let test_name = Ident::new(
+      &format!("{}_encase_layout", ident.to_string().to_snake()),
+      Span::call_site(),
+    );
+
+    let members: Vec<_> = self
+      .members
+      .iter()
+      .map(|m| {
+        let offset = Index::from(m.naga_member.offset as usize);
+        (m.name_ident.clone(), offset)
+      })
+      .collect();
+    let assertions = encase_layout_assertions(
+      &quote!(#ident),
+      &members,
+      self.layout.size,
+      self.has_rts_array,
+    );
+
+    quote! {
+      #[cfg(test)]
+      #[test]
+      fn #test_name() {
+        #assertions
+      }
+    }
+  }
+
   pub fn build(&self) -> TokenStream {
     let struct_name_def = self.struct_name_in_definition_fragment();
     let struct_name_usage = self.struct_name_in_usage_fragment();
@@ -452,12 +1213,12 @@ impl<'a> RustStructBuilder<'a> {
     let derives = self.build_derives();
 
     let alignment = Index::from((self.layout.alignment * 1u32) as usize);
-    let repr_c = if !has_rts_array {
-      if should_generate_padding {
-        quote!(#[repr(C, align(#alignment))])
-      } else {
-        quote!(#[repr(C)])
-      }
+    // The bytemuck structs with runtime-sized arrays become const generics, which need the
+    // C layout like any other `Pod` struct.
+    let repr_c = if should_generate_padding {
+      quote!(#[repr(C, align(#alignment))])
+    } else if !has_rts_array {
+      quote!(#[repr(C)])
     } else {
       quote!()
     };
@@ -469,33 +1230,64 @@ impl<'a> RustStructBuilder<'a> {
         quote!()
       };
 
+    let visibility = self.visibility();
     let fields = self.build_fields();
     let struct_new_fn = self.build_fn_new();
+    let accessors = self.build_accessors();
+    let conversions = self.build_conversions();
+    let default_impl = self.build_default_impl();
+    let byte_helpers = self.build_byte_helpers();
+    let encase_helpers = self.build_encase_helpers();
+    let aligned_size = self.build_aligned_size();
+    let field_offsets = self.build_field_offsets();
     let init_struct = self.build_init_struct();
+    let runtime_array_helpers = self.build_runtime_array_helpers();
+    let struct_builder = self.build_struct_builder();
     let assert_layout = self.build_assert_layout();
 
-    let unsafe_bytemuck_pod_impl =
-      if self.options.serialization_strategy == WgslTypeSerializeStrategy::Bytemuck {
-        quote! {
-          unsafe #impl_fragment bytemuck::Zeroable for #struct_name_usage {}
-          unsafe #impl_fragment bytemuck::Pod for #struct_name_usage {}
-        }
-      } else {
-        quote!()
-      };
+    let unsafe_bytemuck_pod_impl = if self.options.serialization_strategy
+      == WgslTypeSerializeStrategy::Bytemuck
+      && !self.derives_bytemuck()
+    {
+      quote! {
+        unsafe #impl_fragment bytemuck::Zeroable for #struct_name_usage {}
+        unsafe #impl_fragment bytemuck::Pod for #struct_name_usage {}
+      }
+    } else {
+      quote!()
+    };
+
+    let comments = self.docs.map(|docs| docs.docs.as_slice()).unwrap_or_default();
+    let serde_attribute = match &self.options.serde_rename_all {
+      Some(rename_all) if self.options.derive_serde => {
+        quote!(#[serde(rename_all = #rename_all)])
+      }
+      _ => quote!(),
+    };
 
     quote! {
+        #(#[doc = #comments])*
         #ignore_case_tokens
         #repr_c
         #[derive(#(#derives),*)]
-        pub struct #struct_name_def {
+        #serde_attribute
+        #visibility struct #struct_name_def {
             #(#fields),*
         }
 
         #struct_new_fn
+        #accessors
+        #conversions
+        #default_impl
+        #byte_helpers
+        #encase_helpers
+        #aligned_size
+        #field_offsets
         #unsafe_bytemuck_pod_impl
         #assert_layout
         #init_struct
+        #runtime_array_helpers
+        #struct_builder
     }
   }
 
@@ -509,7 +1301,9 @@ impl<'a> RustStructBuilder<'a> {
     is_host_sharable: bool,
     has_rts_array: bool,
   ) -> Self {
+    let wgsl_name = demangle(naga_type.name.as_ref().unwrap());
     let members = RustStructMemberEntry::from_naga(
+      wgsl_name.clone(),
       naga_members,
       naga_module,
       options,
@@ -517,21 +1311,29 @@ impl<'a> RustStructBuilder<'a> {
       is_directly_sharable,
     );
 
-    let name = naga_type.name.as_ref().unwrap().into();
-
-    let mut builder = RustStructBuilder {
-      name,
+    RustStructBuilder {
+      name: options.rust_struct_name(&wgsl_name).into(),
+      wgsl_name,
       members,
       is_host_sharable,
       naga_module,
       options: &options,
+      docs: None,
       has_rts_array,
+      is_uniform: false,
       layout,
-    };
+    }
+  }
+
+  /// Adds the comments of the WGSL struct to the docs.
+  pub fn with_docs(mut self, docs: Option<&'a StructDocs>) -> Self {
+    self.docs = docs;
+    self
+  }
 
-    // we don't need full qualification here
-    let (_, demangled_name) = demangle_splitting_mod_path_and_item(&builder.name);
-    builder.name = demangled_name.into();
-    builder
+  /// Marks the struct as the type of a uniform buffer.
+  pub fn with_uniform(mut self, is_uniform: bool) -> Self {
+    self.is_uniform = is_uniform;
+    self
   }
 }
@@ -5,8 +5,10 @@ use strum::IntoEnumIterator;
 use syn::Index;
 
 use crate::{
-  quote_gen::demangle_and_qualify, WgslTypeSerializeStrategy, WgslType, WgslTypeMapExt,
-  WgslBindgenOption,
+  bevy_util::demangle,
+  quote_gen::{demangle_and_qualify, mod_reference_root},
+  WgslBindgenOption, WgslBoolMemberStrategy, WgslType, WgslTypeMapExt,
+  WgslTypeSerializeStrategy,
 };
 
 #[derive(Debug, Clone)]
@@ -41,9 +43,7 @@ pub(crate) fn add_prelude_types_assertions(options: &WgslBindgenOption) -> Token
 
   let assertions = WgslType::iter()
     .filter_map(|built_in| {
-      let ty = options
-        .wgsl_type_map
-        .get_rust_type_info(built_in)?;
+      let ty = mapped_rust_type_info(options, built_in)?;
       let size_after_alignment = ty.size_after_alignment()?;
 
       let alignment = Index::from(ty.alignment_value());
@@ -63,6 +63,151 @@ pub(crate) fn add_prelude_types_assertions(options: &WgslBindgenOption) -> Token
   }
 }
 
+/// The Rust type `wgsl_type_map` maps the built-in type to, taking packed types into account.
+fn mapped_rust_type_info(options: &WgslBindgenOption, ty: WgslType) -> Option<RustTypeInfo> {
+  if options.wgsl_type_map_packed_types.contains(&ty) {
+    options.wgsl_type_map.get_packed_rust_type_info(ty)
+  } else {
+    options.wgsl_type_map.get_rust_type_info(ty)
+  }
+}
+
+pub(crate) fn add_prelude_bool_type(options: &WgslBindgenOption) -> TokenStream {
+  if options.bool_member_strategy != WgslBoolMemberStrategy::U32
+    || options.serialization_strategy.is_encase()
+  {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// A `bool` stored as a `u32`, as `bool` can't be shared with the host.
+    #[repr(transparent)]
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+    #visibility struct WgslBool(#visibility u32);
+    unsafe impl bytemuck::Zeroable for WgslBool {}
+    unsafe impl bytemuck::Pod for WgslBool {}
+    impl From<bool> for WgslBool {
+      fn from(value: bool) -> Self {
+        Self(value as u32)
+      }
+    }
+    impl From<WgslBool> for bool {
+      fn from(value: WgslBool) -> Self {
+        value.0 != 0
+      }
+    }
+  }
+}
+
+pub(crate) fn add_prelude_serde_padding(options: &WgslBindgenOption) -> TokenStream {
+  if !options.derive_serde || !options.serde_skip_padding {
+    return quote!();
+  }
+
+  quote! {
+    /// The value of the padding fields skipped by serde.
+    pub fn zeroed_padding<const N: usize>() -> [u8; N] {
+      [0; N]
+    }
+  }
+}
+
+pub(crate) fn add_prelude_padded_matrix_types(options: &WgslBindgenOption) -> TokenStream {
+  let uses_padded_matrix_types = options.padded_matrix_types
+    || !options.wgsl_type_map_unpadded_matrix_types.is_empty();
+  if !uses_padded_matrix_types || options.serialization_strategy.is_encase() {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  let is_glam = options.wgsl_type_map[WgslType::Vec3f]
+    .as_ref()
+    .is_some_and(|ty| ty.to_string().starts_with("glam"));
+  let glam_conversions = if is_glam {
+    quote! {
+      impl From<glam::Mat3> for PaddedMat3 {
+        fn from(matrix: glam::Mat3) -> Self {
+          matrix.to_cols_array_2d().into()
+        }
+      }
+      impl From<PaddedMat3> for glam::Mat3 {
+        fn from(matrix: PaddedMat3) -> Self {
+          glam::Mat3::from_cols_array_2d(&matrix.into())
+        }
+      }
+    }
+  } else {
+    quote!()
+  };
+  let unpadded_conversions = options
+    .wgsl_type_map_unpadded_matrix_types
+    .iter()
+    .filter_map(|(ty, rust_type)| {
+      let (name, columns) = match ty {
+        WgslType::Mat2x3f => (quote!(PaddedMat2x3), Index::from(2)),
+        WgslType::Mat3x3f => (quote!(PaddedMat3), Index::from(3)),
+        WgslType::Mat4x3f => (quote!(PaddedMat4x3), Index::from(4)),
+        _ => return None,
+      };
+      Some(quote! {
+        impl From<#rust_type> for #name {
+          fn from(matrix: #rust_type) -> Self {
+            let columns: [[f32; 3]; #columns] = matrix.into();
+            columns.into()
+          }
+        }
+        impl From<#name> for #rust_type {
+          fn from(matrix: #name) -> Self {
+            let columns: [[f32; 3]; #columns] = matrix.into();
+            columns.into()
+          }
+        }
+      })
+    });
+
+  quote! {
+    /// A `matCx3<f32>` with `C` columns, each padded to 16 bytes like in WGSL.
+    #[repr(C)]
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #visibility struct PaddedMatCx3<const C: usize>(#visibility [[f32; 4]; C]);
+    #visibility type PaddedMat2x3 = PaddedMatCx3<2>;
+    #visibility type PaddedMat3 = PaddedMatCx3<3>;
+    #visibility type PaddedMat4x3 = PaddedMatCx3<4>;
+    unsafe impl<const C: usize> bytemuck::Zeroable for PaddedMatCx3<C> {}
+    unsafe impl<const C: usize> bytemuck::Pod for PaddedMatCx3<C> {}
+    impl<const C: usize> Default for PaddedMatCx3<C> {
+      fn default() -> Self {
+        Self([[0.0; 4]; C])
+      }
+    }
+    impl<const C: usize> From<[[f32; 3]; C]> for PaddedMatCx3<C> {
+      fn from(columns: [[f32; 3]; C]) -> Self {
+        Self(columns.map(|[x, y, z]| [x, y, z, 0.0]))
+      }
+    }
+    impl<const C: usize> From<PaddedMatCx3<C>> for [[f32; 3]; C] {
+      fn from(matrix: PaddedMatCx3<C>) -> Self {
+        matrix.0.map(|[x, y, z, _]| [x, y, z])
+      }
+    }
+    #glam_conversions
+    #(#unpadded_conversions)*
+  }
+}
+
+/// Type used in place of `bool` with [WgslBoolMemberStrategy::U32].
+fn rust_bool_as_u32_type(options: &WgslBindgenOption) -> RustTypeInfo {
+  let alignment = naga::proc::Alignment::FOUR;
+  match options.serialization_strategy {
+    WgslTypeSerializeStrategy::Encase => RustTypeInfo(quote!(u32), 4, alignment),
+    WgslTypeSerializeStrategy::Bytemuck => {
+      let root = mod_reference_root();
+      RustTypeInfo(quote!(#root::WgslBool), 4, alignment)
+    }
+  }
+}
+
 #[allow(non_snake_case)]
 pub(crate) const fn RustTypeInfo(
   tokens: TokenStream,
@@ -88,6 +233,9 @@ pub(crate) fn rust_scalar_type(
     (ScalarKind::Uint, 2) => RustTypeInfo(quote!(u16), 2, alignment),
     (ScalarKind::Sint, 4) => RustTypeInfo(quote!(i32), 4, alignment),
     (ScalarKind::Uint, 4) => RustTypeInfo(quote!(u32), 4, alignment),
+    (ScalarKind::Sint, 8) => RustTypeInfo(quote!(i64), 8, alignment),
+    (ScalarKind::Uint, 8) => RustTypeInfo(quote!(u64), 8, alignment),
+    (ScalarKind::Float, 2) => RustTypeInfo(quote!(half::f16), 2, alignment),
     (ScalarKind::Float, 4) => RustTypeInfo(quote!(f32), 4, alignment),
     (ScalarKind::Float, 8) => RustTypeInfo(quote!(f64), 8, alignment),
     // TODO: Do booleans have a width?
@@ -96,6 +244,137 @@ pub(crate) fn rust_scalar_type(
   }
 }
 
+/// Unpadded type for a vector whose trailing padding holds the next struct member, like
+/// `vec3<f32>` followed by `f32`, which padded Rust types would overlap. The type map's
+/// `packed_vector_types`, like `glam::Vec3`, are used for the vectors it maps, and arrays
+/// otherwise.
+pub(crate) fn rust_packed_vector_type(
+  ty: &naga::Type,
+  options: &WgslBindgenOption,
+) -> Option<RustTypeInfo> {
+  let naga::TypeInner::Vector { size, scalar } = &ty.inner else {
+    return None;
+  };
+
+  let alignment = naga::proc::Alignment::from_width(scalar.width);
+  let packed_size = *size as usize * scalar.width as usize;
+  let vector_alignment =
+    naga::proc::Alignment::new(naga::proc::Alignment::from(*size) * scalar.width as u32)
+      .unwrap();
+  let packed_type = map_naga_vec_type(*size, *scalar, vector_alignment).and_then(|ty| {
+    options.wgsl_type_map[ty].as_ref()?;
+    options.wgsl_type_map_packed_vector_types.get(&ty)
+  });
+  if let Some(packed_type) = packed_type {
+    return Some(RustTypeInfo(packed_type.clone(), packed_size, alignment));
+  }
+
+  let inner_type = rust_scalar_type(scalar, alignment).tokens;
+  let len = Index::from(*size as usize);
+  Some(RustTypeInfo(quote!([#inner_type; #len]), packed_size, alignment))
+}
+
+/// The unpadded `Pod` type of a `vec3` the type map maps to a type padded like in WGSL,
+/// like `glam::Vec3` for `glam::Vec3A`, whose padding bytes are uninitialized.
+pub(crate) fn rust_unpadded_vector_type(
+  size: VectorSize,
+  scalar: Scalar,
+  options: &WgslBindgenOption,
+) -> Option<TokenStream> {
+  if size != VectorSize::Tri {
+    return None;
+  }
+
+  let alignment =
+    naga::proc::Alignment::new(naga::proc::Alignment::from(size) * scalar.width as u32)
+      .unwrap();
+  let ty = map_naga_vec_type(size, scalar, alignment)?;
+  options.wgsl_type_map[ty].as_ref()?;
+  if options.wgsl_type_map_packed_types.contains(&ty) {
+    return None;
+  }
+
+  let vector = naga::Type {
+    name: None,
+    inner: naga::TypeInner::Vector { size, scalar },
+  };
+  rust_packed_vector_type(&vector, options).map(|info| info.tokens)
+}
+
+/// Like [rust_unpadded_vector_type] for the columns of a `matCx3` the type map maps to a
+/// type padded like in WGSL, like `glam::Mat3A`, with the stride of the columns.
+pub(crate) fn rust_unpadded_matrix_column_type(
+  columns: VectorSize,
+  rows: VectorSize,
+  scalar: Scalar,
+  options: &WgslBindgenOption,
+) -> Option<(TokenStream, u32)> {
+  if rows != VectorSize::Tri {
+    return None;
+  }
+
+  let stride = naga::proc::Alignment::from(rows) * scalar.width as u32;
+  let alignment = naga::proc::Alignment::new(stride).unwrap();
+  let ty = map_naga_mat_type(columns, rows, scalar, alignment)?;
+  options.wgsl_type_map[ty].as_ref()?;
+  // The unpadded matrix types are stored in the `Pod` padded matrix types instead.
+  let packed_types = &options.wgsl_type_map_packed_types;
+  let unpadded_types = &options.wgsl_type_map_unpadded_matrix_types;
+  if packed_types.contains(&ty) || unpadded_types.contains_key(&ty) {
+    return None;
+  }
+
+  let column = naga::Type {
+    name: None,
+    inner: naga::TypeInner::Vector { size: rows, scalar },
+  };
+  rust_packed_vector_type(&column, options).map(|info| (info.tokens, stride))
+}
+
+/// The components of an overridden vertex format, with normalized formats stored as
+/// their integer components, like `[u8; 4]` for `Unorm8x4`.
+pub(crate) fn rust_vertex_format_type(format: wgpu::VertexFormat) -> RustTypeInfo {
+  use wgpu::VertexFormat as F;
+  let (kind, width, count) = match format {
+    F::Uint8x2 | F::Unorm8x2 => (ScalarKind::Uint, 1, 2),
+    F::Uint8x4 | F::Unorm8x4 => (ScalarKind::Uint, 1, 4),
+    F::Sint8x2 | F::Snorm8x2 => (ScalarKind::Sint, 1, 2),
+    F::Sint8x4 | F::Snorm8x4 => (ScalarKind::Sint, 1, 4),
+    F::Uint16x2 | F::Unorm16x2 => (ScalarKind::Uint, 2, 2),
+    F::Uint16x4 | F::Unorm16x4 => (ScalarKind::Uint, 2, 4),
+    F::Sint16x2 | F::Snorm16x2 => (ScalarKind::Sint, 2, 2),
+    F::Sint16x4 | F::Snorm16x4 => (ScalarKind::Sint, 2, 4),
+    F::Float16x2 => (ScalarKind::Float, 2, 2),
+    F::Float16x4 => (ScalarKind::Float, 2, 4),
+    F::Float32 => (ScalarKind::Float, 4, 1),
+    F::Float32x2 => (ScalarKind::Float, 4, 2),
+    F::Float32x3 => (ScalarKind::Float, 4, 3),
+    F::Float32x4 => (ScalarKind::Float, 4, 4),
+    F::Uint32 => (ScalarKind::Uint, 4, 1),
+    F::Uint32x2 => (ScalarKind::Uint, 4, 2),
+    F::Uint32x3 => (ScalarKind::Uint, 4, 3),
+    F::Uint32x4 => (ScalarKind::Uint, 4, 4),
+    F::Sint32 => (ScalarKind::Sint, 4, 1),
+    F::Sint32x2 => (ScalarKind::Sint, 4, 2),
+    F::Sint32x3 => (ScalarKind::Sint, 4, 3),
+    F::Sint32x4 => (ScalarKind::Sint, 4, 4),
+    F::Float64 => (ScalarKind::Float, 8, 1),
+    F::Float64x2 => (ScalarKind::Float, 8, 2),
+    F::Float64x3 => (ScalarKind::Float, 8, 3),
+    F::Float64x4 => (ScalarKind::Float, 8, 4),
+  };
+
+  let alignment = naga::proc::Alignment::from_width(width);
+  let scalar = rust_scalar_type(&Scalar { kind, width }, alignment);
+  if count == 1 {
+    return scalar;
+  }
+
+  let inner_type = scalar.tokens;
+  let len = Index::from(count);
+  RustTypeInfo(quote!([#inner_type; #len]), count * width as usize, alignment)
+}
+
 /// Get the array stride and padding in bytes
 fn get_stride_and_padding(
   alignment: naga::proc::Alignment,
@@ -138,6 +417,9 @@ fn map_naga_vec_type(
     (Bi, Float, 2) => Some(Vec2h),
     (Tri, Float, 2) => Some(Vec3h),
     (Quad, Float, 2) => Some(Vec4h),
+    (Bi, Float, 8) => Some(Vec2d),
+    (Tri, Float, 8) => Some(Vec3d),
+    (Quad, Float, 8) => Some(Vec4d),
     _ => None,
   };
 
@@ -185,22 +467,31 @@ fn map_naga_mat_type(
   let built_in_ty = match (columns, rows, scalar.kind, scalar.width) {
     (Bi, Bi, Float, 4) => Some(Mat2x2f),
     (Bi, Bi, Float, 2) => Some(Mat2x2h),
+    (Bi, Bi, Float, 8) => Some(Mat2x2d),
     (Tri, Bi, Float, 4) => Some(Mat3x2f),
     (Tri, Bi, Float, 2) => Some(Mat3x2h),
+    (Tri, Bi, Float, 8) => Some(Mat3x2d),
     (Quad, Bi, Float, 4) => Some(Mat4x2f),
     (Quad, Bi, Float, 2) => Some(Mat4x2h),
+    (Quad, Bi, Float, 8) => Some(Mat4x2d),
     (Bi, Tri, Float, 4) => Some(Mat2x3f),
     (Bi, Tri, Float, 2) => Some(Mat2x3h),
+    (Bi, Tri, Float, 8) => Some(Mat2x3d),
     (Tri, Tri, Float, 4) => Some(Mat3x3f),
     (Tri, Tri, Float, 2) => Some(Mat3x3h),
+    (Tri, Tri, Float, 8) => Some(Mat3x3d),
     (Quad, Tri, Float, 4) => Some(Mat4x3f),
     (Quad, Tri, Float, 2) => Some(Mat4x3h),
+    (Quad, Tri, Float, 8) => Some(Mat4x3d),
     (Bi, Quad, Float, 4) => Some(Mat2x4f),
     (Bi, Quad, Float, 2) => Some(Mat2x4h),
+    (Bi, Quad, Float, 8) => Some(Mat2x4d),
     (Tri, Quad, Float, 4) => Some(Mat3x4f),
     (Tri, Quad, Float, 2) => Some(Mat3x4h),
+    (Tri, Quad, Float, 8) => Some(Mat3x4d),
     (Quad, Quad, Float, 4) => Some(Mat4x4f),
     (Quad, Quad, Float, 2) => Some(Mat4x4h),
+    (Quad, Quad, Float, 8) => Some(Mat4x4d),
     _ => None,
   };
 
@@ -218,6 +509,15 @@ pub(crate) fn rust_type(
   module: &naga::Module,
   ty: &naga::Type,
   options: &WgslBindgenOption,
+) -> RustTypeInfo {
+  rust_type_impl(module, ty, options, true)
+}
+
+fn rust_type_impl(
+  module: &naga::Module,
+  ty: &naga::Type,
+  options: &WgslBindgenOption,
+  allow_packed: bool,
 ) -> RustTypeInfo {
   let t_handle = module.types.get(ty).unwrap();
   let mut layouter = naga::proc::Layouter::default();
@@ -228,14 +528,25 @@ pub(crate) fn rust_type(
   let alignment = type_layout.alignment;
 
   let create_rust_type = |ty: WgslType| -> Option<RustTypeInfo> {
-    let info = options
-      .wgsl_type_map
-      .get_rust_type_info(ty)?;
+    if options.wgsl_type_map_packed_types.contains(&ty) {
+      // Array elements can't be padded explicitly, unlike struct members.
+      let allow_packed = allow_packed || options.serialization_strategy.is_encase();
+      return allow_packed
+        .then(|| options.wgsl_type_map.get_packed_rust_type_info(ty))
+        .flatten();
+    }
+    let info = options.wgsl_type_map.get_rust_type_info(ty)?;
     assert!(alignment == info.alignment);
     Some(info)
   };
 
   match &ty.inner {
+    naga::TypeInner::Scalar(naga::Scalar {
+      kind: ScalarKind::Bool,
+      ..
+    }) if options.bool_member_strategy == WgslBoolMemberStrategy::U32 => {
+      rust_bool_as_u32_type(options)
+    }
     naga::TypeInner::Scalar(scalar) => rust_scalar_type(scalar, alignment),
     naga::TypeInner::Vector { size, scalar } => {
       let rust_type =
@@ -256,8 +567,8 @@ pub(crate) fn rust_type(
       rows,
       scalar,
     } => {
-      let rust_type =
-        map_naga_mat_type(*columns, *rows, *scalar, alignment).and_then(create_rust_type);
+      let wgsl_type = map_naga_mat_type(*columns, *rows, *scalar, alignment);
+      let rust_type = wgsl_type.and_then(create_rust_type);
 
       if let Some(ty) = rust_type {
         ty
@@ -270,6 +581,26 @@ pub(crate) fn rust_type(
         let size = col_array_stride * (*columns as u32);
 
         let cols = Index::from(*columns as usize);
+        let is_padded_f32_matrix = *rows == VectorSize::Tri && *scalar == Scalar::F32;
+        // The matrices of the type map lacking the column padding convert from the padded types.
+        let is_unpadded_in_type_map = wgsl_type.is_some_and(|ty| {
+          options
+            .wgsl_type_map_unpadded_matrix_types
+            .contains_key(&ty)
+        });
+        if is_padded_f32_matrix
+          && (options.padded_matrix_types || is_unpadded_in_type_map)
+          && options.serialization_strategy.is_bytemuck()
+        {
+          let root = mod_reference_root();
+          let name = match columns {
+            VectorSize::Bi => quote!(PaddedMat2x3),
+            VectorSize::Tri => quote!(PaddedMat3),
+            VectorSize::Quad => quote!(PaddedMat4x3),
+          };
+          return RustTypeInfo(quote!(#root::#name), size as usize, alignment);
+        }
+
         let rows = Index::from((col_array_stride / scalar.width as u32) as usize);
         RustTypeInfo(quote!([[#inner_type; #rows]; #cols]), size as usize, alignment)
       }
@@ -284,7 +615,7 @@ pub(crate) fn rust_type(
       size: naga::ArraySize::Constant(size),
       stride,
     } => {
-      let inner_ty = rust_type(module, &module.types[*base], options);
+      let inner_ty = rust_type_impl(module, &module.types[*base], options, false);
       let count = Index::from(size.get() as usize);
 
       RustTypeInfo(quote!([#inner_ty; #count]), *stride as usize, alignment)
@@ -295,7 +626,7 @@ pub(crate) fn rust_type(
       ..
     } => {
       // panic!("Runtime-sized arrays can only be used in variable declarations or as the last field of a struct.");
-      let element_type = rust_type(module, &module.types[*base], &options);
+      let element_type = rust_type_impl(module, &module.types[*base], options, false);
       let member_type = match options.serialization_strategy {
         WgslTypeSerializeStrategy::Encase => {
           quote!(Vec<#element_type>)
@@ -315,7 +646,18 @@ pub(crate) fn rust_type(
       span: _,
     } => {
       // TODO: Support structs?
-      let name = demangle_and_qualify(ty.name.as_ref().unwrap());
+      let name_str = ty.name.as_ref().unwrap();
+      let demangled = demangle(name_str);
+      let name = match options.type_overrides.get(demangled.as_ref()) {
+        Some(rust_type) => rust_type.clone(),
+        None => {
+          let rust_name = options.rust_struct_name(&demangled);
+          match demangled.rsplit_once("::") {
+            Some((mod_path, _)) => demangle_and_qualify(&format!("{mod_path}::{rust_name}")),
+            None => demangle_and_qualify(&rust_name),
+          }
+        }
+      };
       RustTypeInfo(name, type_layout.size as usize, alignment)
     }
     naga::TypeInner::BindingArray { base: _, size: _ } => todo!(),
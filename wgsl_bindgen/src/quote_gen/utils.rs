@@ -1,4 +1,4 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Group, Spacing, TokenStream, TokenTree};
 
 use super::constants::MOD_REFERENCE_ROOT;
 use crate::bevy_util::{demangle, demangle_splitting_mod_path_and_item};
@@ -37,6 +37,48 @@ pub(crate) fn demangle_and_qualify(string: &str) -> TokenStream {
   }
 }
 
+/// Replaces the crate at the start of the paths with `crate_path`, like
+/// `bevy::render::render_resource` for the `wgpu` of `wgpu::Device`.
+pub(crate) fn replace_crate_path(
+  tokens: TokenStream,
+  crate_name: &str,
+  crate_path: &TokenStream,
+) -> TokenStream {
+  let tokens: Vec<_> = tokens.into_iter().collect();
+  let mut output = TokenStream::new();
+  for (i, token) in tokens.iter().enumerate() {
+    match token {
+      TokenTree::Group(group) => {
+        let stream = replace_crate_path(group.stream(), crate_name, crate_path);
+        let mut replaced = Group::new(group.delimiter(), stream);
+        replaced.set_span(group.span());
+        output.extend([TokenTree::Group(replaced)]);
+      }
+      // Skip the crates of other paths, like `naga_oil::wgpu`.
+      TokenTree::Ident(ident)
+        if ident == crate_name
+          && is_path_separator(tokens.get(i + 1), Spacing::Joint)
+          && !(i >= 2
+            && is_path_separator(tokens.get(i - 1), Spacing::Alone)
+            && is_path_separator(tokens.get(i - 2), Spacing::Joint)) =>
+      {
+        output.extend(crate_path.clone());
+      }
+      token => output.extend([token.clone()]),
+    }
+  }
+  output
+}
+
+/// Whether the token is the first `:` of a `::` for `Spacing::Joint`, or the second one
+/// for `Spacing::Alone`.
+fn is_path_separator(token: Option<&TokenTree>, spacing: Spacing) -> bool {
+  matches!(
+    token,
+    Some(TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == spacing
+  )
+}
+
 /// Represents a Rust source item.
 pub(crate) struct RustSourceItem {
   /// If not present this item belongs at the source root
@@ -70,8 +112,9 @@ impl RustSourceItem {
 #[cfg(test)]
 mod tests {
   use pretty_assertions::assert_eq;
+  use quote::quote;
 
-  use super::demangle_and_qualify;
+  use super::{demangle_and_qualify, replace_crate_path};
 
   #[test]
   fn should_fully_qualify_mangled_string() {
@@ -86,4 +129,26 @@ mod tests {
     let actual = demangle_and_qualify(string);
     assert_eq!(actual.to_string(), "MatricesF64");
   }
+
+  #[test]
+  fn should_replace_crate_path() {
+    let tokens = quote! {
+      fn create(device: &wgpu::Device, label: wgpu::Label) -> Vec<wgpu::BindGroup> {
+        let source = other::wgpu::Source;
+        let wgpu = 0;
+      }
+    };
+    let actual =
+      replace_crate_path(tokens, "wgpu", &quote!(bevy::render::render_resource));
+    let expected = quote! {
+      fn create(
+        device: &bevy::render::render_resource::Device,
+        label: bevy::render::render_resource::Label
+      ) -> Vec<bevy::render::render_resource::BindGroup> {
+        let source = other::wgpu::Source;
+        let wgpu = 0;
+      }
+    };
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
 }
@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::bevy_util::demangle_splitting_mod_path_and_item;
+use crate::bindgroup::GroupData;
+use crate::quote_gen::rust_type;
+use crate::structs::struct_has_rts_array_member;
+use crate::WgslBindgenOption;
+
+/// The `read_buffer` function and the `ReadbackError` of the prelude, mapping a copy of
+/// a buffer.
+pub(crate) fn add_prelude_read_buffer(options: &WgslBindgenOption) -> TokenStream {
+  if !options.generate_readback_helpers {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// The errors of reading a buffer back into a Rust type.
+    #[derive(Debug)]
+    #visibility enum ReadbackError {
+      /// The staging buffer couldn't be mapped.
+      Map(wgpu::BufferAsyncError),
+      /// The buffer holds fewer bytes than the type read from it, like a runtime-sized
+      /// array with more elements than the buffer has room for.
+      BufferTooSmall { size: usize, required: usize },
+    }
+
+    impl From<wgpu::BufferAsyncError> for ReadbackError {
+      fn from(error: wgpu::BufferAsyncError) -> Self {
+        Self::Map(error)
+      }
+    }
+
+    impl std::fmt::Display for ReadbackError {
+      fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+          Self::Map(error) => write!(f, "{error}"),
+          Self::BufferTooSmall { size, required } => write!(
+            f,
+            "the buffer holds {size} bytes but {required} bytes were required"
+          ),
+        }
+      }
+    }
+
+    impl std::error::Error for ReadbackError {}
+
+    /// Copies the buffer into a staging buffer and reads it back once mapped.
+    ///
+    /// The buffer needs the `wgpu::BufferUsages::COPY_SRC` usage to be copied. The
+    /// mapping only completes when the device is polled, like with
+    /// `device.poll(wgpu::Maintain::Wait)` on native.
+    #visibility async fn read_buffer(
+      device: &wgpu::Device,
+      queue: &wgpu::Queue,
+      buffer: &wgpu::Buffer,
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+      let size = buffer.size();
+      let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback staging buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+      });
+      let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("readback encoder"),
+      });
+      encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+      queue.submit(Some(encoder.finish()));
+
+      // The result of the mapping and the waker of the task awaiting it.
+      let state = std::sync::Arc::new(std::sync::Mutex::new((
+        None,
+        None::<std::task::Waker>,
+      )));
+      let callback_state = state.clone();
+      let slice = staging.slice(..);
+      slice.map_async(wgpu::MapMode::Read, move |result| {
+        let mut state = callback_state.lock().unwrap();
+        state.0 = Some(result);
+        if let Some(waker) = state.1.take() {
+          waker.wake();
+        }
+      });
+      std::future::poll_fn(|cx| {
+        let mut state = state.lock().unwrap();
+        match state.0.take() {
+          Some(result) => std::task::Poll::Ready(result),
+          None => {
+            state.1 = Some(cx.waker().clone());
+            std::task::Poll::Pending
+          }
+        }
+      })
+      .await?;
+
+      let bytes = slice.get_mapped_range().to_vec();
+      staging.unmap();
+      Ok(bytes)
+    }
+  }
+}
+
+/// A `read_{binding}` function for each read-write storage buffer, reading the contents
+/// of the buffer back into its Rust type.
+pub(crate) fn readback_functions(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_readback_helpers {
+    return quote!();
+  }
+
+  let visibility = options.item_visibility_tokens(1);
+  let functions = bind_group_data.values().flat_map(|group| {
+    group
+      .bindings
+      .iter()
+      .filter(|binding| binding.array_count.is_none())
+      .filter(|binding| match binding.address_space {
+        naga::AddressSpace::Storage { access } => {
+          access.contains(naga::StorageAccess::STORE)
+        }
+        _ => false,
+      })
+      .map(|binding| {
+        let (_, name) =
+          demangle_splitting_mod_path_and_item(binding.name.as_ref().unwrap());
+        let fn_name = Ident::new(&format!("read_{name}"), Span::call_site());
+        let (generics, ty, value) = readback_type(module, binding.binding_type, options);
+        let doc = format!(
+          " Reads the contents of `{name}` back from the buffer bound to it, which needs \
+           the `wgpu::BufferUsages::COPY_SRC` usage."
+        );
+
+        quote! {
+          #[doc = #doc]
+          #visibility async fn #fn_name #generics(
+            device: &wgpu::Device,
+            queue: &wgpu::Queue,
+            buffer: &wgpu::Buffer,
+          ) -> Result<#ty, _root::ReadbackError> {
+            let bytes = _root::read_buffer(device, queue, buffer).await?;
+            #value
+          }
+        }
+      })
+  });
+
+  quote!(#(#functions)*)
+}
+
+/// The generics and the Rust type of the readback function, with the result of reading
+/// the value from the `bytes`.
+fn readback_type(
+  module: &naga::Module,
+  ty: &naga::Type,
+  options: &WgslBindgenOption,
+) -> (TokenStream, TokenStream, TokenStream) {
+  if options.serialization_strategy.is_encase() {
+    // Runtime-sized arrays are already `Vec`s with encase.
+    let rust_type = rust_type(module, ty, options);
+    return (
+      quote!(),
+      quote!(#rust_type),
+      quote! {
+        encase::StorageBuffer::new(&bytes[..]).create().map_err(
+          |encase::internal::Error::BufferTooSmall { expected, found }| {
+            _root::ReadbackError::BufferTooSmall {
+              size: found as usize,
+              required: expected as usize,
+            }
+          },
+        )
+      },
+    );
+  }
+
+  match &ty.inner {
+    naga::TypeInner::Array {
+      base,
+      size: naga::ArraySize::Dynamic,
+      ..
+    } => {
+      let element_type = rust_type(module, &module.types[*base], options);
+      (
+        quote!(),
+        quote!(Vec<#element_type>),
+        quote! {
+          Ok(
+            bytes
+              .chunks_exact(std::mem::size_of::<#element_type>())
+              .map(bytemuck::pod_read_unaligned)
+              .collect(),
+          )
+        },
+      )
+    }
+    naga::TypeInner::Struct { members, .. }
+      if struct_has_rts_array_member(members, module) =>
+    {
+      // The number of elements of the runtime-sized array is chosen by the caller.
+      let rust_type = rust_type(module, ty, options);
+      let ty = quote!(#rust_type<N>);
+      (quote!(<const N: usize>), ty.clone(), read_pod(ty))
+    }
+    _ => {
+      let rust_type = rust_type(module, ty, options);
+      (quote!(), quote!(#rust_type), read_pod(quote!(#rust_type)))
+    }
+  }
+}
+
+/// Reads a `ty` from the `bytes`, failing when the buffer is smaller than it.
+fn read_pod(ty: TokenStream) -> TokenStream {
+  quote! {
+    let required = std::mem::size_of::<#ty>();
+    let value = bytes.get(..required).ok_or_else(|| {
+      _root::ReadbackError::BufferTooSmall {
+        size: bytes.len(),
+        required,
+      }
+    })?;
+    Ok(bytemuck::pod_read_unaligned(value))
+  }
+}
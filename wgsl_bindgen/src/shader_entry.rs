@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Ident, Index};
+
+use crate::bindgroup::GroupData;
+use crate::{indexed_name_to_ident, WgslBindgenOption, WgslShaderSourceOutputType};
+
+/// The signature of `create_shader_module` in the `ShaderEntry` trait, where the shaders
+/// loaded from the files take the directory they are loaded from.
+fn create_shader_module_signature(
+  output_type: WgslShaderSourceOutputType,
+) -> TokenStream {
+  if output_type == WgslShaderSourceOutputType::RuntimeFiles {
+    quote! {
+      fn create_shader_module(
+        &self,
+        device: &wgpu::Device,
+        dir: &std::path::Path,
+      ) -> Result<wgpu::ShaderModule, Box<dyn std::error::Error>>
+    }
+  } else {
+    quote!(fn create_shader_module(&self, device: &wgpu::Device) -> wgpu::ShaderModule)
+  }
+}
+
+/// The items of the `shader_entry` module, with the `ShaderEntry` trait implemented by the
+/// `Shader` struct of each entry point module.
+pub(crate) fn shader_entry_trait(options: &WgslBindgenOption) -> TokenStream {
+  let visibility = options.item_visibility_tokens(1);
+  let create_shader_module =
+    create_shader_module_signature(options.shader_source_output_type);
+  quote! {
+    /// The pipeline items of a shader, implemented by the `Shader` struct of each entry
+    /// point module, to manage different shaders generically like in a pipeline cache.
+    #visibility trait ShaderEntry {
+      /// The names of the entry points of the shader.
+      fn entry_point_names(&self) -> &'static [&'static str];
+
+      #create_shader_module;
+
+      /// The layouts of the bind groups, indexed by group, with empty layouts for the
+      /// groups without bindings.
+      fn bind_group_layouts(&self, device: &wgpu::Device) -> Vec<wgpu::BindGroupLayout>;
+
+      fn create_pipeline_layout(&self, device: &wgpu::Device) -> wgpu::PipelineLayout;
+    }
+  }
+}
+
+/// The `Shader` struct of an entry point module implementing the `ShaderEntry` trait with
+/// the functions of the module. `output_type` is the output type of the trait, which
+/// differs from the one of the entry point when its source has to be embedded.
+pub(crate) fn shader_entry_impl(
+  bind_group_data: &BTreeMap<u32, GroupData>,
+  module: &naga::Module,
+  output_type: WgslShaderSourceOutputType,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_shader_entry {
+    return quote!();
+  }
+
+  let entry_point_names = module.entry_points.iter().map(|entry_point| {
+    let name = format!("ENTRY_{}", entry_point.name.to_uppercase());
+    Ident::new(&name, Span::call_site())
+  });
+
+  let signature = create_shader_module_signature(output_type);
+  let create_shader_module = match (
+    output_type == WgslShaderSourceOutputType::RuntimeFiles,
+    options.shader_source_output_type == WgslShaderSourceOutputType::RuntimeFiles,
+  ) {
+    (true, true) => quote!(create_shader_module(device, dir)),
+    // The source of the shader is embedded, so it isn't loaded from the directory.
+    (true, false) => quote! {
+      let _ = dir;
+      Ok(create_shader_module(device))
+    },
+    _ => quote!(create_shader_module(device)),
+  };
+
+  // The groups left without bindings by remapping are given to `create_pipeline_layout`.
+  let group_count = bind_group_data.keys().max().map_or(0, |group| group + 1);
+  let bind_group_layouts = (0..group_count).map(|group_no| {
+    if bind_group_data.contains_key(&group_no) {
+      let group = indexed_name_to_ident("BindGroup", group_no);
+      quote!(bind_groups::#group::get_bind_group_layout(device))
+    } else {
+      quote! {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+          label: None,
+          entries: &[],
+        })
+      }
+    }
+  });
+  let missing_groups: Vec<_> = (0..group_count)
+    .filter(|group_no| !bind_group_data.contains_key(group_no))
+    .map(|group_no| Index::from(group_no as usize))
+    .collect();
+  let create_pipeline_layout = if missing_groups.is_empty() {
+    quote!(create_pipeline_layout(device))
+  } else {
+    quote! {
+      let layouts = self.bind_group_layouts(device);
+      create_pipeline_layout(device #(, &layouts[#missing_groups])*)
+    }
+  };
+
+  let visibility = options.item_visibility_tokens(1);
+  quote! {
+    /// The items of the shader as a `ShaderEntry`, like `Box::new(Shader)`.
+    #[derive(Debug, Clone, Copy, Default)]
+    #visibility struct Shader;
+
+    impl _root::shader_entry::ShaderEntry for Shader {
+      fn entry_point_names(&self) -> &'static [&'static str] {
+        &[#(#entry_point_names),*]
+      }
+
+      #signature {
+        #create_shader_module
+      }
+
+      fn bind_group_layouts(&self, device: &wgpu::Device) -> Vec<wgpu::BindGroupLayout> {
+        vec![#(#bind_group_layouts),*]
+      }
+
+      fn create_pipeline_layout(&self, device: &wgpu::Device) -> wgpu::PipelineLayout {
+        #create_pipeline_layout
+      }
+    }
+  }
+}
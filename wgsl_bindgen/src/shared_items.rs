@@ -0,0 +1,264 @@
+use std::collections::{BTreeMap, HashSet};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use serde::{Deserialize, Serialize};
+
+use crate::quote_gen::mod_reference_root;
+use crate::{WgslBindgenOption, WgslEntryItem};
+
+/// The kinds of items which several entry points can generate identically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) enum SharedItemKind {
+  /// A struct of the entry point, shared with `unify_duplicate_structs`.
+  Struct,
+  /// A bind group of the entry point, shared with `share_bind_groups`.
+  BindGroup,
+}
+
+impl SharedItemKind {
+  fn is_enabled(self, options: &WgslBindgenOption) -> bool {
+    match self {
+      Self::Struct => options.unify_duplicate_structs,
+      Self::BindGroup => options.share_bind_groups,
+    }
+  }
+
+  /// The module the shared items go in.
+  fn mod_path(self, shared_mod: &str) -> String {
+    match self {
+      Self::Struct => shared_mod.to_string(),
+      Self::BindGroup => format!("{shared_mod}::bind_groups"),
+    }
+  }
+}
+
+/// Moves the items that several entry points generate identically, like their structs,
+/// into the shared module, and replaces them with re-exports of the shared ones.
+pub(crate) fn share_duplicate_items(
+  mut entries: Vec<(String, Vec<WgslEntryItem>)>,
+  options: &WgslBindgenOption,
+) -> Vec<(String, Vec<WgslEntryItem>)> {
+  let shared_mod = options
+    .shared_module
+    .clone()
+    .unwrap_or_else(|| "shared".to_string());
+
+  // The definition of each item which can be shared, leaving out the ones whose
+  // visibility would change in the shared module.
+  let definitions: Vec<Vec<Option<String>>> = entries
+    .iter()
+    .map(|(_, items)| {
+      items
+        .iter()
+        .map(|item| {
+          let kind = item.shareable.filter(|kind| kind.is_enabled(options))?;
+          let visibility = options.module_items_visibility_tokens(&item.mod_path);
+          let shared_visibility =
+            options.module_items_visibility_tokens(&kind.mod_path(&shared_mod));
+          (visibility.to_string() == shared_visibility.to_string())
+            .then(|| item.item.to_string())
+        })
+        .collect()
+    })
+    .collect();
+
+  // The definition generated by the most entry points is shared, when there are several.
+  let mut counts = BTreeMap::<(SharedItemKind, &str), Vec<(&str, usize)>>::new();
+  for (item, definition) in entries
+    .iter()
+    .flat_map(|(_, items)| items)
+    .zip(definitions.iter().flatten())
+  {
+    let Some(definition) = definition else {
+      continue;
+    };
+    let key = (item.shareable.unwrap(), item.name.as_deref().unwrap());
+    let counts = counts.entry(key).or_default();
+    match counts.iter_mut().find(|(other, _)| *other == definition) {
+      Some((_, count)) => *count += 1,
+      None => counts.push((definition, 1)),
+    }
+  }
+  let mut chosen = BTreeMap::new();
+  for (key, counts) in counts {
+    let mut best: Option<(&str, usize)> = None;
+    for (definition, count) in counts {
+      if count > 1 && best.is_none_or(|(_, best_count)| count > best_count) {
+        best = Some((definition, count));
+      }
+    }
+    if let Some((definition, _)) = best {
+      chosen.insert(key, definition);
+    }
+  }
+  let mut shared: Vec<Vec<bool>> = entries
+    .iter()
+    .zip(&definitions)
+    .map(|((_, items), definitions)| {
+      items
+        .iter()
+        .zip(definitions)
+        .map(|(item, definition)| {
+          definition.as_ref().is_some_and(|definition| {
+            let key = (item.shareable.unwrap(), item.name.as_deref().unwrap());
+            chosen.get(&key) == Some(&definition.as_str())
+          })
+        })
+        .collect()
+    })
+    .collect();
+
+  // Identical definitions can still reference different items of the same name, so an
+  // item is only shared when the items of its module it references are shared too.
+  loop {
+    let mut changed = false;
+    for ((_, items), shared) in entries.iter().zip(shared.iter_mut()) {
+      for index in 0..items.len() {
+        if !shared[index] {
+          continue;
+        }
+
+        let mut idents = HashSet::new();
+        collect_idents(&items[index].item, &mut idents);
+        let references_local_item =
+          items.iter().zip(shared.iter()).any(|(other, is_shared)| {
+            !is_shared
+              && other.mod_path == items[index].mod_path
+              && other
+                .name
+                .as_ref()
+                .is_some_and(|name| idents.contains(name))
+          });
+        if references_local_item {
+          shared[index] = false;
+          changed = true;
+        }
+      }
+    }
+    if !changed {
+      break;
+    }
+  }
+
+  let root = mod_reference_root();
+  let mut shared_items: Vec<WgslEntryItem> = Vec::new();
+  for (item, _) in entries
+    .iter_mut()
+    .flat_map(|(_, items)| items)
+    .zip(shared.into_iter().flatten())
+    .filter(|(_, is_shared)| *is_shared)
+  {
+    let kind = item.shareable.take().unwrap();
+    let mod_path = kind.mod_path(&shared_mod);
+    let shared_path: syn::Path = syn::parse_str(&mod_path).unwrap();
+    let names = item_names(&item.item);
+    let visibility = options.module_items_visibility_tokens(&item.mod_path);
+    let reexport = quote! {
+      #[allow(unused_imports)]
+      #visibility use #root::#shared_path::{#(#names),*};
+    };
+    let definition = std::mem::replace(&mut item.item, reexport);
+    if !shared_items
+      .iter()
+      .any(|shared| shared.mod_path == mod_path && shared.name == item.name)
+    {
+      shared_items.push(WgslEntryItem {
+        mod_path,
+        name: item.name.clone(),
+        item: definition,
+        shareable: None,
+      });
+    }
+  }
+
+  entries.insert(0, (shared_mod, shared_items));
+  entries
+}
+
+/// The names of the items defined by the tokens, like the struct and its `Init` struct.
+fn item_names(tokens: &TokenStream) -> Vec<syn::Ident> {
+  let file: syn::File = syn::parse2(tokens.clone()).unwrap();
+  file
+    .items
+    .into_iter()
+    .filter_map(|item| match item {
+      syn::Item::Struct(item) => Some(item.ident),
+      syn::Item::Enum(item) => Some(item.ident),
+      syn::Item::Type(item) => Some(item.ident),
+      syn::Item::Mod(item) => Some(item.ident),
+      syn::Item::Fn(item) => Some(item.sig.ident),
+      syn::Item::Const(item) if item.ident != "_" => Some(item.ident),
+      _ => None,
+    })
+    .collect()
+}
+
+fn collect_idents(tokens: &TokenStream, idents: &mut HashSet<String>) {
+  for token in tokens.clone() {
+    match token {
+      TokenTree::Ident(ident) => {
+        idents.insert(ident.to_string());
+      }
+      TokenTree::Group(group) => collect_idents(&group.stream(), idents),
+      _ => {}
+    }
+  }
+}
+
+/// A line diff of two definitions of the item `name`, limited to the struct definition
+/// when both define the struct, so the report shows the members that differ.
+pub(crate) fn definitions_diff(name: &str, existing: &str, received: &str) -> String {
+  let existing = definition_lines(name, existing);
+  let received = definition_lines(name, received);
+
+  // The longest common subsequence of the lines from each position on.
+  let mut common = vec![vec![0; received.len() + 1]; existing.len() + 1];
+  for i in (0..existing.len()).rev() {
+    for j in (0..received.len()).rev() {
+      common[i][j] = if existing[i] == received[j] {
+        common[i + 1][j + 1] + 1
+      } else {
+        common[i + 1][j].max(common[i][j + 1])
+      };
+    }
+  }
+
+  let mut diff = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < existing.len() || j < received.len() {
+    if i < existing.len() && j < received.len() && existing[i] == received[j] {
+      diff.push(format!("  {}", existing[i]));
+      i += 1;
+      j += 1;
+    } else if j == received.len()
+      || i < existing.len() && common[i + 1][j] >= common[i][j + 1]
+    {
+      diff.push(format!("- {}", existing[i]));
+      i += 1;
+    } else {
+      diff.push(format!("+ {}", received[j]));
+      j += 1;
+    }
+  }
+  diff.join("\n")
+}
+
+/// The pretty printed lines of the struct `name` of the tokens, or of all the items when
+/// they aren't a struct.
+fn definition_lines(name: &str, tokens: &str) -> Vec<String> {
+  let Ok(mut file) = syn::parse_str::<syn::File>(tokens) else {
+    return vec![tokens.to_string()];
+  };
+  let definition = file.items.iter().find(
+    |item| matches!(item, syn::Item::Struct(definition) if definition.ident == name),
+  );
+  if let Some(definition) = definition {
+    file.items = vec![definition.clone()];
+  }
+
+  prettyplease::unparse(&file)
+    .lines()
+    .map(ToString::to_string)
+    .collect()
+}
@@ -0,0 +1,136 @@
+use crate::bevy_util::SourceWithFullDependenciesResult;
+use crate::FxIndexMap;
+
+/// The `//` comments preceding a WGSL struct and its members.
+#[derive(Debug, Default)]
+pub struct StructDocs {
+  pub docs: Vec<String>,
+  pub members: FxIndexMap<String, Vec<String>>,
+}
+
+/// The comments of the WGSL structs, keyed by their demangled names.
+///
+/// naga drops the comments, so they are parsed from the source files instead.
+#[derive(Debug, Default)]
+pub struct WgslStructDocs(FxIndexMap<String, StructDocs>);
+
+impl WgslStructDocs {
+  pub fn from_entry(entry: &SourceWithFullDependenciesResult<'_>) -> Self {
+    let mut docs = Self::default();
+    docs.add_source(&entry.source_file.content, None);
+    for dependency in entry.full_dependencies.iter() {
+      let module_name = dependency.module_name.as_ref().map(|name| name.as_str());
+      docs.add_source(&dependency.content, module_name);
+    }
+    docs
+  }
+
+  pub fn get(&self, demangled_struct_name: &str) -> Option<&StructDocs> {
+    self.0.get(demangled_struct_name)
+  }
+
+  /// Adds the comments of the structs in `source`, whose names are prefixed with the `module_name`.
+  pub fn add_source(&mut self, source: &str, module_name: Option<&str>) {
+    let mut comments = Vec::new();
+    let mut current_struct: Option<String> = None;
+
+    for line in source.lines().map(str::trim) {
+      if let Some(comment) = line.strip_prefix("//") {
+        comments.push(comment.trim_start_matches('/').to_owned());
+        continue;
+      }
+      let comments = std::mem::take(&mut comments);
+
+      if let Some(struct_name) = current_struct.as_ref() {
+        if line.starts_with('}') {
+          current_struct = None;
+        } else if let Some((member, _)) = strip_attributes(line).split_once(':') {
+          let struct_docs = self.0.entry(struct_name.clone()).or_default();
+          if !comments.is_empty() {
+            struct_docs.members.insert(member.trim().to_owned(), comments);
+          }
+        }
+      } else if let Some(declaration) = line.strip_prefix("struct ") {
+        let name = declaration
+          .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+          .next()
+          .unwrap_or_default();
+        let name = match module_name {
+          Some(module_name) => format!("{module_name}::{name}"),
+          None => name.to_owned(),
+        };
+
+        self.0.entry(name.clone()).or_default().docs = comments;
+        if !line.contains('}') {
+          current_struct = Some(name);
+        }
+      }
+    }
+  }
+}
+
+/// Strips the attributes like `@location(0)` before a struct member.
+fn strip_attributes(mut declaration: &str) -> &str {
+  while let Some(attribute) = declaration.strip_prefix('@') {
+    let name_end = attribute
+      .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+      .unwrap_or(attribute.len());
+    let rest = attribute[name_end..].trim_start();
+    declaration = match rest.strip_prefix('(') {
+      Some(arguments) => arguments.split_once(')').map_or("", |(_, rest)| rest),
+      None => rest,
+    }
+    .trim_start();
+  }
+  declaration
+}
+
+#[cfg(test)]
+mod tests {
+  use indoc::indoc;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn parse_struct_and_member_comments() {
+    let source = indoc! {r#"
+        // Not a struct comment.
+
+        // A point light.
+        /// Lights the scene.
+        struct PointLight {
+            // The position in world space.
+            @location(0) position: vec3<f32>,
+
+            radius: f32,
+            // The color.
+            // Alpha is unused.
+            @location(1) @interpolate(flat) color: vec4<f32>,
+        };
+
+        // Not a member comment.
+        fn main() {
+            // Neither is this.
+            let radius: f32 = 1.0;
+        }
+    "#};
+
+    let mut docs = WgslStructDocs::default();
+    docs.add_source(source, Some("lights"));
+
+    assert!(docs.get("PointLight").is_none());
+    let struct_docs = docs.get("lights::PointLight").unwrap();
+    assert_eq!(struct_docs.docs, [" A point light.", " Lights the scene."]);
+    assert_eq!(
+      struct_docs.members.iter().collect::<Vec<_>>(),
+      [
+        (&"position".to_owned(), &vec![" The position in world space.".to_owned()]),
+        (
+          &"color".to_owned(),
+          &vec![" The color.".to_owned(), " Alpha is unused.".to_owned()]
+        ),
+      ]
+    );
+  }
+}
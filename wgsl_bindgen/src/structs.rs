@@ -1,29 +1,34 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use case::CaseExt;
 use naga::{Handle, Type};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Ident, Index};
 
 use crate::{
-  quote_gen::{RustSourceItem, RustStructBuilder},
-  WgslBindgenOption, WgslTypeSerializeStrategy,
+  bevy_util::{
+    demangle, demangle_generic_arguments, demangle_splitting_mod_path_and_item,
+  },
+  quote_gen::{encase_layout_assertions, rust_type, RustSourceItem, RustStructBuilder},
+  struct_docs::{StructDocs, WgslStructDocs},
+  wgsl, CreateModuleError, WgslBindgenOption, WgslBoolMemberStrategy, WgslTypeSerializeStrategy,
 };
 
 pub fn structs_items(
   module: &naga::Module,
   options: &WgslBindgenOption,
-) -> Vec<RustSourceItem> {
+  docs: &WgslStructDocs,
+) -> Result<Vec<RustSourceItem>, CreateModuleError> {
   // Initialize the layout calculator provided by naga.
   let mut layouter = naga::proc::Layouter::default();
   layouter.update(module.to_ctx()).unwrap();
 
-  let mut global_variable_types = HashSet::new();
-  for g in module.global_variables.iter() {
-    add_types_recursive(&mut global_variable_types, module, g.1.ty);
-  }
+  let global_variable_types = global_variable_types(module, options);
 
   // Create matching Rust structs for WGSL structs.
   // This is a UniqueArena, so each struct will only be generated once.
-  module
+  let struct_types: Vec<_> = module
     .types
     .iter()
     .filter(|(h, _)| {
@@ -41,19 +46,32 @@ pub fn structs_items(
           .any(|e| e.function.arguments.iter().any(|a| a.ty == *h))
         || global_variable_types.contains(h)
     })
+    .filter(|(_, t)| {
+      // Structs overridden by the user are referenced but never generated.
+      let name = t.name.as_deref().map(demangle).unwrap_or_default();
+      !options.type_overrides.contains_key(name.as_ref())
+    })
+    .collect();
+  check_struct_name_collisions(&struct_types, options)?;
+
+  struct_types
+    .into_iter()
     .filter_map(|(t_handle, t)| {
       if let naga::TypeInner::Struct { members, .. } = &t.inner {
         let rust_struct = rust_struct(
           t,
           members,
-          &layouter,
-          t_handle,
+          layouter[t_handle],
+          global_variable_types.contains(&t_handle),
           module,
           options,
-          &global_variable_types,
-        );
+          docs.get(&demangle(t.name.as_deref().unwrap())),
+        )
+        .map(|rust_struct| {
+          RustSourceItem::from_mangled(t.name.as_ref().unwrap(), rust_struct)
+        });
 
-        Some(RustSourceItem::from_mangled(t.name.as_ref().unwrap(), rust_struct))
+        Some(rust_struct)
       } else {
         None
       }
@@ -63,7 +81,8 @@ pub fn structs_items(
 
 #[allow(unused)]
 pub fn structs(module: &naga::Module, options: &WgslBindgenOption) -> Vec<TokenStream> {
-  structs_items(module, options)
+  structs_items(module, options, &WgslStructDocs::default())
+    .unwrap()
     .into_iter()
     .map(|s| s.item)
     .collect()
@@ -72,26 +91,62 @@ pub fn structs(module: &naga::Module, options: &WgslBindgenOption) -> Vec<TokenS
 fn rust_struct(
   naga_type: &naga::Type,
   naga_members: &[naga::StructMember],
-  layouter: &naga::proc::Layouter,
-  t_handle: naga::Handle<naga::Type>,
+  layout: naga::proc::TypeLayout,
+  is_global_variable_type: bool,
   naga_module: &naga::Module,
   options: &WgslBindgenOption,
-  global_variable_types: &HashSet<Handle<Type>>,
-) -> TokenStream {
-  let layout = layouter[t_handle];
-
+  docs: Option<&StructDocs>,
+) -> Result<TokenStream, CreateModuleError> {
   // Assume types used in global variables are host shareable and require validation.
   // This includes storage, uniform, and workgroup variables.
   // This also means types that are never used will not be validated.
   // Structs used only for vertex inputs do not require validation on desktop platforms.
   // Vertex input layout is handled already by setting the attribute offsets and types.
   // This allows vertex input field types without padding like vec3 for positions.
-  let is_host_sharable = global_variable_types.contains(&t_handle);
+  let mut is_host_sharable = is_global_variable_type;
+
+  let bool_member = naga_members
+    .iter()
+    .find(|m| type_contains_bool(naga_module, m.ty));
+  if let (true, Some(member)) = (is_host_sharable, bool_member) {
+    match options.bool_member_strategy {
+      WgslBoolMemberStrategy::Error => {
+        return Err(CreateModuleError::BoolMember {
+          struct_name: demangle(naga_type.name.as_deref().unwrap()).into(),
+          member: member.name.clone().unwrap_or_default(),
+        });
+      }
+      // The `u32` members can't follow naga's layout for `bool`.
+      WgslBoolMemberStrategy::U32 => is_host_sharable = false,
+    }
+  }
 
-  let has_rts_array = struct_has_rts_array_member(naga_members, naga_module);
+  let rts_member = naga_members
+    .iter()
+    .position(|m| is_rts_array(naga_module, m.ty));
+  if let Some(index) = rts_member.filter(|&index| index + 1 != naga_members.len()) {
+    return Err(CreateModuleError::RuntimeSizedArrayNotLast {
+      struct_name: demangle(naga_type.name.as_deref().unwrap()).into(),
+      member: naga_members[index].name.clone().unwrap_or_default(),
+    });
+  }
+
+  let has_rts_array = rts_member.is_some();
   let is_directly_sharable = options.serialization_strategy
     == WgslTypeSerializeStrategy::Bytemuck
     && is_host_sharable;
+  let naga_handle = naga_module.types.get(naga_type);
+  let is_uniform = naga_module.global_variables.iter().any(|(_, global)| {
+    global.space == naga::AddressSpace::Uniform && Some(global.ty) == naga_handle
+  });
+
+  let mut layout = layout;
+  match naga_handle {
+    Some(handle) if is_directly_sharable && !has_rts_array => {
+      layout.size = padded_uniform_struct_size(naga_module, handle, layout.size, options);
+    }
+    _ => (),
+  }
 
   let builder = RustStructBuilder::from_naga(
     naga_type,
@@ -102,11 +157,238 @@ fn rust_struct(
     is_directly_sharable,
     is_host_sharable,
     has_rts_array,
-  );
-  builder.build()
+  )
+  .with_docs(docs)
+  .with_uniform(is_uniform);
+  Ok(builder.build())
+}
+
+/// The size of a struct with `pad_uniform_structs`, where the trailing padding makes the size
+/// the same as in uniform buffers, where nested structs take a multiple of 16 bytes, so the
+/// struct can be used in storage buffers too. Structs without room for the padding where
+/// they are used, like the elements of a storage `array<S, N>` with the unpadded stride,
+/// keep their size.
+fn padded_uniform_struct_size(
+  module: &naga::Module,
+  handle: Handle<Type>,
+  size: u32,
+  options: &WgslBindgenOption,
+) -> u32 {
+  if !options.pad_uniform_structs {
+    return size;
+  }
+
+  let mut uniform_types = HashSet::new();
+  for (_, global) in module.global_variables.iter() {
+    if global.space == naga::AddressSpace::Uniform {
+      add_types_recursive(&mut uniform_types, module, global.ty);
+    }
+  }
+  let padded_size = size.next_multiple_of(16);
+  let has_room = module.types.iter().all(|(_, ty)| match &ty.inner {
+    naga::TypeInner::Array { base, stride, .. } => {
+      *base != handle || *stride == padded_size
+    }
+    naga::TypeInner::Struct { members, span } => {
+      members.iter().enumerate().all(|(i, member)| {
+        let next_offset = members.get(i + 1).map_or(*span, |next| next.offset);
+        member.ty != handle || next_offset - member.offset >= padded_size
+      })
+    }
+    _ => true,
+  });
+
+  if uniform_types.contains(&handle) && has_room {
+    padded_size
+  } else {
+    size
+  }
+}
+
+/// The number of elements used to check the layout of runtime-sized arrays.
+const LAYOUT_TEST_RTS_LENGTHS: [u32; 3] = [1, 2, 4];
+
+/// Checks that the structs generated in the same module have different Rust names, which
+/// the names of generic structs or `name_casing` could make the same.
+fn check_struct_name_collisions(
+  struct_types: &[(Handle<Type>, &Type)],
+  options: &WgslBindgenOption,
+) -> Result<(), CreateModuleError> {
+  let mut rust_names = HashMap::new();
+  for (_, t) in struct_types {
+    let (naga::TypeInner::Struct { .. }, Some(name)) = (&t.inner, t.name.as_ref()) else {
+      continue;
+    };
+    let wgsl_name = demangle(name).into_owned();
+    let (mod_path, _) = demangle_splitting_mod_path_and_item(name);
+    let rust_name = options.rust_struct_name(&wgsl_name);
+    if let Some(first) =
+      rust_names.insert((mod_path, rust_name.clone()), wgsl_name.clone())
+    {
+      return Err(CreateModuleError::StructNameCollision {
+        first,
+        second: wgsl_name,
+        rust_name,
+      });
+    }
+  }
+  Ok(())
+}
+
+/// A `layout_tests` module checking the host-shareable structs against naga's layout.
+pub fn layout_tests_module(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  if !options.generate_layout_tests {
+    return quote!();
+  }
+
+  let mut layouter = naga::proc::Layouter::default();
+  layouter.update(module.to_ctx()).unwrap();
+
+  let global_variable_types = global_variable_types(module, options);
+
+  let tests: Vec<_> = module
+    .types
+    .iter()
+    .filter(|(h, _)| global_variable_types.contains(h))
+    .filter_map(|(h, t)| match &t.inner {
+      naga::TypeInner::Struct { members, .. } => {
+        let name = demangle(t.name.as_deref().unwrap());
+        let is_overridden = options.type_overrides.contains_key(name.as_ref());
+        // Structs with `bool` members are either rejected or not host-shareable.
+        let has_bool = members.iter().any(|m| type_contains_bool(module, m.ty));
+        (!is_overridden && !has_bool)
+          .then(|| struct_layout_test(h, t, members, layouter[h], module, options))
+      }
+      _ => None,
+    })
+    .collect();
+
+  if tests.is_empty() {
+    return quote!();
+  }
+
+  quote! {
+    #[cfg(test)]
+    mod layout_tests {
+      use super::*;
+
+      #(#tests)*
+    }
+  }
+}
+
+fn struct_layout_test(
+  handle: Handle<Type>,
+  naga_type: &naga::Type,
+  naga_members: &[naga::StructMember],
+  layout: naga::proc::TypeLayout,
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> TokenStream {
+  let wgsl_name = demangle(naga_type.name.as_deref().unwrap());
+  // Converting `types_Scalars` as a whole would give `types__scalars`.
+  let test_name: Vec<_> = demangle_generic_arguments(&wgsl_name)
+    .split("::")
+    .map(|segment| segment.to_snake())
+    .collect();
+  let test_name =
+    Ident::new(&format!("{}_layout", test_name.join("_")), Span::call_site());
+  let path = rust_type(module, naga_type, options);
+  let alignment = Index::from(layout.alignment.round_up(1) as usize);
+  let has_rts_array = struct_has_rts_array_member(naga_members, module);
+
+  // Hidden padding makes the fields private to the struct's module.
+  let member_names: Vec<_> = if options.hide_padding {
+    Vec::new()
+  } else {
+    naga_members
+      .iter()
+      .map(|m| {
+        let name = options.rust_member_name(&wgsl_name, m.name.as_deref().unwrap());
+        (Ident::new(&name, Span::call_site()), Index::from(m.offset as usize))
+      })
+      .collect()
+  };
+
+  let assertions = match options.serialization_strategy {
+    WgslTypeSerializeStrategy::Bytemuck if has_rts_array => {
+      // The runtime-sized array is the last member.
+      let last = naga_members.last().unwrap();
+      let stride = match module.types[last.ty].inner {
+        naga::TypeInner::Array { stride, .. } => stride,
+        _ => unreachable!(),
+      };
+      let lengths = LAYOUT_TEST_RTS_LENGTHS.iter().map(|&n| {
+        let size =
+          Index::from(layout.alignment.round_up(last.offset + n * stride) as usize);
+        let n = Index::from(n as usize);
+        let offsets = member_names.iter().map(|(name, offset)| {
+          quote!(assert_eq!(std::mem::offset_of!(#path<#n>, #name), #offset);)
+        });
+        quote! {
+          #(#offsets)*
+          assert_eq!(std::mem::size_of::<#path<#n>>(), #size);
+          assert_eq!(std::mem::align_of::<#path<#n>>(), #alignment);
+        }
+      });
+      quote!(#(#lengths)*)
+    }
+    WgslTypeSerializeStrategy::Bytemuck => {
+      let size = padded_uniform_struct_size(module, handle, layout.size, options);
+      let size = Index::from(size as usize);
+      let offsets = member_names.iter().map(
+        |(name, offset)| quote!(assert_eq!(std::mem::offset_of!(#path, #name), #offset);),
+      );
+      quote! {
+        #(#offsets)*
+        assert_eq!(std::mem::size_of::<#path>(), #size);
+        assert_eq!(std::mem::align_of::<#path>(), #alignment);
+      }
+    }
+    WgslTypeSerializeStrategy::Encase => {
+      encase_layout_assertions(&quote!(#path), &member_names, layout.size, has_rts_array)
+    }
+  };
+
+  quote! {
+    #[test]
+    fn #test_name() {
+      #assertions
+    }
+  }
+}
+
+fn type_contains_bool(module: &naga::Module, ty: Handle<Type>) -> bool {
+  match &module.types[ty].inner {
+    naga::TypeInner::Scalar(scalar) => scalar.kind == naga::ScalarKind::Bool,
+    naga::TypeInner::Vector { scalar, .. } => scalar.kind == naga::ScalarKind::Bool,
+    naga::TypeInner::Array { base, .. } => type_contains_bool(module, *base),
+    _ => false,
+  }
 }
 
-fn add_types_recursive(
+/// The types of the global variables, or with `skip_unused_structs` of the bindings and
+/// push constants used by the entry points.
+fn global_variable_types(
+  module: &naga::Module,
+  options: &WgslBindgenOption,
+) -> HashSet<Handle<Type>> {
+  let used_globals = wgsl::entry_point_globals(module);
+  let mut types = HashSet::new();
+  for (handle, global) in module.global_variables.iter() {
+    let is_used_interface = used_globals.contains(&handle)
+      && (global.binding.is_some() || global.space == naga::AddressSpace::PushConstant);
+    if !options.skip_unused_structs || is_used_interface {
+      add_types_recursive(&mut types, module, global.ty);
+    }
+  }
+  types
+}
+
+pub(crate) fn add_types_recursive(
   types: &mut HashSet<naga::Handle<naga::Type>>,
   module: &naga::Module,
   ty: Handle<Type>,
@@ -128,19 +410,21 @@ fn add_types_recursive(
   }
 }
 
-fn struct_has_rts_array_member(
+pub(crate) fn struct_has_rts_array_member(
   members: &[naga::StructMember],
   module: &naga::Module,
 ) -> bool {
-  members.iter().any(|m| {
-    matches!(
-      module.types[m.ty].inner,
-      naga::TypeInner::Array {
-        size: naga::ArraySize::Dynamic,
-        ..
-      }
-    )
-  })
+  members.iter().any(|m| is_rts_array(module, m.ty))
+}
+
+fn is_rts_array(module: &naga::Module, ty: Handle<Type>) -> bool {
+  matches!(
+    module.types[ty].inner,
+    naga::TypeInner::Array {
+      size: naga::ArraySize::Dynamic,
+      ..
+    }
+  )
 }
 
 #[cfg(test)]
@@ -756,21 +1040,29 @@ mod tests {
   }
 
   #[test]
-  fn write_all_structs_encase() {
+  fn write_all_structs_cgmath() {
     let source = indoc! {r#"
-            struct Input0 {
-                a: u32,
-                b: i32,
-                c: f32,
+            struct VectorsU32 {
+                a: vec2<u32>,
+                b: vec3<u32>,
+                c: vec4<u32>,
             };
+            var<uniform> b: VectorsU32;
 
-            struct Nested {
-                a: Input0,
-                b: f32
-            }
+            struct VectorsF32 {
+                a: vec2<f32>,
+                b: vec3<f32>,
+                c: vec4<f32>,
+            };
+            var<uniform> d: VectorsF32;
 
-            var<uniform> a: Input0;
-            var<storage, read> b: Nested;
+            struct MatricesF32 {
+                a: mat4x4<f32>,
+                b: mat4x2<f32>,
+                c: mat3x3<f32>,
+                d: mat2x2<f32>,
+            };
+            var<uniform> f: MatricesF32;
 
             @fragment
             fn main() {}
@@ -781,9 +1073,7 @@ mod tests {
     let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Encase,
-        derive_serde: false,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        wgsl_type_map: CgmathWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
         ..Default::default()
       },
     );
@@ -793,25 +1083,52 @@ mod tests {
       quote! {
           #[repr(C)]
           #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
-          pub struct Input0 {
-              pub a: u32,
-              pub b: i32,
-              pub c: f32,
+          pub struct VectorsU32 {
+              pub a: cgmath::Vector2<u32>,
+              pub b: cgmath::Vector3<u32>,
+              pub c: cgmath::Vector4<u32>,
           }
-          impl Input0 {
-            pub fn new(a: u32, b: i32, c: f32) -> Self {
+          impl VectorsU32 {
+            pub fn new(
+              a: cgmath::Vector2<u32>,
+              b: cgmath::Vector3<u32>,
+              c: cgmath::Vector4<u32>,
+            ) -> Self {
                 Self { a, b, c }
             }
           }
           #[repr(C)]
           #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
-          pub struct Nested {
-              pub a: Input0,
-              pub b: f32,
+          pub struct VectorsF32 {
+              pub a: cgmath::Vector2<f32>,
+              pub b: cgmath::Vector3<f32>,
+              pub c: cgmath::Vector4<f32>,
           }
-          impl Nested {
-            pub fn new(a: Input0, b: f32) -> Self {
-                Self { a, b }
+          impl VectorsF32 {
+            pub fn new(
+              a: cgmath::Vector2<f32>,
+              b: cgmath::Vector3<f32>,
+              c: cgmath::Vector4<f32>,
+            ) -> Self {
+                Self { a, b, c }
+            }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct MatricesF32 {
+              pub a: cgmath::Matrix4<f32>,
+              pub b: [[f32; 2]; 4],
+              pub c: cgmath::Matrix3<f32>,
+              pub d: cgmath::Matrix2<f32>,
+          }
+          impl MatricesF32 {
+            pub fn new(
+                a: cgmath::Matrix4<f32>,
+                b: [[f32; 2]; 4],
+                c: cgmath::Matrix3<f32>,
+                d: cgmath::Matrix2<f32>,
+            ) -> Self {
+                Self { a, b, c, d }
             }
           }
       },
@@ -820,24 +1137,24 @@ mod tests {
   }
 
   #[test]
-  fn write_all_structs_serde_encase() {
+  fn write_all_structs_mint() {
     let source = indoc! {r#"
-            struct Input0 {
-                a: u32,
-                b: i32,
-                c: f32,
+            struct VectorsI32 {
+                a: vec2<i32>,
+                b: vec3<i32>,
+                c: vec4<i32>,
             };
+            var<uniform> c: VectorsI32;
 
-            struct Nested {
-                a: Input0,
-                b: f32
-            }
-
-            var<workgroup> a: Input0;
-            var<uniform> b: Nested;
+            struct MatricesF32 {
+                a: mat4x4<f32>,
+                b: mat4x3<f32>,
+                c: mat2x3<f32>,
+                d: mat3x3<f32>,
+            };
+            var<uniform> f: MatricesF32;
 
-            @compute
-            @workgroup_size(64)
+            @fragment
             fn main() {}
         "#};
 
@@ -846,9 +1163,7 @@ mod tests {
     let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Encase,
-        derive_serde: true,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        wgsl_type_map: MintWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
         ..Default::default()
       },
     );
@@ -857,42 +1172,37 @@ mod tests {
     assert_tokens_eq!(
       quote! {
           #[repr(C)]
-          #[derive(
-              Debug,
-              PartialEq,
-              Clone,
-              Copy,
-              encase::ShaderType,
-              serde::Serialize,
-              serde::Deserialize
-          )]
-          pub struct Input0 {
-              pub a: u32,
-              pub b: i32,
-              pub c: f32,
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct VectorsI32 {
+              pub a: mint::Vector2<i32>,
+              pub b: mint::Vector3<i32>,
+              pub c: mint::Vector4<i32>,
           }
-          impl Input0 {
-            pub fn new(a: u32, b: i32, c: f32) -> Self {
+          impl VectorsI32 {
+            pub fn new(
+              a: mint::Vector2<i32>,
+              b: mint::Vector3<i32>,
+              c: mint::Vector4<i32>,
+            ) -> Self {
                 Self { a, b, c }
             }
           }
           #[repr(C)]
-          #[derive(
-              Debug,
-              PartialEq,
-              Clone,
-              Copy,
-              encase::ShaderType,
-              serde::Serialize,
-              serde::Deserialize
-          )]
-          pub struct Nested {
-              pub a: Input0,
-              pub b: f32,
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct MatricesF32 {
+              pub a: mint::ColumnMatrix4<f32>,
+              pub b: mint::ColumnMatrix3x4<f32>,
+              pub c: mint::ColumnMatrix3x2<f32>,
+              pub d: mint::ColumnMatrix3<f32>,
           }
-          impl Nested {
-            pub fn new(a: Input0, b: f32) -> Self {
-                Self { a, b }
+          impl MatricesF32 {
+            pub fn new(
+                a: mint::ColumnMatrix4<f32>,
+                b: mint::ColumnMatrix3x4<f32>,
+                c: mint::ColumnMatrix3x2<f32>,
+                d: mint::ColumnMatrix3<f32>,
+            ) -> Self {
+                Self { a, b, c, d }
             }
           }
       },
@@ -901,67 +1211,272 @@ mod tests {
   }
 
   #[test]
-  fn write_all_structs_skip_stage_outputs() {
+  fn write_all_structs_ultraviolet() {
     let source = indoc! {r#"
-            struct Input0 {
-                a: u32,
-                b: i32,
-                c: f32,
+            struct VectorsF32 {
+                a: vec2<f32>,
+                b: vec3<f32>,
+                c: vec4<f32>,
             };
+            var<uniform> d: VectorsF32;
 
-            struct Output0 {
-                a: f32
-            }
-
-            struct Unused {
-                a: vec3<f32>
-            }
+            struct MatricesF32 {
+                a: mat4x4<f32>,
+                b: mat3x3<f32>,
+            };
+            var<uniform> f: MatricesF32;
 
             @fragment
-            fn main(in: Input0) -> Output0 {
-                var out: Output0;
-                return out;
-            }
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
-    let structs = structs(
+    let encase_structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
-        derive_serde: false,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map: UltravioletWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
         ..Default::default()
       },
     );
-    let actual = quote!(#(#structs)*);
+    let actual = quote!(#(#encase_structs)*);
 
     assert_tokens_eq!(
       quote! {
           #[repr(C)]
-          #[derive(Debug, PartialEq, Clone, Copy)]
-          pub struct Input0 {
-              pub a: u32,
-              pub b: i32,
-              pub c: f32,
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct VectorsF32 {
+              pub a: ultraviolet::Vec2,
+              pub b: ultraviolet::Vec3,
+              pub c: ultraviolet::Vec4,
           }
-          impl Input0 {
-            pub fn new(a: u32, b: i32, c: f32) -> Self {
+          impl VectorsF32 {
+            pub fn new(
+              a: ultraviolet::Vec2,
+              b: ultraviolet::Vec3,
+              c: ultraviolet::Vec4,
+            ) -> Self {
                 Self { a, b, c }
             }
           }
-          unsafe impl bytemuck::Zeroable for Input0 {}
-          unsafe impl bytemuck::Pod for Input0 {}
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct MatricesF32 {
+              pub a: ultraviolet::Mat4,
+              pub b: ultraviolet::Mat3,
+          }
+          impl MatricesF32 {
+            pub fn new(a: ultraviolet::Mat4, b: ultraviolet::Mat3) -> Self {
+                Self { a, b }
+            }
+          }
+      },
+      actual
+    );
+
+    // The types are padded explicitly, except for `ultraviolet::Mat3` lacking the padding
+    // between the columns.
+    let bytemuck_structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        wgsl_type_map: UltravioletWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map_packed_types: UltravioletWgslTypeMap
+          .packed_types()
+          .into_iter()
+          .collect(),
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#bytemuck_structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct VectorsF32 {
+              /// size: 8, offset: 0x0, type: `vec2<f32>`
+              pub a: ultraviolet::Vec2,
+              pub _pad_a: [u8; 0x10 - core::mem::size_of::<ultraviolet::Vec2>()],
+              /// size: 12, offset: 0x10, type: `vec3<f32>`
+              pub b: ultraviolet::Vec3,
+              pub _pad_b: [u8; 0x10 - core::mem::size_of::<ultraviolet::Vec3>()],
+              /// size: 16, offset: 0x20, type: `vec4<f32>`
+              pub c: ultraviolet::Vec4,
+          }
+          impl VectorsF32 {
+              pub fn new(
+                  a: ultraviolet::Vec2,
+                  b: ultraviolet::Vec3,
+                  c: ultraviolet::Vec4,
+              ) -> Self {
+                  Self {
+                      a,
+                      _pad_a: [0; 0x10 - core::mem::size_of::<ultraviolet::Vec2>()],
+                      b,
+                      _pad_b: [0; 0x10 - core::mem::size_of::<ultraviolet::Vec3>()],
+                      c,
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for VectorsF32 {}
+          unsafe impl bytemuck::Pod for VectorsF32 {}
+          const _: () = {
+              assert!(std::mem::offset_of!(VectorsF32, a) == 0);
+              assert!(std::mem::offset_of!(VectorsF32, b) == 16);
+              assert!(std::mem::offset_of!(VectorsF32, c) == 32);
+              assert!(std::mem::size_of::<VectorsF32>() == 48);
+          };
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct MatricesF32 {
+              /// size: 64, offset: 0x0, type: `mat4x4<f32>`
+              pub a: ultraviolet::Mat4,
+              /// size: 48, offset: 0x40, type: `mat3x3<f32>`
+              pub b: [[f32; 4]; 3],
+          }
+          impl MatricesF32 {
+              pub fn new(a: ultraviolet::Mat4, b: [[f32; 4]; 3]) -> Self {
+                  Self { a, b }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for MatricesF32 {}
+          unsafe impl bytemuck::Pod for MatricesF32 {}
+          const _: () = {
+              assert!(std::mem::offset_of!(MatricesF32, a) == 0);
+              assert!(std::mem::offset_of!(MatricesF32, b) == 64);
+              assert!(std::mem::size_of::<MatricesF32>() == 112);
+          };
       },
       actual
     );
   }
 
   #[test]
-  fn write_all_structs_bytemuck_skip_input_layout_validation() {
-    // Structs used only for vertex inputs don't require layout validation.
-    // Correctly specifying the offsets is handled by the buffer layout itself.
+  fn write_all_structs_vek() {
+    let source = indoc! {r#"
+            struct VectorsU32 {
+                a: vec2<u32>,
+                b: vec4<u32>,
+            };
+            var<uniform> b: VectorsU32;
+
+            struct MatricesF32 {
+                a: mat4x4<f32>,
+                b: mat3x2<f32>,
+                c: mat2x2<f32>,
+            };
+            var<uniform> f: MatricesF32;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        wgsl_type_map: VekWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct VectorsU32 {
+              pub a: vek::Vec2<u32>,
+              pub b: vek::Vec4<u32>,
+          }
+          impl VectorsU32 {
+            pub fn new(a: vek::Vec2<u32>, b: vek::Vec4<u32>) -> Self {
+                Self { a, b }
+            }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct MatricesF32 {
+              pub a: vek::Mat4<f32>,
+              pub b: [[f32; 2]; 3],
+              pub c: vek::Mat2<f32>,
+          }
+          impl MatricesF32 {
+            pub fn new(a: vek::Mat4<f32>, b: [[f32; 2]; 3], c: vek::Mat2<f32>) -> Self {
+                Self { a, b, c }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_glam_f64() {
+    let source = indoc! {r#"
+            struct VectorsF64 {
+                a: vec2<f64>,
+                b: vec3<f64>,
+                c: vec4<f64>,
+            };
+            var<uniform> d: VectorsF64;
+
+            struct MatricesF64 {
+                a: mat4x4<f64>,
+                b: mat3x2<f64>,
+            };
+            var<uniform> f: MatricesF64;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        wgsl_type_map: GlamWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct VectorsF64 {
+              pub a: glam::DVec2,
+              pub b: glam::DVec3,
+              pub c: glam::DVec4,
+          }
+          impl VectorsF64 {
+            pub fn new(a: glam::DVec2, b: glam::DVec3, c: glam::DVec4) -> Self {
+                Self { a, b, c }
+            }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct MatricesF64 {
+              pub a: glam::DMat4,
+              pub b: [[f64; 2]; 3],
+          }
+          impl MatricesF64 {
+            pub fn new(a: glam::DMat4, b: [[f64; 2]; 3]) -> Self {
+                Self { a, b }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_encase() {
     let source = indoc! {r#"
             struct Input0 {
                 a: u32,
@@ -969,10 +1484,16 @@ mod tests {
                 c: f32,
             };
 
-            @vertex
-            fn main(input: Input0) -> vec4<f32> {
-                return vec4(0.0);
+            struct Nested {
+                a: Input0,
+                b: f32
             }
+
+            var<uniform> a: Input0;
+            var<storage, read> b: Nested;
+
+            @fragment
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
@@ -980,9 +1501,9 @@ mod tests {
     let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
         derive_serde: false,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Encase),
         ..Default::default()
       },
     );
@@ -991,52 +1512,53 @@ mod tests {
     assert_tokens_eq!(
       quote! {
           #[repr(C)]
-          #[derive(Debug, PartialEq, Clone, Copy)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
           pub struct Input0 {
               pub a: u32,
               pub b: i32,
               pub c: f32,
           }
           impl Input0 {
-              pub fn new(a: u32, b: i32, c: f32) -> Self {
-                  Self { a, b, c }
-              }
+            pub fn new(a: u32, b: i32, c: f32) -> Self {
+                Self { a, b, c }
+            }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Nested {
+              pub a: Input0,
+              pub b: f32,
+          }
+          impl Nested {
+            pub fn new(a: Input0, b: f32) -> Self {
+                Self { a, b }
+            }
           }
-          unsafe impl bytemuck::Zeroable for Input0 {}
-          unsafe impl bytemuck::Pod for Input0 {}
       },
       actual
     );
   }
 
   #[test]
-  fn write_all_structs_bytemuck_input_layout_validation() {
-    // The struct is also used with a storage buffer and should be validated.
+  fn write_all_structs_serde_encase() {
     let source = indoc! {r#"
             struct Input0 {
-                @size(8)
                 a: u32,
                 b: i32,
-                @align(32)
                 c: f32,
             };
 
-            var<storage, read_write> test: Input0;
-
-            struct Outer {
-                inner: Inner
-            }
-
-            struct Inner {
-                a: f32
+            struct Nested {
+                a: Input0,
+                b: f32
             }
 
-            var<storage, read_write> test2: array<Outer>;
+            var<workgroup> a: Input0;
+            var<uniform> b: Nested;
 
-            @vertex
-            fn main(input: Input0) -> vec4<f32> {
-                return vec4(0.0);
-            }
+            @compute
+            @workgroup_size(64)
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
@@ -1044,9 +1566,9 @@ mod tests {
     let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
-        derive_serde: false,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        derive_serde: true,
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Encase),
         ..Default::default()
       },
     );
@@ -1054,33 +1576,231 @@ mod tests {
 
     assert_tokens_eq!(
       quote! {
-        #[repr(C, align(4))]
-        #[derive(Debug, PartialEq, Clone, Copy)]
-        pub struct Input0 {
-            /// size: 4, offset: 0x0, type: `u32`
-            pub a: u32,
-            pub _pad_a: [u8; 0x8 - core::mem::size_of::<u32>()],
-            /// size: 4, offset: 0x8, type: `i32`
-            pub b: i32,
-            pub _pad_b: [u8; 0x18 - core::mem::size_of::<i32>()],
-            /// size: 4, offset: 0x20, type: `f32`
-            pub c: f32,
-            pub _pad_c: [u8; 0x20 - core::mem::size_of::<f32>()],
-        }
-        impl Input0 {
+          #[repr(C)]
+          #[derive(
+              Debug,
+              PartialEq,
+              Clone,
+              Copy,
+              encase::ShaderType,
+              serde::Serialize,
+              serde::Deserialize
+          )]
+          pub struct Input0 {
+              pub a: u32,
+              pub b: i32,
+              pub c: f32,
+          }
+          impl Input0 {
             pub fn new(a: u32, b: i32, c: f32) -> Self {
-                Self {
-                    a,
-                    _pad_a: [0; 0x8 - core::mem::size_of::<u32>()],
-                    b,
-                    _pad_b: [0; 0x18 - core::mem::size_of::<i32>()],
-                    c,
-                    _pad_c: [0; 0x20 - core::mem::size_of::<f32>()],
-                }
+                Self { a, b, c }
             }
-        }
-        unsafe impl bytemuck::Zeroable for Input0 {}
-        unsafe impl bytemuck::Pod for Input0 {}
+          }
+          #[repr(C)]
+          #[derive(
+              Debug,
+              PartialEq,
+              Clone,
+              Copy,
+              encase::ShaderType,
+              serde::Serialize,
+              serde::Deserialize
+          )]
+          pub struct Nested {
+              pub a: Input0,
+              pub b: f32,
+          }
+          impl Nested {
+            pub fn new(a: Input0, b: f32) -> Self {
+                Self { a, b }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_skip_stage_outputs() {
+    let source = indoc! {r#"
+            struct Input0 {
+                a: u32,
+                b: i32,
+                c: f32,
+            };
+
+            struct Output0 {
+                a: f32
+            }
+
+            struct Unused {
+                a: vec3<f32>
+            }
+
+            @fragment
+            fn main(in: Input0) -> Output0 {
+                var out: Output0;
+                return out;
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_serde: false,
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Input0 {
+              pub a: u32,
+              pub b: i32,
+              pub c: f32,
+          }
+          impl Input0 {
+            pub fn new(a: u32, b: i32, c: f32) -> Self {
+                Self { a, b, c }
+            }
+          }
+          unsafe impl bytemuck::Zeroable for Input0 {}
+          unsafe impl bytemuck::Pod for Input0 {}
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_bytemuck_skip_input_layout_validation() {
+    // Structs used only for vertex inputs don't require layout validation.
+    // Correctly specifying the offsets is handled by the buffer layout itself.
+    let source = indoc! {r#"
+            struct Input0 {
+                a: u32,
+                b: i32,
+                c: f32,
+            };
+
+            @vertex
+            fn main(input: Input0) -> vec4<f32> {
+                return vec4(0.0);
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_serde: false,
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Input0 {
+              pub a: u32,
+              pub b: i32,
+              pub c: f32,
+          }
+          impl Input0 {
+              pub fn new(a: u32, b: i32, c: f32) -> Self {
+                  Self { a, b, c }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Input0 {}
+          unsafe impl bytemuck::Pod for Input0 {}
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_bytemuck_input_layout_validation() {
+    // The struct is also used with a storage buffer and should be validated.
+    let source = indoc! {r#"
+            struct Input0 {
+                @size(8)
+                a: u32,
+                b: i32,
+                @align(32)
+                c: f32,
+            };
+
+            var<storage, read_write> test: Input0;
+
+            struct Outer {
+                inner: Inner
+            }
+
+            struct Inner {
+                a: f32
+            }
+
+            var<storage, read_write> test2: array<Outer>;
+
+            @vertex
+            fn main(input: Input0) -> vec4<f32> {
+                return vec4(0.0);
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_serde: false,
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+        #[repr(C, align(4))]
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct Input0 {
+            /// size: 4, offset: 0x0, type: `u32`
+            pub a: u32,
+            pub _pad_a: [u8; 0x8 - core::mem::size_of::<u32>()],
+            /// size: 4, offset: 0x8, type: `i32`
+            pub b: i32,
+            pub _pad_b: [u8; 0x18 - core::mem::size_of::<i32>()],
+            /// size: 4, offset: 0x20, type: `f32`
+            pub c: f32,
+            pub _pad_c: [u8; 0x20 - core::mem::size_of::<f32>()],
+        }
+        impl Input0 {
+            pub fn new(a: u32, b: i32, c: f32) -> Self {
+                Self {
+                    a,
+                    _pad_a: [0; 0x8 - core::mem::size_of::<u32>()],
+                    b,
+                    _pad_b: [0; 0x18 - core::mem::size_of::<i32>()],
+                    c,
+                    _pad_c: [0; 0x20 - core::mem::size_of::<f32>()],
+                }
+            }
+        }
+        unsafe impl bytemuck::Zeroable for Input0 {}
+        unsafe impl bytemuck::Pod for Input0 {}
         const _: () = {
           assert!(std::mem::offset_of!(Input0, a) == 0);
           assert!(std::mem::offset_of!(Input0, b) == 8);
@@ -1153,67 +1873,2814 @@ mod tests {
   }
 
   #[test]
-  fn write_atomic_types() {
+  fn write_atomic_types() {
+    let source = indoc! {r#"
+            struct Atomics {
+                num: atomic<u32>,
+                numi: atomic<i32>,
+            };
+
+            @group(0) @binding(0)
+            var <storage, read_write> atomics:Atomics;
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        wgsl_type_map: NalgebraWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Atomics {
+              /// An atomic in WGSL, so the buffer must be bound as `var<storage, read_write>`.
+              pub num: u32,
+              /// An atomic in WGSL, so the buffer must be bound as `var<storage, read_write>`.
+              pub numi: i32,
+          }
+          impl Atomics {
+            pub fn new(num: u32, numi: i32) -> Self {
+                Self { num, numi }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  fn runtime_sized_array_module() -> naga::Module {
+    let source = indoc! {r#"
+            struct RtsStruct {
+                other_data: i32,
+                the_array: array<u32>,
+            };
+
+            @group(0) @binding(0)
+            var <storage, read_write> rts:RtsStruct;
+        "#};
+    naga::front::wgsl::parse_str(source).unwrap()
+  }
+
+  #[test]
+  fn write_runtime_sized_array() {
+    let module = runtime_sized_array_module();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[derive(Debug, PartialEq, Clone, encase::ShaderType)]
+          pub struct RtsStruct {
+              pub other_data: i32,
+              #[size(runtime)]
+              pub the_array: Vec<u32>,
+          }
+          impl RtsStruct {
+            pub fn new(other_data: i32, the_array: Vec<u32>) -> Self {
+                Self { other_data, the_array }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_runtime_sized_array_bytemuck() {
+    let module = runtime_sized_array_module();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        ..Default::default()
+      },
+    );
+
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+        #[repr(C, align(4))]
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct RtsStruct<const N: usize> {
+            /// size: 4, offset: 0x0, type: `i32`
+            pub other_data: i32,
+            /// size: 4, offset: 0x4, type: `array<u32>`
+            pub the_array: [u32; N]
+        }
+        impl<const N:usize> RtsStruct<N> {
+            pub fn new(other_data: i32, the_array: [u32; N]) -> Self {
+                Self { other_data, the_array }
+            }
+        }
+        unsafe impl<const N: usize> bytemuck::Zeroable for RtsStruct<N> {}
+        unsafe impl<const N: usize> bytemuck::Pod for RtsStruct<N> {}
+        const _: () = {
+            assert!(std::mem::offset_of!(RtsStruct<1>, other_data) == 0);
+            assert!(std::mem::offset_of!(RtsStruct<1>, the_array) == 4);
+            assert!(std::mem::size_of::<RtsStruct<1> >() == 8);
+        };
+      },
+      actual
+    )
+  }
+
+  #[test]
+  fn write_runtime_sized_array_not_last_field() {
+    let source = indoc! {r#"
+            struct RtsStruct {
+                other_data: i32,
+                the_array: array<u32>,
+                more_data: i32,
+            };
+
+            @group(0) @binding(0)
+            var <storage, read_write> rts:RtsStruct;
+        "#};
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let result = structs_items(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        ..Default::default()
+      },
+      &WgslStructDocs::default(),
+    );
+
+    assert_eq!(
+      Some(CreateModuleError::RuntimeSizedArrayNotLast {
+        struct_name: "RtsStruct".into(),
+        member: "the_array".into(),
+      }),
+      result.err()
+    );
+  }
+
+  #[test]
+  fn write_nonpower_of_2_mats_for_bytemuck_option() {
+    let source = indoc! {r#"
+        struct UniformsData {
+          a: mat3x3<f32>,
+        }
+
+        @group(0) @binding(0)
+            var <uniform> un:UniformsData;
+      "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+        #[repr(C, align(16))]
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct UniformsData {
+            /// size: 48, offset: 0x0, type: `mat3x3<f32>`
+            pub a: [[f32; 4]; 3],
+        }
+        impl UniformsData {
+            pub fn new(a: [[f32; 4]; 3]) -> Self {
+                Self { a }
+            }
+        }
+        unsafe impl bytemuck::Zeroable for UniformsData {}
+        unsafe impl bytemuck::Pod for UniformsData {}
+        const _: () = {
+             assert!(std::mem::offset_of!(UniformsData, a) == 0);
+             assert!(std::mem::size_of::<UniformsData> () == 48);
+        };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_nonpower_of_2_mats_for_bytemuck_glam_option() {
+    let source = indoc! {r#"
+        struct UniformsData {
+          centered_mvp: mat3x3<f32>,
+        }
+
+        @group(0) @binding(0)
+            var <uniform> un:UniformsData;
+      "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        wgsl_type_map: GlamWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+        #[repr(C, align(16))]
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct UniformsData {
+            /// size: 48, offset: 0x0, type: `mat3x3<f32>`
+            pub centered_mvp: glam::Mat3A,
+        }
+        impl UniformsData {
+            pub fn new(centered_mvp: glam::Mat3A) -> Self {
+                Self { centered_mvp }
+            }
+        }
+        unsafe impl bytemuck::Zeroable for UniformsData {}
+        unsafe impl bytemuck::Pod for UniformsData {}
+        const _: () = {
+            assert!(std::mem::offset_of!(UniformsData, centered_mvp) == 0);
+            assert!(std::mem::size_of:: <UniformsData>() == 48);
+        };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_nonpower_of_2_mats() {
+    let source = indoc! {r#"
+          struct MatricesF32 {
+            a: mat4x4<f32>,
+            b: mat4x3<f32>,
+            c: mat4x2<f32>,
+            d: mat3x4<f32>,
+        };
+        @group(0) @binding(0)
+        var<uniform> f: MatricesF32;
+      "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+        #[repr(C, align(16))]
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct MatricesF32 {
+            /// size: 64, offset: 0x0, type: `mat4x4<f32>`
+            pub a: [[f32; 4]; 4],
+            /// size: 64, offset: 0x40, type: `mat4x3<f32>`
+            pub b: [[f32; 4]; 4],
+            /// size: 32, offset: 0x80, type: `mat4x2<f32>`
+            pub c: [[f32; 2]; 4],
+            /// size: 48, offset: 0xA0, type: `mat3x4<f32>`
+            pub d: [[f32; 4]; 3],
+        }
+        impl MatricesF32 {
+            pub fn new(
+                a: [[f32; 4]; 4],
+                b: [[f32; 4]; 4],
+                c: [[f32; 2]; 4],
+                d: [[f32; 4]; 3],
+            ) -> Self {
+                Self { a, b, c, d }
+            }
+        }
+        unsafe impl bytemuck::Zeroable for MatricesF32 {}
+        unsafe impl bytemuck::Pod for MatricesF32 {}
+        const _: () = {
+            assert!(std::mem::offset_of!(MatricesF32, a) == 0);
+            assert!(std::mem::offset_of!(MatricesF32, b) == 64);
+            assert!(std::mem::offset_of!(MatricesF32, c) == 128);
+            assert!(std::mem::offset_of!(MatricesF32, d) == 160);
+            assert!(std::mem::size_of::<MatricesF32>() == 208);
+        };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_with_type_override() {
+    let source = indoc! {r#"
+            struct PointLight {
+                position: vec4<f32>,
+                color: vec4<f32>,
+            };
+
+            struct Lights {
+                main: PointLight,
+                intensity: f32,
+            };
+            var<uniform> lights: Lights;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let mut options = WgslBindgenOption::default();
+    options
+      .type_overrides
+      .insert("PointLight".into(), quote!(my_crate::PointLight));
+
+    let structs = structs(&module, &options);
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Lights {
+              pub main: my_crate::PointLight,
+              pub intensity: f32,
+          }
+          impl Lights {
+            pub fn new(main: my_crate::PointLight, intensity: f32) -> Self {
+                Self { main, intensity }
+            }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_f16_bytemuck() {
+    // The WGSL frontend doesn't parse `enable f16;` yet, so build the module directly.
+    let mut module = naga::Module::default();
+    let f16 = naga::Scalar {
+      kind: naga::ScalarKind::Float,
+      width: 2,
+    };
+    let scalar = module.types.insert(
+      naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar(f16),
+      },
+      naga::Span::UNDEFINED,
+    );
+    let vector = module.types.insert(
+      naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+          size: naga::VectorSize::Tri,
+          scalar: f16,
+        },
+      },
+      naga::Span::UNDEFINED,
+    );
+    let member = |name: &str, ty, offset| naga::StructMember {
+      name: Some(name.into()),
+      ty,
+      binding: None,
+      offset,
+    };
+    let ty = module.types.insert(
+      naga::Type {
+        name: Some("Half".into()),
+        inner: naga::TypeInner::Struct {
+          members: vec![member("a", scalar, 0), member("b", vector, 8)],
+          span: 16,
+        },
+      },
+      naga::Span::UNDEFINED,
+    );
+    module.global_variables.append(
+      naga::GlobalVariable {
+        name: Some("half".into()),
+        space: naga::AddressSpace::Uniform,
+        binding: None,
+        ty,
+        init: None,
+      },
+      naga::Span::UNDEFINED,
+    );
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(8))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Half {
+              /// size: 2, offset: 0x0, type: `f16`
+              pub a: half::f16,
+              pub _pad_a: [u8; 0x8 - core::mem::size_of::<half::f16>()],
+              /// size: 6, offset: 0x8, type: `vec3<f16>`
+              pub b: [half::f16; 4],
+          }
+          impl Half {
+              pub fn new(a: half::f16, b: [half::f16; 4]) -> Self {
+                  Self {
+                      a,
+                      _pad_a: [0; 0x8 - core::mem::size_of::<half::f16>()],
+                      b,
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Half {}
+          unsafe impl bytemuck::Pod for Half {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Half, a) == 0);
+              assert!(std::mem::offset_of!(Half, b) == 8);
+              assert!(std::mem::size_of::<Half>() == 16);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct HalfInit {
+              pub a: half::f16,
+              pub b: [half::f16; 4],
+          }
+          impl HalfInit {
+              pub const fn const_into(&self) -> Half {
+                  Half {
+                      a: self.a,
+                      _pad_a: [0; 0x8 - core::mem::size_of::<half::f16>()],
+                      b: self.b,
+                  }
+              }
+          }
+          impl From<HalfInit> for Half {
+              fn from(data: HalfInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  /// Builds a module with a single uniform struct, for types the WGSL frontend can't parse.
+  fn uniform_struct_module(
+    name: &str,
+    members: &[(&str, naga::TypeInner, u32)],
+    span: u32,
+  ) -> naga::Module {
+    let mut module = naga::Module::default();
+    let members = members
+      .iter()
+      .map(|(name, inner, offset)| {
+        let ty = module.types.insert(
+          naga::Type {
+            name: None,
+            inner: inner.clone(),
+          },
+          naga::Span::UNDEFINED,
+        );
+        naga::StructMember {
+          name: Some(name.to_string()),
+          ty,
+          binding: None,
+          offset: *offset,
+        }
+      })
+      .collect();
+    let ty = module.types.insert(
+      naga::Type {
+        name: Some(name.into()),
+        inner: naga::TypeInner::Struct { members, span },
+      },
+      naga::Span::UNDEFINED,
+    );
+    module.global_variables.append(
+      naga::GlobalVariable {
+        name: Some(name.to_lowercase()),
+        space: naga::AddressSpace::Uniform,
+        binding: None,
+        ty,
+        init: None,
+      },
+      naga::Span::UNDEFINED,
+    );
+    module
+  }
+
+  #[test]
+  fn write_struct_i64_bytemuck() {
+    // The WGSL frontend doesn't parse `i64` and `u64` yet, so build the module directly.
+    let i64 = naga::Scalar {
+      kind: naga::ScalarKind::Sint,
+      width: 8,
+    };
+    let u64 = naga::Scalar {
+      kind: naga::ScalarKind::Uint,
+      width: 8,
+    };
+    let module = uniform_struct_module(
+      "Wide",
+      &[
+        ("a", naga::TypeInner::Scalar(i64), 0),
+        (
+          "b",
+          naga::TypeInner::Vector {
+            size: naga::VectorSize::Tri,
+            scalar: u64,
+          },
+          32,
+        ),
+        ("c", naga::TypeInner::Scalar(u64), 56),
+      ],
+      64,
+    );
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(32))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Wide {
+              /// size: 8, offset: 0x0, type: `i64`
+              pub a: i64,
+              pub _pad_a: [u8; 0x20 - core::mem::size_of::<i64>()],
+              /// size: 24, offset: 0x20, type: `vec3<u64>`
+              pub b: [u64; 3],
+              /// size: 8, offset: 0x38, type: `u64`
+              pub c: u64,
+          }
+          impl Wide {
+              pub fn new(a: i64, b: [u64; 3], c: u64) -> Self {
+                  Self {
+                      a,
+                      _pad_a: [0; 0x20 - core::mem::size_of::<i64>()],
+                      b,
+                      c,
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Wide {}
+          unsafe impl bytemuck::Pod for Wide {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Wide, a) == 0);
+              assert!(std::mem::offset_of!(Wide, b) == 32);
+              assert!(std::mem::offset_of!(Wide, c) == 56);
+              assert!(std::mem::size_of::<Wide>() == 64);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct WideInit {
+              pub a: i64,
+              pub b: [u64; 3],
+              pub c: u64,
+          }
+          impl WideInit {
+              pub const fn const_into(&self) -> Wide {
+                  Wide {
+                      a: self.a,
+                      _pad_a: [0; 0x20 - core::mem::size_of::<i64>()],
+                      b: self.b,
+                      c: self.c,
+                  }
+              }
+          }
+          impl From<WideInit> for Wide {
+              fn from(data: WideInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_bool_member_error() {
+    let source = indoc! {r#"
+            struct Flags {
+                a: u32,
+                enabled: bool,
+            };
+            var<private> flags: Flags;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let result = structs_items(
+      &module,
+      &WgslBindgenOption::default(),
+      &WgslStructDocs::default(),
+    );
+
+    assert_eq!(
+      Some(CreateModuleError::BoolMember {
+        struct_name: "Flags".into(),
+        member: "enabled".into(),
+      }),
+      result.err()
+    );
+  }
+
+  /// Renames the structs of the module, like the generic structs named by other frontends.
+  fn rename_structs(module: &mut naga::Module, names: &[(&str, &str)]) {
+    for &(old_name, new_name) in names {
+      let (handle, ty) = module
+        .types
+        .iter()
+        .find(|(_, ty)| ty.name.as_deref() == Some(old_name))
+        .unwrap();
+      let ty = naga::Type {
+        name: Some(new_name.to_owned()),
+        inner: ty.inner.clone(),
+      };
+      module.types.replace(handle, ty);
+    }
+  }
+
+  #[test]
+  fn write_generic_struct_names() {
+    let source = indoc! {r#"
+            struct StackF { items: array<f32, 4> };
+            struct StackI { items: array<i32, 4> };
+            @group(0) @binding(0) var<uniform> a: StackF;
+            @group(0) @binding(1) var<uniform> b: StackI;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let mut module = naga::front::wgsl::parse_str(source).unwrap();
+    rename_structs(&mut module, &[("StackF", "Stack<f32>"), ("StackI", "Stack<int>")]);
+    let structs = structs(&module, &WgslBindgenOption::default());
+    let actual = quote!(#(#structs)*).to_string();
+
+    assert!(actual.contains("pub struct StackF32 {"));
+    assert!(actual.contains("pub struct StackI32 {"));
+  }
+
+  #[test]
+  fn write_struct_name_collision_error() {
+    let source = indoc! {r#"
+            struct StackF { items: array<f32, 4> };
+            struct StackG { items: array<f32, 4> };
+            @group(0) @binding(0) var<uniform> a: StackF;
+            @group(0) @binding(1) var<uniform> b: StackG;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let mut module = naga::front::wgsl::parse_str(source).unwrap();
+    rename_structs(&mut module, &[("StackF", "Stack<f32>"), ("StackG", "Stack_F32")]);
+    let options = WgslBindgenOption {
+      name_casing: WgslNameCasing::RustConventions,
+      ..Default::default()
+    };
+    let result = structs_items(&module, &options, &WgslStructDocs::default());
+
+    assert_eq!(
+      Some(CreateModuleError::StructNameCollision {
+        first: "Stack<f32>".into(),
+        second: "Stack_F32".into(),
+        rust_name: "StackF32".into(),
+      }),
+      result.err()
+    );
+
+    // Renaming one of them resolves the collision.
+    let options = WgslBindgenOption {
+      struct_renames: [("Stack_F32".to_string(), "StackOfFloats".to_string())]
+        .into_iter()
+        .collect(),
+      ..options
+    };
+    assert!(structs_items(&module, &options, &WgslStructDocs::default()).is_ok());
+  }
+
+  #[test]
+  fn write_struct_bool_member_u32_bytemuck() {
+    let source = indoc! {r#"
+            struct Flags {
+                a: u32,
+                enabled: bool,
+            };
+            var<private> flags: Flags;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        bool_member_strategy: WgslBoolMemberStrategy::U32,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Flags {
+              pub a: u32,
+              pub enabled: _root::WgslBool,
+          }
+          impl Flags {
+              pub fn new(a: u32, enabled: _root::WgslBool) -> Self {
+                  Self { a, enabled }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Flags {}
+          unsafe impl bytemuck::Pod for Flags {}
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_all_structs_glam_packed_bytemuck() {
+    let source = indoc! {r#"
+            struct Packed {
+                a: vec3<f32>,
+                b: f32,
+                c: vec3<u32>,
+                d: vec4<f32>,
+                e: array<vec3<f32>, 2>,
+                f: mat3x3<f32>,
+            };
+            var<uniform> a: Packed;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let mut options = WgslBindgenOption {
+      serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+      wgsl_type_map: GlamWgslTypeMap::packed().build(WgslTypeSerializeStrategy::Bytemuck),
+      ..Default::default()
+    };
+    options
+      .wgsl_type_map_packed_types
+      .extend(GlamWgslTypeMap::packed().packed_types());
+
+    let structs = structs(&module, &options);
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Packed {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub a: glam::Vec3,
+              /// size: 4, offset: 0xC, type: `f32`
+              pub b: f32,
+              /// size: 12, offset: 0x10, type: `vec3<u32>`
+              pub c: glam::UVec3,
+              pub _pad_c: [u8; 0x10 - core::mem::size_of::<glam::UVec3>()],
+              /// size: 16, offset: 0x20, type: `vec4<f32>`
+              pub d: glam::Vec4,
+              /// size: 32, offset: 0x30, type: `array<vec3<f32>, 2>`
+              pub e: [[f32; 4]; 2],
+              pub _pad_e: [u8; 0x20 - core::mem::size_of::<[[f32; 4]; 2]>()],
+              /// size: 48, offset: 0x50, type: `mat3x3<f32>`
+              pub f: [[f32; 4]; 3],
+          }
+          impl Packed {
+              pub fn new(
+                  a: glam::Vec3,
+                  b: f32,
+                  c: glam::UVec3,
+                  d: glam::Vec4,
+                  e: [[f32; 4]; 2],
+                  f: [[f32; 4]; 3],
+              ) -> Self {
+                  Self {
+                      a,
+                      b,
+                      c,
+                      _pad_c: [0; 0x10 - core::mem::size_of::<glam::UVec3>()],
+                      d,
+                      e,
+                      _pad_e: [0; 0x20 - core::mem::size_of::<[[f32; 4]; 2]>()],
+                      f,
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Packed {}
+          unsafe impl bytemuck::Pod for Packed {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Packed, a) == 0);
+              assert!(std::mem::offset_of!(Packed, b) == 12);
+              assert!(std::mem::offset_of!(Packed, c) == 16);
+              assert!(std::mem::offset_of!(Packed, d) == 32);
+              assert!(std::mem::offset_of!(Packed, e) == 48);
+              assert!(std::mem::offset_of!(Packed, f) == 80);
+              assert!(std::mem::size_of::<Packed>() == 128);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct PackedInit {
+              pub a: glam::Vec3,
+              pub b: f32,
+              pub c: glam::UVec3,
+              pub d: glam::Vec4,
+              pub e: [[f32; 4]; 2],
+              pub f: [[f32; 4]; 3],
+          }
+          impl PackedInit {
+              pub const fn const_into(&self) -> Packed {
+                  Packed {
+                      a: self.a,
+                      b: self.b,
+                      c: self.c,
+                      _pad_c: [0; 0x10 - core::mem::size_of::<glam::UVec3>()],
+                      d: self.d,
+                      e: self.e,
+                      _pad_e: [0; 0x20 - core::mem::size_of::<[[f32; 4]; 2]>()],
+                      f: self.f,
+                  }
+              }
+          }
+          impl From<PackedInit> for Packed {
+              fn from(data: PackedInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_additional_derives() {
+    let source = indoc! {r#"
+            struct Id {
+                value: u32,
+            };
+            var<uniform> id: Id;
+
+            struct Position {
+                value: vec2<i32>,
+            };
+            var<uniform> position: Position;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let mut options = WgslBindgenOption {
+      additional_struct_derives: vec![quote!(Hash)],
+      ..Default::default()
+    };
+    options
+      .additional_struct_derives_by_name
+      .insert("Id".into(), vec![quote!(Eq), quote!(PartialOrd)]);
+
+    let structs = structs(&module, &options);
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType, Hash, Eq, PartialOrd)]
+          pub struct Id {
+              pub value: u32,
+          }
+          impl Id {
+              pub fn new(value: u32) -> Self {
+                  Self { value }
+              }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType, Hash)]
+          pub struct Position {
+              pub value: [i32; 2],
+          }
+          impl Position {
+              pub fn new(value: [i32; 2]) -> Self {
+                  Self { value }
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_default_impl_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        impl_default: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub color: [f32; 3],
+              /// size: 4, offset: 0xC, type: `f32`
+              pub intensity: f32,
+          }
+          impl Light {
+              pub fn new(color: [f32; 3], intensity: f32) -> Self {
+                  Self { color, intensity }
+              }
+          }
+          impl Default for Light {
+              fn default() -> Self {
+                  bytemuck::Zeroable::zeroed()
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 12);
+              assert!(std::mem::size_of::<Light>() == 16);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_default_impl_encase() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        impl_default: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self { color, intensity }
+              }
+          }
+          impl Default for Light {
+              fn default() -> Self {
+                  Self {
+                      color: std::array::from_fn(|_| Default::default()),
+                      intensity: Default::default(),
+                  }
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_default_impl_encase_large_arrays() {
+    let source = indoc! {r#"
+            struct Transforms {
+                count: u32,
+                matrices: array<mat4x4<f32>, 512>,
+            };
+            var<storage> transforms: Transforms;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        impl_default: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Transforms {
+              pub count: u32,
+              pub matrices: [[[f32; 4]; 4]; 512],
+          }
+          impl Transforms {
+              pub fn new(count: u32, matrices: [[[f32; 4]; 4]; 512]) -> Self {
+                  Self { count, matrices }
+              }
+          }
+          impl Default for Transforms {
+              fn default() -> Self {
+                  Self {
+                      count: Default::default(),
+                      matrices: std::array::from_fn(|_| std::array::from_fn(|_| std::array::from_fn(|_| Default::default()))),
+                  }
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_item_visibility_pub_crate() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+                direction: vec2<f32>,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        item_visibility: WgslItemVisibility::PubCrate,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub(crate) struct Light {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub(crate) color: [f32; 3],
+              /// size: 4, offset: 0xC, type: `f32`
+              pub(crate) intensity: f32,
+              /// size: 8, offset: 0x10, type: `vec2<f32>`
+              pub(crate) direction: [f32; 2],
+              pub(crate) _pad_direction: [u8; 0x10 - core::mem::size_of::<[f32; 2]>()],
+          }
+          impl Light {
+              pub(crate) fn new(color: [f32; 3], intensity: f32, direction: [f32; 2]) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      direction,
+                      _pad_direction: [0; 0x10 - core::mem::size_of::<[f32; 2]>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 12);
+              assert!(std::mem::offset_of!(Light, direction) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub(crate) struct LightInit {
+              pub(crate) color: [f32; 3],
+              pub(crate) intensity: f32,
+              pub(crate) direction: [f32; 2],
+          }
+          impl LightInit {
+              pub(crate) const fn const_into(&self) -> Light {
+                  Light {
+                      color: self.color,
+                      intensity: self.intensity,
+                      direction: self.direction,
+                      _pad_direction: [0; 0x10 - core::mem::size_of::<[f32; 2]>()],
+                  }
+              }
+          }
+          impl From<LightInit> for Light {
+              fn from(data: LightInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_rust_name_casing() {
+    let source = indoc! {r#"
+            struct lightData {
+                lightColor: vec4<f32>,
+                intensity: f32,
+                flags: u32,
+            };
+            struct camelStruct {
+                someLight: lightData,
+            };
+            var<uniform> light: camelStruct;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        name_casing: WgslNameCasing::RustConventions,
+        struct_renames: [("lightData".to_string(), "Light".to_string())]
+          .into_iter()
+          .collect(),
+        struct_member_renames: [(
+          ("lightData".to_string(), "flags".to_string()),
+          "light_flags".to_string(),
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub light_color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              /// size: 4, offset: 0x14, type: `u32`
+              pub light_flags: u32,
+              pub _pad_light_flags: [u8; 0xC - core::mem::size_of::<u32>()],
+          }
+          impl Light {
+              pub fn new(light_color: [f32; 4], intensity: f32, light_flags: u32) -> Self {
+                  Self {
+                      light_color,
+                      intensity,
+                      light_flags,
+                      _pad_light_flags: [0; 0xC - core::mem::size_of::<u32>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, light_color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::offset_of!(Light, light_flags) == 20);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct LightInit {
+              pub light_color: [f32; 4],
+              pub intensity: f32,
+              pub light_flags: u32,
+          }
+          impl LightInit {
+              pub const fn const_into(&self) -> Light {
+                  Light {
+                      light_color: self.light_color,
+                      intensity: self.intensity,
+                      light_flags: self.light_flags,
+                      _pad_light_flags: [0; 0xC - core::mem::size_of::<u32>()],
+                  }
+              }
+          }
+          impl From<LightInit> for Light {
+              fn from(data: LightInit) -> Self {
+                  data.const_into()
+              }
+          }
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct CamelStruct {
+              /// size: 32, offset: 0x0, type: `struct`
+              pub some_light: Light,
+          }
+          impl CamelStruct {
+              pub fn new(some_light: Light) -> Self {
+                  Self { some_light }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for CamelStruct {}
+          unsafe impl bytemuck::Pod for CamelStruct {}
+          const _: () = {
+              assert!(std::mem::offset_of!(CamelStruct, some_light) == 0);
+              assert!(std::mem::size_of::<CamelStruct>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_conversions() {
+    let source = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>,
+                position: vec4<f32>,
+            };
+            var<uniform> camera: Camera;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        struct_conversions: [(
+          "Camera".to_string(),
+          vec![StructConversion {
+            rust_type: quote!(crate::Camera),
+            fields: [("view_proj".to_string(), "view_projection".to_string())]
+              .into_iter()
+              .collect(),
+          }],
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Camera {
+              /// size: 64, offset: 0x0, type: `mat4x4<f32>`
+              pub view_proj: [[f32; 4]; 4],
+              /// size: 16, offset: 0x40, type: `vec4<f32>`
+              pub position: [f32; 4],
+          }
+          impl Camera {
+              pub fn new(view_proj: [[f32; 4]; 4], position: [f32; 4]) -> Self {
+                  Self { view_proj, position }
+              }
+          }
+          impl From<crate::Camera> for Camera {
+              fn from(value: crate::Camera) -> Self {
+                  Self::new(value.view_projection.into(), value.position.into())
+              }
+          }
+          impl From<Camera> for crate::Camera {
+              fn from(value: Camera) -> Self {
+                  Self {
+                      view_projection: value.view_proj.into(),
+                      position: value.position.into(),
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Camera {}
+          unsafe impl bytemuck::Pod for Camera {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Camera, view_proj) == 0);
+              assert!(std::mem::offset_of!(Camera, position) == 64);
+              assert!(std::mem::size_of::<Camera>() == 80);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_init_struct_always_encase() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        init_struct_strategy: WgslInitStructStrategy::Always,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self { color, intensity }
+              }
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct LightInit {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl LightInit {
+              pub const fn const_into(&self) -> Light {
+                  Light {
+                      color: self.color,
+                      intensity: self.intensity,
+                  }
+              }
+          }
+          impl From<LightInit> for Light {
+              fn from(data: LightInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_builder_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+                direction: vec2<f32>,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        generate_struct_builders: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub color: [f32; 3],
+              /// size: 4, offset: 0xC, type: `f32`
+              pub intensity: f32,
+              /// size: 8, offset: 0x10, type: `vec2<f32>`
+              pub direction: [f32; 2],
+              pub _pad_direction: [u8; 0x10 - core::mem::size_of::<[f32; 2]>()],
+          }
+          impl Light {
+              pub fn new(color: [f32; 3], intensity: f32, direction: [f32; 2]) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      direction,
+                      _pad_direction: [0; 0x10 - core::mem::size_of::<[f32; 2]>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 12);
+              assert!(std::mem::offset_of!(Light, direction) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+          #[derive(Debug, Default, Clone)]
+          pub struct LightBuilder {
+              color: Option<[f32; 3]>,
+              intensity: Option<f32>,
+              direction: Option<[f32; 2]>,
+          }
+          impl LightBuilder {
+              pub fn color(mut self, color: [f32; 3]) -> Self {
+                  self.color = Some(color);
+                  self
+              }
+              pub fn intensity(mut self, intensity: f32) -> Self {
+                  self.intensity = Some(intensity);
+                  self
+              }
+              pub fn direction(mut self, direction: [f32; 2]) -> Self {
+                  self.direction = Some(direction);
+                  self
+              }
+              /// Builds the struct, or returns the name of the first field which wasn't set.
+              pub fn build(self) -> Result<Light, &'static str> {
+                  Ok(
+                      Light::new(
+                          self.color.ok_or("color")?,
+                          self.intensity.ok_or("intensity")?,
+                          self.direction.ok_or("direction")?,
+                      ),
+                  )
+              }
+          }
+          impl Light {
+              pub fn builder() -> LightBuilder {
+                  Default::default()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_hide_padding_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+                direction: vec2<f32>,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        hide_padding: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              color: [f32; 3],
+              /// size: 4, offset: 0xC, type: `f32`
+              intensity: f32,
+              /// size: 8, offset: 0x10, type: `vec2<f32>`
+              direction: [f32; 2],
+              _pad_direction: [u8; 0x10 - core::mem::size_of::<[f32; 2]>()],
+          }
+          impl Light {
+              pub fn new(color: [f32; 3], intensity: f32, direction: [f32; 2]) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      direction,
+                      _pad_direction: [0; 0x10 - core::mem::size_of::<[f32; 2]>()],
+                  }
+              }
+          }
+          impl Light {
+              pub fn color(&self) -> &[f32; 3] {
+                  &self.color
+              }
+              pub fn set_color(&mut self, color: [f32; 3]) {
+                  self.color = color;
+              }
+              pub fn intensity(&self) -> &f32 {
+                  &self.intensity
+              }
+              pub fn set_intensity(&mut self, intensity: f32) {
+                  self.intensity = intensity;
+              }
+              pub fn direction(&self) -> &[f32; 2] {
+                  &self.direction
+              }
+              pub fn set_direction(&mut self, direction: [f32; 2]) {
+                  self.direction = direction;
+              }
+          }
+          impl Default for Light {
+              fn default() -> Self {
+                  bytemuck::Zeroable::zeroed()
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 12);
+              assert!(std::mem::offset_of!(Light, direction) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_docs_from_comments() {
+    let source = indoc! {r#"
+            // A light shining in all directions.
+            struct Light {
+                // The color, alpha is unused.
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let mut docs = WgslStructDocs::default();
+    docs.add_source(source, None);
+
+    let options = WgslBindgenOption {
+      serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+      ..Default::default()
+    };
+    let structs = structs_items(&module, &options, &docs).unwrap();
+    let structs = structs.into_iter().map(|s| s.item);
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          /// A light shining in all directions.
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// The color, alpha is unused.
+              ///
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              pub _pad_intensity: [u8; 0x10 - core::mem::size_of::<f32>()],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct LightInit {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl LightInit {
+              pub const fn const_into(&self) -> Light {
+                  Light {
+                      color: self.color,
+                      intensity: self.intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          impl From<LightInit> for Light {
+              fn from(data: LightInit) -> Self {
+                  data.const_into()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_serde_attributes_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                base_color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_serde: true,
+        serde_rename_all: Some("camelCase".into()),
+        serde_skip_padding: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+          #[serde(rename_all = "camelCase")]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub base_color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              #[serde(skip, default = "_root::zeroed_padding")]
+              pub _pad_intensity: [u8; 0x10 - core::mem::size_of::<f32>()],
+          }
+          impl Light {
+              pub fn new(base_color: [f32; 4], intensity: f32) -> Self {
+                  Self {
+                      base_color,
+                      intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, base_color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_derive_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_bytemuck: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              pub _pad_intensity: [u8; 0x10 - core::mem::size_of::<f32>()],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_derive_bytemuck_vertex_input() {
+    // Vertex inputs aren't padded explicitly, which the derive macros reject.
+    let source = indoc! {r#"
+            struct VertexInput {
+                @location(0) weight: f32,
+                @location(1) color: vec4<f32>,
+            };
+
+            @vertex
+            fn main(input: VertexInput) -> @builtin(position) vec4<f32> {
+                return input.color * input.weight;
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        derive_bytemuck: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct VertexInput {
+              pub weight: f32,
+              pub color: [f32; 4],
+          }
+          impl VertexInput {
+              pub fn new(weight: f32, color: [f32; 4]) -> Self {
+                  Self { weight, color }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for VertexInput {}
+          unsafe impl bytemuck::Pod for VertexInput {}
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_const_fn_new() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        const_fn_new: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              pub _pad_intensity: [u8; 0x10 - core::mem::size_of::<f32>()],
+          }
+          impl Light {
+              pub const fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_byte_helpers_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        generate_byte_helpers: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub color: [f32; 4],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4]) -> Self {
+                  Self { color }
+              }
+          }
+          impl Light {
+              pub fn as_bytes(&self) -> &[u8] {
+                  bytemuck::bytes_of(self)
+              }
+              /// Panics if `bytes` has the wrong size or alignment.
+              pub fn from_bytes(bytes: &[u8]) -> &Self {
+                  bytemuck::from_bytes(bytes)
+              }
+              pub fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+                  bytemuck::cast_slice(slice)
+              }
+              /// Panics if `bytes` has the wrong size or alignment.
+              pub fn slice_from_bytes(bytes: &[u8]) -> &[Self] {
+                  bytemuck::cast_slice(bytes)
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::size_of::<Light>() == 16);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_field_offsets() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        generate_field_offsets: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self { color, intensity }
+              }
+          }
+          /// The offsets of the members of [Light] in bytes.
+          pub mod light_offsets {
+              #[allow(unused_imports)]
+              use super::*;
+              pub const COLOR: u64 = 0;
+              /// Writes `color` to the [Light] at `base_offset` in the buffer.
+              pub fn write_color(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &[f32; 4],
+              ) {
+                  let offset = base_offset + COLOR;
+                  let mut bytes = encase::StorageBuffer::new(Vec::new());
+                  bytes.write(value).unwrap();
+                  queue.write_buffer(buffer, offset, &bytes.into_inner());
+              }
+              pub const INTENSITY: u64 = 16;
+              /// Writes `intensity` to the [Light] at `base_offset` in the buffer.
+              pub fn write_intensity(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &f32,
+              ) {
+                  let offset = base_offset + INTENSITY;
+                  let mut bytes = encase::StorageBuffer::new(Vec::new());
+                  bytes.write(value).unwrap();
+                  queue.write_buffer(buffer, offset, &bytes.into_inner());
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_field_offsets_bytemuck() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            var<uniform> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        generate_field_offsets: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Light {
+              /// size: 16, offset: 0x0, type: `vec4<f32>`
+              pub color: [f32; 4],
+              /// size: 4, offset: 0x10, type: `f32`
+              pub intensity: f32,
+              pub _pad_intensity: [u8; 0x10 - core::mem::size_of::<f32>()],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self {
+                      color,
+                      intensity,
+                      _pad_intensity: [0; 0x10 - core::mem::size_of::<f32>()],
+                  }
+              }
+          }
+          /// The offsets of the members of [Light] in bytes.
+          pub mod light_offsets {
+              #[allow(unused_imports)]
+              use super::*;
+              pub const COLOR: u64 = 0;
+              /// Writes `color` to the [Light] at `base_offset` in the buffer.
+              pub fn write_color(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &[f32; 4],
+              ) {
+                  let offset = base_offset + COLOR;
+                  queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+              }
+              pub const INTENSITY: u64 = 16;
+              /// Writes `intensity` to the [Light] at `base_offset` in the buffer.
+              pub fn write_intensity(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &f32,
+              ) {
+                  let offset = base_offset + INTENSITY;
+                  queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Light {}
+          unsafe impl bytemuck::Pod for Light {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Light, color) == 0);
+              assert!(std::mem::offset_of!(Light, intensity) == 16);
+              assert!(std::mem::size_of::<Light>() == 32);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_field_offsets_bytemuck_glam() {
+    let source = indoc! {r#"
+            struct Material {
+                color: vec4<f32>,
+            };
+            struct Shape {
+                direction: vec3<f32>,
+                rotation: mat3x3<f32>,
+                corners: array<vec3<f32>, 2>,
+                material: Material,
+            };
+            var<uniform> shape: Shape;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        wgsl_type_map: GlamWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map_packed_vector_types: GlamWgslTypeMap
+          .packed_vector_types()
+          .into_iter()
+          .collect(),
+        type_overrides: [("Material".to_owned(), quote!(crate::Material))]
+          .into_iter()
+          .collect(),
+        generate_field_offsets: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Shape {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub direction: glam::Vec3A,
+              /// size: 48, offset: 0x10, type: `mat3x3<f32>`
+              pub rotation: glam::Mat3A,
+              /// size: 32, offset: 0x40, type: `array<vec3<f32>, 2>`
+              pub corners: [glam::Vec3A; 2],
+              pub _pad_corners: [u8; 0x20 - core::mem::size_of::<[glam::Vec3A; 2]>()],
+              /// size: 16, offset: 0x60, type: `struct`
+              pub material: crate::Material,
+          }
+          impl Shape {
+              pub fn new(
+                  direction: glam::Vec3A,
+                  rotation: glam::Mat3A,
+                  corners: [glam::Vec3A; 2],
+                  material: crate::Material,
+              ) -> Self {
+                  Self {
+                      direction,
+                      rotation,
+                      corners,
+                      _pad_corners: [0; 0x20 - core::mem::size_of::<[glam::Vec3A; 2]>()],
+                      material,
+                  }
+              }
+          }
+          /// The offsets of the members of [Shape] in bytes.
+          pub mod shape_offsets {
+              #[allow(unused_imports)]
+              use super::*;
+              pub const DIRECTION: u64 = 0;
+              /// Writes `direction` to the [Shape] at `base_offset` in the buffer.
+              pub fn write_direction(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &glam::Vec3,
+              ) {
+                  let offset = base_offset + DIRECTION;
+                  queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+              }
+              pub const ROTATION: u64 = 16;
+              /// Writes `rotation` to the [Shape] at `base_offset` in the buffer.
+              pub fn write_rotation(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &[glam::Vec3; 3],
+              ) {
+                  let offset = base_offset + ROTATION;
+                  for (i, value) in value.iter().enumerate() {
+                      let offset = offset + i as u64 * 16;
+                      queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+                  }
+              }
+              pub const CORNERS: u64 = 64;
+              /// Writes `corners` to the [Shape] at `base_offset` in the buffer.
+              pub fn write_corners(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &[glam::Vec3; 2],
+              ) {
+                  let offset = base_offset + CORNERS;
+                  for (i, value) in value.iter().enumerate() {
+                      let offset = offset + i as u64 * 16;
+                      queue.write_buffer(buffer, offset, bytemuck::bytes_of(value));
+                  }
+              }
+              pub const MATERIAL: u64 = 96;
+              /// Writes `material` to the [Shape] at `base_offset` in the buffer.
+              ///
+              /// # Safety
+              ///
+              /// `value` must have the layout of the WGSL member, with all its 16 bytes initialized.
+              pub unsafe fn write_material(
+                  queue: &wgpu::Queue,
+                  buffer: &wgpu::Buffer,
+                  base_offset: u64,
+                  value: &crate::Material,
+              ) {
+                  let offset = base_offset + MATERIAL;
+                  let bytes = unsafe {
+                      std::slice::from_raw_parts(
+                          (value as *const crate::Material).cast::<u8>(),
+                          16,
+                      )
+                  };
+                  queue.write_buffer(buffer, offset, bytes);
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Shape {}
+          unsafe impl bytemuck::Pod for Shape {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Shape, direction) == 0);
+              assert!(std::mem::offset_of!(Shape, rotation) == 16);
+              assert!(std::mem::offset_of!(Shape, corners) == 64);
+              assert!(std::mem::offset_of!(Shape, material) == 96);
+              assert!(std::mem::size_of::<Shape>() == 112);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_encase_helpers() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+            };
+            struct Lights {
+                lights: array<Light>,
+            };
+            var<uniform> light: Light;
+            var<storage> lights: Lights;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        generate_encase_helpers: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4]) -> Self {
+                  Self { color }
+              }
+          }
+          impl Light {
+              /// The size of the struct in a buffer, with the padding of the WGSL layout.
+              pub const SHADER_SIZE: u64 = <Self as encase::ShaderSize>::SHADER_SIZE.get();
+              /// The bytes of the struct in a uniform buffer.
+              pub fn write_into_uniform(&self) -> Vec<u8> {
+                  let mut buffer = encase::UniformBuffer::new(Vec::new());
+                  buffer.write(self).unwrap();
+                  buffer.into_inner()
+              }
+              /// The bytes of the struct in a storage buffer.
+              pub fn write_into_storage(&self) -> Vec<u8> {
+                  let mut buffer = encase::StorageBuffer::new(Vec::new());
+                  buffer.write(self).unwrap();
+                  buffer.into_inner()
+              }
+          }
+          #[derive(Debug, PartialEq, Clone, encase::ShaderType)]
+          pub struct Lights {
+              #[size(runtime)]
+              pub lights: Vec<Light>,
+          }
+          impl Lights {
+              pub fn new(lights: Vec<Light>) -> Self {
+                  Self { lights }
+              }
+          }
+          impl Lights {
+              /// The bytes of the struct in a storage buffer.
+              pub fn write_into_storage(&self) -> Vec<u8> {
+                  let mut buffer = encase::StorageBuffer::new(Vec::new());
+                  buffer.write(self).unwrap();
+                  buffer.into_inner()
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_pad_uniform_structs() {
+    let source = indoc! {r#"
+            struct Params {
+                time: f32,
+                count: u32,
+            };
+            struct Particle {
+                speed: f32,
+            };
+            var<uniform> params: Params;
+            var<storage> particles: array<Particle>;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        pad_uniform_structs: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(4))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Params {
+              /// size: 4, offset: 0x0, type: `f32`
+              pub time: f32,
+              /// size: 4, offset: 0x4, type: `u32`
+              pub count: u32,
+              pub _pad_count: [u8; 0xC - core::mem::size_of::<u32>()],
+          }
+          impl Params {
+              pub fn new(time: f32, count: u32) -> Self {
+                  Self {
+                      time,
+                      count,
+                      _pad_count: [0; 0xC - core::mem::size_of::<u32>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Params {}
+          unsafe impl bytemuck::Pod for Params {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Params, time) == 0);
+              assert!(std::mem::offset_of!(Params, count) == 4);
+              assert!(std::mem::size_of::<Params>() == 16);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct ParamsInit {
+              pub time: f32,
+              pub count: u32,
+          }
+          impl ParamsInit {
+              pub const fn const_into(&self) -> Params {
+                  Params {
+                      time: self.time,
+                      count: self.count,
+                      _pad_count: [0; 0xC - core::mem::size_of::<u32>()],
+                  }
+              }
+          }
+          impl From<ParamsInit> for Params {
+              fn from(data: ParamsInit) -> Self {
+                  data.const_into()
+              }
+          }
+          #[repr(C, align(4))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Particle {
+              /// size: 4, offset: 0x0, type: `f32`
+              pub speed: f32,
+          }
+          impl Particle {
+              pub fn new(speed: f32) -> Self {
+                  Self { speed }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Particle {}
+          unsafe impl bytemuck::Pod for Particle {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Particle, speed) == 0);
+              assert!(std::mem::size_of::<Particle>() == 4);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_pad_uniform_structs_array_elements() {
+    let source = indoc! {r#"
+            struct Params {
+                time: f32,
+                count: u32,
+            };
+            var<uniform> params: Params;
+            var<storage> history: array<Params, 4>;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        pad_uniform_structs: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    // The elements of the storage array keep the stride of 8 bytes.
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(4))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Params {
+              /// size: 4, offset: 0x0, type: `f32`
+              pub time: f32,
+              /// size: 4, offset: 0x4, type: `u32`
+              pub count: u32,
+          }
+          impl Params {
+              pub fn new(time: f32, count: u32) -> Self {
+                  Self { time, count }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Params {}
+          unsafe impl bytemuck::Pod for Params {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Params, time) == 0);
+              assert!(std::mem::offset_of!(Params, count) == 4);
+              assert!(std::mem::size_of::<Params>() == 8);
+          };
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_layout_tests_module_pad_uniform_structs() {
+    let source = indoc! {r#"
+            struct Params {
+                time: f32,
+                count: u32,
+            };
+            var<uniform> params: Params;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let actual = layout_tests_module(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        pad_uniform_structs: true,
+        generate_layout_tests: true,
+        ..Default::default()
+      },
+    );
+
+    assert_tokens_eq!(
+      quote! {
+          #[cfg(test)]
+          mod layout_tests {
+              use super::*;
+              #[test]
+              fn params_layout() {
+                  assert_eq!(std::mem::offset_of!(Params, time), 0);
+                  assert_eq!(std::mem::offset_of!(Params, count), 4);
+                  assert_eq!(std::mem::size_of::<Params>(), 16);
+                  assert_eq!(std::mem::align_of::<Params>(), 4);
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_aligned_size() {
+    let source = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>,
+                position: vec4<f32>,
+            };
+            struct Light {
+                color: vec4<f32>,
+            };
+            var<uniform> camera: Camera;
+            var<storage> light: Light;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        generate_aligned_sizes: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Camera {
+              pub view_proj: [[f32; 4]; 4],
+              pub position: [f32; 4],
+          }
+          impl Camera {
+              pub fn new(view_proj: [[f32; 4]; 4], position: [f32; 4]) -> Self {
+                  Self { view_proj, position }
+              }
+          }
+          impl Camera {
+              /// The size of the struct rounded up to the alignment of dynamic offsets, like the
+              /// `min_uniform_buffer_offset_alignment` limit, for the stride between instances.
+              pub const fn aligned_size(alignment: u32) -> u64 {
+                  80u64.next_multiple_of(alignment as u64)
+              }
+              /// The size of the struct rounded up to the default alignment of dynamic offsets.
+              pub const ALIGNED_SIZE: u64 = Self::aligned_size(256);
+          }
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+          }
+          impl Light {
+              pub fn new(color: [f32; 4]) -> Self {
+                  Self { color }
+              }
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_struct_encase_layout_tests() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec4<f32>,
+                intensity: f32,
+            };
+            struct Lights {
+                count: u32,
+                lights: array<Light>,
+            };
+            var<storage> lights: Lights;
+
+            @fragment
+            fn main() {}
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    let structs = structs(
+      &module,
+      &WgslBindgenOption {
+        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        generate_encase_layout_tests: true,
+        ..Default::default()
+      },
+    );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
+          pub struct Light {
+              pub color: [f32; 4],
+              pub intensity: f32,
+          }
+          impl Light {
+              pub fn new(color: [f32; 4], intensity: f32) -> Self {
+                  Self { color, intensity }
+              }
+          }
+          #[cfg(test)]
+          #[test]
+          fn light_encase_layout() {
+              let zeroed = || -> Light {
+                  encase::StorageBuffer::new(vec![0u8; 32]).create().unwrap()
+              };
+              let ones = encase::StorageBuffer::new(vec![1u8; 32]);
+              let written_offset = |value: &Light| {
+                  let mut buffer = encase::StorageBuffer::new(Vec::new());
+                  buffer.write(value).unwrap();
+                  buffer.into_inner().iter().position(|&byte| byte != 0)
+              };
+              let mut value = zeroed();
+              value.color = ones.create().unwrap();
+              assert_eq!(written_offset(&value), Some(0), "color");
+              let mut value = zeroed();
+              value.intensity = ones.create().unwrap();
+              assert_eq!(written_offset(&value), Some(16), "intensity");
+              assert_eq!(<Light as encase::ShaderType>::min_size().get(), 32);
+          }
+          #[derive(Debug, PartialEq, Clone, encase::ShaderType)]
+          pub struct Lights {
+              pub count: u32,
+              #[size(runtime)]
+              pub lights: Vec<Light>,
+          }
+          impl Lights {
+              pub fn new(count: u32, lights: Vec<Light>) -> Self {
+                  Self { count, lights }
+              }
+          }
+          #[cfg(test)]
+          #[test]
+          fn lights_encase_layout() {
+              let zeroed = || -> Lights {
+                  encase::StorageBuffer::new(vec![0u8; 48]).create().unwrap()
+              };
+              let ones = encase::StorageBuffer::new(vec![1u8; 48]);
+              let written_offset = |value: &Lights| {
+                  let mut buffer = encase::StorageBuffer::new(Vec::new());
+                  buffer.write(value).unwrap();
+                  buffer.into_inner().iter().position(|&byte| byte != 0)
+              };
+              let mut value = zeroed();
+              value.count = ones.create().unwrap();
+              assert_eq!(written_offset(&value), Some(0), "count");
+              let mut value = zeroed();
+              value.lights = ones.create().unwrap();
+              assert_eq!(written_offset(&value), Some(16), "lights");
+          }
+      },
+      actual
+    );
+  }
+
+  #[test]
+  fn write_layout_tests_module_bytemuck() {
     let source = indoc! {r#"
-            struct Atomics {
-                num: atomic<u32>,
-                numi: atomic<i32>,
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+            };
+            struct Lights {
+                count: u32,
+                lights: array<Light>,
             };
+            var<storage> lights: Lights;
 
-            @group(0) @binding(0)
-            var <storage, read_write> atomics:Atomics;
+            @fragment
+            fn main() {}
         "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
-    let structs = structs(
+    let actual = layout_tests_module(
       &module,
       &WgslBindgenOption {
-        wgsl_type_map: NalgebraWgslTypeMap.build(WgslTypeSerializeStrategy::Encase),
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        generate_layout_tests: true,
         ..Default::default()
       },
     );
-    let actual = quote!(#(#structs)*);
 
     assert_tokens_eq!(
       quote! {
-          #[repr(C)]
-          #[derive(Debug, PartialEq, Clone, Copy, encase::ShaderType)]
-          pub struct Atomics {
-              pub num: u32,
-              pub numi: i32,
-          }
-          impl Atomics {
-            pub fn new(num: u32, numi: i32) -> Self {
-                Self { num, numi }
-            }
+          #[cfg(test)]
+          mod layout_tests {
+              use super::*;
+              #[test]
+              fn light_layout() {
+                  assert_eq!(std::mem::offset_of!(Light, color), 0);
+                  assert_eq!(std::mem::offset_of!(Light, intensity), 12);
+                  assert_eq!(std::mem::size_of::<Light>(), 16);
+                  assert_eq!(std::mem::align_of::<Light>(), 16);
+              }
+              #[test]
+              fn lights_layout() {
+                  assert_eq!(std::mem::offset_of!(Lights<1>, count), 0);
+                  assert_eq!(std::mem::offset_of!(Lights<1>, lights), 16);
+                  assert_eq!(std::mem::size_of::<Lights<1>>(), 32);
+                  assert_eq!(std::mem::align_of::<Lights<1>>(), 16);
+                  assert_eq!(std::mem::offset_of!(Lights<2>, count), 0);
+                  assert_eq!(std::mem::offset_of!(Lights<2>, lights), 16);
+                  assert_eq!(std::mem::size_of::<Lights<2>>(), 48);
+                  assert_eq!(std::mem::align_of::<Lights<2>>(), 16);
+                  assert_eq!(std::mem::offset_of!(Lights<4>, count), 0);
+                  assert_eq!(std::mem::offset_of!(Lights<4>, lights), 16);
+                  assert_eq!(std::mem::size_of::<Lights<4>>(), 80);
+                  assert_eq!(std::mem::align_of::<Lights<4>>(), 16);
+              }
           }
       },
       actual
     );
   }
 
-  fn runtime_sized_array_module() -> naga::Module {
+  #[test]
+  fn write_struct_runtime_array_helpers_bytemuck() {
     let source = indoc! {r#"
-            struct RtsStruct {
-                other_data: i32,
-                the_array: array<u32>,
+            struct Lights {
+                count: u32,
+                lights: array<vec3<f32>>,
             };
+            var<storage> lights: Lights;
 
-            @group(0) @binding(0)
-            var <storage, read_write> rts:RtsStruct;
+            @fragment
+            fn main() {}
         "#};
-    naga::front::wgsl::parse_str(source).unwrap()
-  }
 
-  #[test]
-  fn write_runtime_sized_array() {
-    let module = runtime_sized_array_module();
+    let module = naga::front::wgsl::parse_str(source).unwrap();
 
     let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        generate_runtime_array_helpers: true,
         ..Default::default()
       },
     );
@@ -1221,16 +4688,71 @@ mod tests {
 
     assert_tokens_eq!(
       quote! {
-          #[derive(Debug, PartialEq, Clone, encase::ShaderType)]
-          pub struct RtsStruct {
-              pub other_data: i32,
-              #[size(runtime)]
-              pub the_array: Vec<u32>,
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Lights<const N: usize> {
+              /// size: 4, offset: 0x0, type: `u32`
+              pub count: u32,
+              pub _pad_count: [u8; 0x10 - core::mem::size_of::<u32>()],
+              /// size: 16, offset: 0x10, type: `array<vec3<f32>>`
+              pub lights: [[f32; 4]; N],
           }
-          impl RtsStruct {
-            pub fn new(other_data: i32, the_array: Vec<u32>) -> Self {
-                Self { other_data, the_array }
-            }
+          impl<const N: usize> Lights<N> {
+              pub fn new(count: u32, lights: [[f32; 4]; N]) -> Self {
+                  Self {
+                      count,
+                      _pad_count: [0; 0x10 - core::mem::size_of::<u32>()],
+                      lights,
+                  }
+              }
+          }
+          unsafe impl<const N: usize> bytemuck::Zeroable for Lights<N> {}
+          unsafe impl<const N: usize> bytemuck::Pod for Lights<N> {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Lights<1>, count) == 0);
+              assert!(std::mem::offset_of!(Lights<1>, lights) == 16);
+              assert!(std::mem::size_of::<Lights<1> >() == 32);
+          };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct LightsInit<const N: usize> {
+              pub count: u32,
+              pub lights: [[f32; 4]; N],
+          }
+          impl<const N: usize> LightsInit<N> {
+              pub const fn const_into(&self) -> Lights<N> {
+                  Lights {
+                      count: self.count,
+                      _pad_count: [0; 0x10 - core::mem::size_of::<u32>()],
+                      lights: self.lights,
+                  }
+              }
+          }
+          impl<const N: usize> From<LightsInit<N>> for Lights<N> {
+              fn from(data: LightsInit<N>) -> Self {
+                  data.const_into()
+              }
+          }
+          #[derive(Debug, PartialEq, Clone)]
+          pub struct LightsVec {
+              pub count: u32,
+              pub lights: Vec<[f32; 4]>,
+          }
+          impl LightsVec {
+              /// The bytes following the WGSL layout, with at least one array element.
+              pub fn to_bytes(&self) -> Vec<u8> {
+                  let len = self.lights.len().max(1);
+                  let size = (16 + len * 16).next_multiple_of(16);
+                  let mut bytes = vec![0u8; size];
+                  bytes[0..0 + core::mem::size_of::<u32>()]
+                      .copy_from_slice(bytemuck::bytes_of(&self.count));
+                  for (i, element) in self.lights.iter().enumerate() {
+                      let offset = 16 + i * 16;
+                      bytes[offset..offset + core::mem::size_of::<[f32; 4]>()]
+                          .copy_from_slice(bytemuck::bytes_of(element));
+                  }
+                  bytes
+              }
           }
       },
       actual
@@ -1238,79 +4760,135 @@ mod tests {
   }
 
   #[test]
-  fn write_runtime_sized_array_bytemuck() {
-    let module = runtime_sized_array_module();
+  fn write_atomic_types_bytemuck() {
+    let source = indoc! {r#"
+            struct Atomics {
+                num: atomic<u32>,
+                v: vec4<f32>,
+                counts: array<atomic<i32>, 2>,
+            };
+
+            @group(0) @binding(0)
+            var <storage, read_write> atomics:Atomics;
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
 
     let structs = structs(
       &module,
       &WgslBindgenOption {
         serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        init_struct_strategy: WgslInitStructStrategy::Never,
         ..Default::default()
       },
     );
-
     let actual = quote!(#(#structs)*);
 
     assert_tokens_eq!(
       quote! {
-        #[derive(Debug, PartialEq, Clone, Copy)]
-        pub struct RtsStruct<const N: usize> {
-            /// size: 4, offset: 0x0, type: `i32`
-            pub other_data: i32,
-            /// size: 4, offset: 0x4, type: `array<u32>`
-            pub the_array: [u32; N]
-        }
-        impl<const N:usize> RtsStruct<N> {
-            pub fn new(other_data: i32, the_array: [u32; N]) -> Self {
-                Self { other_data, the_array }
-            }
-        }
-        unsafe impl<const N: usize> bytemuck::Zeroable for RtsStruct<N> {}
-        unsafe impl<const N: usize> bytemuck::Pod for RtsStruct<N> {}
-        const _: () = {
-            assert!(std::mem::offset_of!(RtsStruct<1>, other_data) == 0);
-            assert!(std::mem::offset_of!(RtsStruct<1>, the_array) == 4);
-            assert!(std::mem::size_of::<RtsStruct<1> >() == 8);
-        };
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Atomics {
+              /// An atomic in WGSL, so the buffer must be bound as `var<storage, read_write>`.
+              ///
+              /// size: 4, offset: 0x0, type: `atomic<u32>`
+              pub num: u32,
+              pub _pad_num: [u8; 0x10 - core::mem::size_of::<u32>()],
+              /// size: 16, offset: 0x10, type: `vec4<f32>`
+              pub v: [f32; 4],
+              /// An atomic in WGSL, so the buffer must be bound as `var<storage, read_write>`.
+              ///
+              /// size: 8, offset: 0x20, type: `array<atomic<i32>, 2>`
+              pub counts: [i32; 2],
+              pub _pad_counts: [u8; 0x10 - core::mem::size_of::<[i32; 2]>()],
+          }
+          impl Atomics {
+              pub fn new(num: u32, v: [f32; 4], counts: [i32; 2]) -> Self {
+                  Self {
+                      num,
+                      _pad_num: [0; 0x10 - core::mem::size_of::<u32>()],
+                      v,
+                      counts,
+                      _pad_counts: [0; 0x10 - core::mem::size_of::<[i32; 2]>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Atomics {}
+          unsafe impl bytemuck::Pod for Atomics {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Atomics, num) == 0);
+              assert!(std::mem::offset_of!(Atomics, v) == 16);
+              assert!(std::mem::offset_of!(Atomics, counts) == 32);
+              assert!(std::mem::size_of::<Atomics>() == 48);
+          };
       },
       actual
-    )
+    );
   }
 
   #[test]
-  #[should_panic]
-  fn write_runtime_sized_array_not_last_field() {
+  fn write_struct_padded_matrix_types() {
     let source = indoc! {r#"
-            struct RtsStruct {
-                other_data: i32,
-                the_array: array<u32>,
-                more_data: i32,
+            struct Transforms {
+                normal: mat3x3<f32>,
+                basis: mat2x3<f32>,
             };
 
             @group(0) @binding(0)
-            var <storage, read_write> rts:RtsStruct;
+            var<uniform> transforms: Transforms;
         "#};
+
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
-    let _structs = structs(
+    let structs = structs(
       &module,
       &WgslBindgenOption {
-        serialization_strategy: WgslTypeSerializeStrategy::Encase,
+        serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        padded_matrix_types: true,
+        init_struct_strategy: WgslInitStructStrategy::Never,
         ..Default::default()
       },
     );
+    let actual = quote!(#(#structs)*);
+
+    assert_tokens_eq!(
+      quote! {
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Transforms {
+              /// size: 48, offset: 0x0, type: `mat3x3<f32>`
+              pub normal: _root::PaddedMat3,
+              /// size: 32, offset: 0x30, type: `mat2x3<f32>`
+              pub basis: _root::PaddedMat2x3,
+          }
+          impl Transforms {
+              pub fn new(normal: _root::PaddedMat3, basis: _root::PaddedMat2x3) -> Self {
+                  Self { normal, basis }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Transforms {}
+          unsafe impl bytemuck::Pod for Transforms {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Transforms, normal) == 0);
+              assert!(std::mem::offset_of!(Transforms, basis) == 48);
+              assert!(std::mem::size_of::<Transforms>() == 80);
+          };
+      },
+      actual
+    );
   }
 
   #[test]
-  fn write_nonpower_of_2_mats_for_bytemuck_option() {
+  fn write_struct_glam_packed_vector_member() {
     let source = indoc! {r#"
-        struct UniformsData {
-          a: mat3x3<f32>,
-        }
+            struct Camera {
+                position: vec3<f32>,
+                width: f32,
+            };
 
-        @group(0) @binding(0)
-            var <uniform> un:UniformsData;
-      "#};
+            @group(0) @binding(0)
+            var<uniform> camera: Camera;
+        "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
@@ -1318,6 +4896,12 @@ mod tests {
       &module,
       &WgslBindgenOption {
         serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
+        wgsl_type_map: GlamWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map_packed_vector_types: GlamWgslTypeMap
+          .packed_vector_types()
+          .into_iter()
+          .collect(),
+        init_struct_strategy: WgslInitStructStrategy::Never,
         ..Default::default()
       },
     );
@@ -1325,38 +4909,43 @@ mod tests {
 
     assert_tokens_eq!(
       quote! {
-        #[repr(C, align(16))]
-        #[derive(Debug, PartialEq, Clone, Copy)]
-        pub struct UniformsData {
-            /// size: 48, offset: 0x0, type: `mat3x3<f32>`
-            pub a: [[f32; 4]; 3],
-        }
-        impl UniformsData {
-            pub fn new(a: [[f32; 4]; 3]) -> Self {
-                Self { a }
-            }
-        }
-        unsafe impl bytemuck::Zeroable for UniformsData {}
-        unsafe impl bytemuck::Pod for UniformsData {}
-        const _: () = {
-             assert!(std::mem::offset_of!(UniformsData, a) == 0);
-             assert!(std::mem::size_of::<UniformsData> () == 48);
-        };
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Camera {
+              /// size: 12, offset: 0x0, type: `vec3<f32>`
+              pub position: glam::Vec3,
+              /// size: 4, offset: 0xC, type: `f32`
+              pub width: f32,
+          }
+          impl Camera {
+              pub fn new(position: glam::Vec3, width: f32) -> Self {
+                  Self { position, width }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Camera {}
+          unsafe impl bytemuck::Pod for Camera {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Camera, position) == 0);
+              assert!(std::mem::offset_of!(Camera, width) == 12);
+              assert!(std::mem::size_of::<Camera>() == 16);
+          };
       },
       actual
     );
   }
 
   #[test]
-  fn write_nonpower_of_2_mats_for_bytemuck_glam_option() {
+  fn write_struct_nalgebra_unpadded_matrix_types() {
     let source = indoc! {r#"
-        struct UniformsData {
-          centered_mvp: mat3x3<f32>,
-        }
+            struct Transforms {
+                model: mat4x4<f32>,
+                normal: mat3x3<f32>,
+                color: vec3<f32>,
+            };
 
-        @group(0) @binding(0)
-            var <uniform> un:UniformsData;
-      "#};
+            @group(0) @binding(0)
+            var<uniform> transforms: Transforms;
+        "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
@@ -1364,7 +4953,16 @@ mod tests {
       &module,
       &WgslBindgenOption {
         serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
-        wgsl_type_map: GlamWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map: NalgebraWgslTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        wgsl_type_map_packed_types: NalgebraWgslTypeMap
+          .packed_types()
+          .into_iter()
+          .collect(),
+        wgsl_type_map_unpadded_matrix_types: NalgebraWgslTypeMap
+          .unpadded_matrix_types()
+          .into_iter()
+          .collect(),
+        init_struct_strategy: WgslInitStructStrategy::Never,
         ..Default::default()
       },
     );
@@ -1372,40 +4970,57 @@ mod tests {
 
     assert_tokens_eq!(
       quote! {
-        #[repr(C, align(16))]
-        #[derive(Debug, PartialEq, Clone, Copy)]
-        pub struct UniformsData {
-            /// size: 48, offset: 0x0, type: `mat3x3<f32>`
-            pub centered_mvp: glam::Mat3A,
-        }
-        impl UniformsData {
-            pub fn new(centered_mvp: glam::Mat3A) -> Self {
-                Self { centered_mvp }
-            }
-        }
-        unsafe impl bytemuck::Zeroable for UniformsData {}
-        unsafe impl bytemuck::Pod for UniformsData {}
-        const _: () = {
-            assert!(std::mem::offset_of!(UniformsData, centered_mvp) == 0);
-            assert!(std::mem::size_of:: <UniformsData>() == 48);
-        };
+          #[repr(C, align(16))]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct Transforms {
+              /// size: 64, offset: 0x0, type: `mat4x4<f32>`
+              pub model: nalgebra::SMatrix<f32, 4, 4>,
+              /// size: 48, offset: 0x40, type: `mat3x3<f32>`
+              pub normal: _root::PaddedMat3,
+              /// size: 12, offset: 0x70, type: `vec3<f32>`
+              pub color: nalgebra::SVector<f32, 3>,
+              pub _pad_color: [u8; 0x10 - core::mem::size_of::<nalgebra::SVector<f32, 3>>()],
+          }
+          impl Transforms {
+              pub fn new(
+                  model: nalgebra::SMatrix<f32, 4, 4>,
+                  normal: _root::PaddedMat3,
+                  color: nalgebra::SVector<f32, 3>,
+              ) -> Self {
+                  Self {
+                      model,
+                      normal,
+                      color,
+                      _pad_color: [0; 0x10 - core::mem::size_of::<nalgebra::SVector<f32, 3>>()],
+                  }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for Transforms {}
+          unsafe impl bytemuck::Pod for Transforms {}
+          const _: () = {
+              assert!(std::mem::offset_of!(Transforms, model) == 0);
+              assert!(std::mem::offset_of!(Transforms, normal) == 64);
+              assert!(std::mem::offset_of!(Transforms, color) == 112);
+              assert!(std::mem::size_of::<Transforms>() == 128);
+          };
       },
       actual
     );
   }
 
   #[test]
-  fn write_nonpower_of_2_mats() {
+  fn write_vertex_input_vertex_format_override() {
     let source = indoc! {r#"
-          struct MatricesF32 {
-            a: mat4x4<f32>,
-            b: mat4x3<f32>,
-            c: mat4x2<f32>,
-            d: mat3x4<f32>,
-        };
-        @group(0) @binding(0)
-        var<uniform> f: MatricesF32;
-      "#};
+            struct VertexInput {
+                @location(0) position: vec3<f32>,
+                @location(1) color: vec4<f32>,
+            };
+
+            @vertex
+            fn main(input: VertexInput) -> @builtin(position) vec4<f32> {
+                return vec4(input.position, 1.0) * input.color;
+            }
+        "#};
 
     let module = naga::front::wgsl::parse_str(source).unwrap();
 
@@ -1413,7 +5028,12 @@ mod tests {
       &module,
       &WgslBindgenOption {
         serialization_strategy: WgslTypeSerializeStrategy::Bytemuck,
-        wgsl_type_map: WgslRustTypeMap.build(WgslTypeSerializeStrategy::Bytemuck),
+        vertex_format_overrides: [(
+          ("VertexInput".to_string(), "color".to_string()),
+          wgpu::VertexFormat::Unorm8x4,
+        )]
+        .into_iter()
+        .collect(),
         ..Default::default()
       },
     );
@@ -1421,37 +5041,19 @@ mod tests {
 
     assert_tokens_eq!(
       quote! {
-        #[repr(C, align(16))]
-        #[derive(Debug, PartialEq, Clone, Copy)]
-        pub struct MatricesF32 {
-            /// size: 64, offset: 0x0, type: `mat4x4<f32>`
-            pub a: [[f32; 4]; 4],
-            /// size: 64, offset: 0x40, type: `mat4x3<f32>`
-            pub b: [[f32; 4]; 4],
-            /// size: 32, offset: 0x80, type: `mat4x2<f32>`
-            pub c: [[f32; 2]; 4],
-            /// size: 48, offset: 0xA0, type: `mat3x4<f32>`
-            pub d: [[f32; 4]; 3],
-        }
-        impl MatricesF32 {
-            pub fn new(
-                a: [[f32; 4]; 4],
-                b: [[f32; 4]; 4],
-                c: [[f32; 2]; 4],
-                d: [[f32; 4]; 3],
-            ) -> Self {
-                Self { a, b, c, d }
-            }
-        }
-        unsafe impl bytemuck::Zeroable for MatricesF32 {}
-        unsafe impl bytemuck::Pod for MatricesF32 {}
-        const _: () = {
-            assert!(std::mem::offset_of!(MatricesF32, a) == 0);
-            assert!(std::mem::offset_of!(MatricesF32, b) == 64);
-            assert!(std::mem::offset_of!(MatricesF32, c) == 128);
-            assert!(std::mem::offset_of!(MatricesF32, d) == 160);
-            assert!(std::mem::size_of::<MatricesF32>() == 208);
-        };
+          #[repr(C)]
+          #[derive(Debug, PartialEq, Clone, Copy)]
+          pub struct VertexInput {
+              pub position: [f32; 4],
+              pub color: [u8; 4],
+          }
+          impl VertexInput {
+              pub fn new(position: [f32; 4], color: [u8; 4]) -> Self {
+                  Self { position, color }
+              }
+          }
+          unsafe impl bytemuck::Zeroable for VertexInput {}
+          unsafe impl bytemuck::Pod for VertexInput {}
       },
       actual
     );
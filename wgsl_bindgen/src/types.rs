@@ -19,14 +19,52 @@ impl SourceFilePath {
     Self(value.into())
   }
 
+  /// The contents of the file, where the words of SPIR-V binaries are written in hex, one
+  /// per line, so they can be hashed and cached like the text sources.
   pub fn read_contents(&self) -> Result<String, std::io::Error> {
-    std::fs::read_to_string(self.as_path())
+    if !self.is_spirv() {
+      return std::fs::read_to_string(self.as_path());
+    }
+
+    let bytes = std::fs::read(self.as_path())?;
+    if bytes.len() % 4 != 0 {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the SPIR-V binary isn't made of 4-byte words",
+      ));
+    }
+    let words = bytes
+      .chunks_exact(4)
+      .map(|word| format!("{:08x}", u32::from_le_bytes(word.try_into().unwrap())))
+      .collect::<Vec<_>>();
+    Ok(words.join("\n"))
   }
 
   pub fn dir(&self) -> SourceFileDir {
     SourceFileDir(self.parent().unwrap().into())
   }
 
+  /// Whether the file is a SPIR-V binary, from its `.spv` extension.
+  pub fn is_spirv(&self) -> bool {
+    self.extension().is_some_and(|extension| extension == "spv")
+  }
+
+  /// Whether the source is GLSL or SPIR-V, which naga translates to WGSL instead of
+  /// composing it with its imports.
+  pub fn needs_translation(&self) -> bool {
+    self.is_spirv() || self.glsl_shader_stage().is_some()
+  }
+
+  /// The shader stage of a GLSL source, from its `.vert`, `.frag` or `.comp` extension.
+  pub fn glsl_shader_stage(&self) -> Option<naga::ShaderStage> {
+    match self.extension()?.to_str()? {
+      "vert" => Some(naga::ShaderStage::Vertex),
+      "frag" => Some(naga::ShaderStage::Fragment),
+      "comp" => Some(naga::ShaderStage::Compute),
+      _ => None,
+    }
+  }
+
   pub fn file_prefix(&self) -> String {
     // file_prefix is only available in nightly
     let file_name = self.0.file_stem().unwrap().to_str().unwrap();
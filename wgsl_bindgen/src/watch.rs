@@ -0,0 +1,94 @@
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{WGSLBindgen, WgslBindgenError};
+
+/// The modification times of the watched files, and of the files in the watched
+/// directories, which also changes when files are added or removed.
+#[derive(Debug, PartialEq, Eq)]
+struct Snapshot(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl Snapshot {
+  fn take(paths: &[PathBuf]) -> Self {
+    let mut files = Vec::new();
+    for path in paths {
+      collect_modified_times(path, &mut files);
+    }
+    files.sort();
+    Self(files)
+  }
+}
+
+fn collect_modified_times(path: &Path, files: &mut Vec<(PathBuf, Option<SystemTime>)>) {
+  if let Ok(entries) = std::fs::read_dir(path) {
+    for entry in entries.flatten() {
+      collect_modified_times(&entry.path(), files);
+    }
+  } else {
+    let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    files.push((path.to_path_buf(), modified.ok()));
+  }
+}
+
+impl WGSLBindgen {
+  /// Generates the bindings, then generates them again whenever one of the
+  /// `files_to_watch` changes, checking them every `poll_interval`. This is for dev tools
+  /// and asset pipelines running outside of cargo builds.
+  ///
+  /// `on_generate` gets the result of each generation, like the errors of shaders being
+  /// edited, and stops watching by returning `ControlFlow::Break`. The imports are
+  /// resolved again on each change, so newly imported shaders are watched too.
+  pub fn watch(
+    self,
+    poll_interval: Duration,
+    mut on_generate: impl FnMut(Result<(), WgslBindgenError>) -> ControlFlow<()>,
+  ) {
+    let mut options = self.options.clone();
+    options.emit_rerun_if_change = false;
+
+    let mut files_to_watch = self.files_to_watch();
+    let mut snapshot = Snapshot::take(&files_to_watch);
+    if on_generate(self.generate()).is_break() {
+      return;
+    }
+
+    loop {
+      std::thread::sleep(poll_interval);
+      let new_snapshot = Snapshot::take(&files_to_watch);
+      if new_snapshot == snapshot {
+        continue;
+      }
+      snapshot = new_snapshot;
+
+      let result = WGSLBindgen::new(options.clone()).and_then(|bindgen| {
+        files_to_watch = bindgen.files_to_watch();
+        snapshot = Snapshot::take(&files_to_watch);
+        bindgen.generate()
+      });
+      if on_generate(result).is_break() {
+        return;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn snapshot_changes_with_the_files_of_watched_directories() {
+    let dir = std::env::temp_dir().join("wgsl_bindgen_watch_snapshot");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.wgsl"), "").unwrap();
+
+    let paths = [dir.clone()];
+    let snapshot = Snapshot::take(&paths);
+    assert_eq!(snapshot, Snapshot::take(&paths));
+
+    std::fs::write(dir.join("b.wgsl"), "").unwrap();
+    assert_ne!(snapshot, Snapshot::take(&paths));
+  }
+}
@@ -1,6 +1,10 @@
 use naga::StructMember;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
+use syn::Ident;
+
+use crate::bevy_util::demangle;
+use crate::{CreateModuleError, FxIndexMap, FxIndexSet, WgslBindgenOption};
 
 pub fn shader_stages(module: &naga::Module) -> wgpu::ShaderStages {
   module
@@ -14,6 +18,600 @@ pub fn shader_stages(module: &naga::Module) -> wgpu::ShaderStages {
     .collect()
 }
 
+pub fn shader_stages_tokens(shader_stages: wgpu::ShaderStages) -> TokenStream {
+  if shader_stages == wgpu::ShaderStages::all() {
+    return quote!(wgpu::ShaderStages::all());
+  }
+
+  match shader_stages {
+    wgpu::ShaderStages::VERTEX_FRAGMENT => quote!(wgpu::ShaderStages::VERTEX_FRAGMENT),
+    wgpu::ShaderStages::COMPUTE => quote!(wgpu::ShaderStages::COMPUTE),
+    wgpu::ShaderStages::VERTEX => quote!(wgpu::ShaderStages::VERTEX),
+    wgpu::ShaderStages::FRAGMENT => quote!(wgpu::ShaderStages::FRAGMENT),
+    _ => {
+      let stages: Vec<_> = shader_stages
+        .iter_names()
+        .map(|(name, _)| {
+          let name = Ident::new(name, Span::call_site());
+          quote!(wgpu::ShaderStages::#name)
+        })
+        .collect();
+      let first = &stages[0];
+      let rest = &stages[1..];
+      quote!(#first #(.union(#rest))*)
+    }
+  }
+}
+
+/// The size of the `var<push_constant>` globals used by the entry points of each stage.
+pub fn push_constant_ranges(module: &naga::Module) -> Vec<(wgpu::ShaderStages, u32)> {
+  [
+    (naga::ShaderStage::Vertex, wgpu::ShaderStages::VERTEX),
+    (naga::ShaderStage::Fragment, wgpu::ShaderStages::FRAGMENT),
+    (naga::ShaderStage::Compute, wgpu::ShaderStages::COMPUTE),
+  ]
+  .into_iter()
+  .filter_map(|(stage, shader_stages)| {
+    let mut globals = FxIndexSet::default();
+    for entry in module
+      .entry_points
+      .iter()
+      .filter(|entry| entry.stage == stage)
+    {
+      used_globals(module, &entry.function, &mut globals);
+    }
+
+    globals
+      .into_iter()
+      .map(|handle| &module.global_variables[handle])
+      .filter(|global| global.space == naga::AddressSpace::PushConstant)
+      .map(|global| module.types[global.ty].inner.size(module.to_ctx()))
+      .max()
+      .map(|size| (shader_stages, size))
+  })
+  .collect()
+}
+
+/// The sorted groups of the resource bindings used by the entry point.
+pub fn used_bind_groups(module: &naga::Module, entry: &naga::EntryPoint) -> Vec<u32> {
+  let mut globals = FxIndexSet::default();
+  used_globals(module, &entry.function, &mut globals);
+
+  let mut groups: Vec<_> = globals
+    .into_iter()
+    .filter_map(|handle| module.global_variables[handle].binding.as_ref())
+    .map(|binding| binding.group)
+    .collect();
+  groups.sort_unstable();
+  groups.dedup();
+  groups
+}
+
+/// The globals used by any of the entry points.
+pub fn entry_point_globals(
+  module: &naga::Module,
+) -> FxIndexSet<naga::Handle<naga::GlobalVariable>> {
+  let mut globals = FxIndexSet::default();
+  for entry in module.entry_points.iter() {
+    used_globals(module, &entry.function, &mut globals);
+  }
+  globals
+}
+
+/// Moves the bindings to the groups and binding indices of `group_remaps` and
+/// `binding_remaps`, which fails when two bindings end up with the same indices.
+pub fn remap_bindings(
+  module: &mut naga::Module,
+  options: &WgslBindgenOption,
+) -> Result<(), CreateModuleError> {
+  if !options.remaps_bindings() {
+    return Ok(());
+  }
+
+  let mut remapped_globals = FxIndexMap::default();
+  for (_, global) in module.global_variables.iter_mut() {
+    let Some(binding) = global.binding.as_mut() else {
+      continue;
+    };
+
+    let key = (binding.group, binding.binding);
+    if let Some(&(group, index)) = options.binding_remaps.get(&key) {
+      binding.group = group;
+      binding.binding = index;
+    } else if let Some(&group) = options.group_remaps.get(&binding.group) {
+      binding.group = group;
+    }
+
+    let name = demangle(global.name.as_deref().unwrap_or_default()).into_owned();
+    let key = (binding.group, binding.binding);
+    if let Some(first) = remapped_globals.insert(key, name.clone()) {
+      return Err(CreateModuleError::RemappedBindingCollision {
+        first,
+        second: name,
+        group: binding.group,
+        binding: binding.binding,
+      });
+    }
+  }
+  Ok(())
+}
+
+/// The groups without bindings before the last group with bindings, whose layouts are
+/// given by the caller, like the group of the globals of an engine after remapping.
+pub fn missing_bind_groups(module: &naga::Module) -> Vec<u32> {
+  let groups: FxIndexSet<u32> = module
+    .global_variables
+    .iter()
+    .filter_map(|(_, global)| global.binding.as_ref())
+    .map(|binding| binding.group)
+    .collect();
+  let group_count = groups.iter().max().map_or(0, |group| group + 1);
+  (0..group_count)
+    .filter(|group| !groups.contains(group))
+    .collect()
+}
+
+/// The stages of the entry points using the global, or of all the entry points when none
+/// of them uses it.
+pub fn global_stages(
+  module: &naga::Module,
+  global: naga::Handle<naga::GlobalVariable>,
+) -> wgpu::ShaderStages {
+  let stages = module
+    .entry_points
+    .iter()
+    .filter(|entry| {
+      let mut globals = FxIndexSet::default();
+      used_globals(module, &entry.function, &mut globals);
+      globals.contains(&global)
+    })
+    .map(|entry| match entry.stage {
+      naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+      naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+      naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    })
+    .collect();
+
+  if stages == wgpu::ShaderStages::NONE {
+    shader_stages(module)
+  } else {
+    stages
+  }
+}
+
+/// The resource bindings which aren't used by any of the entry points.
+pub fn unused_bindings(module: &naga::Module) -> Vec<&naga::GlobalVariable> {
+  let used = entry_point_globals(module);
+  module
+    .global_variables
+    .iter()
+    .filter(|(handle, global)| global.binding.is_some() && !used.contains(handle))
+    .map(|(_, global)| global)
+    .collect()
+}
+
+/// Collects the globals used by `function` and the functions it calls.
+fn used_globals(
+  module: &naga::Module,
+  function: &naga::Function,
+  globals: &mut FxIndexSet<naga::Handle<naga::GlobalVariable>>,
+) {
+  for (_, expression) in function.expressions.iter() {
+    if let naga::Expression::GlobalVariable(handle) = expression {
+      globals.insert(*handle);
+    }
+  }
+
+  let mut functions = Vec::new();
+  called_functions(&function.body, &mut functions);
+  for handle in functions {
+    used_globals(module, &module.functions[handle], globals);
+  }
+}
+
+fn called_functions(
+  block: &naga::Block,
+  functions: &mut Vec<naga::Handle<naga::Function>>,
+) {
+  for statement in block.iter() {
+    match statement {
+      naga::Statement::Block(block) => called_functions(block, functions),
+      naga::Statement::If { accept, reject, .. } => {
+        called_functions(accept, functions);
+        called_functions(reject, functions);
+      }
+      naga::Statement::Switch { cases, .. } => {
+        for case in cases {
+          called_functions(&case.body, functions);
+        }
+      }
+      naga::Statement::Loop {
+        body, continuing, ..
+      } => {
+        called_functions(body, functions);
+        called_functions(continuing, functions);
+      }
+      naga::Statement::Call { function, .. } => functions.push(*function),
+      _ => (),
+    }
+  }
+}
+
+/// Collects the [wgpu::Features] a device must support to create the shader module.
+pub fn required_features(module: &naga::Module) -> wgpu::Features {
+  let type_features = module.types.iter().map(|(_, ty)| match &ty.inner {
+    naga::TypeInner::Scalar(scalar)
+    | naga::TypeInner::Vector { scalar, .. }
+    | naga::TypeInner::Matrix { scalar, .. } => scalar_features(scalar),
+    naga::TypeInner::Image {
+      class: naga::ImageClass::Storage { format, access },
+      ..
+    } => storage_texture_features(*format, *access),
+    _ => wgpu::Features::empty(),
+  });
+  let binding_array_features = module.global_variables.iter().map(|(_, global)| {
+    match module.types[global.ty].inner {
+      naga::TypeInner::BindingArray { base, .. } => {
+        binding_array_features(&module.types[base], global.space)
+      }
+      _ => wgpu::Features::empty(),
+    }
+  });
+
+  let push_constant_features = push_constant_ranges(module)
+    .first()
+    .map(|_| wgpu::Features::PUSH_CONSTANTS)
+    .into_iter();
+  let entry_point_features = module
+    .entry_points
+    .iter()
+    .map(|entry_point| entry_point_features(module, entry_point));
+
+  type_features
+    .chain(binding_array_features)
+    .chain(push_constant_features)
+    .chain(entry_point_features)
+    .collect()
+}
+
+/// The features needed by the inputs, outputs and attributes of the entry point, and by
+/// the vertex stage writing to storage.
+fn entry_point_features(
+  module: &naga::Module,
+  entry_point: &naga::EntryPoint,
+) -> wgpu::Features {
+  let mut features = wgpu::Features::empty();
+  if entry_point.early_depth_test.is_some() {
+    features |= wgpu::Features::SHADER_EARLY_DEPTH_TEST;
+  }
+
+  let function = &entry_point.function;
+  let arguments = function
+    .arguments
+    .iter()
+    .map(|argument| (argument.binding.as_ref(), argument.ty, true));
+  let result = function
+    .result
+    .iter()
+    .map(|result| (result.binding.as_ref(), result.ty, false));
+  for (binding, ty, is_input) in arguments.chain(result) {
+    // The members of struct inputs and outputs have the bindings.
+    let members = match (binding, &module.types[ty].inner) {
+      (None, naga::TypeInner::Struct { members, .. }) => members
+        .iter()
+        .filter_map(|member| Some((member.binding.as_ref()?, member.ty)))
+        .collect(),
+      (Some(binding), _) => vec![(binding, ty)],
+      (None, _) => Vec::new(),
+    };
+
+    for (binding, ty) in members {
+      features |= match binding {
+        naga::Binding::BuiltIn(naga::BuiltIn::PrimitiveIndex) => {
+          wgpu::Features::SHADER_PRIMITIVE_INDEX
+        }
+        naga::Binding::BuiltIn(naga::BuiltIn::ViewIndex) => wgpu::Features::MULTIVIEW,
+        naga::Binding::Location {
+          second_blend_source: true,
+          ..
+        } => wgpu::Features::DUAL_SOURCE_BLENDING,
+        naga::Binding::Location { .. }
+          if is_input
+            && entry_point.stage == naga::ShaderStage::Vertex
+            && module.types[ty].inner.scalar_width() == Some(8) =>
+        {
+          wgpu::Features::VERTEX_ATTRIBUTE_64BIT
+        }
+        _ => wgpu::Features::empty(),
+      };
+    }
+  }
+
+  if entry_point.stage == naga::ShaderStage::Vertex {
+    let mut globals = FxIndexSet::default();
+    used_globals(module, function, &mut globals);
+    let writes_storage = globals.into_iter().any(|handle| {
+      let global = &module.global_variables[handle];
+      match (global.space, &module.types[global.ty].inner) {
+        (naga::AddressSpace::Storage { access }, _) => {
+          access.contains(naga::StorageAccess::STORE)
+        }
+        (
+          _,
+          naga::TypeInner::Image {
+            class: naga::ImageClass::Storage { access, .. },
+            ..
+          },
+        ) => access.contains(naga::StorageAccess::STORE),
+        _ => false,
+      }
+    });
+    if writes_storage {
+      features |= wgpu::Features::VERTEX_WRITABLE_STORAGE;
+    }
+  }
+
+  features
+}
+
+fn binding_array_features(
+  base: &naga::Type,
+  space: naga::AddressSpace,
+) -> wgpu::Features {
+  match base.inner {
+    naga::TypeInner::Image {
+      class: naga::ImageClass::Storage { .. },
+      ..
+    } => {
+      wgpu::Features::TEXTURE_BINDING_ARRAY
+        | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY
+    }
+    naga::TypeInner::Image { .. } | naga::TypeInner::Sampler { .. } => {
+      wgpu::Features::TEXTURE_BINDING_ARRAY
+    }
+    _ if matches!(space, naga::AddressSpace::Storage { .. }) => {
+      wgpu::Features::BUFFER_BINDING_ARRAY
+        | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY
+    }
+    _ => wgpu::Features::BUFFER_BINDING_ARRAY,
+  }
+}
+
+fn scalar_features(scalar: &naga::Scalar) -> wgpu::Features {
+  match (scalar.kind, scalar.width) {
+    (naga::ScalarKind::Float, 2) => wgpu::Features::SHADER_F16,
+    (naga::ScalarKind::Float, 8) => wgpu::Features::SHADER_F64,
+    (naga::ScalarKind::Sint | naga::ScalarKind::Uint, 2) => wgpu::Features::SHADER_I16,
+    _ => wgpu::Features::empty(),
+  }
+}
+
+fn storage_texture_features(
+  format: naga::StorageFormat,
+  access: naga::StorageAccess,
+) -> wgpu::Features {
+  let format = storage_texture_format(format);
+  let is_storage_format = format
+    .guaranteed_format_features(wgpu::Features::empty())
+    .allowed_usages
+    .contains(wgpu::TextureUsages::STORAGE_BINDING);
+
+  // Reading storage textures is a native only extension.
+  if access.contains(naga::StorageAccess::LOAD) {
+    wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+  } else if format == wgpu::TextureFormat::Bgra8Unorm {
+    wgpu::Features::BGRA8UNORM_STORAGE
+  } else if !is_storage_format {
+    wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+  } else {
+    wgpu::Features::empty()
+  }
+}
+
+/// Checks the bind groups, push constants and vertex inputs of the shader against the
+/// `limits` of the devices it runs on, see `WgslBindgenOption::limits`.
+pub fn validate_limits(
+  module: &naga::Module,
+  limits: &wgpu::Limits,
+) -> Result<(), CreateModuleError> {
+  let exceeded = |limit, max, required, usage| {
+    Err(CreateModuleError::LimitExceeded {
+      limit,
+      max,
+      required,
+      usage,
+    })
+  };
+
+  for (_, global) in module.global_variables.iter() {
+    let Some(binding) = global.binding.as_ref() else {
+      continue;
+    };
+    let name = demangle(global.name.as_deref().unwrap_or_default()).into_owned();
+    let usage =
+      format!("`{name}` at @group({}) @binding({})", binding.group, binding.binding);
+    if binding.group >= limits.max_bind_groups {
+      let max = limits.max_bind_groups;
+      return exceeded("max_bind_groups", max, binding.group + 1, usage);
+    }
+    if binding.binding >= limits.max_bindings_per_bind_group {
+      let max = limits.max_bindings_per_bind_group;
+      return exceeded("max_bindings_per_bind_group", max, binding.binding + 1, usage);
+    }
+  }
+
+  for (stages, size) in push_constant_ranges(module) {
+    if size > limits.max_push_constant_size {
+      let stage = stages.iter_names().next().map_or("", |(name, _)| name);
+      let usage = format!("the push constants of the {} stage", stage.to_lowercase());
+      let max = limits.max_push_constant_size;
+      return exceeded("max_push_constant_size", max, size, usage);
+    }
+  }
+
+  for entry_point in module.entry_points.iter() {
+    if entry_point.stage != naga::ShaderStage::Vertex {
+      continue;
+    }
+
+    // The attributes are the arguments with a location, and the members of struct arguments.
+    let attributes: usize = entry_point
+      .function
+      .arguments
+      .iter()
+      .map(|argument| match (&argument.binding, &module.types[argument.ty].inner) {
+        (Some(naga::Binding::Location { .. }), _) => 1,
+        (None, naga::TypeInner::Struct { members, .. }) => members
+          .iter()
+          .filter(|member| matches!(member.binding, Some(naga::Binding::Location { .. })))
+          .count(),
+        _ => 0,
+      })
+      .sum();
+    if attributes as u32 > limits.max_vertex_attributes {
+      let usage = format!("the inputs of the vertex entry point `{}`", entry_point.name);
+      let max = limits.max_vertex_attributes;
+      return exceeded("max_vertex_attributes", max, attributes as u32, usage);
+    }
+  }
+
+  Ok(())
+}
+
+/// Checks the types of uniform buffers against the layout rules of the uniform address
+/// space, naming the member breaking them unlike the errors of naga's validation.
+pub fn validate_uniform_layouts(module: &naga::Module) -> Result<(), CreateModuleError> {
+  let mut layouter = naga::proc::Layouter::default();
+  layouter.update(module.to_ctx()).unwrap();
+
+  for (_, global) in module.global_variables.iter() {
+    if global.space != naga::AddressSpace::Uniform {
+      continue;
+    }
+    let name = demangle(global.name.as_deref().unwrap_or_default());
+    validate_uniform_type(module, &layouter, global.ty, &format!("`{name}`"))?;
+  }
+
+  Ok(())
+}
+
+fn validate_uniform_type(
+  module: &naga::Module,
+  layouter: &naga::proc::Layouter,
+  ty: naga::Handle<naga::Type>,
+  member: &str,
+) -> Result<(), CreateModuleError> {
+  let invalid = |member: String, reason: String| {
+    Err(CreateModuleError::UniformLayout { member, reason })
+  };
+
+  match &module.types[ty].inner {
+    naga::TypeInner::Array { base, stride, .. } => {
+      if stride % 16 != 0 {
+        let reason = format!(
+          "has an array stride of {stride} bytes, which must be a multiple of 16"
+        );
+        return invalid(member.to_string(), reason);
+      }
+      validate_uniform_type(module, layouter, *base, member)
+    }
+    naga::TypeInner::Struct { members, .. } => {
+      let struct_name = demangle(module.types[ty].name.as_deref().unwrap_or_default());
+      let member_name = |member: &naga::StructMember| {
+        let name = member.name.as_deref().unwrap_or_default();
+        format!("member `{name}` of struct `{struct_name}`")
+      };
+
+      for (index, struct_member) in members.iter().enumerate() {
+        let inner = &module.types[struct_member.ty].inner;
+        let layout = layouter[struct_member.ty];
+        let offset = struct_member.offset;
+
+        // Struct and array members are aligned to at least 16 bytes.
+        if matches!(inner, naga::TypeInner::Struct { .. } | naga::TypeInner::Array { .. })
+        {
+          let alignment = (layout.alignment * 1u32).max(16);
+          if offset % alignment != 0 {
+            let reason =
+              format!("is at offset {offset}, which must be a multiple of {alignment}");
+            return invalid(member_name(struct_member), reason);
+          }
+        }
+
+        // The members following a struct member start at least 16 bytes after it.
+        if let (naga::TypeInner::Struct { .. }, Some(next)) =
+          (inner, members.get(index + 1))
+        {
+          let min_offset = offset + layout.size.next_multiple_of(16);
+          if next.offset < min_offset {
+            let name = struct_member.name.as_deref().unwrap_or_default();
+            let reason = format!(
+              "is at offset {}, before the end of the struct member `{name}` at {min_offset}",
+              next.offset
+            );
+            return invalid(member_name(next), reason);
+          }
+        }
+
+        validate_uniform_type(
+          module,
+          layouter,
+          struct_member.ty,
+          &member_name(struct_member),
+        )?;
+      }
+
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
+
+pub fn storage_texture_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+  match format {
+    naga::StorageFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+    naga::StorageFormat::R8Snorm => wgpu::TextureFormat::R8Snorm,
+    naga::StorageFormat::R8Uint => wgpu::TextureFormat::R8Uint,
+    naga::StorageFormat::R8Sint => wgpu::TextureFormat::R8Sint,
+    naga::StorageFormat::R16Uint => wgpu::TextureFormat::R16Uint,
+    naga::StorageFormat::R16Sint => wgpu::TextureFormat::R16Sint,
+    naga::StorageFormat::R16Float => wgpu::TextureFormat::R16Float,
+    naga::StorageFormat::Rg8Unorm => wgpu::TextureFormat::Rg8Unorm,
+    naga::StorageFormat::Rg8Snorm => wgpu::TextureFormat::Rg8Snorm,
+    naga::StorageFormat::Rg8Uint => wgpu::TextureFormat::Rg8Uint,
+    naga::StorageFormat::Rg8Sint => wgpu::TextureFormat::Rg8Sint,
+    naga::StorageFormat::R32Uint => wgpu::TextureFormat::R32Uint,
+    naga::StorageFormat::R32Sint => wgpu::TextureFormat::R32Sint,
+    naga::StorageFormat::R32Float => wgpu::TextureFormat::R32Float,
+    naga::StorageFormat::Rg16Uint => wgpu::TextureFormat::Rg16Uint,
+    naga::StorageFormat::Rg16Sint => wgpu::TextureFormat::Rg16Sint,
+    naga::StorageFormat::Rg16Float => wgpu::TextureFormat::Rg16Float,
+    naga::StorageFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+    naga::StorageFormat::Rgba8Snorm => wgpu::TextureFormat::Rgba8Snorm,
+    naga::StorageFormat::Rgba8Uint => wgpu::TextureFormat::Rgba8Uint,
+    naga::StorageFormat::Rgba8Sint => wgpu::TextureFormat::Rgba8Sint,
+    naga::StorageFormat::Bgra8Unorm => wgpu::TextureFormat::Bgra8Unorm,
+    naga::StorageFormat::Rgb10a2Uint => wgpu::TextureFormat::Rgb10a2Uint,
+    naga::StorageFormat::Rgb10a2Unorm => wgpu::TextureFormat::Rgb10a2Unorm,
+    naga::StorageFormat::Rg11b10Float => wgpu::TextureFormat::Rg11b10Float,
+    naga::StorageFormat::Rg32Uint => wgpu::TextureFormat::Rg32Uint,
+    naga::StorageFormat::Rg32Sint => wgpu::TextureFormat::Rg32Sint,
+    naga::StorageFormat::Rg32Float => wgpu::TextureFormat::Rg32Float,
+    naga::StorageFormat::Rgba16Uint => wgpu::TextureFormat::Rgba16Uint,
+    naga::StorageFormat::Rgba16Sint => wgpu::TextureFormat::Rgba16Sint,
+    naga::StorageFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+    naga::StorageFormat::Rgba32Uint => wgpu::TextureFormat::Rgba32Uint,
+    naga::StorageFormat::Rgba32Sint => wgpu::TextureFormat::Rgba32Sint,
+    naga::StorageFormat::Rgba32Float => wgpu::TextureFormat::Rgba32Float,
+    naga::StorageFormat::R16Unorm => wgpu::TextureFormat::R16Unorm,
+    naga::StorageFormat::R16Snorm => wgpu::TextureFormat::R16Snorm,
+    naga::StorageFormat::Rg16Unorm => wgpu::TextureFormat::Rg16Unorm,
+    naga::StorageFormat::Rg16Snorm => wgpu::TextureFormat::Rg16Snorm,
+    naga::StorageFormat::Rgba16Unorm => wgpu::TextureFormat::Rgba16Unorm,
+    naga::StorageFormat::Rgba16Snorm => wgpu::TextureFormat::Rgba16Snorm,
+  }
+}
+
 pub fn buffer_binding_type(storage: naga::AddressSpace) -> TokenStream {
   match storage {
     naga::AddressSpace::Uniform => quote!(wgpu::BufferBindingType::Uniform),
@@ -50,6 +648,7 @@ pub fn vertex_format(ty: &naga::Type) -> wgpu::VertexFormat {
         (naga::ScalarKind::Uint, 2) => wgpu::VertexFormat::Uint16x2,
         (naga::ScalarKind::Uint, 4) => wgpu::VertexFormat::Uint32x2,
         (naga::ScalarKind::Sint, 4) => wgpu::VertexFormat::Sint32x2,
+        (naga::ScalarKind::Float, 2) => wgpu::VertexFormat::Float16x2,
         (naga::ScalarKind::Float, 4) => wgpu::VertexFormat::Float32x2,
         (naga::ScalarKind::Float, 8) => wgpu::VertexFormat::Float64x2,
         _ => todo!(),
@@ -68,6 +667,7 @@ pub fn vertex_format(ty: &naga::Type) -> wgpu::VertexFormat {
         (naga::ScalarKind::Uint, 2) => wgpu::VertexFormat::Uint16x4,
         (naga::ScalarKind::Uint, 4) => wgpu::VertexFormat::Uint32x4,
         (naga::ScalarKind::Sint, 4) => wgpu::VertexFormat::Sint32x4,
+        (naga::ScalarKind::Float, 2) => wgpu::VertexFormat::Float16x4,
         (naga::ScalarKind::Float, 4) => wgpu::VertexFormat::Float32x4,
         (naga::ScalarKind::Float, 8) => wgpu::VertexFormat::Float64x4,
         _ => todo!(),
@@ -82,6 +682,49 @@ pub struct VertexInput {
   pub fields: Vec<(u32, StructMember)>,
 }
 
+/// The locations of the fragment outputs with their scalar kinds and names, which are the
+/// struct member names or `@location(N)` for a single output.
+pub fn fragment_outputs(
+  module: &naga::Module,
+  entry: &naga::EntryPoint,
+) -> Result<Vec<(u32, naga::ScalarKind, String)>, CreateModuleError> {
+  let Some(result) = entry.function.result.as_ref() else {
+    return Ok(Vec::new());
+  };
+  let output = |location: u32, ty: naga::Handle<naga::Type>, name: String| {
+    let inner = &module.types[ty].inner;
+    match *inner {
+      naga::TypeInner::Scalar(scalar) | naga::TypeInner::Vector { scalar, .. } => {
+        Ok((location, scalar.kind, name))
+      }
+      _ => Err(CreateModuleError::UnsupportedFragmentOutput {
+        entry_point: entry.name.clone(),
+        output: name,
+      }),
+    }
+  };
+
+  let mut outputs: Vec<_> = match (&result.binding, &module.types[result.ty].inner) {
+    (Some(naga::Binding::Location { location, .. }), _) => {
+      let name = format!("@location({location})");
+      vec![output(*location, result.ty, name)?]
+    }
+    (None, naga::TypeInner::Struct { members, .. }) => members
+      .iter()
+      .filter_map(|member| match member.binding {
+        Some(naga::Binding::Location { location, .. }) => {
+          let name = member.name.clone().unwrap_or_default();
+          Some(output(location, member.ty, name))
+        }
+        _ => None,
+      })
+      .collect::<Result<_, _>>()?,
+    _ => Vec::new(),
+  };
+  outputs.sort_by_key(|(location, _, _)| *location);
+  Ok(outputs)
+}
+
 // TODO: Handle errors.
 // Collect the necessary data to generate an equivalent Rust struct.
 pub fn get_vertex_input_structs(module: &naga::Module) -> Vec<VertexInput> {
@@ -134,6 +777,31 @@ mod tests {
 
   use super::*;
 
+  #[test]
+  fn unused_bindings_not_used_by_entry_points() {
+    let source = indoc! {r#"
+            @group(0) @binding(0) var<uniform> used: vec4<f32>;
+            @group(0) @binding(1) var<uniform> used_by_helper: vec4<f32>;
+            @group(1) @binding(0) var<uniform> unused: vec4<f32>;
+
+            fn helper() -> vec4<f32> {
+                return used_by_helper;
+            }
+
+            @fragment
+            fn main() -> @location(0) vec4<f32> {
+                return used + helper();
+            }
+        "#};
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let unused: Vec<_> = unused_bindings(&module)
+      .into_iter()
+      .map(|global| global.name.as_deref().unwrap())
+      .collect();
+    assert_eq!(vec!["unused"], unused);
+  }
+
   #[test]
   fn shader_stages_none() {
     let source = indoc! {r#"
@@ -254,4 +922,96 @@ mod tests {
     assert_eq!("in5", vertex_inputs[1].fields[2].1.name.as_ref().unwrap());
     assert_eq!(5, vertex_inputs[1].fields[2].0);
   }
+
+  fn uniform_layout_error(source: &str) -> Option<String> {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    validate_uniform_layouts(&module)
+      .err()
+      .map(|error| error.to_string())
+  }
+
+  #[test]
+  fn uniform_layouts_valid() {
+    let source = indoc! {r#"
+            struct Light {
+                color: vec3<f32>,
+                intensity: f32,
+            };
+            struct Lights {
+                count: u32,
+                lights: array<Light, 4>,
+                weights: array<vec4<f32>, 2>,
+            };
+            @group(0) @binding(0) var<uniform> lights: Lights;
+            @group(0) @binding(1) var<storage> values: array<f32>;
+        "#};
+
+    assert_eq!(None, uniform_layout_error(source));
+  }
+
+  #[test]
+  fn uniform_layouts_array_stride() {
+    let source = indoc! {r#"
+            struct Params {
+                scale: vec4<f32>,
+                weights: array<f32, 4>,
+            };
+            @group(0) @binding(0) var<uniform> params: Params;
+        "#};
+
+    assert_eq!(
+      Some(
+        "member `weights` of struct `Params` in a uniform buffer has an array stride of 4 \
+         bytes, which must be a multiple of 16"
+          .to_string()
+      ),
+      uniform_layout_error(source)
+    );
+  }
+
+  #[test]
+  fn uniform_layouts_struct_member_alignment() {
+    let source = indoc! {r#"
+            struct Light {
+                intensity: f32,
+            };
+            struct Params {
+                count: u32,
+                light: Light,
+            };
+            @group(0) @binding(0) var<uniform> params: Params;
+        "#};
+
+    assert_eq!(
+      Some(
+        "member `light` of struct `Params` in a uniform buffer is at offset 4, which must \
+         be a multiple of 16"
+          .to_string()
+      ),
+      uniform_layout_error(source)
+    );
+  }
+
+  #[test]
+  fn uniform_layouts_member_after_struct() {
+    let source = indoc! {r#"
+            struct Light {
+                intensity: f32,
+            };
+            struct Params {
+                light: Light,
+                count: u32,
+            };
+            @group(0) @binding(0) var<uniform> params: Params;
+        "#};
+
+    assert_eq!(
+      Some(
+        "member `count` of struct `Params` in a uniform buffer is at offset 4, before the \
+         end of the struct member `light` at 16"
+          .to_string()
+      ),
+      uniform_layout_error(source)
+    );
+  }
 }
@@ -1,23 +1,38 @@
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use case::CaseExt;
 use derive_builder::Builder;
 use miette::Diagnostic;
 use naga_oil::compose::{
-  ComposableModuleDescriptor, Composer, ComposerError, NagaModuleDescriptor,
-  ShaderLanguage,
+  ComposableModuleDescriptor, Composer, ComposerError, ComposerErrorInner,
+  NagaModuleDescriptor, ShaderLanguage,
 };
+pub use naga_oil::compose::ShaderDefValue;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
 use thiserror::Error;
 
 use self::source_file::SourceFile;
 use crate::{
-  bevy_util::*, WgslEntryResult, WgslTypeMap, WgslTypeMapBuild, WgslTypeSerializeStrategy,
+  bevy_util::*, FxIndexMap, FxIndexSet, WgslEntryItem, WgslEntryResult, WgslType,
+  WgslTypeMap, WgslTypeMapBuild, WgslTypeSerializeStrategy,
+};
+use crate::{
+  create_entry_items, create_rust_binding_files, create_rust_bindings,
+  entry_cache::EntryCache,
+  markdown_docs::entry_markdown,
+  naga_util::{glsl_to_module, module_to_backend_sources, spirv_to_module},
+  wgsl, CreateModuleError, SourceFilePath,
 };
-use crate::{create_rust_bindings, CreateModuleError, SourceFilePath};
 
 const PKG_VER: &str = env!("CARGO_PKG_VERSION");
 const PKG_NAME: &str = env!("CARGO_PKG_NAME");
 
+/// The paths of generated files, relative to the output directory, with their contents.
+type GeneratedFiles = Vec<(PathBuf, String)>;
+
 /// Enum representing the possible errors that can occur in the `wgsl_bindgen` process.
 ///
 /// This enum is used to represent all the different kinds of errors that can occur
@@ -46,9 +61,130 @@ pub enum WgslBindgenError {
 
   #[error("Output file is not specified. Maybe use `generate_string` instead")]
   OutputFileNotSpecified,
+
+  #[error("Failed to read the shader sources matching `{pattern}`")]
+  EntryPointGlobError {
+    pattern: String,
+    #[source]
+    inner: std::io::Error,
+  },
+
+  #[error("Failed to read the config file `{}`", path.display())]
+  ConfigReadError {
+    path: PathBuf,
+    #[source]
+    inner: std::io::Error,
+  },
+
+  #[error("Failed to parse the config")]
+  ConfigParseError(#[from] toml::de::Error),
+
+  #[error("Invalid Rust tokens `{tokens}` for `{option}` in the config")]
+  ConfigTokensError { option: String, tokens: String },
+
+  #[error("Invalid value `{value}` for `{option}` in the config")]
+  ConfigValueError { option: String, value: String },
+
+  #[error("Failed to parse the GLSL shader `{entry}`\n{message}")]
+  GlslParseError { entry: String, message: String },
+
+  #[error("Failed to parse the SPIR-V shader `{entry}`\n{message}")]
+  SpirvParseError { entry: String, message: String },
+
+  #[error("Failed to translate `{entry}` to {backend:?}\n{message}")]
+  BackendTranslationError {
+    entry: String,
+    backend: WgslShaderBackend,
+    message: String,
+  },
+
+  #[error(
+    "Failed to generate the bindings of {} entry points\n{}",
+    errors.len(),
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+  )]
+  EntryPointErrors { errors: Vec<WgslBindgenError> },
+}
+
+impl WgslBindgenError {
+  /// The error of the entry point, or an `EntryPointErrors` with the errors of all of them.
+  fn from_entry_point_errors(mut errors: Vec<WgslBindgenError>) -> Result<(), Self> {
+    match errors.len() {
+      0 => Ok(()),
+      1 => Err(errors.remove(0)),
+      _ => Err(Self::EntryPointErrors { errors }),
+    }
+  }
+}
+
+/// How `bool` members of host-shareable structs are generated.
+/// `bool` has no memory layout in WGSL, so it can't be shared with the host as is.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WgslBoolMemberStrategy {
+  /// Fail the generation with an error naming the struct member.
+  #[default]
+  Error,
+  /// Use a `u32` in place of the `bool`. With bytemuck this is a `WgslBool` newtype
+  /// which converts from and into `bool`. Structs with such members don't follow the WGSL layout.
+  U32,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+/// When to generate the `XxxInit` companion structs, which don't have the padding fields.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WgslInitStructStrategy {
+  /// Never generate them, structs are then constructed with `new`.
+  Never,
+  /// Only for the structs with padding fields, which are only generated with bytemuck.
+  #[default]
+  WhenPadded,
+  /// For all structs, except for encase structs with runtime-sized arrays which aren't `Copy`.
+  Always,
+}
+
+/// The visibility of the generated structs, fields, consts and functions.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WgslItemVisibility {
+  /// `pub`
+  #[default]
+  Pub,
+  /// `pub(crate)`
+  PubCrate,
+  /// Only visible to the module including the generated file, like `pub(super)` for its items.
+  PubSuper,
+}
+
+impl WgslItemVisibility {
+  /// The visibility tokens for an item nested `depth` modules deep in the generated file.
+  /// E.g. items of the entry modules have a depth of 1.
+  pub(crate) fn tokens(self, depth: usize) -> TokenStream {
+    match self {
+      Self::Pub => quote!(pub),
+      Self::PubCrate => quote!(pub(crate)),
+      Self::PubSuper if depth == 0 => quote!(pub(super)),
+      Self::PubSuper => {
+        let supers = (0..=depth).map(|_| quote!(super));
+        quote!(pub(in #(#supers)::*))
+      }
+    }
+  }
+}
+
+/// How the names of WGSL structs and their members are converted to Rust names.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WgslNameCasing {
+  /// Keep the WGSL names as they are.
+  #[default]
+  Preserve,
+  /// Convert struct names to `UpperCamelCase` and member names to `snake_case`.
+  RustConventions,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WgslShaderSourceOutputType {
   /// Include the final shader string directly in the output
   #[default]
@@ -56,6 +192,41 @@ pub enum WgslShaderSourceOutputType {
 
   /// Use Composer including helper functions which will be executed on runtime
   Composer,
+
+  /// Load the shader files relative to a directory at runtime, composing and validating
+  /// them instead of embedding the sources. `create_shader_module` takes the directory
+  /// and returns a `Result`, and compute pipelines take the created shader module.
+  RuntimeFiles,
+}
+
+/// The wgpu version the generated code is written for, see `wgpu_version`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum WgpuVersion {
+  /// wgpu 0.19.
+  #[default]
+  #[serde(rename = "0.19")]
+  V0_19,
+  /// wgpu 0.20, adding the `compilation_options` of the pipeline stages.
+  #[serde(rename = "0.20")]
+  V0_20,
+  /// wgpu 22, adding the `cache` of pipeline descriptors.
+  #[serde(rename = "22")]
+  V22,
+  /// wgpu 23, making the entry points of the pipeline stages optional.
+  #[serde(rename = "23")]
+  V23,
+}
+
+/// A shading language the shaders are translated to with naga, see `backend_outputs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WgslShaderBackend {
+  /// Metal Shading Language, in a `.metal` file.
+  Msl,
+  /// HLSL for shader model 5.1, in a `.hlsl` file.
+  Hlsl,
+  /// GLSL ES 3.10, in a file per entry point like `vs_main.vert`.
+  Glsl,
 }
 
 /// A struct representing a directory to scan for additional source files.
@@ -65,6 +236,9 @@ pub enum WgslShaderSourceOutputType {
 /// is used to specify the root prefix or namespace that should be applied to all
 /// shaders given as the entrypoints, and the `directory` field is used to specify
 /// the directory to scan for additional source files.
+///
+/// Imports starting with the `module_import_root` are remapped to the directory, so
+/// `(Some("my_lib"), "../shaders/lib")` resolves `my_lib::utils` to `../shaders/lib/utils.wgsl`.
 #[derive(Debug, Clone, Default)]
 pub struct AdditionalScanDirectory {
   pub module_import_root: Option<String>,
@@ -80,7 +254,17 @@ impl From<(Option<&str>, &str)> for AdditionalScanDirectory {
   }
 }
 
-#[derive(Debug, Default, Builder)]
+/// A user type converted from and into the generated struct for a WGSL struct, see
+/// `WgslBindgenOptionBuilder::add_struct_conversion`.
+#[derive(Debug, Clone)]
+pub struct StructConversion {
+  /// The path of the user type, like `quote!(crate::Camera)`.
+  pub rust_type: TokenStream,
+  /// The fields of the user type, keyed by the member names of the WGSL struct.
+  pub fields: FxIndexMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, Builder)]
 #[builder(
   setter(into),
   field(private),
@@ -88,9 +272,20 @@ impl From<(Option<&str>, &str)> for AdditionalScanDirectory {
 )]
 pub struct WgslBindgenOption {
   /// A vector of entry points to be added. Each entry point is represented as a `String`.
-  #[builder(setter(each(name = "add_entry_point", into)))]
+  #[builder(default, setter(each(name = "add_entry_point", into)))]
   pub entry_points: Vec<String>,
 
+  /// Globs like `shaders/**/*.wgsl` for the shader sources to scan. The files with entry points
+  /// are added as entry points, and the others can be imported relative to the directory
+  /// before the first wildcard.
+  #[builder(default, setter(each(name = "add_entry_points_glob", into)))]
+  pub entry_point_globs: Vec<String>,
+
+  /// In-memory WGSL entry point sources keyed by module path, like `generated::blur`, which
+  /// is also the path of the generated module.
+  #[builder(default, setter(custom))]
+  pub entry_point_sources: FxIndexMap<String, String>,
+
   /// The root prefix/namespace if any applied to all shaders given as the entrypoints.
   #[builder(default, setter(strip_option, into))]
   pub module_import_root: Option<String>,
@@ -103,11 +298,35 @@ pub struct WgslBindgenOption {
   #[builder(default = "false")]
   pub skip_header_comments: bool,
 
+  /// The comment at the top of the generated files, in place of the default comment saying
+  /// the file is generated. Defaults to `None`.
+  #[builder(default, setter(strip_option, into))]
+  pub header_comment: Option<String>,
+
+  /// A boolean flag indicating whether to skip the version of wgsl_bindgen and the
+  /// `SourceHash` in the header comments, which also disables the hash check. Defaults
+  /// to `false`.
+  #[builder(default = "false")]
+  pub skip_header_provenance: bool,
+
   /// A boolean flag indicating whether to skip the hash check. This will avoid reruns of bindings generation if
   /// entry shaders including their imports has not changed. Defaults to `false`.
   #[builder(default = "false")]
   pub skip_hash_check: bool,
 
+  /// The directory caching the items generated for each entry point module, like
+  /// `$OUT_DIR/wgsl_bindgen`. Only the modules whose shaders, including their imports, or
+  /// options changed are generated again, and the `warn_unused_bindings` warnings of the
+  /// others are emitted from the cache. Defaults to `None`.
+  #[builder(default, setter(strip_option, into))]
+  pub incremental_cache_dir: Option<PathBuf>,
+
+  /// Leave out the entry points failing to compose, instead of failing the generation, when
+  /// `true`. `generate` then writes the bindings of the other entry points before returning
+  /// the errors, without the `SourceHash` so they are generated again until fixed.
+  #[builder(default = "false")]
+  pub continue_on_error: bool,
+
   /// Derive [encase::ShaderType](https://docs.rs/encase/latest/encase/trait.ShaderType.html#)
   /// for user defined WGSL structs when `WgslTypeSerializeStrategy::Encase`.
   /// else derive bytemuck
@@ -120,26 +339,405 @@ pub struct WgslBindgenOption {
   #[builder(default = "false")]
   pub derive_serde: bool,
 
+  /// Derive `bytemuck::Pod` and `bytemuck::Zeroable` instead of implementing them with `unsafe impl`
+  /// when `true`, so the derive macros verify the structs. Requires the `derive` feature of bytemuck
+  /// and a `wgsl_type_map` whose types are all `Pod`, like `GlamWgslTypeMap::packed()` unlike the
+  /// `glam::Vec3A` of `GlamWgslTypeMap`. Structs with runtime-sized arrays are generic and the
+  /// structs only used as vertex inputs have implicit padding, so both keep the `unsafe impl`.
+  #[builder(default = "false")]
+  pub derive_bytemuck: bool,
+
+  /// The `#[serde(rename_all = "...")]` casing of the fields when `derive_serde`, like `camelCase`.
+  #[builder(default, setter(strip_option, into))]
+  pub serde_rename_all: Option<String>,
+
+  /// Skip the `_pad_*` fields when serializing with serde, zeroing them when deserializing.
+  #[builder(default = "false")]
+  pub serde_skip_padding: bool,
+
+  /// How to generate `bool` members of host-shareable structs. Defaults to `Error`.
+  #[builder(default)]
+  pub bool_member_strategy: WgslBoolMemberStrategy,
+
+  /// When to generate the `XxxInit` companion structs. Defaults to `WhenPadded`.
+  #[builder(default)]
+  pub init_struct_strategy: WgslInitStructStrategy,
+
+  /// Make the fields of structs with padding private when `true`, so the `_pad_*` fields don't leak
+  /// into the public API. Such structs get getters and setters for their other fields and `Default`.
+  #[builder(default = "false")]
+  pub hide_padding: bool,
+
+  /// Generate `const fn new` constructors when `true`, so the structs can be used in `const` items.
+  /// Encase structs with runtime-sized arrays keep a non-const `new`.
+  #[builder(default = "false")]
+  pub const_fn_new: bool,
+
+  /// Use `PaddedMat2x3`, `PaddedMat3` and `PaddedMat4x3` wrappers with conversions from `[[f32; 3]; C]` for the `matCx3<f32>` types not mapped by `wgsl_type_map`, for bytemuck.
+  #[builder(default = "false")]
+  pub padded_matrix_types: bool,
+
+  /// Generate a `XxxVec` struct with a `Vec` for the runtime-sized array and a `to_bytes` method for bytemuck, or a `to_bytes` method for encase.
+  #[builder(default = "false")]
+  pub generate_runtime_array_helpers: bool,
+
+  /// Generate the structs and consts with `core` instead of `std` when `true`, without the runtime array helpers needing `alloc` and the wgpu vertex layouts of the vertex input structs, for sharing the types with `no_std` crates.
+  #[builder(default = "false")]
+  pub no_std: bool,
+
+  /// Generate a `#[cfg(test)] mod layout_tests` per shader checking the size, alignment and member offsets of the host-shareable structs.
+  #[builder(default = "false")]
+  pub generate_layout_tests: bool,
+
+  /// Generate a `#[cfg(test)]` test per host-shareable struct comparing the encase layout with the WGSL layout.
+  #[builder(default = "false")]
+  pub generate_encase_layout_tests: bool,
+
+  /// Only generate the structs of the bindings and push constants used by an entry point,
+  /// and of the entry point arguments, when `true`. Otherwise the structs of all the global
+  /// variables are generated, including those of unused bindings in shared shader libraries.
+  #[builder(default = "false")]
+  pub skip_unused_structs: bool,
+
+  /// Emit a `cargo:warning` for each binding which isn't used by any entry point when `true`.
+  #[builder(default = "false")]
+  pub warn_unused_bindings: bool,
+
+  /// Generate `as_bytes` and `from_bytes` methods, and their slice variants, on bytemuck structs when `true`.
+  #[builder(default = "false")]
+  pub generate_byte_helpers: bool,
+
+  /// Generate `write_into_uniform` and `write_into_storage` methods returning the bytes of encase structs, and a `SHADER_SIZE` const for the structs without runtime-sized arrays, when `true`.
+  #[builder(default = "false")]
+  pub generate_encase_helpers: bool,
+
+  /// Generate a `buffers` module with `UniformBuffer<T>` and `StorageBuffer<T>` wrappers of `wgpu::Buffer` when `true`, with `new`, `write` and `binding` methods and a `SIZE` const for the structs.
+  #[builder(default = "false")]
+  pub generate_typed_buffers: bool,
+
+  /// Generate an async `read_{binding}` function for each read-write storage buffer when
+  /// `true`, copying the buffer into a staging buffer and reading it back into its Rust
+  /// type. The buffers need the `wgpu::BufferUsages::COPY_SRC` usage, which the storage
+  /// buffers of `generate_typed_buffers` get with this option.
+  #[builder(default = "false")]
+  pub generate_readback_helpers: bool,
+
+  /// Generate an `aligned_size(alignment)` const fn and an `ALIGNED_SIZE` const on the
+  /// structs of uniform buffers when `true`, for the stride between instances in buffers
+  /// bound with dynamic offsets.
+  #[builder(default = "false")]
+  pub generate_aligned_sizes: bool,
+
+  /// Generate a `{struct}_offsets` module for the structs of buffers when `true`, with the
+  /// offset of each member and `write_{member}` functions for partial buffer updates. With
+  /// bytemuck, the members padded like `glam::Vec3A` are written from their unpadded types
+  /// like `glam::Vec3`, and the functions of `type_overrides` are `unsafe`.
+  #[builder(default = "false")]
+  pub generate_field_offsets: bool,
+
+  /// Round the size of the structs used in uniform buffers up to a multiple of 16 bytes
+  /// with trailing padding when `true` and using bytemuck, so the same struct can be used
+  /// in uniform and storage buffers. Structs without room for the padding where they are
+  /// used, like the elements of a storage `array<S, N>`, keep their size.
+  #[builder(default = "false")]
+  pub pad_uniform_structs: bool,
+
+  /// Define the structs that several entry points define identically only once when
+  /// `true`, in the `shared_module` or in a `shared` module without one, and re-export
+  /// them from the entry point modules. Vertex input structs are left in each entry point
+  /// module, since their vertex layouts depend on the shader.
+  #[builder(default = "false")]
+  pub unify_duplicate_structs: bool,
+
+  /// Define the bind groups that several entry points declare identically only once when
+  /// `true`, in the `bind_groups` module of the `shared_module` or of a `shared` module
+  /// without one, and re-export them from the entry point modules. This way a bind group,
+  /// like one for the camera, is created once and set in the pipelines of all of them.
+  #[builder(default = "false")]
+  pub share_bind_groups: bool,
+
+  /// Make the bindings visible to all shader stages with `wgpu::ShaderStages::all()` when
+  /// `true`, instead of to the stages of the entry points using them, so the bind group
+  /// layouts can be shared with pipelines of other stages.
+  #[builder(default = "false")]
+  pub all_stages_visibility: bool,
+
+  /// Generate a `BindGroupXBuilder` for each bind group when `true`, which takes the
+  /// bindings left unset from a `BindingFallback`.
+  #[builder(default = "false")]
+  pub generate_bind_group_builders: bool,
+
+  /// Generate a `BindGroupXCache` for each bind group when `true`, which reuses the bind
+  /// groups created for the same resources.
+  #[builder(default = "false")]
+  pub generate_bind_group_caches: bool,
+
+  /// Generate the expected sample type, view dimension and multisampling of each texture
+  /// binding when `true`, with a `create_xxx_view` function asserting a texture matches them
+  /// in debug builds.
+  #[builder(default = "false")]
+  pub generate_texture_metadata: bool,
+
+  /// Generate an unsafe `create_shader_module_relaxed` function when `true`, which skips
+  /// wgpu's runtime checks like bounds checking when the `relaxed-shaders` feature of the
+  /// crate including the bindings is enabled, like for its release builds.
+  #[builder(default = "false")]
+  pub generate_relaxed_shader_module: bool,
+
+  /// Generate a `create_shader_module_from_path` function when `true`, behind the
+  /// `hot-reload` feature of the crate including the bindings, which composes the shader
+  /// from the WGSL files at runtime. Requires `naga_oil` as a dependency of that crate.
+  #[builder(default = "false")]
+  pub generate_hot_reload_shader_module: bool,
+
+  /// Generate a `bevy` module when `true`, behind the `bevy` feature of the crate including
+  /// the bindings, with the bind group layouts and vertex buffer layouts in bevy's render
+  /// types for driving custom pipelines. Requires `bevy` as a dependency of that crate.
+  #[builder(default = "false")]
+  pub generate_bevy_glue: bool,
+
+  /// Translate the shaders to SPIR-V with naga when `true`, embedding the words in a
+  /// `SHADER_SPIRV` constant with the `create_shader_module_spirv` and
+  /// `create_shader_module_spirv_passthrough` functions.
+  #[builder(default = "false")]
+  pub generate_spirv_shader_module: bool,
+
+  /// The shading languages the shaders are translated to by `generate`, in files next to
+  /// the generated bindings named after the entry point modules, like `triangle.metal`.
+  #[builder(default, setter(custom))]
+  pub backend_outputs: FxIndexSet<WgslShaderBackend>,
+
+  /// Generate a `reflection` module when `true`, with the bindings of the shader in a
+  /// `BINDINGS` const, for building resource binding systems without parsing the shaders.
+  #[builder(default = "false")]
+  pub generate_reflection: bool,
+
+  /// Write a Markdown document per entry point module with `generate` when `true`, next to
+  /// the generated bindings like `triangle.md`, with the entry points, bind groups, vertex
+  /// inputs and the member offsets of the buffer structs.
+  #[builder(default = "false")]
+  pub generate_markdown_docs: bool,
+
+  /// Generate an `entry_points` module when `true`, with a module for each entry point
+  /// grouping its name, stage, used bind groups and helpers.
+  #[builder(default = "false")]
+  pub generate_entry_point_modules: bool,
+
+  /// Generate a `fragment` module when `true`, with the number and sample types of the color
+  /// targets of each fragment entry point and a function creating their `ColorTargetState`s.
+  #[builder(default = "false")]
+  pub generate_fragment_targets: bool,
+
+  /// Generate a `Shader` struct in each entry point module when `true`, implementing the
+  /// `ShaderEntry` trait of the root module with the shader module, the pipeline layout,
+  /// the bind group layouts and the entry point names, to store different shaders behind
+  /// `Box<dyn ShaderEntry>`.
+  #[builder(default = "false")]
+  pub generate_shader_entry: bool,
+
+  /// Generate a `XxxBuilder` for each user defined WGSL struct when `true`, with a setter
+  /// per field as an alternative to the positional `new`.
+  #[builder(default = "false")]
+  pub generate_struct_builders: bool,
+
+  /// Implement `Default` for user defined WGSL structs when `true`.
+  /// With bytemuck the default value is zeroed, including the padding.
+  #[builder(default = "false")]
+  pub impl_default: bool,
+
+  /// Additional derives for all generated structs, like `quote!(Hash)` or `quote!(my_crate::MyDerive)`.
+  #[builder(default, setter(into, each(name = "additional_struct_derive")))]
+  pub additional_struct_derives: Vec<TokenStream>,
+
+  /// Additional derives for the generated structs with the given demangled WGSL names,
+  /// added after `additional_struct_derives`.
+  #[builder(default, setter(custom))]
+  pub additional_struct_derives_by_name: FxIndexMap<String, Vec<TokenStream>>,
+
+  /// How the names of generated structs and their fields are cased. Defaults to `Preserve`.
+  #[builder(default)]
+  pub name_casing: WgslNameCasing,
+
+  /// The names of the uniform and storage buffer bindings using dynamic offsets.
+  #[builder(default, setter(custom))]
+  pub dynamic_offset_bindings: FxIndexSet<String>,
+
+  /// The names of the samplers and float texture bindings which aren't filtering.
+  #[builder(default, setter(custom))]
+  pub non_filtering_bindings: FxIndexSet<String>,
+
+  /// The demangled names of the vertex input structs stepping per instance.
+  #[builder(default, setter(custom))]
+  pub instance_step_mode_structs: FxIndexSet<String>,
+
+  /// A pattern like `*Instance` for the names of the vertex input structs stepping per
+  /// instance, where `*` matches any characters.
+  #[builder(default, setter(strip_option, into))]
+  pub instance_step_mode_pattern: Option<String>,
+
+  /// Generate one `VertexBufferLayout` per vertex input member when `true`, for members
+  /// stored tightly packed in separate buffers, instead of one interleaved layout per struct.
+  #[builder(default = "false")]
+  pub non_interleaved_vertex_buffers: bool,
+
+  /// The vertex formats of vertex input struct members, keyed by the demangled struct name
+  /// and the member name, e.g. to store a `vec4<f32>` color as `Unorm8x4`.
+  #[builder(default, setter(custom))]
+  pub vertex_format_overrides: FxIndexMap<(String, String), wgpu::VertexFormat>,
+
+  /// Rust names for the WGSL structs with the given demangled names, taking precedence over `name_casing`.
+  #[builder(default, setter(custom))]
+  pub struct_renames: FxIndexMap<String, String>,
+
+  /// Rust names for the members of WGSL structs, keyed by the demangled struct name and the member name.
+  #[builder(default, setter(custom))]
+  pub struct_member_renames: FxIndexMap<(String, String), String>,
+
+  /// The user types converted from and into the generated structs, keyed by the demangled
+  /// struct name.
+  #[builder(default, setter(custom))]
+  pub struct_conversions: FxIndexMap<String, Vec<StructConversion>>,
+
+  /// The `@group` indices the bindings are moved to, keyed by their group in the shaders.
+  /// The groups left without bindings are parameters of `create_pipeline_layout`.
+  #[builder(default, setter(custom))]
+  pub group_remaps: FxIndexMap<u32, u32>,
+
+  /// The `@group` and `@binding` indices single bindings are moved to, keyed by their
+  /// indices in the shaders, taking precedence over `group_remaps`.
+  #[builder(default, setter(custom))]
+  pub binding_remaps: FxIndexMap<(u32, u32), (u32, u32)>,
+
+  /// The visibility of the generated items. Defaults to `Pub`.
+  #[builder(default)]
+  pub item_visibility: WgslItemVisibility,
+
+  /// The directory the entry point modules mirror the subdirectories of, like
+  /// `effects::blur` for `shaders/effects/blur.wgsl` with `shaders`. Defaults to `None`,
+  /// which names the flat entry point modules after their files, like `blur`.
+  #[builder(default, setter(strip_option, into))]
+  pub entry_modules_root_dir: Option<PathBuf>,
+
+  /// The module wrapping all the generated modules, like `shaders`. Defaults to `None`.
+  #[builder(default, setter(strip_option, into))]
+  pub root_module: Option<String>,
+
+  /// The module the modules of imported shaders go in, like `shared` for `shared::types`.
+  /// Defaults to `None`, which puts them next to the entry point modules.
+  #[builder(default, setter(strip_option, into))]
+  pub shared_module: Option<String>,
+
+  /// Puts the structs and consts of each entry point module in a `types` submodule, and
+  /// the pipeline helpers like `create_shader_module` in a `pipeline` submodule, next to
+  /// the `bind_groups` submodule. Defaults to `false`.
+  #[builder(default = "false")]
+  pub split_entry_modules: bool,
+
+  /// The number of modules the entry point module the items are generated for is nested
+  /// in, like 1 for `effects::blur`.
+  #[builder(setter(skip))]
+  pub(crate) entry_module_nesting: usize,
+
   /// The type of output for the shader source. Defaults to `FinalShaderString`.
   #[builder(default)]
   pub shader_source_output_type: WgslShaderSourceOutputType,
 
+  /// The path the generated code uses for the wgpu crate, like
+  /// `::bevy::render::render_resource` for bevy's re-exports or the name of a renamed
+  /// dependency. Defaults to `None`, which uses `wgpu`.
+  #[builder(default, setter(strip_option, into))]
+  pub wgpu_crate_path: Option<String>,
+
+  /// The wgpu version the generated code is written for, which selects the fields of the
+  /// pipeline descriptors. Defaults to `V0_19`.
+  #[builder(default)]
+  pub wgpu_version: WgpuVersion,
+
+  /// The paths the generated code uses for other crates, keyed by the crate name, like
+  /// `::bevy::math` for `glam` or a re-export of `bytemuck`, so the crate including the
+  /// bindings doesn't need them as dependencies. The derive macros of `encase` and `serde`
+  /// still refer to their own crates.
+  #[builder(default, setter(custom))]
+  pub crate_paths: FxIndexMap<String, String>,
+
+  /// The capabilities used by naga to validate the shaders, like `FLOAT64` for `f64` types.
+  /// Defaults to all capabilities, as the device features are only known at runtime.
+  #[builder(default = "naga::valid::Capabilities::all()")]
+  pub naga_capabilities: naga::valid::Capabilities,
+
+  /// The limits of the devices the shaders run on, like `wgpu::Limits::downlevel_defaults()`.
+  /// The generation fails when a shader needs more bind groups, bindings per bind group,
+  /// push constant bytes or vertex attributes. Defaults to `None`, which doesn't check them.
+  #[builder(default, setter(strip_option))]
+  pub limits: Option<wgpu::Limits>,
+
+  /// The shader defs passed to the composer, which select the code paths of `#ifdef FOO`
+  /// and `#if FOO == 1` conditionals in the generated bindings and shader source.
+  #[builder(default, setter(custom))]
+  pub shader_defs: FxIndexMap<String, ShaderDefValue>,
+
+  /// Named sets of shader defs, added to `shader_defs`. When not empty, the bindings of each
+  /// entry point are generated once per permutation, in a submodule with its name.
+  #[builder(default, setter(custom))]
+  pub shader_def_permutations: FxIndexMap<String, FxIndexMap<String, ShaderDefValue>>,
+
   /// A mapping operation for WGSL built-in types. This is used to map WGSL built-in types to their corresponding representations.
   #[builder(setter(custom))]
   pub wgsl_type_map: WgslTypeMap,
 
+  /// WGSL built-in types mapped by `wgsl_type_map` to Rust types only aligned to their scalar.
+  #[builder(default, setter(custom))]
+  pub wgsl_type_map_packed_types: FxIndexSet<WgslType>,
+
+  /// `matCx3<f32>` types whose Rust types in `wgsl_type_map` lack the column padding of WGSL,
+  /// stored in the padded matrix types with [WgslTypeSerializeStrategy::Bytemuck].
+  #[builder(default, setter(custom))]
+  pub wgsl_type_map_unpadded_matrix_types: FxIndexMap<WgslType, TokenStream>,
+
+  /// Rust types only aligned to their scalar for the vectors of `wgsl_type_map`, used when
+  /// the next struct member is packed into the trailing padding of the vector.
+  #[builder(default, setter(custom))]
+  pub wgsl_type_map_packed_vector_types: FxIndexMap<WgslType, TokenStream>,
+
+  /// Individual WGSL built-in type mappings which take precedence over the ones in `wgsl_type_map`.
+  /// The Rust types are only aligned to their scalar, like `[f32; 3]`, and padded explicitly.
+  #[builder(default, setter(custom))]
+  pub wgsl_type_map_overrides: FxIndexMap<WgslType, TokenStream>,
+
+  /// Like `wgsl_type_map_overrides`, with Rust types having the size and alignment of the WGSL
+  /// type, like `glam::Vec3A` for `vec3<f32>`.
+  #[builder(default, setter(custom))]
+  pub wgsl_type_map_aligned_overrides: FxIndexMap<WgslType, TokenStream>,
+
+  /// WGSL structs which should not be generated, mapped to existing Rust types used in their place.
+  /// Keys are the demangled struct names, e.g. `lighting::PointLight` for imported structs.
+  #[builder(default, setter(custom))]
+  pub type_overrides: FxIndexMap<String, TokenStream>,
+
   /// The output file path for the generated Rust bindings. Defaults to `None`.
   #[builder(default, setter(strip_option, into))]
   pub output_file: Option<PathBuf>,
 
+  /// The output directory for the generated Rust bindings, which `generate` writes as a
+  /// `mod.rs` and a file per module, like `a.rs` and `a/b.rs`, instead of the
+  /// `output_file`. Defaults to `None`.
+  #[builder(default, setter(strip_option, into))]
+  pub output_dir: Option<PathBuf>,
+
   /// The additional set of directories to scan for source files.
   #[builder(default, setter(into, each(name = "additional_scan_dir", into)))]
   pub additional_scan_dirs: Vec<AdditionalScanDirectory>,
+
+  /// WGSL sources to `#import` by module path, like library code shipped by another crate,
+  /// used for the imports which don't resolve to a file.
+  #[builder(default, setter(custom))]
+  pub virtual_modules: FxIndexMap<String, String>,
 }
 
 impl WgslBindgenOptionBuilder {
   pub fn build(&self) -> Result<WGSLBindgen, WgslBindgenError> {
-    let options = self.fallible_build()?;
+    let mut options = self.fallible_build()?;
+    options.apply_wgsl_type_map_overrides();
     WGSLBindgen::new(options)
   }
 
@@ -149,76 +747,671 @@ impl WgslBindgenOptionBuilder {
       .expect("Serialization strategy must be set before `wgs_type_map`");
 
     self.wgsl_type_map = Some(map_build.build(serialization_strategy));
+    self.wgsl_type_map_packed_types = Some(map_build.packed_types().into_iter().collect());
+    self.wgsl_type_map_unpadded_matrix_types =
+      Some(map_build.unpadded_matrix_types().into_iter().collect());
+    self.wgsl_type_map_packed_vector_types =
+      Some(map_build.packed_vector_types().into_iter().collect());
+    self
+  }
+
+  /// Overrides the mapping of a single WGSL built-in type, on top of the map given to `wgsl_type_map`.
+  ///
+  /// For example, keep using `GlamWgslTypeMap` but map `vec3<f32>` to `[f32; 3]` instead.
+  /// The Rust type is only aligned to its scalar, and structs get explicit padding after it.
+  pub fn override_wgsl_type(&mut self, ty: WgslType, tokens: TokenStream) -> &mut Self {
+    self
+      .wgsl_type_map_overrides
+      .get_or_insert_with(Default::default)
+      .insert(ty, tokens);
+    self
+  }
+
+  /// Like `override_wgsl_type`, for a Rust type with the size and alignment of the WGSL type,
+  /// like `glam::Vec3A` for `vec3<f32>`.
+  pub fn override_wgsl_type_aligned(
+    &mut self,
+    ty: WgslType,
+    tokens: TokenStream,
+  ) -> &mut Self {
+    self
+      .wgsl_type_map_aligned_overrides
+      .get_or_insert_with(Default::default)
+      .insert(ty, tokens);
+    self
+  }
+
+  /// Defines a shader def for the composer, like `add_shader_def("SHADOWS", ShaderDefValue::Bool(true))`.
+  pub fn add_shader_def(
+    &mut self,
+    name: impl Into<String>,
+    value: ShaderDefValue,
+  ) -> &mut Self {
+    self
+      .shader_defs
+      .get_or_insert_with(Default::default)
+      .insert(name.into(), value);
+    self
+  }
+
+  /// Adds an in-memory WGSL source as an entry point, like WGSL generated by the build
+  /// script, which generates the module with the given module path.
+  pub fn add_source_str(
+    &mut self,
+    module_path: impl Into<String>,
+    source: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .entry_point_sources
+      .get_or_insert_with(Default::default)
+      .insert(module_path.into(), source.into());
+    self
+  }
+
+  /// Registers the WGSL source of a module which shaders can `#import` with the given module
+  /// path, like `add_virtual_module("my_lib::lighting", my_lib::LIGHTING_WGSL)`.
+  pub fn add_virtual_module(
+    &mut self,
+    module_path: impl Into<String>,
+    source: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .virtual_modules
+      .get_or_insert_with(Default::default)
+      .insert(module_path.into(), source.into());
+    self
+  }
+
+  /// Generates the bindings of each entry point in a submodule with the given name, using the
+  /// given shader defs on top of `shader_defs`.
+  ///
+  /// For example, `add_shader_def_permutation("shadows", [("SHADOWS", ShaderDefValue::Bool(true))])`
+  /// together with `add_shader_def_permutation("no_shadows", [])` generates `shader::shadows` and
+  /// `shader::no_shadows` for `shader.wgsl`.
+  pub fn add_shader_def_permutation<N: Into<String>>(
+    &mut self,
+    name: impl Into<String>,
+    shader_defs: impl IntoIterator<Item = (N, ShaderDefValue)>,
+  ) -> &mut Self {
+    let shader_defs = shader_defs
+      .into_iter()
+      .map(|(name, value)| (name.into(), value))
+      .collect();
+    self
+      .shader_def_permutations
+      .get_or_insert_with(Default::default)
+      .insert(name.into(), shader_defs);
+    self
+  }
+
+  /// Adds a derive only to the generated struct for the WGSL struct with the given name.
+  ///
+  /// The name is the demangled struct name, like in `type_override`.
+  pub fn additional_struct_derive_for(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    derive: TokenStream,
+  ) -> &mut Self {
+    self
+      .additional_struct_derives_by_name
+      .get_or_insert_with(Default::default)
+      .entry(wgsl_struct_name.into())
+      .or_default()
+      .push(derive);
+    self
+  }
+
+  /// Uses the path for the crate in the generated code, like
+  /// `crate_path("glam", "::bevy::math")`.
+  pub fn crate_path(
+    &mut self,
+    crate_name: impl Into<String>,
+    path: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .crate_paths
+      .get_or_insert_with(Default::default)
+      .insert(crate_name.into(), path.into());
+    self
+  }
+
+  /// Names the generated struct for the WGSL struct with the given demangled name.
+  pub fn rename_struct(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    rust_name: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .struct_renames
+      .get_or_insert_with(Default::default)
+      .insert(wgsl_struct_name.into(), rust_name.into());
+    self
+  }
+
+  /// Binds the uniform or storage buffer with the given name using dynamic offsets.
+  ///
+  /// Its bind group gets a `set_with_offsets` method and a `NAME_ALIGNED_SIZE` const.
+  pub fn add_dynamic_offset_binding(
+    &mut self,
+    binding_name: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .dynamic_offset_bindings
+      .get_or_insert_with(Default::default)
+      .insert(binding_name.into());
+    self
+  }
+
+  /// Marks the sampler or float texture binding with the given name as non filtering.
+  ///
+  /// Samplers use `wgpu::SamplerBindingType::NonFiltering` and textures use
+  /// `wgpu::TextureSampleType::Float { filterable: false }`, as needed for formats like
+  /// `R32Float` which can't be filtered without `wgpu::Features::FLOAT32_FILTERABLE`.
+  pub fn add_non_filtering_binding(
+    &mut self,
+    binding_name: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .non_filtering_bindings
+      .get_or_insert_with(Default::default)
+      .insert(binding_name.into());
+    self
+  }
+
+  /// Steps the vertex input struct with the given demangled name per instance in its
+  /// `VERTEX_BUFFER_LAYOUT`.
+  pub fn add_instance_step_mode_struct(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .instance_step_mode_structs
+      .get_or_insert_with(Default::default)
+      .insert(wgsl_struct_name.into());
+    self
+  }
+
+  /// Uses the given vertex format for a member of the vertex input struct with the given
+  /// demangled name. The generated field stores the packed components, e.g. `[u8; 4]` for
+  /// `Unorm8x4`.
+  pub fn override_vertex_format(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    wgsl_member_name: impl Into<String>,
+    format: wgpu::VertexFormat,
+  ) -> &mut Self {
+    self
+      .vertex_format_overrides
+      .get_or_insert_with(Default::default)
+      .insert((wgsl_struct_name.into(), wgsl_member_name.into()), format);
     self
   }
+
+  /// Names the field generated for a member of the WGSL struct with the given demangled name.
+  pub fn rename_struct_member(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    wgsl_member_name: impl Into<String>,
+    rust_name: impl Into<String>,
+  ) -> &mut Self {
+    self
+      .struct_member_renames
+      .get_or_insert_with(Default::default)
+      .insert((wgsl_struct_name.into(), wgsl_member_name.into()), rust_name.into());
+    self
+  }
+
+  /// Implements `From` in both directions between the generated struct for the WGSL struct
+  /// with the given demangled name and a user type, like `quote!(crate::Camera)`.
+  ///
+  /// `fields` maps the members of the WGSL struct to the fields of the user type, like
+  /// `[("view_proj", "view_projection")]`. The other members use the fields named like the
+  /// generated fields. The fields are converted with `Into`, so the user type can use other
+  /// math types than the generated struct.
+  pub fn add_struct_conversion<M: Into<String>, F: Into<String>>(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    rust_type: TokenStream,
+    fields: impl IntoIterator<Item = (M, F)>,
+  ) -> &mut Self {
+    let fields = fields
+      .into_iter()
+      .map(|(member, field)| (member.into(), field.into()))
+      .collect();
+    self
+      .struct_conversions
+      .get_or_insert_with(Default::default)
+      .entry(wgsl_struct_name.into())
+      .or_default()
+      .push(StructConversion { rust_type, fields });
+    self
+  }
+
+  /// Moves the bindings of a group to another group in the bindings and the embedded shader
+  /// source, e.g. `remap_group(0, 1)` to reserve group 0 for the globals of an engine.
+  pub fn remap_group(&mut self, group: u32, new_group: u32) -> &mut Self {
+    self
+      .group_remaps
+      .get_or_insert_with(Default::default)
+      .insert(group, new_group);
+    self
+  }
+
+  /// Moves the binding with the given `(group, binding)` indices to new indices in the
+  /// bindings and the embedded shader source.
+  pub fn remap_binding(
+    &mut self,
+    binding: (u32, u32),
+    new_binding: (u32, u32),
+  ) -> &mut Self {
+    self
+      .binding_remaps
+      .get_or_insert_with(Default::default)
+      .insert(binding, new_binding);
+    self
+  }
+
+  /// Uses an existing Rust type instead of generating one for the WGSL struct with the given name.
+  ///
+  /// The name is the demangled struct name, which includes the module path for imported structs,
+  /// e.g. `type_override("lighting::PointLight", quote!(my_crate::PointLight))`.
+  pub fn type_override(
+    &mut self,
+    wgsl_struct_name: impl Into<String>,
+    rust_type: TokenStream,
+  ) -> &mut Self {
+    self
+      .type_overrides
+      .get_or_insert_with(Default::default)
+      .insert(wgsl_struct_name.into(), rust_type);
+    self
+  }
+
+  /// Writes the shaders translated to the shading language next to the generated
+  /// bindings, see `backend_outputs`.
+  pub fn add_backend_output(&mut self, backend: WgslShaderBackend) -> &mut Self {
+    self
+      .backend_outputs
+      .get_or_insert_with(Default::default)
+      .insert(backend);
+    self
+  }
+}
+
+impl WgslBindgenOption {
+  /// The name of the Rust struct generated for a WGSL struct, without its module path.
+  pub(crate) fn rust_struct_name(&self, wgsl_struct_name: &str) -> String {
+    if let Some(rust_name) = self.struct_renames.get(wgsl_struct_name) {
+      return rust_name.clone();
+    }
+
+    let (_, name) = demangle_splitting_mod_path_and_item(wgsl_struct_name);
+    let name = demangle_generic_arguments(&name).into_owned();
+    match self.name_casing {
+      WgslNameCasing::Preserve => name,
+      WgslNameCasing::RustConventions => name.to_camel(),
+    }
+  }
+
+  /// Whether the vertex input struct with the given demangled name steps per instance.
+  pub(crate) fn is_instance_step_mode(&self, wgsl_struct_name: &str) -> bool {
+    let matches_pattern = self.instance_step_mode_pattern.as_ref().is_some_and(|pattern| {
+      let pattern = regex::escape(pattern).replace(r"\*", ".*");
+      regex::Regex::new(&format!("^{pattern}$"))
+        .expect("Failed to compile regex")
+        .is_match(wgsl_struct_name)
+    });
+    matches_pattern || self.instance_step_mode_structs.contains(wgsl_struct_name)
+  }
+
+  /// Whether the `@group` or `@binding` indices of the shaders are remapped.
+  pub(crate) fn remaps_bindings(&self) -> bool {
+    !self.group_remaps.is_empty() || !self.binding_remaps.is_empty()
+  }
+
+  /// The overridden vertex format of a member of a vertex input struct.
+  pub(crate) fn vertex_format_override(
+    &self,
+    wgsl_struct_name: &str,
+    member: &str,
+  ) -> Option<wgpu::VertexFormat> {
+    let key = (wgsl_struct_name.to_owned(), member.to_owned());
+    self.vertex_format_overrides.get(&key).copied()
+  }
+
+  /// The name of the Rust field generated for a member of a WGSL struct.
+  pub(crate) fn rust_member_name(&self, wgsl_struct_name: &str, member: &str) -> String {
+    let key = (wgsl_struct_name.to_owned(), member.to_owned());
+    if let Some(rust_name) = self.struct_member_renames.get(&key) {
+      return rust_name.clone();
+    }
+
+    match self.name_casing {
+      WgslNameCasing::Preserve => member.to_owned(),
+      WgslNameCasing::RustConventions => member.to_snake(),
+    }
+  }
+
+  /// The path of the module generated for the entry point.
+  pub(crate) fn entry_module_path(&self, file_path: &SourceFilePath) -> String {
+    let dirs = self
+      .entry_modules_root_dir
+      .as_ref()
+      .and_then(|root_dir| file_path.parent()?.strip_prefix(root_dir).ok());
+
+    let mut mod_path = dirs
+      .map(|dirs| {
+        dirs
+          .iter()
+          .map(|dir| format!("{}::", dir.to_string_lossy()))
+          .collect::<String>()
+      })
+      .unwrap_or_default();
+    mod_path.push_str(&file_path.file_prefix());
+    // The stages of a GLSL shader are in separate files, like `blur.vert` and `blur.frag`.
+    if file_path.glsl_shader_stage().is_some() {
+      let extension = file_path.extension().unwrap().to_string_lossy();
+      mod_path.push_str(&format!("_{extension}"));
+    }
+    mod_path
+  }
+
+  /// The path of the module with the items of an imported module, or of the entry point
+  /// module for `None`, relative to the generated modules.
+  pub(crate) fn items_module_path(&self, mod_path: Option<&str>) -> Option<String> {
+    match (mod_path, self.shared_module.as_ref()) {
+      (Some(mod_path), Some(shared_module)) => {
+        Some(format!("{shared_module}::{mod_path}"))
+      }
+      (mod_path, _) => mod_path.map(ToString::to_string),
+    }
+  }
+
+  /// The options for generating the items of the entry point module with the given path.
+  pub(crate) fn for_entry_module(&self, mod_path: &str) -> Self {
+    Self {
+      entry_module_nesting: mod_path.split("::").count() - 1,
+      ..self.clone()
+    }
+  }
+
+  /// The visibility tokens for an item nested `depth` modules deep, where the items of the
+  /// entry point module have a depth of 1.
+  pub(crate) fn item_visibility_tokens(&self, depth: usize) -> TokenStream {
+    self.visibility_tokens(depth + self.entry_module_nesting)
+  }
+
+  /// The visibility tokens for the items of an imported module, or of the entry point
+  /// module for `None`.
+  pub(crate) fn items_visibility_tokens(&self, mod_path: Option<&str>) -> TokenStream {
+    match self.items_module_path(mod_path) {
+      Some(mod_path) => self.visibility_tokens(mod_path.split("::").count()),
+      None => self.item_visibility_tokens(1),
+    }
+  }
+
+  /// The visibility tokens for the items of the module at `mod_path`, relative to the
+  /// generated modules.
+  pub(crate) fn module_items_visibility_tokens(&self, mod_path: &str) -> TokenStream {
+    self.visibility_tokens(mod_path.split("::").count())
+  }
+
+  /// The visibility tokens for the items of a module nested in the items of an imported
+  /// module, or of the entry point module for `None`.
+  pub(crate) fn nested_items_visibility_tokens(
+    &self,
+    mod_path: Option<&str>,
+  ) -> TokenStream {
+    match self.items_module_path(mod_path) {
+      Some(mod_path) => self.visibility_tokens(mod_path.split("::").count() + 1),
+      None => self.item_visibility_tokens(2),
+    }
+  }
+
+  /// The generated modules are one module deeper in the file with a `root_module`.
+  fn visibility_tokens(&self, depth: usize) -> TokenStream {
+    let root_depth = usize::from(self.root_module.is_some());
+    self.item_visibility.tokens(depth + root_depth)
+  }
+
+  /// The `entry_point` field of pipeline stages, followed by the `compilation_options`
+  /// field for the wgpu versions having them.
+  pub(crate) fn entry_point_fields(
+    &self,
+    entry_point: TokenStream,
+    compilation_options_field: TokenStream,
+  ) -> TokenStream {
+    match self.wgpu_version {
+      WgpuVersion::V0_19 => quote!(entry_point: #entry_point,),
+      WgpuVersion::V0_20 | WgpuVersion::V22 => quote! {
+        entry_point: #entry_point,
+        #compilation_options_field,
+      },
+      WgpuVersion::V23 => quote! {
+        entry_point: Some(#entry_point),
+        #compilation_options_field,
+      },
+    }
+  }
+
+  /// The `compilation_options` parameter of the functions creating pipelines, for the wgpu
+  /// versions having them.
+  pub(crate) fn compilation_options_param(&self) -> TokenStream {
+    if self.wgpu_version >= WgpuVersion::V0_20 {
+      quote!(compilation_options: wgpu::PipelineCompilationOptions<'_>,)
+    } else {
+      quote!()
+    }
+  }
+
+  /// The `cache` field of pipeline descriptors for the wgpu versions having it.
+  pub(crate) fn pipeline_cache_field(&self) -> TokenStream {
+    if self.wgpu_version >= WgpuVersion::V22 {
+      quote!(cache: None,)
+    } else {
+      quote!()
+    }
+  }
+
+  fn apply_wgsl_type_map_overrides(&mut self) {
+    for (ty, tokens) in self.wgsl_type_map_overrides.iter() {
+      self.wgsl_type_map[*ty] = Some(tokens.clone());
+      self.wgsl_type_map_packed_types.insert(*ty);
+      self.wgsl_type_map_unpadded_matrix_types.shift_remove(ty);
+      self.wgsl_type_map_packed_vector_types.shift_remove(ty);
+    }
+    for (ty, tokens) in self.wgsl_type_map_aligned_overrides.iter() {
+      self.wgsl_type_map[*ty] = Some(tokens.clone());
+      self.wgsl_type_map_packed_types.shift_remove(ty);
+      self.wgsl_type_map_unpadded_matrix_types.shift_remove(ty);
+      self.wgsl_type_map_packed_vector_types.shift_remove(ty);
+    }
+  }
 }
 
 pub struct WGSLBindgen {
   dependency_tree: DependencyTree,
-  options: WgslBindgenOption,
+  pub(crate) options: WgslBindgenOption,
   content_hash: String,
+  /// The directories of the entry point globs, where new shaders may be added.
+  glob_dirs: Vec<PathBuf>,
 }
 
 impl WGSLBindgen {
-  fn new(options: WgslBindgenOption) -> Result<Self, WgslBindgenError> {
-    let entry_points = options
+  pub(crate) fn new(options: WgslBindgenOption) -> Result<Self, WgslBindgenError> {
+    let mut entry_points: Vec<_> = options
       .entry_points
       .iter()
       .cloned()
       .map(SourceFilePath::new)
       .collect();
+    let mut additional_scan_dirs = options.additional_scan_dirs.clone();
+    let mut glob_dirs = Vec::new();
+
+    for pattern in options.entry_point_globs.iter() {
+      let map_err = |inner| WgslBindgenError::EntryPointGlobError {
+        pattern: pattern.clone(),
+        inner,
+      };
+      let glob = SourceGlob::expand(pattern).map_err(map_err)?;
+
+      for file in glob.files {
+        let file = SourceFilePath::new(file);
+        let source = file.read_contents().map_err(map_err)?;
+        let is_entry_point = has_entry_point(&source) || file.needs_translation();
+        if is_entry_point && !entry_points.contains(&file) {
+          entry_points.push(file);
+        }
+      }
+
+      additional_scan_dirs.push(AdditionalScanDirectory {
+        module_import_root: None,
+        directory: glob.base_dir.to_string_lossy().into_owned(),
+      });
+      glob_dirs.push(glob.base_dir);
+    }
+
+    // In-memory sources are virtual modules, which use their module path as source path.
+    let mut virtual_modules = options.virtual_modules.clone();
+    for (module_path, source) in options.entry_point_sources.iter() {
+      virtual_modules.insert(module_path.clone(), source.clone());
+      entry_points.push(SourceFilePath::new(module_path));
+    }
 
     let dependency_tree = DependencyTree::try_build(
       options.module_import_root.clone(),
       entry_points,
-      options.additional_scan_dirs.clone(),
+      additional_scan_dirs,
+      virtual_modules,
     )?;
 
     let content_hash = Self::get_contents_hash(&options, &dependency_tree);
 
-    if options.emit_rerun_if_change {
-      for file in Self::iter_files_to_watch(&dependency_tree) {
-        println!("cargo:rerun-if-changed={}", file);
-      }
-    }
-
-    Ok(Self {
+    let bindgen = Self {
       dependency_tree,
       options,
       content_hash,
-    })
+      glob_dirs,
+    };
+
+    if bindgen.options.emit_rerun_if_change {
+      for path in bindgen.files_to_watch() {
+        println!("cargo:rerun-if-changed={}", path.display());
+      }
+    }
+
+    Ok(bindgen)
   }
 
-  fn iter_files_to_watch(dep_tree: &DependencyTree) -> impl Iterator<Item = String> {
-    dep_tree
-      .all_files_including_dependencies()
+  /// The paths the bindings depend on, for `cargo:rerun-if-changed` directives when
+  /// `emit_rerun_if_change` is disabled. These are the entry points and all the shaders
+  /// they import, transitively, and the directories of the entry point globs.
+  pub fn files_to_watch(&self) -> Vec<PathBuf> {
+    let mut files = self
+      .dependency_tree
+      .parsed_files()
       .into_iter()
-      .map(|path| path.to_string())
+      .filter(|source| !source.is_virtual)
+      .map(|source| source.file_path.to_path_buf())
+      .collect::<Vec<_>>();
+    files.sort();
+    files.dedup();
+    files.extend(self.glob_dirs.iter().cloned());
+    files
   }
 
   fn get_contents_hash(options: &WgslBindgenOption, dep_tree: &DependencyTree) -> String {
     let mut hasher = blake3::Hasher::new();
 
+    // The hash only changes with the sources, not with the order they were added in or
+    // with the line endings of the checkout.
+    let mut options = options.clone();
+    options.entry_points.sort();
     hasher.update(format!("{:?}", options).as_bytes());
     hasher.update(PKG_VER.as_bytes());
 
-    for SourceFile { content, .. } in dep_tree.parsed_files() {
-      hasher.update(content.as_bytes());
+    let mut files = dep_tree.parsed_files();
+    files.sort_by(|a, b| a.file_path.as_path().cmp(b.file_path.as_path()));
+    for SourceFile { content, .. } in files {
+      hasher.update(content.replace("\r\n", "\n").as_bytes());
     }
 
     hasher.finalize().to_string()
   }
 
-  fn generate_naga_module_for_entry(
-    entry: SourceWithFullDependenciesResult<'_>,
-  ) -> Result<WgslEntryResult, WgslBindgenError> {
+  /// The hash of what the items of an entry point module are generated from, which are the
+  /// sources of the entry point and its imports, the shader defs and the options, except
+  /// for the entry points.
+  fn get_entry_hash(
+    options: &WgslBindgenOption,
+    mod_name: &str,
+    entry: &SourceWithFullDependenciesResult<'_>,
+    shader_defs: &FxIndexMap<String, ShaderDefValue>,
+  ) -> String {
+    let mut hasher = blake3::Hasher::new();
+
+    let mut options = options.clone();
+    options.entry_points.clear();
+    options.entry_point_globs.clear();
+    options.entry_point_sources.clear();
+    hasher.update(format!("{:?}", options).as_bytes());
+    hasher.update(PKG_VER.as_bytes());
+    hasher.update(mod_name.as_bytes());
+    hasher.update(format!("{:?}", shader_defs).as_bytes());
+
+    let sources =
+      std::iter::once(entry.source_file).chain(entry.full_dependencies.iter().copied());
+    for SourceFile {
+      file_path, content, ..
+    } in sources
+    {
+      hasher.update(file_path.to_string().as_bytes());
+      hasher.update(content.replace("\r\n", "\n").as_bytes());
+    }
+
+    hasher.finalize().to_string()
+  }
+
+  fn generate_naga_module_for_entry<'a>(
+    mod_name: String,
+    entry: SourceWithFullDependenciesResult<'a>,
+    shader_defs: FxIndexMap<String, ShaderDefValue>,
+    capabilities: naga::valid::Capabilities,
+  ) -> Result<WgslEntryResult<'a>, WgslBindgenError> {
     let map_err = |err: ComposerError| WgslBindgenError::NagaModuleComposeError {
       entry: entry.source_file.file_path.to_string(),
       inner: err.inner,
     };
 
-    let mut composer = Composer::default();
     let source = entry.source_file;
+    let translated_module = if let Some(stage) = source.file_path.glsl_shader_stage() {
+      let module = glsl_to_module(&source.content, stage, &shader_defs, capabilities)
+        .map_err(|message| WgslBindgenError::GlslParseError {
+          entry: source.file_path.to_string(),
+          message,
+        })?;
+      Some(module)
+    } else if source.file_path.is_spirv() {
+      let module = spirv_to_module(&source.content, capabilities).map_err(|message| {
+        WgslBindgenError::SpirvParseError {
+          entry: source.file_path.to_string(),
+          message,
+        }
+      })?;
+      Some(module)
+    } else {
+      None
+    };
+    if let Some(module) = translated_module {
+      return Ok(WgslEntryResult {
+        mod_name,
+        naga_module: module,
+        source_including_deps: entry,
+        shader_defs,
+      });
+    }
+
+    let mut composer = Composer::default().with_capabilities(capabilities);
 
     for dependency in entry.full_dependencies.iter() {
       composer
@@ -232,54 +1425,400 @@ impl WGSLBindgen {
         .map_err(map_err)?;
     }
 
-    let module = composer
-      .make_naga_module(NagaModuleDescriptor {
-        source: &source.content,
-        file_path: &source.file_path.to_string(),
-        ..Default::default()
-      })
-      .map_err(map_err)?;
+    let file_path = source.file_path.to_string();
+    let descriptor = || NagaModuleDescriptor {
+      source: &source.content,
+      file_path: &file_path,
+      shader_defs: shader_defs.clone().into_iter().collect(),
+      ..Default::default()
+    };
+    let module = match composer.make_naga_module(descriptor()) {
+      Ok(module) => module,
+      // The validation errors of uniform layouts only have the handles of the types, so
+      // the module is composed again without validation to name the members.
+      Err(err) if matches!(err.inner, ComposerErrorInner::ShaderValidationError(_)) => {
+        composer.validate = false;
+        if let Ok(module) = composer.make_naga_module(descriptor()) {
+          wgsl::validate_uniform_layouts(&module)?;
+        }
+        return Err(map_err(err));
+      }
+      Err(err) => return Err(map_err(err)),
+    };
 
     Ok(WgslEntryResult {
-      mod_name: source.file_path.file_prefix(),
+      mod_name,
       naga_module: module,
       source_including_deps: entry,
+      shader_defs,
     })
   }
 
-  pub fn generate_string(&self) -> Result<String, WgslBindgenError> {
-    use std::fmt::Write;
-    let entry_results = self
+  /// The entry point modules with their sources and shader defs, sorted by module name.
+  fn entries(
+    &self,
+  ) -> Vec<(
+    String,
+    SourceWithFullDependenciesResult<'_>,
+    FxIndexMap<String, ShaderDefValue>,
+  )> {
+    let options = &self.options;
+    let mut entries = Vec::new();
+    for entry in self
       .dependency_tree
       .get_source_files_with_full_dependencies()
+    {
+      let mod_name = options.entry_module_path(&entry.source_file.file_path);
+      if options.shader_def_permutations.is_empty() {
+        entries.push((mod_name, entry, options.shader_defs.clone()));
+        continue;
+      }
+
+      for (name, permutation_defs) in options.shader_def_permutations.iter() {
+        let mut shader_defs = options.shader_defs.clone();
+        shader_defs.extend(permutation_defs.clone());
+        entries.push((format!("{mod_name}::{name}"), entry.clone(), shader_defs));
+      }
+    }
+
+    // The modules are generated in the order of the entry points, which is kept stable
+    // regardless of the order they were added or found in.
+    entries.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+    entries
+  }
+
+  /// The items of the entry point modules, and the errors of the entry points which failed,
+  /// which don't stop the others from being generated.
+  fn entry_items(&self) -> (Vec<(String, Vec<WgslEntryItem>)>, Vec<WgslBindgenError>) {
+    let options = &self.options;
+    let entries = self.entries();
+    let cache = options.incremental_cache_dir.as_ref().map(EntryCache::new);
+    let mut entry_items = Vec::new();
+    let mut uncached_entries = Vec::new();
+    for (mod_name, entry, shader_defs) in entries {
+      let hash = cache
+        .as_ref()
+        .map(|_| Self::get_entry_hash(options, &mod_name, &entry, &shader_defs));
+      let cached_items = cache
+        .as_ref()
+        .zip(hash.as_ref())
+        .and_then(|(cache, hash)| cache.load(&mod_name, hash));
+
+      match cached_items {
+        Some((items, warnings)) => {
+          Self::emit_warnings(&warnings);
+          entry_items.push((mod_name, items));
+        }
+        None => uncached_entries.push((mod_name, entry, shader_defs, hash)),
+      }
+    }
+
+    // Composing is the slowest part, and independent for each entry point.
+    let capabilities = options.naga_capabilities;
+    let results =
+      parallel_map(uncached_entries, |(mod_name, entry, shader_defs, hash)| {
+        let result = Self::generate_naga_module_for_entry(
+          mod_name,
+          entry,
+          shader_defs,
+          capabilities,
+        );
+        (result, hash)
+      });
+
+    let mut errors = Vec::new();
+    for (result, hash) in results {
+      let items = result.and_then(|mut result| {
+        wgsl::remap_bindings(&mut result.naga_module, options)?;
+        let warnings = if options.warn_unused_bindings {
+          Self::unused_binding_warnings(&result)
+        } else {
+          Vec::new()
+        };
+        Self::emit_warnings(&warnings);
+        let items = create_entry_items(&result, options)?;
+        if let Some((cache, hash)) = cache.as_ref().zip(hash) {
+          cache.store(&result.mod_name, &hash, &items, &warnings)?;
+        }
+        Ok((result.mod_name, items))
+      });
+      match items {
+        Ok(items) => entry_items.push(items),
+        Err(err) => errors.push(err),
+      }
+    }
+
+    entry_items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    (entry_items, errors)
+  }
+
+  fn unused_binding_warnings(entry: &WgslEntryResult) -> Vec<String> {
+    wgsl::unused_bindings(&entry.naga_module)
       .into_iter()
-      .map(Self::generate_naga_module_for_entry)
-      .collect::<Result<Vec<_>, _>>()?;
+      .map(|global| {
+        let name = demangle(global.name.as_deref().unwrap_or_default());
+        let binding = global.binding.as_ref().unwrap();
+        format!(
+          "`{name}` at @group({}) @binding({}) of `{}` isn't used by any entry point",
+          binding.group, binding.binding, entry.mod_name
+        )
+      })
+      .collect()
+  }
+
+  /// Emits the warnings of an entry point module, including the ones kept in the
+  /// `incremental_cache_dir` for the modules which aren't generated again.
+  fn emit_warnings(warnings: &[String]) {
+    for warning in warnings {
+      println!("cargo:warning={warning}");
+    }
+  }
 
+  /// The header comments, with the `SourceHash` when `with_source_hash` and not skipping the
+  /// provenance.
+  fn header_comments(&self, with_source_hash: bool) -> String {
+    use std::fmt::Write;
     let mut text = String::new();
 
-    if !self.options.skip_header_comments {
-      writeln!(&mut text, "// File automatically generated by {PKG_NAME}^").unwrap();
-      writeln!(&mut text, "//").unwrap();
-      writeln!(&mut text, "// ^ {PKG_NAME} version {PKG_VER}",).unwrap();
-      writeln!(&mut text, "// Changes made to this file will not be saved.").unwrap();
+    if self.options.skip_header_comments {
+      return text;
+    }
+
+    let provenance = !self.options.skip_header_provenance;
+    match self.options.header_comment.as_ref() {
+      Some(header_comment) => {
+        for line in header_comment.lines() {
+          writeln!(&mut text, "{}", format!("// {line}").trim_end()).unwrap();
+        }
+        if provenance {
+          writeln!(&mut text, "// {PKG_NAME} version {PKG_VER}",).unwrap();
+        }
+      }
+      None if provenance => {
+        writeln!(&mut text, "// File automatically generated by {PKG_NAME}^").unwrap();
+        writeln!(&mut text, "//").unwrap();
+        writeln!(&mut text, "// ^ {PKG_NAME} version {PKG_VER}",).unwrap();
+        writeln!(&mut text, "// Changes made to this file will not be saved.").unwrap();
+      }
+      None => {
+        writeln!(&mut text, "// File automatically generated by {PKG_NAME}").unwrap();
+        writeln!(&mut text, "// Changes made to this file will not be saved.").unwrap();
+      }
+    }
+    if provenance && with_source_hash {
       writeln!(&mut text, "// SourceHash: {}", self.content_hash).unwrap();
-      writeln!(&mut text).unwrap();
     }
+    writeln!(&mut text).unwrap();
 
-    let output = create_rust_bindings(entry_results, &self.options)?;
-    text += &output;
+    text
+  }
 
+  /// The hash of the options, the sources and the version of wgsl_bindgen, which is in the
+  /// `SourceHash` header comment of the generated bindings.
+  pub fn source_hash(&self) -> &str {
+    &self.content_hash
+  }
+
+  /// Whether the bindings in the output file, or in the `mod.rs` of the output directory,
+  /// were generated from the current options and sources, like for checking that the
+  /// committed bindings aren't stale in CI. Always `false` without the `SourceHash`.
+  pub fn is_up_to_date(&self) -> bool {
+    let output_path = match (&self.options.output_dir, &self.options.output_file) {
+      (Some(output_dir), _) => output_dir.join("mod.rs"),
+      (None, Some(output_file)) => output_file.clone(),
+      (None, None) => return false,
+    };
+    !self.is_hash_changed(&output_path)
+  }
+
+  pub fn generate_string(&self) -> Result<String, WgslBindgenError> {
+    let (text, errors) = self.generate_string_with_errors()?;
+    WgslBindgenError::from_entry_point_errors(errors)?;
     Ok(text)
   }
 
+  /// The bindings of the entry points which were generated, and the errors of the others.
+  /// Without the `SourceHash` when some failed, so they are generated again.
+  fn generate_string_with_errors(
+    &self,
+  ) -> Result<(String, Vec<WgslBindgenError>), WgslBindgenError> {
+    let (entry_items, errors) = self.entry_items();
+    let mut text = self.header_comments(errors.is_empty());
+    let output = create_rust_bindings(entry_items, &self.options)?;
+    text += &output;
+
+    Ok((text, errors))
+  }
+
+  /// Generates the bindings as a `mod.rs` and a file per module, with the paths relative
+  /// to the `mod.rs`. Only the `mod.rs` has the `SourceHash`, so the files of the other
+  /// modules only change with their contents.
+  pub fn generate_files(&self) -> Result<Vec<(PathBuf, String)>, WgslBindgenError> {
+    let (files, errors) = self.generate_files_with_errors()?;
+    WgslBindgenError::from_entry_point_errors(errors)?;
+    Ok(files)
+  }
+
+  /// Like `generate_string_with_errors`, with a file per module.
+  fn generate_files_with_errors(
+    &self,
+  ) -> Result<(GeneratedFiles, Vec<WgslBindgenError>), WgslBindgenError> {
+    let (entry_items, errors) = self.entry_items();
+    let files = create_rust_binding_files(entry_items, &self.options)?
+      .into_iter()
+      .map(|(path, output)| {
+        let with_source_hash = path == Path::new("mod.rs") && errors.is_empty();
+        let header = self.header_comments(with_source_hash);
+        (path, format!("{header}{output}"))
+      })
+      .collect();
+
+    Ok((files, errors))
+  }
+
+  /// Translates the shaders to the `backend_outputs`, with the paths of the files relative
+  /// to the generated bindings. The entry points are composed again, without the cache.
+  pub fn generate_backend_sources(
+    &self,
+  ) -> Result<Vec<(PathBuf, String)>, WgslBindgenError> {
+    let options = &self.options;
+    if options.backend_outputs.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let capabilities = options.naga_capabilities;
+    let mut files = Vec::new();
+    for result in self.compose_entries() {
+      let result = result?;
+      let file_stem = result.mod_name.replace("::", ".");
+      let entry = result
+        .source_including_deps
+        .source_file
+        .file_path
+        .to_string();
+      for &backend in options.backend_outputs.iter() {
+        let sources =
+          module_to_backend_sources(&result.naga_module, capabilities, backend).map_err(
+            |message| WgslBindgenError::BackendTranslationError {
+              entry: entry.clone(),
+              backend,
+              message,
+            },
+          )?;
+        for (extension, source) in sources {
+          files.push((PathBuf::from(format!("{file_stem}.{extension}")), source));
+        }
+      }
+    }
+
+    Ok(files)
+  }
+
+  /// The Markdown documents of the entry point modules when `generate_markdown_docs`, with
+  /// the paths of the files relative to the generated bindings.
+  pub fn generate_markdown_docs(
+    &self,
+  ) -> Result<Vec<(PathBuf, String)>, WgslBindgenError> {
+    if !self.options.generate_markdown_docs {
+      return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for result in self.compose_entries() {
+      let result = result?;
+      let path = PathBuf::from(format!("{}.md", result.mod_name.replace("::", ".")));
+      files.push((path, entry_markdown(&result)?));
+    }
+
+    Ok(files)
+  }
+
+  /// Composes all the entry points, without the cache. The entry points failing to compose
+  /// are left out with `continue_on_error`, as the bindings report their errors.
+  fn compose_entries(&self) -> Vec<Result<WgslEntryResult<'_>, WgslBindgenError>> {
+    let capabilities = self.options.naga_capabilities;
+    let mut results = parallel_map(self.entries(), |(mod_name, entry, shader_defs)| {
+      Self::generate_naga_module_for_entry(mod_name, entry, shader_defs, capabilities)
+    });
+    for result in results.iter_mut() {
+      if let Ok(entry_result) = result {
+        if let Err(error) =
+          wgsl::remap_bindings(&mut entry_result.naga_module, &self.options)
+        {
+          *result = Err(error.into());
+        }
+      }
+    }
+    if self.options.continue_on_error {
+      results.retain(Result::is_ok);
+    }
+    results
+  }
+
   pub fn generate(&self) -> Result<(), WgslBindgenError> {
+    if let Some(output_dir) = self.options.output_dir.as_ref() {
+      let mod_file = output_dir.join("mod.rs");
+      if self.options.skip_hash_check || self.is_hash_changed(&mod_file) {
+        let (files, errors) = self.generate_files_with_errors()?;
+        if !errors.is_empty() && !self.options.continue_on_error {
+          return WgslBindgenError::from_entry_point_errors(errors);
+        }
+
+        self.write_shader_files(output_dir)?;
+        for (path, content) in files {
+          let path = output_dir.join(path);
+          // Unchanged files aren't written, so their modification time stays the same.
+          if std::fs::read_to_string(&path)
+            .is_ok_and(|old_content| old_content == content)
+          {
+            continue;
+          }
+          if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+          }
+          std::fs::File::create(path)?.write_all(content.as_bytes())?
+        }
+        WgslBindgenError::from_entry_point_errors(errors)?;
+      }
+      return Ok(());
+    }
+
     let output_path = self
       .options
       .output_file
       .as_ref()
       .ok_or(WgslBindgenError::OutputFileNotSpecified)?;
 
+    if self.options.skip_hash_check || self.is_hash_changed(output_path) {
+      let (content, errors) = self.generate_string_with_errors()?;
+      if !errors.is_empty() && !self.options.continue_on_error {
+        return WgslBindgenError::from_entry_point_errors(errors);
+      }
+
+      let output_dir = output_path.parent().unwrap_or(Path::new(""));
+      self.write_shader_files(output_dir)?;
+      std::fs::File::create(output_path)?.write_all(content.as_bytes())?;
+      WgslBindgenError::from_entry_point_errors(errors)?;
+    }
+
+    Ok(())
+  }
+
+  /// Writes the `backend_outputs` and the Markdown documents next to the bindings.
+  fn write_shader_files(&self, output_dir: &Path) -> Result<(), WgslBindgenError> {
+    let backend_sources = self.generate_backend_sources()?;
+    let markdown_docs = self.generate_markdown_docs()?;
+    for (path, source) in backend_sources.into_iter().chain(markdown_docs) {
+      let path = output_dir.join(path);
+      if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+      }
+      std::fs::write(path, source)?;
+    }
+    Ok(())
+  }
+
+  fn is_hash_changed(&self, output_path: &Path) -> bool {
     let old_content =
       std::fs::read_to_string(output_path).unwrap_or_else(|_| String::new());
 
@@ -288,14 +1827,140 @@ impl WGSLBindgen {
       .find(|line| line.starts_with("// SourceHash:"))
       .unwrap_or("");
 
-    let is_hash_changed =
-      || old_hashstr_comment != format!("// SourceHash: {}", &self.content_hash);
+    old_hashstr_comment != format!("// SourceHash: {}", &self.content_hash)
+  }
+}
+
+/// Maps the items on the available threads, keeping their order.
+fn parallel_map<T: Send, R: Send>(items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Vec<R> {
+  let threads = std::thread::available_parallelism()
+    .map_or(1, |threads| threads.get())
+    .min(items.len());
+  if threads <= 1 {
+    return items.into_iter().map(f).collect();
+  }
 
-    if self.options.skip_hash_check || is_hash_changed() {
-      let content = self.generate_string()?;
-      std::fs::File::create(output_path)?.write_all(content.as_bytes())?
-    }
+  let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+  let mut results = std::thread::scope(|scope| {
+    let workers = (0..threads)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut results = Vec::new();
+          loop {
+            let Some((index, item)) = queue.lock().unwrap().next() else {
+              break results;
+            };
+            results.push((index, f(item)));
+          }
+        })
+      })
+      .collect::<Vec<_>>();
 
-    Ok(())
+    workers
+      .into_iter()
+      .flat_map(|worker| {
+        worker
+          .join()
+          .unwrap_or_else(|err| std::panic::resume_unwind(err))
+      })
+      .collect::<Vec<_>>()
+  });
+
+  results.sort_by_key(|(index, _)| *index);
+  results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use quote::quote;
+
+  use super::*;
+  use crate::GlamWgslTypeMap;
+
+  #[test]
+  fn wgsl_type_map_overrides_take_precedence() {
+    let mut options = WgslBindgenOptionBuilder::default()
+      .add_entry_point("main.wgsl")
+      .serialization_strategy(WgslTypeSerializeStrategy::Encase)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .override_wgsl_type(WgslType::Vec3f, quote!([f32; 3]))
+      .fallible_build()
+      .unwrap();
+
+    options.apply_wgsl_type_map_overrides();
+
+    let to_string = |ty| options.wgsl_type_map[ty].as_ref().map(|t| t.to_string());
+    assert_eq!(to_string(WgslType::Vec3f), Some(quote!([f32; 3]).to_string()));
+    assert_eq!(to_string(WgslType::Vec4f), Some(quote!(glam::Vec4).to_string()));
+    let packed_types = &options.wgsl_type_map_packed_types;
+    assert!(packed_types.contains(&WgslType::Vec3f));
+  }
+
+  #[test]
+  fn wgsl_type_map_aligned_overrides_keep_wgsl_layout() {
+    let mut options = WgslBindgenOptionBuilder::default()
+      .add_entry_point("main.wgsl")
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap::packed())
+      .override_wgsl_type_aligned(WgslType::Vec3f, quote!(glam::Vec3A))
+      .fallible_build()
+      .unwrap();
+
+    options.apply_wgsl_type_map_overrides();
+
+    let to_string = |ty| options.wgsl_type_map[ty].as_ref().map(|t| t.to_string());
+    assert_eq!(to_string(WgslType::Vec3f), Some(quote!(glam::Vec3A).to_string()));
+    let packed_types = &options.wgsl_type_map_packed_types;
+    assert!(!packed_types.contains(&WgslType::Vec3f));
+    assert!(packed_types.contains(&WgslType::Vec3i));
+  }
+
+  #[test]
+  fn instance_step_mode_structs_match_pattern_or_name() {
+    let options = WgslBindgenOptionBuilder::default()
+      .add_entry_point("main.wgsl")
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .instance_step_mode_pattern("*Instance")
+      .add_instance_step_mode_struct("Particle")
+      .fallible_build()
+      .unwrap();
+
+    assert!(options.is_instance_step_mode("PointInstance"));
+    assert!(options.is_instance_step_mode("Particle"));
+    assert!(!options.is_instance_step_mode("InstanceData"));
+    assert!(!options.is_instance_step_mode("Vertex"));
+  }
+
+  #[test]
+  fn entry_module_path_mirrors_entry_modules_root_dir() {
+    let options = WgslBindgenOptionBuilder::default()
+      .add_entry_point("main.wgsl")
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .entry_modules_root_dir("shaders")
+      .fallible_build()
+      .unwrap();
+
+    let path = |path: &str| SourceFilePath::new(path);
+    assert_eq!(
+      options.entry_module_path(&path("shaders/effects/blur.wgsl")),
+      "effects::blur"
+    );
+    assert_eq!(options.entry_module_path(&path("shaders/main.wgsl")), "main");
+    assert_eq!(options.entry_module_path(&path("other/main.wgsl")), "main");
+    assert_eq!(
+      options.entry_module_path(&path("shaders/effects/blur.vert")),
+      "effects::blur_vert"
+    );
+  }
+
+  #[test]
+  fn parallel_map_keeps_the_order() {
+    let items = (0..100).collect::<Vec<u32>>();
+
+    let results = parallel_map(items, |item| item * 2);
+
+    assert_eq!(results, (0..100).map(|item| item * 2).collect::<Vec<_>>());
   }
 }
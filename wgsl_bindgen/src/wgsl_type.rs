@@ -3,13 +3,16 @@ use std::fmt::Debug;
 use enum_map::{enum_map, Enum, EnumMap};
 use proc_macro2::TokenStream;
 use quote::quote;
+use serde::Deserialize;
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{quote_gen::RustTypeInfo, WgslTypeSerializeStrategy};
 
 /// The `WgslType` enum represents various WGSL types, such as vectors and matrices.
 /// See [spec](https://www.w3.org/TR/WGSL/#alignment-and-size)
-#[derive(Debug, Clone, Copy, Enum, Hash, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, Copy, Enum, Hash, PartialEq, Eq, EnumIter, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WgslType {
   Vec2i,
   Vec3i,
@@ -23,6 +26,9 @@ pub enum WgslType {
   Vec2h,
   Vec3h,
   Vec4h,
+  Vec2d,
+  Vec3d,
+  Vec4d,
   Mat2x2f,
   Mat2x3f,
   Mat2x4f,
@@ -41,6 +47,15 @@ pub enum WgslType {
   Mat4x2h,
   Mat4x3h,
   Mat4x4h,
+  Mat2x2d,
+  Mat2x3d,
+  Mat2x4d,
+  Mat3x2d,
+  Mat3x3d,
+  Mat3x4d,
+  Mat4x2d,
+  Mat4x3d,
+  Mat4x4d,
 }
 
 impl WgslType {
@@ -50,30 +65,74 @@ impl WgslType {
     match self {
       Vec2i | Vec2u | Vec2f => (8, 8),
       Vec2h => (4, 4),
+      Vec2d => (16, 16),
       Vec3i | Vec3u | Vec3f => (16, 12),
       Vec3h => (8, 6),
+      Vec3d => (32, 24),
       Vec4i | Vec4u | Vec4f => (16, 16),
       Vec4h => (8, 8),
+      Vec4d => (32, 32),
 
       // AlignOf(vecR), SizeOf(array<vecR, C>)
       Mat2x2f => (8, 16),
       Mat2x2h => (4, 8),
+      Mat2x2d => (16, 32),
       Mat3x2f => (8, 24),
       Mat3x2h => (4, 12),
+      Mat3x2d => (16, 48),
       Mat4x2f => (8, 32),
       Mat4x2h => (4, 16),
+      Mat4x2d => (16, 64),
       Mat2x3f => (16, 32),
       Mat2x3h => (8, 16),
+      Mat2x3d => (32, 64),
       Mat3x3f => (16, 48),
       Mat3x3h => (8, 24),
+      Mat3x3d => (32, 96),
       Mat4x3f => (16, 64),
       Mat4x3h => (8, 32),
+      Mat4x3d => (32, 128),
       Mat2x4f => (16, 32),
       Mat2x4h => (8, 16),
+      Mat2x4d => (32, 64),
       Mat3x4f => (16, 48),
       Mat3x4h => (8, 24),
+      Mat3x4d => (32, 96),
       Mat4x4f => (16, 64),
       Mat4x4h => (8, 32),
+      Mat4x4d => (32, 128),
+    }
+  }
+
+  /// Returns the alignment and size of a Rust type with the same components which is only
+  /// aligned to its scalar, like `glam::Vec3` for `vec3<f32>`.
+  pub const fn packed_alignment_and_size(&self) -> (u8, usize) {
+    let width = self.scalar_width();
+    (width, width as usize * self.component_count())
+  }
+
+  const fn scalar_width(&self) -> u8 {
+    use WgslType::*;
+    match self {
+      Vec2h | Vec3h | Vec4h | Mat2x2h | Mat2x3h | Mat2x4h | Mat3x2h | Mat3x3h
+      | Mat3x4h | Mat4x2h | Mat4x3h | Mat4x4h => 2,
+      Vec2d | Vec3d | Vec4d | Mat2x2d | Mat2x3d | Mat2x4d | Mat3x2d | Mat3x3d
+      | Mat3x4d | Mat4x2d | Mat4x3d | Mat4x4d => 8,
+      _ => 4,
+    }
+  }
+
+  const fn component_count(&self) -> usize {
+    use WgslType::*;
+    match self {
+      Vec2i | Vec2u | Vec2f | Vec2h | Vec2d => 2,
+      Vec3i | Vec3u | Vec3f | Vec3h | Vec3d => 3,
+      Vec4i | Vec4u | Vec4f | Vec4h | Vec4d | Mat2x2f | Mat2x2h | Mat2x2d => 4,
+      Mat2x3f | Mat2x3h | Mat2x3d | Mat3x2f | Mat3x2h | Mat3x2d => 6,
+      Mat2x4f | Mat2x4h | Mat2x4d | Mat4x2f | Mat4x2h | Mat4x2d => 8,
+      Mat3x3f | Mat3x3h | Mat3x3d => 9,
+      Mat3x4f | Mat3x4h | Mat3x4d | Mat4x3f | Mat4x3h | Mat4x3d => 12,
+      Mat4x4f | Mat4x4h | Mat4x4d => 16,
     }
   }
 
@@ -91,7 +150,10 @@ impl WgslType {
       | WgslType::Vec4f
       | WgslType::Vec2h
       | WgslType::Vec3h
-      | WgslType::Vec4h => true,
+      | WgslType::Vec4h
+      | WgslType::Vec2d
+      | WgslType::Vec3d
+      | WgslType::Vec4d => true,
       _ => false,
     }
   }
@@ -116,7 +178,16 @@ impl WgslType {
       | WgslType::Mat3x4h
       | WgslType::Mat4x2h
       | WgslType::Mat4x3h
-      | WgslType::Mat4x4h => true,
+      | WgslType::Mat4x4h
+      | WgslType::Mat2x2d
+      | WgslType::Mat2x3d
+      | WgslType::Mat2x4d
+      | WgslType::Mat3x2d
+      | WgslType::Mat3x3d
+      | WgslType::Mat3x4d
+      | WgslType::Mat4x2d
+      | WgslType::Mat4x3d
+      | WgslType::Mat4x4d => true,
       _ => false,
     }
   }
@@ -136,12 +207,36 @@ pub type WgslTypeMap = EnumMap<WgslType, Option<TokenStream>>;
 pub trait WgslTypeMapBuild {
   /// Builds the `WgslTypeMap` based on the given serialization strategy.
   fn build(&self, strategy: WgslTypeSerializeStrategy) -> WgslTypeMap;
+
+  /// WGSL types which are mapped to Rust types only aligned to their scalar, like `glam::Vec3`.
+  /// Structs get explicit padding after such members instead of relying on their alignment.
+  fn packed_types(&self) -> Vec<WgslType> {
+    Vec::new()
+  }
+
+  /// Rust types for `matCx3<f32>` whose columns aren't padded to 16 bytes like in WGSL, like
+  /// `nalgebra::SMatrix<f32, 3, 3>`, converting from and into arrays of columns like
+  /// `[[f32; 3]; 3]`. With [WgslTypeSerializeStrategy::Bytemuck], the matrices are stored in
+  /// the padded types of `padded_matrix_types` instead, with conversions from and into these.
+  fn unpadded_matrix_types(&self) -> Vec<(WgslType, TokenStream)> {
+    Vec::new()
+  }
+
+  /// Rust types only aligned to their scalar for the vectors the map aligns like WGSL, like
+  /// `glam::Vec3` for `glam::Vec3A`, used when the next struct member is packed into the
+  /// trailing padding of the vector. Arrays like `[f32; 3]` are used otherwise.
+  fn packed_vector_types(&self) -> Vec<(WgslType, TokenStream)> {
+    Vec::new()
+  }
 }
 
 /// Provides an extension method for `WgslTypeMap` to convert WGSL types to `RustTypeInfo`.
 pub(crate) trait WgslTypeMapExt {
   /// Gets the `RustTypeInfo` for the given WGSL type.
   fn get_rust_type_info(&self, wgsl_ty: WgslType) -> Option<RustTypeInfo>;
+
+  /// Gets the `RustTypeInfo` for the given WGSL type mapped to a packed Rust type.
+  fn get_packed_rust_type_info(&self, wgsl_ty: WgslType) -> Option<RustTypeInfo>;
 }
 
 impl WgslTypeMapExt for WgslTypeMap {
@@ -151,6 +246,13 @@ impl WgslTypeMapExt for WgslTypeMap {
     let alignment = naga::proc::Alignment::from_width(alignment_width);
     Some(RustTypeInfo(ty, size, alignment))
   }
+
+  fn get_packed_rust_type_info(&self, wgsl_ty: WgslType) -> Option<RustTypeInfo> {
+    let (alignment_width, size) = wgsl_ty.packed_alignment_and_size();
+    let ty = self[wgsl_ty].as_ref()?.clone();
+    let alignment = naga::proc::Alignment::from_width(alignment_width);
+    Some(RustTypeInfo(ty, size, alignment))
+  }
 }
 
 impl<T: WgslTypeMapBuild + 'static> From<T> for Box<dyn WgslTypeMapBuild> {
@@ -196,17 +298,197 @@ impl WgslTypeMapBuild for GlamWgslTypeMap {
       WgslType::Mat2x2f if is_encase => Some(quote!(glam::Mat2)),
       WgslType::Mat3x3f => Some(quote!(glam::Mat3A)),
       WgslType::Mat4x4f => Some(quote!(glam::Mat4)),
+      WgslType::Vec2d if is_encase => Some(quote!(glam::DVec2)),
+      WgslType::Vec3d if is_encase => Some(quote!(glam::DVec3)),
+      WgslType::Vec4d if is_encase => Some(quote!(glam::DVec4)),
+      WgslType::Mat2x2d if is_encase => Some(quote!(glam::DMat2)),
+      WgslType::Mat3x3d if is_encase => Some(quote!(glam::DMat3)),
+      WgslType::Mat4x4d if is_encase => Some(quote!(glam::DMat4)),
+      _ => None,
+    }
+  }
+
+  fn packed_vector_types(&self) -> Vec<(WgslType, TokenStream)> {
+    vec![
+      (WgslType::Vec3i, quote!(glam::IVec3)),
+      (WgslType::Vec3u, quote!(glam::UVec3)),
+      (WgslType::Vec3f, quote!(glam::Vec3)),
+    ]
+  }
+}
+
+impl GlamWgslTypeMap {
+  /// Prefers the unaligned `glam::Vec3` and `glam::Mat3`, see [GlamPackedWgslTypeMap].
+  pub fn packed() -> GlamPackedWgslTypeMap {
+    GlamPackedWgslTypeMap
+  }
+}
+
+/// `glam` types like [GlamWgslTypeMap], but using `glam::Vec3` and `glam::Mat3` instead of the
+/// 16 byte aligned `glam::Vec3A` and `glam::Mat3A`. Padding after `vec3` members is generated
+/// explicitly. `glam::Mat3` lacks the padding between `mat3x3<f32>` columns, so it is only used
+/// with [WgslTypeSerializeStrategy::Encase].
+#[derive(Clone)]
+pub struct GlamPackedWgslTypeMap;
+
+impl WgslTypeMapBuild for GlamPackedWgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let mut map = GlamWgslTypeMap.build(serialize_strategy);
+    map[WgslType::Vec3i] = Some(quote!(glam::IVec3));
+    map[WgslType::Vec3u] = Some(quote!(glam::UVec3));
+    map[WgslType::Vec3f] = Some(quote!(glam::Vec3));
+    map[WgslType::Mat3x3f] = serialize_strategy
+      .is_encase()
+      .then(|| quote!(glam::Mat3));
+    map
+  }
+
+  fn packed_types(&self) -> Vec<WgslType> {
+    vec![
+      WgslType::Vec3i,
+      WgslType::Vec3u,
+      WgslType::Vec3f,
+      WgslType::Mat3x3f,
+    ]
+  }
+}
+
+/// `cgmath` types like `cgmath::Vector4<f32>` or `cgmath::Matrix4<f32>`.
+/// `cgmath` types are only aligned to their scalar, so they are only used with
+/// [WgslTypeSerializeStrategy::Encase]. Other types fall back to [WgslRustTypeMap].
+#[derive(Clone)]
+pub struct CgmathWgslTypeMap;
+
+impl WgslTypeMapBuild for CgmathWgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let is_encase = serialize_strategy.is_encase();
+    enum_map! {
+      WgslType::Vec2i if is_encase => Some(quote!(cgmath::Vector2<i32>)),
+      WgslType::Vec3i if is_encase => Some(quote!(cgmath::Vector3<i32>)),
+      WgslType::Vec4i if is_encase => Some(quote!(cgmath::Vector4<i32>)),
+      WgslType::Vec2u if is_encase => Some(quote!(cgmath::Vector2<u32>)),
+      WgslType::Vec3u if is_encase => Some(quote!(cgmath::Vector3<u32>)),
+      WgslType::Vec4u if is_encase => Some(quote!(cgmath::Vector4<u32>)),
+      WgslType::Vec2f if is_encase => Some(quote!(cgmath::Vector2<f32>)),
+      WgslType::Vec3f if is_encase => Some(quote!(cgmath::Vector3<f32>)),
+      WgslType::Vec4f if is_encase => Some(quote!(cgmath::Vector4<f32>)),
+      WgslType::Mat2x2f if is_encase => Some(quote!(cgmath::Matrix2<f32>)),
+      WgslType::Mat3x3f if is_encase => Some(quote!(cgmath::Matrix3<f32>)),
+      WgslType::Mat4x4f if is_encase => Some(quote!(cgmath::Matrix4<f32>)),
+      _ => None,
+    }
+  }
+
+  fn unpadded_matrix_types(&self) -> Vec<(WgslType, TokenStream)> {
+    vec![(WgslType::Mat3x3f, quote!(cgmath::Matrix3<f32>))]
+  }
+}
+
+/// `mint` types like `mint::Vector4<f32>` or `mint::ColumnMatrix4<f32>`.
+/// Note that `mint` names matrices by rows first, so `mat2x3<f32>` maps to `mint::ColumnMatrix3x2<f32>`.
+/// `mint` types are only aligned to their scalar, so they are only used with
+/// [WgslTypeSerializeStrategy::Encase]. Other types fall back to [WgslRustTypeMap].
+#[derive(Clone)]
+pub struct MintWgslTypeMap;
+
+impl WgslTypeMapBuild for MintWgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let is_encase = serialize_strategy.is_encase();
+    enum_map! {
+      WgslType::Vec2i if is_encase => Some(quote!(mint::Vector2<i32>)),
+      WgslType::Vec3i if is_encase => Some(quote!(mint::Vector3<i32>)),
+      WgslType::Vec4i if is_encase => Some(quote!(mint::Vector4<i32>)),
+      WgslType::Vec2u if is_encase => Some(quote!(mint::Vector2<u32>)),
+      WgslType::Vec3u if is_encase => Some(quote!(mint::Vector3<u32>)),
+      WgslType::Vec4u if is_encase => Some(quote!(mint::Vector4<u32>)),
+      WgslType::Vec2f if is_encase => Some(quote!(mint::Vector2<f32>)),
+      WgslType::Vec3f if is_encase => Some(quote!(mint::Vector3<f32>)),
+      WgslType::Vec4f if is_encase => Some(quote!(mint::Vector4<f32>)),
+      WgslType::Mat2x2f if is_encase => Some(quote!(mint::ColumnMatrix2<f32>)),
+      WgslType::Mat2x3f if is_encase => Some(quote!(mint::ColumnMatrix3x2<f32>)),
+      WgslType::Mat2x4f if is_encase => Some(quote!(mint::ColumnMatrix4x2<f32>)),
+      WgslType::Mat3x2f if is_encase => Some(quote!(mint::ColumnMatrix2x3<f32>)),
+      WgslType::Mat3x3f if is_encase => Some(quote!(mint::ColumnMatrix3<f32>)),
+      WgslType::Mat3x4f if is_encase => Some(quote!(mint::ColumnMatrix4x3<f32>)),
+      WgslType::Mat4x2f if is_encase => Some(quote!(mint::ColumnMatrix2x4<f32>)),
+      WgslType::Mat4x3f if is_encase => Some(quote!(mint::ColumnMatrix3x4<f32>)),
+      WgslType::Mat4x4f if is_encase => Some(quote!(mint::ColumnMatrix4<f32>)),
+      _ => None,
+    }
+  }
+}
+
+/// `ultraviolet` types like `ultraviolet::Vec4` or `ultraviolet::Mat4`.
+/// `ultraviolet` types are only aligned to their scalar, so structs get explicit padding after
+/// them. The columns of `ultraviolet::Mat3` lack the padding of `mat3x3<f32>`, so it is only
+/// used with [WgslTypeSerializeStrategy::Encase].
+#[derive(Clone)]
+pub struct UltravioletWgslTypeMap;
+
+impl WgslTypeMapBuild for UltravioletWgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let is_encase = serialize_strategy.is_encase();
+    enum_map! {
+      WgslType::Vec2i => Some(quote!(ultraviolet::IVec2)),
+      WgslType::Vec3i => Some(quote!(ultraviolet::IVec3)),
+      WgslType::Vec4i => Some(quote!(ultraviolet::IVec4)),
+      WgslType::Vec2u => Some(quote!(ultraviolet::UVec2)),
+      WgslType::Vec3u => Some(quote!(ultraviolet::UVec3)),
+      WgslType::Vec4u => Some(quote!(ultraviolet::UVec4)),
+      WgslType::Vec2f => Some(quote!(ultraviolet::Vec2)),
+      WgslType::Vec3f => Some(quote!(ultraviolet::Vec3)),
+      WgslType::Vec4f => Some(quote!(ultraviolet::Vec4)),
+      WgslType::Mat2x2f => Some(quote!(ultraviolet::Mat2)),
+      WgslType::Mat3x3f if is_encase => Some(quote!(ultraviolet::Mat3)),
+      WgslType::Mat4x4f => Some(quote!(ultraviolet::Mat4)),
+      _ => None,
+    }
+  }
+
+  fn packed_types(&self) -> Vec<WgslType> {
+    WgslType::iter()
+      .filter(|ty| self.build(WgslTypeSerializeStrategy::Encase)[*ty].is_some())
+      .collect()
+  }
+}
+
+/// `vek` types like `vek::Vec4<f32>` or `vek::Mat4<f32>`.
+/// The default `repr_c` `vek` types are only aligned to their scalar, so they are only
+/// used with [WgslTypeSerializeStrategy::Encase]. Other types fall back to [WgslRustTypeMap].
+#[derive(Clone)]
+pub struct VekWgslTypeMap;
+
+impl WgslTypeMapBuild for VekWgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let is_encase = serialize_strategy.is_encase();
+    enum_map! {
+      WgslType::Vec2i if is_encase => Some(quote!(vek::Vec2<i32>)),
+      WgslType::Vec3i if is_encase => Some(quote!(vek::Vec3<i32>)),
+      WgslType::Vec4i if is_encase => Some(quote!(vek::Vec4<i32>)),
+      WgslType::Vec2u if is_encase => Some(quote!(vek::Vec2<u32>)),
+      WgslType::Vec3u if is_encase => Some(quote!(vek::Vec3<u32>)),
+      WgslType::Vec4u if is_encase => Some(quote!(vek::Vec4<u32>)),
+      WgslType::Vec2f if is_encase => Some(quote!(vek::Vec2<f32>)),
+      WgslType::Vec3f if is_encase => Some(quote!(vek::Vec3<f32>)),
+      WgslType::Vec4f if is_encase => Some(quote!(vek::Vec4<f32>)),
+      WgslType::Mat2x2f if is_encase => Some(quote!(vek::Mat2<f32>)),
+      WgslType::Mat3x3f if is_encase => Some(quote!(vek::Mat3<f32>)),
+      WgslType::Mat4x4f if is_encase => Some(quote!(vek::Mat4<f32>)),
       _ => None,
     }
   }
 }
 
 /// `nalgebra` types like `nalgebra::SVector<f64, 4>` or `nalgebra::SMatrix<f32, 2, 3>`.
+/// `nalgebra` types are only aligned to their scalar, so structs get explicit padding after
+/// them. The columns of `nalgebra::SMatrix<f32, 3, C>` lack the padding of `matCx3<f32>`, so
+/// they are only used with [WgslTypeSerializeStrategy::Encase].
 #[derive(Clone)]
 pub struct NalgebraWgslTypeMap;
 
 impl WgslTypeMapBuild for NalgebraWgslTypeMap {
-  fn build(&self, _: WgslTypeSerializeStrategy) -> WgslTypeMap {
+  fn build(&self, serialize_strategy: WgslTypeSerializeStrategy) -> WgslTypeMap {
+    let is_encase = serialize_strategy.is_encase();
     enum_map! {
       WgslType::Vec2i => Some(quote!(nalgebra::SVector<i32, 2>)),
       WgslType::Vec3i => Some(quote!(nalgebra::SVector<i32, 3>)),
@@ -218,15 +500,29 @@ impl WgslTypeMapBuild for NalgebraWgslTypeMap {
       WgslType::Vec3f => Some(quote!(nalgebra::SVector<f32, 3>)),
       WgslType::Vec4f => Some(quote!(nalgebra::SVector<f32, 4>)),
       WgslType::Mat2x2f => Some(quote!(nalgebra::SMatrix<f32, 2, 2>)),
-      WgslType::Mat2x3f => Some(quote!(nalgebra::SMatrix<f32, 3, 2>)),
+      WgslType::Mat2x3f if is_encase => Some(quote!(nalgebra::SMatrix<f32, 3, 2>)),
       WgslType::Mat2x4f => Some(quote!(nalgebra::SMatrix<f32, 4, 2>)),
       WgslType::Mat3x2f => Some(quote!(nalgebra::SMatrix<f32, 2, 3>)),
-      WgslType::Mat3x3f => Some(quote!(nalgebra::SMatrix<f32, 3, 3>)),
+      WgslType::Mat3x3f if is_encase => Some(quote!(nalgebra::SMatrix<f32, 3, 3>)),
       WgslType::Mat3x4f => Some(quote!(nalgebra::SMatrix<f32, 4, 3>)),
       WgslType::Mat4x2f => Some(quote!(nalgebra::SMatrix<f32, 2, 4>)),
-      WgslType::Mat4x3f => Some(quote!(nalgebra::SMatrix<f32, 3, 4>)),
+      WgslType::Mat4x3f if is_encase => Some(quote!(nalgebra::SMatrix<f32, 3, 4>)),
       WgslType::Mat4x4f => Some(quote!(nalgebra::SMatrix<f32, 4, 4>)),
       _ => None,
     }
   }
+
+  fn packed_types(&self) -> Vec<WgslType> {
+    WgslType::iter()
+      .filter(|ty| self.build(WgslTypeSerializeStrategy::Encase)[*ty].is_some())
+      .collect()
+  }
+
+  fn unpadded_matrix_types(&self) -> Vec<(WgslType, TokenStream)> {
+    vec![
+      (WgslType::Mat2x3f, quote!(nalgebra::SMatrix<f32, 3, 2>)),
+      (WgslType::Mat3x3f, quote!(nalgebra::SMatrix<f32, 3, 3>)),
+      (WgslType::Mat4x3f, quote!(nalgebra::SMatrix<f32, 3, 4>)),
+    ]
+  }
 }
@@ -1,7 +1,11 @@
+use indoc::indoc;
 use miette::{IntoDiagnostic, Result};
 use pretty_assertions::assert_eq;
+use quote::quote;
 use wgsl_bindgen::{
-  WgslBindgenOptionBuilder, GlamWgslTypeMap, WgslTypeSerializeStrategy,
+  GlamWgslTypeMap, NalgebraWgslTypeMap, ShaderDefValue, WgpuVersion, WgslBindgenError,
+  WgslBindgenOption, WgslBindgenOptionBuilder, WgslShaderBackend,
+  WgslShaderSourceOutputType, WgslType, WgslTypeSerializeStrategy,
 };
 
 #[test]
@@ -42,6 +46,1410 @@ fn test_main_bindgen() -> Result<()> {
   Ok(())
 }
 
+#[test]
+fn test_shader_defs_bindgen() -> Result<()> {
+  let generate = |shader_defs: &[(&str, ShaderDefValue)]| -> Result<String> {
+    let mut builder = WgslBindgenOptionBuilder::default();
+    for (name, value) in shader_defs {
+      builder.add_shader_def(*name, value.clone());
+    }
+
+    builder
+      .add_entry_point("tests/shaders/basic/shader_defs.wgsl")
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .skip_header_comments(true)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  assert!(generate(&[("SHADOWS", ShaderDefValue::Bool(true))])?.contains("shadow_map"));
+  assert!(!generate(&[])?.contains("shadow_map"));
+  Ok(())
+}
+
+#[test]
+fn test_shader_def_permutations_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/shader_defs.wgsl")
+    .add_shader_def_permutation("shadows", [("SHADOWS", ShaderDefValue::Bool(true))])
+    .add_shader_def_permutation::<&str>("no_shadows", [])
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  let (no_shadows, shadows) = actual.split_once("pub mod shadows").unwrap();
+  assert!(no_shadows.contains("pub mod no_shadows"));
+  assert!(!no_shadows.contains("shadow_map"));
+  assert!(shadows.contains("shadow_map"));
+  Ok(())
+}
+
+#[test]
+fn test_virtual_module_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/virtual_import.wgsl")
+    .add_virtual_module(
+      "my_lib::lighting",
+      "struct PointLight {\n  color: vec4<f32>,\n}\n",
+    )
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub mod my_lib"));
+  assert!(actual.contains("pub light: wgpu::BufferBinding<'a>"));
+  Ok(())
+}
+
+#[test]
+fn test_source_str_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "generated::blur",
+      "@group(0) @binding(0) var<uniform> radius: f32;\n\n@compute @workgroup_size(1)\nfn main() {}\n",
+    )
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub mod generated"));
+  assert!(actual.contains("pub mod blur"));
+  assert!(actual.contains("pub radius: wgpu::BufferBinding<'a>"));
+  Ok(())
+}
+
+#[test]
+fn test_split_output_files_bindgen() -> Result<()> {
+  let files = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_files()
+    .into_diagnostic()?;
+
+  let paths = files
+    .iter()
+    .map(|(path, _)| path.to_str().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(paths, vec!["mod.rs", "_root.rs", "main.rs"]);
+  assert!(files[0].1.contains("pub mod main;"));
+  assert!(files[2].1.contains("pub fn create_shader_module("));
+  Ok(())
+}
+
+#[test]
+fn test_split_output_source_hash_bindgen() -> Result<()> {
+  let files = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .build()?
+    .generate_files()
+    .into_diagnostic()?;
+
+  // Only the `mod.rs` changes with every source, the other files with their modules.
+  assert!(files[0].1.contains("// SourceHash: "));
+  assert!(files[2]
+    .1
+    .starts_with("// File automatically generated by wgsl_bindgen"));
+  assert!(!files[2].1.contains("// SourceHash: "));
+  Ok(())
+}
+
+#[test]
+fn test_module_tree_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/virtual_import.wgsl")
+    .add_virtual_module(
+      "my_lib::lighting",
+      "struct PointLight {\n  color: vec4<f32>,\n}\n",
+    )
+    .entry_modules_root_dir("tests/shaders")
+    .root_module("shaders")
+    .shared_module("shared")
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.starts_with("pub mod shaders {"));
+  assert!(actual.contains("pub use super::shared::*;"));
+  assert!(actual.contains("pub mod shared {"));
+  assert!(actual.contains("pub mod lighting {"));
+  assert!(actual.contains("pub mod basic {"));
+  assert!(actual.contains("pub mod virtual_import {"));
+  Ok(())
+}
+
+#[test]
+fn test_split_entry_modules_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      "struct Params {\n  radius: f32,\n}\n\n@group(0) @binding(0) var<uniform> params: Params;\n\n@compute @workgroup_size(1)\nfn main() {}\n",
+    )
+    .split_entry_modules(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  let (types, pipeline) = actual.split_once("pub mod pipeline {").unwrap();
+  let types = types.split_once("pub mod types {").unwrap().1;
+  assert!(types.contains("pub struct Params {"));
+  assert!(pipeline.contains("use super::{types::*, *};"));
+  assert!(pipeline.contains("pub fn create_shader_module("));
+  Ok(())
+}
+
+#[test]
+fn test_deterministic_output_bindgen() -> Result<()> {
+  let generate = |entry_points: &[&str]| {
+    let mut builder = WgslBindgenOptionBuilder::default();
+    for entry_point in entry_points {
+      builder.add_entry_point(*entry_point);
+    }
+    builder
+      .additional_scan_dir((None, "tests/shaders/additional"))
+      .additional_scan_dir((None, "tests/shaders/glob"))
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  let main = "tests/shaders/basic/main.wgsl";
+  let effect = "tests/shaders/glob/nested/effect.wgsl";
+  let actual = generate(&[main, effect])?;
+  assert_eq!(actual, generate(&[main, effect])?);
+  assert_eq!(actual, generate(&[effect, main])?);
+  Ok(())
+}
+
+#[test]
+fn test_header_comment_bindgen() -> Result<()> {
+  let bindgen = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/glob/main.wgsl")
+    .header_comment("Generated from the shaders of my_crate.\n\nDo not edit.")
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .build()?;
+  let actual = bindgen.generate_string().into_diagnostic()?;
+
+  let expected = format!(
+    "// Generated from the shaders of my_crate.\n//\n// Do not edit.\n// wgsl_bindgen version {}\n// SourceHash: {}\n\n",
+    env!("CARGO_PKG_VERSION"),
+    bindgen.source_hash()
+  );
+  assert!(actual.starts_with(&expected));
+  Ok(())
+}
+
+#[test]
+fn test_is_up_to_date_bindgen() -> Result<()> {
+  let output_file = std::env::temp_dir().join("wgsl_bindgen_is_up_to_date.rs");
+  let _ = std::fs::remove_file(&output_file);
+  let bindgen = |skip_header_provenance| {
+    WgslBindgenOptionBuilder::default()
+      .add_entry_point("tests/shaders/glob/main.wgsl")
+      .skip_header_provenance(skip_header_provenance)
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .output_file(&output_file)
+      .build()
+  };
+
+  let bindgen_with_provenance = bindgen(false)?;
+  assert!(!bindgen_with_provenance.is_up_to_date());
+  bindgen_with_provenance.generate().into_diagnostic()?;
+  assert!(bindgen_with_provenance.is_up_to_date());
+
+  let bindgen_without_provenance = bindgen(true)?;
+  bindgen_without_provenance.generate().into_diagnostic()?;
+  assert!(!bindgen_without_provenance.is_up_to_date());
+  assert!(!std::fs::read_to_string(&output_file)
+    .into_diagnostic()?
+    .contains("SourceHash"));
+  Ok(())
+}
+
+#[test]
+fn test_continue_on_error_bindgen() -> Result<()> {
+  let output_file = std::env::temp_dir().join("wgsl_bindgen_continue_on_error.rs");
+  let _ = std::fs::remove_file(&output_file);
+  let bindgen = WgslBindgenOptionBuilder::default()
+    .add_source_str("valid", "@compute @workgroup_size(1) fn main() {}")
+    .add_source_str("missing_type", "@group(0) @binding(0) var<uniform> a: Missing;")
+    .add_source_str("syntax_error", "@compute @workgroup_size(1) fn main() {")
+    .continue_on_error(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .output_file(&output_file)
+    .build()?;
+
+  let Err(WgslBindgenError::EntryPointErrors { errors }) = bindgen.generate() else {
+    panic!("expected the errors of the entry points");
+  };
+  assert_eq!(errors.len(), 2);
+
+  // The bindings of the valid shader are written, and generated again until fixed.
+  let content = std::fs::read_to_string(&output_file).into_diagnostic()?;
+  assert!(content.contains("pub mod valid {"));
+  assert!(!content.contains("pub mod syntax_error {"));
+  assert!(!content.contains("SourceHash"));
+  assert!(!bindgen.is_up_to_date());
+  Ok(())
+}
+
+#[test]
+fn test_config_file_bindgen() -> Result<()> {
+  let actual = WgslBindgenOption::from_config_file("tests/config/wgsl_bindgen.toml")
+    .into_diagnostic()?
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  let expected = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .add_entry_point("tests/shaders/basic/light.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .override_wgsl_type(WgslType::Vec3f, quote!([f32; 3]))
+    .override_wgsl_type_aligned(WgslType::Vec4f, quote!(glam::Vec4))
+    .rename_struct_member("Light", "color_rgb", "color")
+    .derive_serde(true)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub color: [f32; 3],"));
+  assert_eq!(actual, expected);
+  Ok(())
+}
+
+#[test]
+fn test_files_to_watch_bindgen() -> Result<()> {
+  let bindgen = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .add_entry_points_glob("tests/shaders/glob/**/*.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .build()?;
+
+  let files_to_watch = bindgen
+    .files_to_watch()
+    .iter()
+    .map(|path| path.to_str().unwrap().replace('\\', "/"))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    files_to_watch,
+    vec![
+      "tests/shaders/additional/types.wgsl",
+      "tests/shaders/basic/bindings.wgsl",
+      "tests/shaders/basic/main.wgsl",
+      "tests/shaders/glob/common.wgsl",
+      "tests/shaders/glob/main.wgsl",
+      "tests/shaders/glob/nested/effect.wgsl",
+      "tests/shaders/glob",
+    ]
+  );
+  Ok(())
+}
+
+#[test]
+fn test_incremental_cache_bindgen() -> Result<()> {
+  let cache_dir = std::env::temp_dir().join("wgsl_bindgen_incremental_cache");
+  let _ = std::fs::remove_dir_all(&cache_dir);
+  let generate = |derive_serde| {
+    WgslBindgenOptionBuilder::default()
+      .add_entry_point("tests/shaders/basic/main.wgsl")
+      .additional_scan_dir((None, "tests/shaders/additional"))
+      .incremental_cache_dir(&cache_dir)
+      .derive_serde(derive_serde)
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .skip_header_comments(true)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  let expected = generate(false)?;
+  let cache_file = cache_dir.join("main.toml");
+  assert!(cache_file.is_file());
+  assert_eq!(generate(false)?, expected);
+
+  // The cached items are used until the shaders or the options change.
+  let cache = std::fs::read_to_string(&cache_file).into_diagnostic()?;
+  let cache = cache.replace("create_shader_module", "create_cached_shader_module");
+  std::fs::write(&cache_file, cache).into_diagnostic()?;
+  assert!(generate(false)?.contains("fn create_cached_shader_module("));
+  assert!(!generate(true)?.contains("fn create_cached_shader_module("));
+  Ok(())
+}
+
+#[test]
+fn test_incremental_cache_warnings_bindgen() -> Result<()> {
+  let cache_dir = std::env::temp_dir().join("wgsl_bindgen_incremental_cache_warnings");
+  let _ = std::fs::remove_dir_all(&cache_dir);
+  let generate = || {
+    WgslBindgenOptionBuilder::default()
+      .add_source_str(
+        "lighting",
+        r#"
+          struct Light { color: vec4<f32> }
+
+          @group(0) @binding(0) var<uniform> light: Light;
+
+          @vertex
+          fn vs_main() -> @builtin(position) vec4<f32> {
+            return vec4(0.0);
+          }
+        "#,
+      )
+      .incremental_cache_dir(&cache_dir)
+      .warn_unused_bindings(true)
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .skip_header_comments(true)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  // The warnings are kept with the items, to be emitted again when the items are loaded.
+  let expected = generate()?;
+  let cache =
+    std::fs::read_to_string(cache_dir.join("lighting.toml")).into_diagnostic()?;
+  assert!(cache.contains(
+    "`light` at @group(0) @binding(0) of `lighting` isn't used by any entry point"
+  ));
+  assert_eq!(generate()?, expected);
+  Ok(())
+}
+
+#[test]
+fn test_hot_reload_shader_module_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/virtual_import.wgsl")
+    .add_virtual_module(
+      "my_lib::lighting",
+      "struct PointLight {\n  color: vec4<f32>,\n}\n",
+    )
+    .generate_hot_reload_shader_module(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains(
+    r#"#[cfg(feature = "hot-reload")]
+    pub fn create_shader_module_from_path(
+        device: &wgpu::Device,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<wgpu::ShaderModule, Box<dyn std::error::Error>> {"#
+  ));
+  // The virtual modules are embedded, and the files are read from `dir`.
+  assert!(actual.contains(r#"source: "struct PointLight {\n  color: vec4<f32>,\n}\n","#));
+  assert!(actual.contains(r#"dir.join("tests/shaders/basic/virtual_import.wgsl"),"#));
+  Ok(())
+}
+
+#[test]
+fn test_runtime_files_shader_source_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .shader_source_output_type(WgslShaderSourceOutputType::RuntimeFiles)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(
+    actual.contains(r#"pub const SHADER_PATH: &str = "tests/shaders/basic/main.wgsl";"#)
+  );
+  assert!(actual.contains(
+    r#"pub fn create_shader_module(
+        device: &wgpu::Device,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<wgpu::ShaderModule, Box<dyn std::error::Error>> {"#
+  ));
+  assert!(actual.contains(r#"dir.join("tests/shaders/basic/main.wgsl"),"#));
+  assert!(actual.contains(
+    r#"pub fn create_main_pipeline(
+            device: &wgpu::Device,
+            module: &wgpu::ShaderModule,
+        ) -> wgpu::ComputePipeline {"#
+  ));
+  // The sources are only read at runtime.
+  assert!(!actual.contains("include_str!"));
+  assert!(!actual.contains("pub const SHADER_STRING"));
+  Ok(())
+}
+
+#[test]
+fn test_spirv_shader_module_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .generate_spirv_shader_module(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  // The words start with the SPIR-V magic number.
+  assert!(actual.contains("pub const SHADER_SPIRV: &[u32] = &[\n        0x07230203,"));
+  assert!(actual.contains(
+    "pub fn create_shader_module_spirv(device: &wgpu::Device) -> wgpu::ShaderModule {"
+  ));
+  assert!(actual.contains("pub unsafe fn create_shader_module_spirv_passthrough("));
+  Ok(())
+}
+
+#[test]
+fn test_backend_outputs_bindgen() -> Result<()> {
+  let files = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/basic/main.wgsl")
+    .additional_scan_dir((None, "tests/shaders/additional"))
+    .add_backend_output(WgslShaderBackend::Msl)
+    .add_backend_output(WgslShaderBackend::Hlsl)
+    .add_backend_output(WgslShaderBackend::Glsl)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .build()?
+    .generate_backend_sources()
+    .into_diagnostic()?;
+
+  let paths: Vec<_> = files
+    .iter()
+    .map(|(path, _)| path.to_str().unwrap())
+    .collect();
+  assert_eq!(paths, ["main.metal", "main.hlsl", "main.main.comp"]);
+  let source = |path| {
+    &files
+      .iter()
+      .find(|(p, _)| p.to_str() == Some(path))
+      .unwrap()
+      .1
+  };
+  assert!(source("main.metal").contains("#include <metal_stdlib>"));
+  assert!(source("main.hlsl").contains("[numthreads(1, 1, 1)]"));
+  assert!(source("main.main.comp").starts_with("#version 310 es"));
+  Ok(())
+}
+
+#[test]
+fn test_glsl_entry_points_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_points_glob("tests/shaders/glsl/*")
+    .add_shader_def("GRAYSCALE", ShaderDefValue::Bool(true))
+    .shader_source_output_type(WgslShaderSourceOutputType::Composer)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  // The stages are in separate modules, named after their files.
+  assert!(actual.contains("pub mod sum_comp {"));
+  assert!(actual.contains("pub mod triangle_frag {"));
+  assert!(actual.contains("pub mod triangle_vert {"));
+  assert!(actual.contains("pub const MAIN_WORKGROUP_SIZE: [u32; 3] = [64, 1, 1];"));
+  // Blocks without an instance name are bound by the name of the block.
+  assert!(actual.contains("pub camera: wgpu::BufferBinding<'a>,"));
+  assert!(actual.contains("pub view_proj: glam::Mat4,"));
+  // The translated WGSL is embedded, with the shader defs as defines.
+  assert!(actual.contains("value = _e6.x;"));
+  assert!(!actual.contains("naga_oil::compose::Composer"));
+  Ok(())
+}
+
+#[test]
+fn test_spirv_entry_point_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_point("tests/shaders/spirv/particles.spv")
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub mod particles {"));
+  assert!(actual.contains("pub struct Particle {"));
+  assert!(actual.contains("pub struct Params {"));
+  assert!(actual.contains("pub const MAIN_WORKGROUP_SIZE: [u32; 3] = [64, 1, 1];"));
+  assert!(actual.contains("pub params: wgpu::BufferBinding<'a>,"));
+  assert!(actual.contains("pub particles: wgpu::BufferBinding<'a>,"));
+  // The block wrapping the uniform struct has no name in the binary.
+  assert!(actual.contains("pub struct ParamsBlock {"));
+  assert!(actual.contains("pub member_0: Params,"));
+  // The translated WGSL is embedded.
+  assert!(actual.contains("const SHADER_STRING: &'static str"));
+  Ok(())
+}
+
+#[test]
+fn test_reflection_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      "struct Params {\n  radius: f32,\n}\n\n@group(0) @binding(0) var<uniform> params: Params;\n@group(0) @binding(1) var<storage, read> weights: array<f32>;\n@group(1) @binding(0) var input: texture_2d<f32>;\n@group(1) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;\n@group(1) @binding(2) var input_sampler: sampler;\n\n@compute @workgroup_size(1)\nfn main() {}\n",
+    )
+    .generate_reflection(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub struct BindingReflection {"));
+  assert!(actual.contains(
+    r#"            super::_root::BindingReflection {
+                name: "params",
+                group: 0,
+                binding: 0,
+                kind: super::_root::BindingKind::UniformBuffer,
+                type_name: "Params",
+                size: Some(4),
+                count: None,
+                visibility: wgpu::ShaderStages::COMPUTE,
+            },"#
+  ));
+  assert!(actual.contains(
+    r#"                kind: super::_root::BindingKind::StorageBuffer {
+                    read_only: true,
+                },
+                type_name: "array<f32>","#
+  ));
+  assert!(actual.contains(
+    r#"                kind: super::_root::BindingKind::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                },"#
+  ));
+  assert!(actual.contains(
+    r#"                kind: super::_root::BindingKind::Sampler {
+                    comparison: false,
+                },
+                type_name: "sampler",
+                size: None,"#
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_bevy_glue_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "material",
+      indoc! {r#"
+        struct VertexInput {
+          @location(0) position: vec3<f32>,
+          @location(1) uv: vec2<f32>,
+        };
+
+        @group(0) @binding(0) var color_texture: texture_2d<f32>;
+        @group(0) @binding(1) var color_sampler: sampler;
+
+        @vertex
+        fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
+          return vec4(in.position, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+          return textureSample(color_texture, color_sampler, vec2(0.0));
+        }
+      "#},
+    )
+    .generate_bevy_glue(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("#[cfg(feature = \"bevy\")]\n    pub mod bevy {"));
+  assert!(actual.contains(
+    r#"        ) -> ::bevy::render::render_resource::BindGroupLayout {
+            render_device
+                .create_bind_group_layout(
+                    "material::BindGroup0",
+                    super::bind_groups::LAYOUT_DESCRIPTOR0.entries,
+                )
+        }"#
+  ));
+  assert!(actual.contains("vec![create_bind_group_layout0(render_device)]"));
+  assert!(actual.contains(
+    r#"            ::bevy::render::render_resource::VertexBufferLayout {
+                array_stride: super::VertexInput::VERTEX_BUFFER_LAYOUT.array_stride,
+                step_mode: super::VertexInput::STEP_MODE,
+                attributes: super::VertexInput::VERTEX_ATTRIBUTES.to_vec(),
+            }"#
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_wgpu_crate_path_and_version_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "sum",
+      indoc! {r#"
+        @group(0) @binding(0) var<storage, read_write> values: array<f32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+          values[id.x] += 1.0;
+        }
+      "#},
+    )
+    .wgpu_crate_path("::bevy::render::render_resource")
+    .wgpu_version(WgpuVersion::V23)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(!actual.contains("wgpu::"));
+  assert!(actual.contains(
+    r#"        pub fn create_main_pipeline(
+            device: &::bevy::render::render_resource::Device,
+            compilation_options: ::bevy::render::render_resource::PipelineCompilationOptions<
+                '_,
+            >,
+        ) -> ::bevy::render::render_resource::ComputePipeline {
+            let module = super::create_shader_module(device);
+            let layout = super::create_pipeline_layout(device);
+            device
+                .create_compute_pipeline(
+                    &::bevy::render::render_resource::ComputePipelineDescriptor {
+                        label: Some("Compute Pipeline main"),
+                        layout: Some(&layout),
+                        module: &module,
+                        entry_point: Some("main"),
+                        compilation_options,
+                        cache: None,
+                    },
+                )
+        }"#
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_no_std_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "particles",
+      indoc! {r#"
+        struct VertexInput {
+          @location(0) position: vec2<f32>,
+        };
+
+        struct Particles {
+          count: u32,
+          positions: array<vec2<f32>>,
+        };
+
+        @group(0) @binding(0) var<storage, read> particles: Particles;
+
+        @vertex
+        fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
+          return vec4(in.position + particles.positions[0], 0.0, 1.0);
+        }
+      "#},
+    )
+    .no_std(true)
+    .split_entry_modules(true)
+    .generate_runtime_array_helpers(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  let types_start = actual.find("pub mod types {").unwrap();
+  let types_end = actual.find("pub mod pipeline {").unwrap();
+  let types = &actual[types_start..types_end];
+  assert!(types.contains("core::mem::size_of"));
+  assert!(!types.contains("std::"));
+  assert!(!types.contains("wgpu::"));
+  assert!(!types.contains("Vec"));
+  assert!(actual[types_end..].contains("impl VertexInput {"));
+  Ok(())
+}
+
+#[test]
+fn test_crate_paths_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "light",
+      indoc! {r#"
+        struct Light {
+          transform: mat4x4<f32>,
+          intensity: f32,
+        };
+
+        @group(0) @binding(0) var<uniform> light: Light;
+
+        @compute @workgroup_size(1)
+        fn main() {}
+      "#},
+    )
+    .crate_path("glam", "::bevy::math")
+    .crate_path("bytemuck", "crate::reexports::bytemuck")
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(!actual.contains(" glam::"));
+  assert!(!actual.contains(" bytemuck::"));
+  assert!(actual.contains("pub transform: ::bevy::math::Mat4,"));
+  assert!(actual.contains("unsafe impl crate::reexports::bytemuck::Pod for Light {}"));
+  Ok(())
+}
+
+#[test]
+fn test_typed_buffers_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "light",
+      indoc! {r#"
+        struct Light {
+          transform: mat4x4<f32>,
+          intensity: f32,
+        };
+
+        @group(0) @binding(0) var<uniform> light: Light;
+
+        @compute @workgroup_size(1)
+        fn main() {}
+      "#},
+    )
+    .generate_typed_buffers(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub mod buffers {"));
+  assert!(actual.contains("pub struct UniformBuffer<T> {"));
+  assert!(actual.contains("pub struct StorageBuffer<T> {"));
+  assert!(actual.contains("impl<T: bytemuck::Pod> UniformBuffer<T> {"));
+  assert!(actual.contains("pub const SIZE: u64 = std::mem::size_of::<T>() as u64;"));
+  assert!(actual.contains("usage: wgpu::BufferUsages::UNIFORM"));
+  assert!(
+    actual.contains("queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(value));")
+  );
+  Ok(())
+}
+
+#[test]
+fn test_readback_helpers_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "sum",
+      indoc! {r#"
+        struct Stats {
+          total: f32,
+          count: u32,
+        };
+
+        @group(0) @binding(0) var<storage, read> input: array<f32>;
+        @group(0) @binding(1) var<storage, read_write> values: array<f32>;
+        @group(0) @binding(2) var<storage, read_write> stats: Stats;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          values[0] = input[0];
+          stats.total = values[0];
+        }
+      "#},
+    )
+    .generate_readback_helpers(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  assert!(actual.contains("pub async fn read_buffer("));
+  assert!(!actual.contains("fn read_input("));
+  assert!(actual.contains("pub async fn read_values("));
+  assert!(actual.contains(") -> Result<Vec<f32>, _root::ReadbackError> {"));
+  assert!(actual.contains("pub async fn read_stats("));
+  assert!(actual.contains(") -> Result<Stats, _root::ReadbackError> {"));
+  assert!(
+    actual.contains("let bytes = _root::read_buffer(device, queue, buffer).await?;")
+  );
+  assert!(actual.contains("let required = std::mem::size_of::<Stats>();"));
+  assert!(actual.contains("_root::ReadbackError::BufferTooSmall {"));
+  Ok(())
+}
+
+#[test]
+fn test_readback_helpers_typed_storage_buffer_bindgen() -> Result<()> {
+  let source = indoc! {r#"
+    @group(0) @binding(0) var<storage, read_write> values: array<f32, 4>;
+
+    @compute @workgroup_size(1)
+    fn main() {
+      values[0] = 1.0;
+    }
+  "#};
+  let generate = |readback_helpers| -> Result<String> {
+    WgslBindgenOptionBuilder::default()
+      .add_source_str("values", source)
+      .generate_typed_buffers(true)
+      .generate_readback_helpers(readback_helpers)
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .skip_header_comments(true)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  let copy_src = "wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC";
+  assert!(generate(true)?.contains(copy_src));
+  assert!(!generate(false)?.contains(copy_src));
+  Ok(())
+}
+
+#[test]
+fn test_uniform_layout_error_bindgen() -> Result<()> {
+  let result = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      indoc! {r#"
+        struct Params {
+          weights: array<f32, 4>,
+          radius: f32,
+        };
+
+        @group(0) @binding(0) var<uniform> params: Params;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let weight = params.weights[0];
+        }
+      "#},
+    )
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string();
+
+  assert_eq!(
+    result.unwrap_err().to_string(),
+    "member `weights` of struct `Params` in a uniform buffer has an array stride of 4 bytes, \
+     which must be a multiple of 16"
+  );
+  Ok(())
+}
+
+#[test]
+fn test_unify_duplicate_structs_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "sprite",
+      indoc! {r#"
+        struct Camera {
+          view_proj: mat4x4<f32>,
+        };
+
+        struct Params {
+          camera: Camera,
+          scale: f32,
+        };
+
+        @group(0) @binding(0) var<uniform> camera: Camera;
+        @group(0) @binding(1) var<uniform> params: Params;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let view_proj = camera.view_proj * params.scale;
+        }
+      "#},
+    )
+    .add_source_str(
+      "terrain",
+      indoc! {r#"
+        struct Camera {
+          view_proj: mat4x4<f32>,
+        };
+
+        struct Params {
+          camera: Camera,
+          height: f32,
+        };
+
+        @group(0) @binding(0) var<uniform> camera: Camera;
+        @group(0) @binding(1) var<uniform> params: Params;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let view_proj = camera.view_proj * params.height;
+        }
+      "#},
+    )
+    .unify_duplicate_structs(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()?;
+
+  assert!(actual.contains("pub mod shared {"));
+  assert_eq!(actual.matches("pub struct Camera {").count(), 1);
+  assert_eq!(actual.matches("pub use _root::shared::Camera;").count(), 2);
+  // The structs that differ stay in the entry point modules.
+  assert_eq!(actual.matches("pub struct Params {").count(), 2);
+  Ok(())
+}
+
+#[test]
+fn test_conflicting_imported_struct_bindgen() -> Result<()> {
+  let result = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "lit",
+      indoc! {r#"
+        #import my_lib::lighting::PointLight
+
+        @group(0) @binding(0) var<uniform> light: PointLight;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let color = light.color;
+        }
+      "#},
+    )
+    .add_virtual_module(
+      "my_lib::lighting",
+      indoc! {r#"
+        struct PointLight {
+          color: vec4<f32>,
+        #ifdef SHADOWS
+          shadow_bias: vec4<f32>,
+        #endif
+        }
+      "#},
+    )
+    .add_shader_def_permutation("shadows", [("SHADOWS", ShaderDefValue::Bool(true))])
+    .add_shader_def_permutation::<&str>("no_shadows", [])
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string();
+
+  let error = result.unwrap_err().to_string();
+  assert!(error.starts_with(
+    "`PointLight` in `my_lib::lighting` is generated differently for `lit::no_shadows` \
+     and `lit::shadows`:"
+  ));
+  assert!(error.contains("  pub struct PointLight {"));
+  assert!(error.contains("+     pub shadow_bias: glam::Vec4,"));
+  Ok(())
+}
+
+#[test]
+fn test_remap_bind_groups_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      indoc! {r#"
+        @group(0) @binding(0) var<storage, read_write> pixels: array<f32>;
+        @group(1) @binding(0) var<uniform> radius: f32;
+        @group(1) @binding(1) var<uniform> strength: f32;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          pixels[0] = radius * strength;
+        }
+      "#},
+    )
+    .remap_group(0, 1)
+    .remap_group(1, 2)
+    .remap_binding((1, 1), (2, 4))
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()?;
+
+  assert!(!actual.contains("BindGroup0"));
+  assert!(actual.contains("pub struct BindGroup1"));
+  assert!(actual.contains("pub struct BindGroup2"));
+  assert!(actual.contains("bind_group_layout0: &wgpu::BindGroupLayout,"));
+  assert!(actual
+    .contains("let layout = super::create_pipeline_layout(device, bind_group_layout0);"));
+  // The embedded shader source is remapped too.
+  assert!(actual.contains("@group(1) @binding(0)"));
+  assert!(actual.contains("@group(2) @binding(0)"));
+  assert!(actual.contains("@group(2) @binding(4)"));
+  Ok(())
+}
+
+#[test]
+fn test_remap_bind_groups_collision_bindgen() -> Result<()> {
+  let result = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      indoc! {r#"
+        @group(0) @binding(0) var<storage, read_write> pixels: array<f32>;
+        @group(1) @binding(0) var<uniform> radius: f32;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          pixels[0] = radius;
+        }
+      "#},
+    )
+    .remap_group(0, 1)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string();
+
+  assert_eq!(
+    result.unwrap_err().to_string(),
+    "`pixels` and `radius` are both remapped to @group(1) @binding(0)"
+  );
+  Ok(())
+}
+
+#[test]
+fn test_share_bind_groups_bindgen() -> Result<()> {
+  let shader = |name: &str| {
+    format!(
+      r#"
+        struct Camera {{
+          position: vec4<f32>,
+        }};
+
+        @group(0) @binding(0) var<uniform> camera: Camera;
+        @group(1) @binding(0) var<uniform> {name}: f32;
+
+        @compute @workgroup_size(1)
+        fn main() {{
+          let position = camera.position * {name};
+        }}
+      "#
+    )
+  };
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str("sprite", shader("sprite"))
+    .add_source_str("terrain", shader("terrain"))
+    .add_source_str(
+      "blur",
+      indoc! {r#"
+        @group(0) @binding(0) var<uniform> radius: f32;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let weight = radius;
+        }
+      "#},
+    )
+    .share_bind_groups(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()?;
+
+  let (shared, entries) = actual.split_once("pub mod blur").unwrap();
+  assert!(shared.contains("pub camera: wgpu::BufferBinding<'a>"));
+  assert_eq!(
+    entries
+      .matches("pub use _root::shared::bind_groups::{")
+      .count(),
+    2
+  );
+  // The bind groups that differ stay in the entry point modules.
+  assert_eq!(entries.matches("pub struct BindGroup0(").count(), 1);
+  assert_eq!(entries.matches("pub struct BindGroup1(").count(), 2);
+  Ok(())
+}
+
+#[test]
+fn test_shader_entry_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "blur",
+      indoc! {r#"
+        @group(0) @binding(0) var<uniform> radius: f32;
+
+        @compute @workgroup_size(1)
+        fn horizontal() {
+          let weight = radius;
+        }
+
+        @compute @workgroup_size(1)
+        fn vertical() {
+          let weight = radius;
+        }
+      "#},
+    )
+    .generate_shader_entry(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()?;
+
+  assert!(actual.contains("pub mod shader_entry {"));
+  assert!(actual.contains("pub trait ShaderEntry {"));
+  assert!(actual.contains("impl _root::shader_entry::ShaderEntry for Shader {"));
+  assert!(actual.contains("&[ENTRY_HORIZONTAL, ENTRY_VERTICAL]"));
+  assert!(actual.contains("vec![bind_groups::BindGroup0::get_bind_group_layout(device)]"));
+  Ok(())
+}
+
+#[test]
+fn test_nalgebra_padded_matrix_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "normals",
+      indoc! {r#"
+        struct Transforms {
+          normal: mat3x3<f32>,
+        };
+
+        @group(0) @binding(0) var<uniform> transforms: Transforms;
+
+        @compute @workgroup_size(1)
+        fn main() {
+          let normal = transforms.normal;
+        }
+      "#},
+    )
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(NalgebraWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()?;
+
+  assert!(actual.contains("pub normal: _root::PaddedMat3,"));
+  assert!(actual.contains("pub struct PaddedMatCx3<const C: usize>(pub [[f32; 4]; C]);"));
+  assert!(actual.contains("impl From<nalgebra::SMatrix<f32, 3, 3>> for PaddedMat3 {"));
+  assert!(actual.contains("impl From<PaddedMat3> for nalgebra::SMatrix<f32, 3, 3> {"));
+  assert!(!actual.contains("align_of::<nalgebra::SMatrix<f32, 3, 3>>()"));
+  Ok(())
+}
+
+#[test]
+fn test_markdown_docs_bindgen() -> Result<()> {
+  let files = WgslBindgenOptionBuilder::default()
+    .add_source_str(
+      "sprite",
+      r#"
+        // The camera of the scene.
+        struct Camera {
+          view_proj: mat4x4<f32>,
+          // The position of the camera in world space.
+          position: vec3<f32>,
+          exposure: f32,
+        }
+
+        struct VertexInput {
+          @location(0) position: vec3<f32>,
+          @location(1) uv: vec2<f32>,
+        }
+
+        @group(0) @binding(0) var<uniform> camera: Camera;
+        @group(1) @binding(0) var sprite_texture: texture_2d<f32>;
+        @group(1) @binding(1) var sprite_sampler: sampler;
+
+        @vertex
+        fn vs_main(input: VertexInput) -> @builtin(position) vec4<f32> {
+          return camera.view_proj * vec4(input.position, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+          return textureSample(sprite_texture, sprite_sampler, vec2(0.5)) * camera.exposure;
+        }
+      "#,
+    )
+    .generate_markdown_docs(true)
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .build()?
+    .generate_markdown_docs()
+    .into_diagnostic()?;
+
+  assert_eq!(files.len(), 1);
+  let (path, markdown) = &files[0];
+  assert_eq!(path.to_str(), Some("sprite.md"));
+  assert!(markdown.starts_with("# `sprite`\n"));
+  assert!(markdown.contains("| `fs_main` | fragment | 0, 1 |  |\n"));
+  assert!(markdown.contains("| 0 | `camera` | uniform buffer | `Camera` | 80 bytes |\n"));
+  assert!(markdown.contains("| 1 | `sprite_sampler` | sampler | `sampler` |  |\n"));
+  assert!(markdown.contains("| 1 | `uv` | `vec2<f32>` |\n"));
+  assert!(markdown.contains(indoc! {"
+    ### `Camera`
+
+    The camera of the scene.
+
+    Size: 80 bytes, alignment: 16 bytes.
+  "}));
+  assert!(markdown.contains(
+    "| 64 | `position` | `vec3<f32>` | 12 bytes | The position of the camera in world space. |\n"
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_skip_unused_structs_bindgen() -> Result<()> {
+  let bindgen = |skip_unused_structs| {
+    WgslBindgenOptionBuilder::default()
+      .add_source_str(
+        "lighting",
+        r#"
+          struct Camera { view_proj: mat4x4<f32> }
+          struct Light { color: vec4<f32> }
+          struct Scratch { value: vec4<f32> }
+
+          @group(0) @binding(0) var<uniform> camera: Camera;
+          @group(0) @binding(1) var<uniform> light: Light;
+          var<private> scratch: Scratch;
+
+          @vertex
+          fn vs_main() -> @builtin(position) vec4<f32> {
+            return camera.view_proj * vec4(0.0);
+          }
+        "#,
+      )
+      .skip_unused_structs(skip_unused_structs)
+      .warn_unused_bindings(true)
+      .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+      .wgsl_type_map(GlamWgslTypeMap)
+      .emit_rerun_if_change(false)
+      .skip_header_comments(true)
+      .build()?
+      .generate_string()
+      .into_diagnostic()
+  };
+
+  let all_structs = bindgen(false)?;
+  assert!(all_structs.contains("pub struct Light {"));
+  assert!(all_structs.contains("pub struct Scratch {"));
+
+  let used_structs = bindgen(true)?;
+  assert!(used_structs.contains("pub struct Camera {"));
+  assert!(!used_structs.contains("pub struct Light {"));
+  assert!(!used_structs.contains("pub struct Scratch {"));
+  // The unused binding stays in the bind group layout.
+  assert!(used_structs.contains("pub light: wgpu::BufferBinding<'a>,"));
+  Ok(())
+}
+
+#[test]
+fn test_entry_points_glob_bindgen() -> Result<()> {
+  let actual = WgslBindgenOptionBuilder::default()
+    .add_entry_points_glob("tests/shaders/glob/**/*.wgsl")
+    .serialization_strategy(WgslTypeSerializeStrategy::Bytemuck)
+    .wgsl_type_map(GlamWgslTypeMap)
+    .emit_rerun_if_change(false)
+    .skip_header_comments(true)
+    .build()?
+    .generate_string()
+    .into_diagnostic()?;
+
+  // `common.wgsl` has no entry point, so it's only imported.
+  assert!(actual.contains("pub mod main"));
+  assert!(actual.contains("pub mod effect"));
+  assert_eq!(actual.matches("fn create_shader_module(").count(), 2);
+  Ok(())
+}
+
 #[test]
 #[ignore = "It doesn't like path symbols inside a nested type like array."]
 fn test_path_import() -> Result<()> {
@@ -57,6 +57,7 @@ fn build_bevy_deptree() -> DependencyTree {
       SourceFilePath::new("tests/shaders/bevy_pbr_wgsl/wireframe.wgsl"),
     ],
     vec![],
+    Default::default(),
   )
   .into_diagnostic()
   .expect("build_bevy_deptree error")
@@ -123,6 +124,7 @@ fn test_example_wgsl_dep_tree() {
     None,
     vec![SourceFilePath::new("../example/src/shader/testbed.wgsl")],
     vec![],
+    Default::default(),
   )
   .unwrap();
 
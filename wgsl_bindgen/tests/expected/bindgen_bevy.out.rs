@@ -54,6 +54,8 @@ pub mod bevy_pbr {
                 pub metallic: f32,
                 /// size: 4, offset: 0x28, type: `f32`
                 pub reflectance: f32,
+                /// 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+                ///
                 /// size: 4, offset: 0x2C, type: `u32`
                 pub flags: u32,
                 /// size: 4, offset: 0x30, type: `f32`
@@ -151,8 +153,7 @@ pub mod bevy_pbr {
             /// size: 64, offset: 0x140, type: `mat4x4<f32>`
             pub inverse_projection: glam::Mat4,
             /// size: 12, offset: 0x180, type: `vec3<f32>`
-            pub world_position: glam::Vec3A,
-            pub _pad_world_position: [u8; 0xC - core::mem::size_of::<glam::Vec3A>()],
+            pub world_position: glam::Vec3,
             /// size: 4, offset: 0x18C, type: `f32`
             pub width: f32,
             /// size: 4, offset: 0x190, type: `f32`
@@ -167,7 +168,7 @@ pub mod bevy_pbr {
                 inverse_view: glam::Mat4,
                 projection: glam::Mat4,
                 inverse_projection: glam::Mat4,
-                world_position: glam::Vec3A,
+                world_position: glam::Vec3,
                 width: f32,
                 height: f32,
             ) -> Self {
@@ -179,7 +180,6 @@ pub mod bevy_pbr {
                     projection,
                     inverse_projection,
                     world_position,
-                    _pad_world_position: [0; 0xC - core::mem::size_of::<glam::Vec3A>()],
                     width,
                     height,
                     _pad_height: [0; 0x10 - core::mem::size_of::<f32>()],
@@ -209,7 +209,7 @@ pub mod bevy_pbr {
             pub inverse_view: glam::Mat4,
             pub projection: glam::Mat4,
             pub inverse_projection: glam::Mat4,
-            pub world_position: glam::Vec3A,
+            pub world_position: glam::Vec3,
             pub width: f32,
             pub height: f32,
         }
@@ -223,7 +223,6 @@ pub mod bevy_pbr {
                     projection: self.projection,
                     inverse_projection: self.inverse_projection,
                     world_position: self.world_position,
-                    _pad_world_position: [0; 0xC - core::mem::size_of::<glam::Vec3A>()],
                     width: self.width,
                     height: self.height,
                     _pad_height: [0; 0x10 - core::mem::size_of::<f32>()],
@@ -243,8 +242,9 @@ pub mod bevy_pbr {
             /// size: 16, offset: 0x40, type: `vec4<f32>`
             pub color: glam::Vec4,
             /// size: 12, offset: 0x50, type: `vec3<f32>`
-            pub direction_to_light: glam::Vec3A,
-            pub _pad_direction_to_light: [u8; 0xC - core::mem::size_of::<glam::Vec3A>()],
+            pub direction_to_light: glam::Vec3,
+            /// 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+            ///
             /// size: 4, offset: 0x5C, type: `u32`
             pub flags: u32,
             /// size: 4, offset: 0x60, type: `f32`
@@ -257,7 +257,7 @@ pub mod bevy_pbr {
             pub fn new(
                 view_projection: glam::Mat4,
                 color: glam::Vec4,
-                direction_to_light: glam::Vec3A,
+                direction_to_light: glam::Vec3,
                 flags: u32,
                 shadow_depth_bias: f32,
                 shadow_normal_bias: f32,
@@ -266,8 +266,6 @@ pub mod bevy_pbr {
                     view_projection,
                     color,
                     direction_to_light,
-                    _pad_direction_to_light: [0; 0xC
-                        - core::mem::size_of::<glam::Vec3A>()],
                     flags,
                     shadow_depth_bias,
                     shadow_normal_bias,
@@ -291,7 +289,7 @@ pub mod bevy_pbr {
         pub struct DirectionalLightInit {
             pub view_projection: glam::Mat4,
             pub color: glam::Vec4,
-            pub direction_to_light: glam::Vec3A,
+            pub direction_to_light: glam::Vec3,
             pub flags: u32,
             pub shadow_depth_bias: f32,
             pub shadow_normal_bias: f32,
@@ -302,8 +300,6 @@ pub mod bevy_pbr {
                     view_projection: self.view_projection,
                     color: self.color,
                     direction_to_light: self.direction_to_light,
-                    _pad_direction_to_light: [0; 0xC
-                        - core::mem::size_of::<glam::Vec3A>()],
                     flags: self.flags,
                     shadow_depth_bias: self.shadow_depth_bias,
                     shadow_normal_bias: self.shadow_normal_bias,
@@ -319,12 +315,27 @@ pub mod bevy_pbr {
         #[repr(C, align(16))]
         #[derive(Debug, PartialEq, Clone, Copy)]
         pub struct Lights {
+            /// NOTE: this array size must be kept in sync with the constants defined bevy_pbr2/src/render/light.rs
+            ///
             /// size: 112, offset: 0x0, type: `array<DirectionalLightX_naga_oil_mod_XMJSXM6K7OBRHEOR2NVSXG2C7OZUWK527OR4XAZLTX, 1>`
             pub directional_lights: [_root::bevy_pbr::mesh_view_types::DirectionalLight; 1],
             /// size: 16, offset: 0x70, type: `vec4<f32>`
             pub ambient_color: glam::Vec4,
+            /// x/y/z dimensions and n_clusters in w
+            ///
             /// size: 16, offset: 0x80, type: `vec4<u32>`
             pub cluster_dimensions: [u32; 4],
+            /// xy are vec2<f32>(cluster_dimensions.xy) / vec2<f32>(view.width, view.height)
+            ///
+            /// For perspective projections:
+            /// z is cluster_dimensions.z / log(far / near)
+            /// w is cluster_dimensions.z * log(near) / log(far / near)
+            ///
+            /// For orthographic projections:
+            /// NOTE: near and far are +ve but -z is infront of the camera
+            /// z is -near
+            /// w is cluster_dimensions.z / (-far - -near)
+            ///
             /// size: 16, offset: 0x90, type: `vec4<f32>`
             pub cluster_factors: glam::Vec4,
             /// size: 4, offset: 0xA0, type: `u32`
@@ -398,12 +409,17 @@ pub mod bevy_pbr {
         #[repr(C, align(16))]
         #[derive(Debug, PartialEq, Clone, Copy)]
         pub struct PointLight {
+            /// For point lights: the lower-right 2x2 values of the projection matrix [2][2] [2][3] [3][2] [3][3]
+            /// For spot lights: the direction (x,z), spot_scale and spot_offset
+            ///
             /// size: 16, offset: 0x0, type: `vec4<f32>`
             pub light_custom_data: glam::Vec4,
             /// size: 16, offset: 0x10, type: `vec4<f32>`
             pub color_inverse_square_range: glam::Vec4,
             /// size: 16, offset: 0x20, type: `vec4<f32>`
             pub position_radius: glam::Vec4,
+            /// 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+            ///
             /// size: 4, offset: 0x30, type: `u32`
             pub flags: u32,
             /// size: 4, offset: 0x34, type: `f32`
@@ -446,6 +462,7 @@ pub mod bevy_pbr {
             assert!(std::mem::offset_of!(PointLight, spot_light_tan_angle) == 60);
             assert!(std::mem::size_of:: < PointLight > () == 64);
         };
+        #[repr(C, align(16))]
         #[derive(Debug, PartialEq, Clone, Copy)]
         pub struct PointLights<const N: usize> {
             /// size: 64, offset: 0x0, type: `array<PointLightX_naga_oil_mod_XMJSXM6K7OBRHEOR2NVSXG2C7OZUWK527OR4XAZLTX>`
@@ -462,8 +479,11 @@ pub mod bevy_pbr {
             assert!(std::mem::offset_of!(PointLights < 1 >, data) == 0);
             assert!(std::mem::size_of:: < PointLights < 1 > > () == 64);
         };
+        #[repr(C, align(4))]
         #[derive(Debug, PartialEq, Clone, Copy)]
         pub struct ClusterLightIndexLists<const N: usize> {
+            /// each u32 contains 4 u8 indices into the PointLights array
+            ///
             /// size: 4, offset: 0x0, type: `array<u32>`
             pub data: [u32; N],
         }
@@ -478,8 +498,12 @@ pub mod bevy_pbr {
             assert!(std::mem::offset_of!(ClusterLightIndexLists < 1 >, data) == 0);
             assert!(std::mem::size_of:: < ClusterLightIndexLists < 1 > > () == 4);
         };
+        #[repr(C, align(16))]
         #[derive(Debug, PartialEq, Clone, Copy)]
         pub struct ClusterOffsetsAndCounts<const N: usize> {
+            /// each u32 contains a 24-bit index into ClusterLightIndexLists in the high 24 bits
+            /// and an 8-bit count of the number of lights in the low 8 bits
+            ///
             /// size: 16, offset: 0x0, type: `array<vec4<u32>>`
             pub data: [[u32; 4]; N],
         }
@@ -508,6 +532,8 @@ pub mod bevy_pbr {
             pub model: glam::Mat4,
             /// size: 64, offset: 0x40, type: `mat4x4<f32>`
             pub inverse_transpose_model: glam::Mat4,
+            /// 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+            ///
             /// size: 4, offset: 0x80, type: `u32`
             pub flags: u32,
             pub _pad_flags: [u8; 0x10 - core::mem::size_of::<u32>()],
@@ -583,7 +609,7 @@ pub mod pbr {
             pub directional_shadow_textures: &'a wgpu::TextureView,
             pub directional_shadow_textures_sampler: &'a wgpu::Sampler,
         }
-        const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -592,7 +618,7 @@ pub mod pbr {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(416),
                     },
                     count: None,
                 },
@@ -602,7 +628,7 @@ pub mod pbr {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(176),
                     },
                     count: None,
                 },
@@ -614,7 +640,7 @@ pub mod pbr {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(64),
                     },
                     count: None,
                 },
@@ -626,7 +652,7 @@ pub mod pbr {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(4),
                     },
                     count: None,
                 },
@@ -638,7 +664,7 @@ pub mod pbr {
                             read_only: true,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(16),
                     },
                     count: None,
                 },
@@ -647,7 +673,7 @@ pub mod pbr {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Depth,
-                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        view_dimension: wgpu::TextureViewDimension::CubeArray,
                         multisampled: false,
                     },
                     count: None,
@@ -663,7 +689,7 @@ pub mod pbr {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Depth,
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -760,7 +786,7 @@ pub mod pbr {
         pub struct BindGroupLayout1<'a> {
             pub material: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -769,7 +795,7 @@ pub mod pbr {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(64),
                     },
                     count: None,
                 },
@@ -813,7 +839,7 @@ pub mod pbr {
         pub struct BindGroupLayout2<'a> {
             pub mesh: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR2: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR2: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -822,7 +848,7 @@ pub mod pbr {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(144),
                     },
                     count: None,
                 },
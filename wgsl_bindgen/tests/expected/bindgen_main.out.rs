@@ -23,7 +23,7 @@ pub mod main {
         pub struct BindGroupLayout0<'a> {
             pub buffer: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -34,7 +34,7 @@ pub mod main {
                             read_only: false,
                         },
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(4),
                     },
                     count: None,
                 },
@@ -78,7 +78,7 @@ pub mod main {
         pub struct BindGroupLayout1<'a> {
             pub ONE: wgpu::BufferBinding<'a>,
         }
-        const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+        pub const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor<'static> = wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -87,7 +87,7 @@ pub mod main {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: None,
+                        min_binding_size: wgpu::BufferSize::new(4),
                     },
                     count: None,
                 },
@@ -0,0 +1,11 @@
+struct Light {
+  color_rgb: vec3<f32>,
+  intensity: f32,
+}
+
+@group(0) @binding(0) var<uniform> light: Light;
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+  return vec4(light.color_rgb, light.intensity);
+}
@@ -0,0 +1,8 @@
+#ifdef SHADOWS
+@group(0) @binding(0) var shadow_map: texture_depth_2d;
+#endif
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+  return vec4(1.0);
+}
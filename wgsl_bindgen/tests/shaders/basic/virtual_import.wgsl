@@ -0,0 +1,8 @@
+#import my_lib::lighting::PointLight
+
+@group(0) @binding(0) var<uniform> light: PointLight;
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+  return light.color;
+}
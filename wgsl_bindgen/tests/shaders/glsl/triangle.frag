@@ -0,0 +1,17 @@
+#version 450
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 color;
+
+layout(set = 0, binding = 1) uniform texture2D color_texture;
+layout(set = 0, binding = 2) uniform sampler color_sampler;
+
+void main() {
+#ifdef GRAYSCALE
+  float value = texture(sampler2D(color_texture, color_sampler), v_uv).r;
+  color = vec4(value, value, value, 1.0);
+#else
+  color = texture(sampler2D(color_texture, color_sampler), v_uv);
+#endif
+}
@@ -0,0 +1,15 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec2 uv;
+
+layout(location = 0) out vec2 v_uv;
+
+layout(set = 0, binding = 0) uniform Camera {
+  mat4 view_proj;
+};
+
+void main() {
+  v_uv = uv;
+  gl_Position = view_proj * vec4(position, 1.0);
+}